@@ -45,6 +45,7 @@ echo "in root: gitoxide CLI"
 (enter git-date && indent cargo diet -n --package-size-limit 15KB)
 (enter git-hashtable && indent cargo diet -n --package-size-limit 5KB)
 (enter git-filter && indent cargo diet -n --package-size-limit 5KB)
+(enter git-submodule && indent cargo diet -n --package-size-limit 15KB)
 (enter git-lfs && indent cargo diet -n --package-size-limit 5KB)
 (enter git-note && indent cargo diet -n --package-size-limit 5KB)
 (enter git-fetchhead && indent cargo diet -n --package-size-limit 5KB)
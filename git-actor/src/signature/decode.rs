@@ -24,7 +24,7 @@ pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
             context("<email>", terminated(take_until(&b"> "[..]), take(2usize))),
             context("<timestamp>", |i| {
                 terminated(take_until(SPACE), take(1usize))(i).and_then(|(i, v)| {
-                    btoi::<u32>(v)
+                    btoi::<git_date::SecondsSinceUnixEpoch>(v)
                         .map(|v| (i, v))
                         .map_err(|_| nom::Err::Error(E::from_error_kind(i, nom::error::ErrorKind::MapRes)))
                 })
@@ -81,7 +81,7 @@ mod tests {
         fn signature(
             name: &'static str,
             email: &'static str,
-            time: u32,
+            time: git_date::SecondsSinceUnixEpoch,
             sign: Sign,
             offset: i32,
         ) -> SignatureRef<'static> {
@@ -126,6 +126,22 @@ mod tests {
             );
         }
 
+        #[test]
+        fn timestamps_before_1970_and_past_2038() {
+            assert_eq!(
+                decode(b"Sebastian Thiel <byronimo@gmail.com> -14182940 +0000")
+                    .expect("parse to work")
+                    .1,
+                signature("Sebastian Thiel", "byronimo@gmail.com", -14182940, Sign::Plus, 0)
+            );
+            assert_eq!(
+                decode(b"Sebastian Thiel <byronimo@gmail.com> 5680281600 +0000")
+                    .expect("parse to work")
+                    .1,
+                signature("Sebastian Thiel", "byronimo@gmail.com", 5680281600, Sign::Plus, 0)
+            );
+        }
+
         #[test]
         fn empty_name_and_email() {
             assert_eq!(
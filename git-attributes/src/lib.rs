@@ -9,7 +9,7 @@
 #![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use bstr::{BStr, BString};
 use compact_str::CompactString;
@@ -23,6 +23,8 @@ mod state;
 mod match_group;
 pub use match_group::{Attributes, Ignore, Match, Pattern};
 
+mod outcome;
+
 ///
 pub mod parse;
 /// Parse attribute assignments line by line from `bytes`.
@@ -109,7 +111,7 @@ pub struct MatchGroup<T: Pattern = Attributes> {
 ///
 /// Knowing their base which is relative to a source directory, it will ignore all path to match against
 /// that don't also start with said base.
-#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct PatternList<T: Pattern> {
     /// Patterns and their associated data in the order they were loaded in or specified,
     /// the line number in its source file or its sequence number (_`(pattern, value, line_number)`_).
@@ -126,6 +128,16 @@ pub struct PatternList<T: Pattern> {
     pub base: Option<BString>,
 }
 
+impl<T: Pattern> Default for PatternList<T> {
+    fn default() -> Self {
+        PatternList {
+            patterns: Vec::new(),
+            source: None,
+            base: None,
+        }
+    }
+}
+
 /// An association of a pattern with its value, along with a sequence number providing a sort order in relation to its peers.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct PatternMapping<T> {
@@ -136,3 +148,15 @@ pub struct PatternMapping<T> {
     /// Typically the line number in the file the pattern was parsed from.
     pub sequence_number: usize,
 }
+
+/// The state of all attributes that apply to a path, as obtained by matching it against all patterns in all match groups
+/// that are in effect for it, similar to `git check-attr --all`.
+///
+/// It's meant to be reused across queries to avoid allocations.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Outcome {
+    /// All assigned attributes in the order they were assigned, with each attribute assigned at most once.
+    assignments: Vec<Assignment>,
+    /// Macro attributes by name along with the attributes they expand to if they are set.
+    macros: BTreeMap<Name, Vec<Assignment>>,
+}
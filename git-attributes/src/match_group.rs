@@ -6,7 +6,7 @@ use std::{
 
 use bstr::{BStr, BString, ByteSlice, ByteVec};
 
-use crate::{Assignment, MatchGroup, PatternList, PatternMapping};
+use crate::{Assignment, MatchGroup, Outcome, PatternList, PatternMapping};
 
 fn into_owned_assignments<'a>(
     attrs: impl Iterator<Item = Result<crate::AssignmentRef<'a>, crate::name::Error>>,
@@ -53,7 +53,9 @@ impl Pattern for Ignore {
 /// A value of an attribute pattern, which is either a macro definition or
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub enum Value {
+    /// The attributes a macro expands to.
     MacroAttributes(Vec<Assignment>),
+    /// The attributes assigned to paths matching a pattern.
     Assignments(Vec<Assignment>),
 }
 
@@ -129,6 +131,69 @@ where
             .rev()
             .find_map(|pl| pl.pattern_matching_relative_path(relative_path, basename_pos, is_dir, case))
     }
+
+    /// Add the given file at `source` if it exists, otherwise do nothing. If a `root` is provided, it's not considered a global file anymore.
+    /// Returns true if the file was added, or false if it didn't exist.
+    pub fn add_patterns_file(
+        &mut self,
+        source: impl Into<PathBuf>,
+        follow_symlinks: bool,
+        root: Option<&Path>,
+        buf: &mut Vec<u8>,
+    ) -> std::io::Result<bool> {
+        let previous_len = self.patterns.len();
+        self.patterns
+            .extend(PatternList::<T>::from_file(source.into(), root, follow_symlinks, buf)?);
+        Ok(self.patterns.len() != previous_len)
+    }
+
+    /// Add patterns as parsed from `bytes`, providing their `source` path and possibly their `root` path, the path they
+    /// are relative to. This also means that `source` is contained within `root` if `root` is provided.
+    pub fn add_patterns_buffer(&mut self, bytes: &[u8], source: impl Into<PathBuf>, root: Option<&Path>) {
+        self.patterns
+            .push(PatternList::<T>::from_bytes(bytes, source.into(), root));
+    }
+}
+
+impl MatchGroup<Attributes> {
+    /// Match `relative_path`, a path relative to the repository containing all patterns, against all patterns in this group
+    /// and assign the attributes of all matching patterns to `out` unless they were assigned before, expanding macros along the way.
+    /// `is_dir` is true if `relative_path` is a directory, and `case` specifies whether cases should be folded during matching or not.
+    ///
+    /// Patterns are matched from the last to the first, so groups with higher priority must be matched first, and `out` must have been
+    /// [initialized][crate::Outcome::initialize_macros()] with all macros that are in effect.
+    pub fn attributes_matching_relative_path<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+        out: &mut Outcome,
+    ) {
+        let relative_path = relative_path.into();
+        let basename_pos = relative_path.rfind(b"/").map(|p| p + 1);
+        for list in self.patterns.iter().rev() {
+            let (relative_path, basename_start_pos) =
+                match list.strip_base_handle_recompute_basename_pos(relative_path, basename_pos) {
+                    Some(res) => res,
+                    None => continue,
+                };
+            for mapping in list
+                .patterns
+                .iter()
+                .rev()
+                .filter(|pm| Attributes::may_use_glob_pattern(&pm.pattern))
+            {
+                if let Value::Assignments(assignments) = &mapping.value {
+                    if mapping
+                        .pattern
+                        .matches_repo_relative_path(relative_path, basename_start_pos, is_dir, case)
+                    {
+                        out.fill(assignments);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl MatchGroup<Ignore> {
@@ -165,32 +230,6 @@ impl MatchGroup<Ignore> {
             patterns: vec![PatternList::<Ignore>::from_overrides(patterns)],
         }
     }
-
-    /// Add the given file at `source` if it exists, otherwise do nothing. If a `root` is provided, it's not considered a global file anymore.
-    /// Returns true if the file was added, or false if it didn't exist.
-    pub fn add_patterns_file(
-        &mut self,
-        source: impl Into<PathBuf>,
-        follow_symlinks: bool,
-        root: Option<&Path>,
-        buf: &mut Vec<u8>,
-    ) -> std::io::Result<bool> {
-        let previous_len = self.patterns.len();
-        self.patterns.extend(PatternList::<Ignore>::from_file(
-            source.into(),
-            root,
-            follow_symlinks,
-            buf,
-        )?);
-        Ok(self.patterns.len() != previous_len)
-    }
-
-    /// Add patterns as parsed from `bytes`, providing their `source` path and possibly their `root` path, the path they
-    /// are relative to. This also means that `source` is contained within `root` if `root` is provided.
-    pub fn add_patterns_buffer(&mut self, bytes: &[u8], source: impl Into<PathBuf>, root: Option<&Path>) {
-        self.patterns
-            .push(PatternList::<Ignore>::from_bytes(bytes, source.into(), root));
-    }
 }
 
 fn read_in_full_ignore_missing(path: &Path, follow_symlinks: bool, buf: &mut Vec<u8>) -> std::io::Result<bool> {
@@ -205,11 +244,21 @@ fn read_in_full_ignore_missing(path: &Path, follow_symlinks: bool, buf: &mut Vec
             file.read_to_end(buf)?;
             true
         }
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+        Err(err) if is_missing(&err) => false,
         Err(err) => return Err(err),
     })
 }
 
+/// Return true if `err` indicates that the file doesn't exist, either directly or because one of its leading components
+/// isn't a directory, which happens when a directory in the worktree was replaced by a file.
+fn is_missing(err: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(20 /* ENOTDIR */) {
+        return true;
+    }
+    err.kind() == std::io::ErrorKind::NotFound
+}
+
 impl<T> PatternList<T>
 where
     T: Pattern,
@@ -0,0 +1,81 @@
+use bstr::ByteSlice;
+
+use crate::{match_group, Assignment, AssignmentRef, Attributes, Name, Outcome, Pattern, PatternList, State, StateRef};
+
+/// The definition of the `binary` macro, which is always available unless it is redefined.
+const BUILTIN_MACROS: &[u8] = b"[attr]binary -diff -merge -text";
+
+impl Outcome {
+    /// Learn about all macro attributes defined in pattern `lists`, whose order is from the lowest priority to the highest, so that
+    /// later definitions override earlier ones. The built-in `binary` macro is always defined.
+    ///
+    /// Note that only pattern lists without a base may define macros, just like `git` only allows their definition
+    /// in the top-level `.gitattributes` file, `info/attributes` or the global attributes files.
+    /// All previously assigned attributes are forgotten as well.
+    pub fn initialize_macros<'a>(&mut self, lists: impl IntoIterator<Item = &'a PatternList<Attributes>>) {
+        self.macros.clear();
+        self.assignments.clear();
+        let builtins = Attributes::bytes_to_patterns(BUILTIN_MACROS);
+        let definitions = builtins.iter().chain(
+            lists
+                .into_iter()
+                .filter(|list| list.base.is_none())
+                .flat_map(|list| list.patterns.iter()),
+        );
+        for mapping in definitions {
+            if let match_group::Value::MacroAttributes(assignments) = &mapping.value {
+                let name = mapping.pattern.text.to_str().expect("validated macro names are ASCII");
+                self.macros.insert(Name(name.into()), assignments.clone());
+            }
+        }
+    }
+
+    /// Forget all assigned attributes to prepare for a new query, but keep all macro definitions.
+    pub fn reset(&mut self) {
+        self.assignments.clear();
+    }
+
+    /// Return all attributes that were assigned a state other than [unspecified][State::Unspecified], in the order
+    /// they were assigned in.
+    pub fn iter(&self) -> impl Iterator<Item = AssignmentRef<'_>> {
+        self.assignments
+            .iter()
+            .filter(|a| a.state != State::Unspecified)
+            .map(Assignment::as_ref)
+    }
+
+    /// Return the state of the attribute with `name`, or `None` if it wasn't assigned.
+    pub fn get(&self, name: &str) -> Option<StateRef<'_>> {
+        self.assignments
+            .iter()
+            .find(|a| a.name.as_str() == name)
+            .map(|a| a.state.as_ref())
+    }
+
+    /// Assign all `assignments` from last to first unless they are already assigned, and expand macros that are set.
+    pub(crate) fn fill(&mut self, assignments: &[Assignment]) {
+        let Outcome {
+            assignments: assigned,
+            macros,
+        } = self;
+        fill(assigned, macros, assignments);
+    }
+}
+
+fn fill(
+    assigned: &mut Vec<Assignment>,
+    macros: &std::collections::BTreeMap<Name, Vec<Assignment>>,
+    assignments: &[Assignment],
+) {
+    for assignment in assignments.iter().rev() {
+        if assigned.iter().any(|a| a.name == assignment.name) {
+            continue;
+        }
+        assigned.push(assignment.clone());
+        if assignment.state == State::Set {
+            if let Some(macro_assignments) = macros.get(&assignment.name) {
+                fill(assigned, macros, macro_assignments);
+            }
+        }
+    }
+}
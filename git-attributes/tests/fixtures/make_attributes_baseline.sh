@@ -0,0 +1,34 @@
+#!/bin/bash
+set -eu -o pipefail
+
+cat <<EOF >user.attributes
+* global
+*.txt text=auto global-txt
+EOF
+
+mkdir repo;
+(cd repo
+  git init -q
+  git config core.attributesFile ../user.attributes
+
+  cat <<EOF >.git/info/attributes
+*.md info
+*.txt info-wins -global
+EOF
+
+  cat <<EOF >.gitattributes
+[attr]my-macro macro-a -macro-b
+*.bin binary
+*.txt -text my-macro
+dir/** dir-attr=value
+EOF
+
+  mkdir -p dir/sub
+  cat <<EOF >dir/.gitattributes
+[attr]ignored-macro x
+*.txt !my-macro sub-override=1 ignored-macro
+sub/* -dir-attr
+EOF
+
+  git check-attr -a -- a.txt b.bin c.md dir/d.txt dir/d.bin dir/sub/e.txt dir/sub/f.bin unmatched > git-check-attr.baseline 2>/dev/null
+)
@@ -116,3 +116,66 @@ mod ignore {
         }
     }
 }
+
+mod attributes {
+    use bstr::{BString, ByteSlice};
+    use git_attributes::{Attributes, MatchGroup, Outcome, StateRef};
+    use git_glob::pattern::Case;
+
+    #[test]
+    fn all_attributes_of_a_path_match_git_with_macro_expansion() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_read_only("make_attributes_baseline.sh")?;
+        let repo_dir = dir.join("repo");
+        let mut buf = Vec::new();
+
+        let mut globals = MatchGroup::<Attributes>::default();
+        assert!(globals.add_patterns_file(dir.join("user.attributes"), true, None, &mut buf)?);
+        let mut info = MatchGroup::<Attributes>::default();
+        assert!(info.add_patterns_file(repo_dir.join(".git/info/attributes"), true, None, &mut buf)?);
+        let mut stack = MatchGroup::<Attributes>::default();
+        for file in [".gitattributes", "dir/.gitattributes"] {
+            assert!(stack.add_patterns_file(repo_dir.join(file), true, Some(&repo_dir), &mut buf)?);
+        }
+
+        let mut outcome = Outcome::default();
+        outcome.initialize_macros(globals.patterns.iter().chain(&stack.patterns).chain(&info.patterns));
+        let baseline = std::fs::read(repo_dir.join("git-check-attr.baseline"))?;
+        let mut expected = std::collections::BTreeMap::<BString, Vec<(String, String)>>::new();
+        for line in baseline.lines() {
+            let mut tokens = line.split_str(": ");
+            let path = tokens.next().expect("path").as_bstr().to_owned();
+            let name = tokens.next().expect("name").to_str()?.to_owned();
+            let value = tokens.next().expect("value").to_str()?.to_owned();
+            let attrs = expected.entry(path).or_default();
+            attrs.push((name, value));
+            attrs.sort();
+        }
+
+        for (path, expected) in expected {
+            outcome.reset();
+            for group in [&info, &stack, &globals] {
+                group.attributes_matching_relative_path(path.as_bstr(), Some(false), Case::Sensitive, &mut outcome);
+            }
+            let mut actual: Vec<_> = outcome
+                .iter()
+                .map(|a| {
+                    let value = match a.state {
+                        StateRef::Set => "set".into(),
+                        StateRef::Unset => "unset".into(),
+                        StateRef::Value(v) => v.to_string(),
+                        StateRef::Unspecified => unreachable!("never returned"),
+                    };
+                    (a.name.as_str().to_owned(), value)
+                })
+                .collect();
+            actual.sort();
+            assert_eq!(actual, expected, "{}", path);
+        }
+        assert_eq!(
+            outcome.get("macro-a"),
+            None,
+            "the last path is unmatched, and only global attributes apply to it"
+        );
+        Ok(())
+    }
+}
@@ -48,7 +48,7 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
 mod access {
     use std::convert::{TryFrom, TryInto};
 
-    use super::Vec;
+    use super::{rlw, Vec};
 
     impl Vec {
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
@@ -59,17 +59,17 @@ mod access {
             let mut index = 0usize;
             let mut iter = self.bits.iter();
             while let Some(word) = iter.next() {
-                if rlw_runbit_is_set(word) {
-                    let len = rlw_running_len_bits(word);
+                if rlw::run_bit(*word) {
+                    let len = rlw::running_len(*word) * 64;
                     for _ in 0..len {
                         f(index)?;
                         index += 1;
                     }
                 } else {
-                    index += usize::try_from(rlw_running_len_bits(word)).ok()?;
+                    index += usize::try_from(rlw::running_len(*word) * 64).ok()?;
                 }
 
-                for _ in 0..rlw_literal_words(word) {
+                for _ in 0..rlw::literal_words(*word) {
                     let word = iter
                         .next()
                         .expect("BUG: ran out of words while going through uncompressed portion");
@@ -89,29 +89,166 @@ mod access {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
         }
     }
+}
+
+mod write {
+    use std::convert::TryInto;
+
+    use super::{rlw, Vec};
 
-    #[inline]
-    fn rlw_running_len_bits(w: &u64) -> u64 {
-        rlw_running_len(w) * 64
+    impl Default for Vec {
+        fn default() -> Self {
+            Vec {
+                num_bits: 0,
+                bits: vec![0],
+                rlw: 0,
+            }
+        }
     }
 
-    #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
-        (w >> 1) & RLW_LARGEST_RUNNING_COUNT
+    impl Vec {
+        /// Set the bit at `index` to true, which must be larger than the index of all previously set bits.
+        ///
+        /// The bits are compressed exactly like `git` does it to allow writing bitmaps that are identical to the ones written by `git`.
+        pub fn set(&mut self, index: usize) {
+            let index: u32 = index.try_into().expect("bitmaps don't hold more than 2^32 bits");
+            assert!(
+                index >= self.num_bits,
+                "BUG: bits must be set in ascending order, got {} after {} bits",
+                index,
+                self.num_bits
+            );
+            let bit = 1 << (index % 64);
+            let dist = words_for_bits(index + 1) - words_for_bits(self.num_bits);
+            self.num_bits = index + 1;
+            if dist > 0 {
+                if dist > 1 {
+                    self.add_empty_words(false, u64::from(dist - 1));
+                }
+                self.add_literal(bit);
+                return;
+            }
+            let rlw = self.rlw_mut();
+            if rlw::literal_words(*rlw) == 0 {
+                rlw::set_running_len(rlw, rlw::running_len(*rlw) - 1);
+                self.add_literal(bit);
+                return;
+            }
+            let last = self.bits.last_mut().expect("at least one word");
+            *last |= bit;
+            if *last == u64::MAX {
+                self.bits.pop();
+                let rlw = self.rlw_mut();
+                rlw::set_literal_words(rlw, rlw::literal_words(*rlw) - 1);
+                self.add_empty_word(true);
+            }
+        }
+
+        /// Serialize this instance to `out`.
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            let len: u32 = self.bits.len().try_into().expect("not more than 2^32 words");
+            out.write_all(&len.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            let rlw: u32 = self.rlw.try_into().expect("not more than 2^32 words");
+            out.write_all(&rlw.to_be_bytes())
+        }
+
+        fn rlw_mut(&mut self) -> &mut u64 {
+            &mut self.bits[self.rlw as usize]
+        }
+
+        fn push_rlw(&mut self) {
+            self.rlw = self.bits.len() as u64;
+            self.bits.push(0);
+        }
+
+        fn add_literal(&mut self, word: u64) {
+            let literal_words = rlw::literal_words(*self.rlw_mut());
+            if literal_words >= rlw::LARGEST_LITERAL_COUNT {
+                self.push_rlw();
+                rlw::set_literal_words(self.rlw_mut(), 1);
+            } else {
+                rlw::set_literal_words(self.rlw_mut(), literal_words + 1);
+            }
+            self.bits.push(word);
+        }
+
+        fn add_empty_word(&mut self, value: bool) {
+            let word = *self.rlw_mut();
+            let no_literal = rlw::literal_words(word) == 0;
+            let running_len = rlw::running_len(word);
+            if no_literal && running_len == 0 {
+                rlw::set_run_bit(self.rlw_mut(), value);
+            }
+            if no_literal && rlw::run_bit(*self.rlw_mut()) == value && running_len < rlw::LARGEST_RUNNING_COUNT {
+                rlw::set_running_len(self.rlw_mut(), running_len + 1);
+            } else {
+                self.push_rlw();
+                rlw::set_run_bit(self.rlw_mut(), value);
+                rlw::set_running_len(self.rlw_mut(), 1);
+            }
+        }
+
+        fn add_empty_words(&mut self, value: bool, mut count: u64) {
+            let word = *self.rlw_mut();
+            if rlw::run_bit(word) != value && rlw::running_len(word) + rlw::literal_words(word) == 0 {
+                rlw::set_run_bit(self.rlw_mut(), value);
+            } else if rlw::literal_words(word) != 0 || rlw::run_bit(word) != value {
+                self.push_rlw();
+                rlw::set_run_bit(self.rlw_mut(), value);
+            }
+            let running_len = rlw::running_len(*self.rlw_mut());
+            let can_add = count.min(rlw::LARGEST_RUNNING_COUNT - running_len);
+            rlw::set_running_len(self.rlw_mut(), running_len + can_add);
+            count -= can_add;
+            while count > 0 {
+                let len = count.min(rlw::LARGEST_RUNNING_COUNT);
+                self.push_rlw();
+                rlw::set_run_bit(self.rlw_mut(), value);
+                rlw::set_running_len(self.rlw_mut(), len);
+                count -= len;
+            }
+        }
     }
 
-    #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
-        w >> (1 + RLW_RUNNING_BITS)
+    fn words_for_bits(num_bits: u32) -> u32 {
+        (num_bits + 63) / 64
     }
+}
 
-    #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+/// Accessors for the fields of running length words, which are followed by the amount of literal words they specify.
+mod rlw {
+    const RUNNING_BITS: u64 = 4 * 8;
+    const LITERAL_BITS: u64 = 64 - 1 - RUNNING_BITS;
+    pub const LARGEST_RUNNING_COUNT: u64 = (1 << RUNNING_BITS) - 1;
+    pub const LARGEST_LITERAL_COUNT: u64 = (1 << LITERAL_BITS) - 1;
+
+    pub fn run_bit(w: u64) -> bool {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub fn running_len(w: u64) -> u64 {
+        (w >> 1) & LARGEST_RUNNING_COUNT
+    }
+
+    pub fn literal_words(w: u64) -> u64 {
+        w >> (1 + RUNNING_BITS)
+    }
+
+    pub fn set_run_bit(w: &mut u64, value: bool) {
+        *w = (*w & !1) | u64::from(value);
+    }
+
+    pub fn set_running_len(w: &mut u64, len: u64) {
+        *w = (*w & !(LARGEST_RUNNING_COUNT << 1)) | (len << 1);
+    }
+
+    pub fn set_literal_words(w: &mut u64, count: u64) {
+        *w = (*w & ((1 << (1 + RUNNING_BITS)) - 1)) | (count << (1 + RUNNING_BITS));
+    }
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
@@ -0,0 +1,253 @@
+//! Changed-path Bloom filters, which tell if a path may have been changed by a commit compared to its first parent.
+//!
+//! They are stored in the `BIDX` and `BDAT` chunks of commit-graph files, and allow skipping the tree-diff of most
+//! commits that didn't touch a path when looking at its history.
+use std::collections::HashSet;
+
+use bstr::{BStr, ByteSlice};
+
+/// The largest amount of changed paths, including their leading directories, a filter is computed for.
+///
+/// Commits with more changed paths get a filter which reports every path as possibly changed.
+pub const MAX_CHANGED_PATHS: usize = 512;
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+
+/// The parameters used to compute all filters of a commit-graph file, as stored in the header of its `BDAT` chunk.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the murmur3 hash function, which is 1 for the original one that sign-extends bytes larger than 0x7f, and 2 for the
+    /// one that doesn't.
+    pub hash_version: u32,
+    /// The amount of bits to set for each path.
+    pub num_hashes: u32,
+    /// The amount of bits in a filter per changed path.
+    pub bits_per_entry: u32,
+}
+
+impl Default for Settings {
+    /// The settings used by `git` which are understood by all of its versions.
+    fn default() -> Self {
+        Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+        }
+    }
+}
+
+impl Settings {
+    /// Return true if we know how to compute keys with these settings.
+    pub fn is_supported(&self) -> bool {
+        matches!(self.hash_version, 1 | 2) && self.num_hashes != 0 && self.bits_per_entry != 0
+    }
+}
+
+/// The positions of all bits to test or set for a single path, independent of the size of a filter.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key of `path` using `settings`, which must be [supported][Settings::is_supported()].
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        let hash0 = murmur3(SEED0, path, settings.hash_version);
+        let hash1 = murmur3(SEED1, path, settings.hash_version);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+}
+
+/// The keys of a path and all of its leading directories, to check if a path may have changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Keys {
+    keys: Vec<Key>,
+}
+
+impl Keys {
+    /// Compute the keys for `path` and all of its leading directories with `settings`, which must be [supported][Settings::is_supported()].
+    ///
+    /// Leading and trailing slashes of `path` are ignored.
+    pub fn new(path: &BStr, settings: &Settings) -> Self {
+        Keys {
+            keys: path_and_leading_directories(path)
+                .map(|path| Key::new(path, settings))
+                .collect(),
+        }
+    }
+}
+
+/// A filter as stored in a commit-graph file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FilterRef<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> FilterRef<'a> {
+    /// Create a new instance from the raw filter `data`.
+    pub fn from_bytes(data: &'a [u8]) -> Self {
+        FilterRef { data }
+    }
+
+    /// Return the raw filter data.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return true if `key` may be contained in this filter, or false if it's definitely not.
+    pub fn may_contain(&self, key: &Key) -> bool {
+        let num_bits = self.data.len() as u64 * 8;
+        if num_bits == 0 {
+            return true;
+        }
+        key.hashes.iter().all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+
+    /// Return true if the path the `keys` were computed for may have been changed, or false if it definitely wasn't.
+    pub fn may_contain_path(&self, keys: &Keys) -> bool {
+        keys.keys.iter().all(|key| self.may_contain(key))
+    }
+
+    /// Create an owned copy of this filter.
+    pub fn to_owned(&self) -> Filter {
+        Filter {
+            data: self.data.to_owned(),
+        }
+    }
+}
+
+/// An owned filter, typically created from the paths changed by a commit to [write][crate::file::File::write_to()] it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Filter {
+    data: Vec<u8>,
+}
+
+impl Filter {
+    /// Create a filter from all `changed_paths` of a commit compared to its first parent, or compared to the empty tree if it has no parent,
+    /// using `settings` which must be [supported][Settings::is_supported()].
+    ///
+    /// `changed_paths` are the paths of all added, deleted and modified files, and their leading directories are added
+    /// automatically. Note that a file that changed into a directory or vice versa should be passed as well.
+    pub fn from_changed_paths<'a>(changed_paths: impl IntoIterator<Item = &'a BStr>, settings: &Settings) -> Self {
+        let mut paths = HashSet::new();
+        for path in changed_paths {
+            paths.extend(path_and_leading_directories(path));
+            if paths.len() > MAX_CHANGED_PATHS {
+                return Filter { data: vec![0xff] };
+            }
+        }
+        if paths.is_empty() {
+            return Filter { data: vec![0] };
+        }
+        let num_bits = paths.len() as u64 * u64::from(settings.bits_per_entry);
+        let mut data = vec![0; ((num_bits + 7) / 8) as usize];
+        let num_bits = data.len() as u64 * 8;
+        for path in paths {
+            for hash in Key::new(path, settings).hashes {
+                let bit = u64::from(hash) % num_bits;
+                data[(bit / 8) as usize] |= 1 << (bit % 8);
+            }
+        }
+        Filter { data }
+    }
+
+    /// Return a borrowed version of this filter for querying it.
+    pub fn to_ref(&self) -> FilterRef<'_> {
+        FilterRef { data: &self.data }
+    }
+
+    /// Return the raw filter data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Return `path` and all of its leading directories, ignoring leading and trailing slashes.
+fn path_and_leading_directories(path: &BStr) -> impl Iterator<Item = &BStr> {
+    let path = path.trim_with(|c| c == '/').as_bstr();
+    let mut end = (!path.is_empty()).then(|| path.len());
+    std::iter::from_fn(move || {
+        let current = end?;
+        let prefix = path[..current].as_bstr();
+        end = prefix.rfind_byte(b'/');
+        Some(prefix)
+    })
+}
+
+/// The seeded murmur3 hash of `data`, which sign-extends bytes larger than 0x7f in `hash_version` 1 just like `git` does.
+fn murmur3(mut seed: u32, data: &[u8], hash_version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| -> u32 {
+        if hash_version == 1 {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = byte(block[0]) | (byte(block[1]) << 8) | (byte(block[2]) << 16) | (byte(block[3]) << 24);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (shift * 8);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed ^= k;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn murmur3_matches_the_reference_values_used_by_git() {
+        // Taken from git's t/helper/test-bloom.c tests in t0095-bloom.sh.
+        assert_eq!(murmur3(0, b"", 1), 0x0000_0000);
+        assert_eq!(murmur3(0, b"Hello world!", 1), 0x627b_0c2c);
+        assert_eq!(
+            murmur3(0, b"The quick brown fox jumps over the lazy dog", 1),
+            0x2e4f_f723
+        );
+        let high_bits = b"\x99\xaa\xbb\xcc\xdd\xee\xff";
+        assert_eq!(murmur3(0, high_bits, 2), 0xa183_ccfd);
+        assert_ne!(
+            murmur3(0, high_bits, 1),
+            0xa183_ccfd,
+            "version 1 sign-extends bytes like git did"
+        );
+    }
+
+    #[test]
+    fn leading_directories() {
+        let paths: Vec<_> = path_and_leading_directories("/a/b/c/".into()).collect();
+        assert_eq!(paths, ["a/b/c", "a/b", "a"]);
+        assert_eq!(path_and_leading_directories("".into()).count(), 0);
+    }
+}
@@ -13,6 +13,34 @@ impl File {
         self.base_graph_count
     }
 
+    /// Returns the settings used to compute all bloom filters in this file, or `None` if there are no bloom filters we can use.
+    pub fn bloom_filter_settings(&self) -> Option<crate::bloom::Settings> {
+        self.bloom_filters.as_ref().map(|(_, _, settings)| *settings)
+    }
+
+    /// Returns the changed-path bloom filter of the commit at the given lexigraphical position, or `None` if there is none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn bloom_filter_at(&self, pos: file::Position) -> Option<crate::bloom::FilterRef<'_>> {
+        assert!(
+            pos.0 < self.num_commits(),
+            "expected lexigraphical position less than {}, got {}",
+            self.num_commits(),
+            pos.0
+        );
+        let (index_offset, data_range, _settings) = self.bloom_filters.as_ref()?;
+        let end_at = |pos: usize| -> usize {
+            let start = index_offset + pos * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes")) as usize
+        };
+        let pos = pos.0 as usize;
+        let start = if pos == 0 { 0 } else { end_at(pos - 1) };
+        let filter = self.data[data_range.clone()].get(start..end_at(pos))?;
+        (!filter.is_empty()).then(|| crate::bloom::FilterRef::from_bytes(filter))
+    }
+
     /// Returns the commit data for the commit located at the given lexigraphical position.
     ///
     /// `pos` must range from 0 to self.num_commits().
@@ -56,6 +56,22 @@ impl<'a> Commit<'a> {
         }
     }
 
+    /// Returns the changed-path bloom filter of this commit, if there is one.
+    pub fn bloom_filter(&self) -> Option<crate::bloom::FilterRef<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
+    /// Returns false if `path` definitely wasn't changed by this commit compared to its first parent, or true if it may have changed.
+    ///
+    /// If there is no bloom filter for this commit, the path may always have changed. When querying many commits, prefer
+    /// using [`bloom::Keys`][crate::bloom::Keys] with the filter directly to compute the keys for `path` only once.
+    pub fn maybe_changed_path(&self, path: &bstr::BStr) -> bool {
+        match (self.bloom_filter(), self.file.bloom_filter_settings()) {
+            (Some(filter), Some(settings)) => filter.may_contain_path(&crate::bloom::Keys::new(path, &settings)),
+            _ => true,
+        }
+    }
+
     /// Returns the committer timestamp of this commit.
     ///
     /// The value is the number of seconds since 1970-01-01 00:00:00 UTC.
@@ -6,9 +6,13 @@ use std::{
 use bstr::ByteSlice;
 use memmap2::Mmap;
 
-use crate::file::{
-    ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-    EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+use crate::{
+    bloom,
+    file::{
+        ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
 };
 
 /// The error used in [`File::at()`].
@@ -229,9 +233,32 @@ impl TryFrom<&Path> for File {
                 chunk2_commits: commit_data_count,
             });
         }
+        // Like git, we ignore bloom filters we don't understand as they are optional.
+        let bloom_filters = chunks
+            .usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID)
+            .ok()
+            .filter(|index_range| index_range.len() == fan[255] as usize * 4)
+            .zip(chunks.usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).ok())
+            .and_then(|(index_range, data_range)| {
+                let header = data.get(data_range.clone())?.get(..BLOOM_FILTER_DATA_HEADER_LEN)?;
+                let settings = bloom::Settings {
+                    hash_version: u32::from_be_bytes(header[..4].try_into().expect("4 bytes")),
+                    num_hashes: u32::from_be_bytes(header[4..8].try_into().expect("4 bytes")),
+                    bits_per_entry: u32::from_be_bytes(header[8..].try_into().expect("4 bytes")),
+                };
+                settings.is_supported().then(|| {
+                    (
+                        index_range.start,
+                        data_range.start + BLOOM_FILTER_DATA_HEADER_LEN..data_range.end,
+                        settings,
+                    )
+                })
+            });
+
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filters,
             commit_data_offset,
             data,
             extra_edges_list_range,
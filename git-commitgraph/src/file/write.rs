@@ -0,0 +1,307 @@
+//! Auxiliary types used to write commit-graph files.
+use std::{convert::TryInto, io::Write};
+
+use git_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, HEADER_LEN, LAST_EXTENDED_EDGE_MASK, NO_PARENT,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    Graph, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// A commit to write into a commit-graph file, typically obtained by decoding commits during a traversal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree: ObjectId,
+    /// The ids of all parents of the commit, in order.
+    pub parents: Vec<ObjectId>,
+    /// The committer timestamp in seconds since 1970-01-01 00:00:00 UTC.
+    ///
+    /// Timestamps before that are written as 0, and only the lower 34 bits of later ones are stored, just like `git` does.
+    pub committer_timestamp: git_date::SecondsSinceUnixEpoch,
+    /// The changed-path bloom filter of the commit, computed with the [settings][Options::bloom_filter_settings] of the file to write.
+    ///
+    /// It's only written if bloom filters are enabled, and commits without a filter are treated as if any path may have changed.
+    pub bloom_filter: Option<bloom::Filter>,
+}
+
+impl Commit {
+    /// Create a new instance from the commit with `id` as decoded by `commit`, without a bloom filter.
+    ///
+    /// Generation numbers don't need to be known as they are computed from the parents of all commits when writing.
+    pub fn from_commit_iter(
+        id: impl Into<ObjectId>,
+        mut commit: git_object::CommitRefIter<'_>,
+    ) -> Result<Self, git_object::decode::Error> {
+        let tree = commit.tree_id()?;
+        let parents = commit.parent_ids().collect();
+        let committer_timestamp = commit.committer()?.time.seconds_since_unix_epoch;
+        Ok(Commit {
+            id: id.into(),
+            tree,
+            parents,
+            committer_timestamp,
+            bloom_filter: None,
+        })
+    }
+}
+
+/// Options for use in [`File::write_to()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Options {
+    /// The kind of hash to use for all ids and the trailing checksum.
+    pub object_hash: git_hash::Kind,
+    /// If set, the bloom filters of all commits are written as well, which must have been computed with these settings.
+    pub bloom_filter_settings: Option<bloom::Settings>,
+}
+
+/// The error used in [`File::write_to()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Parent {parent} of commit {id} is neither among the commits to write nor in the base graph")]
+    MissingParent { id: ObjectId, parent: ObjectId },
+    #[error(
+        "Commit-graph files contain {0} commits altogether, but only {} commits are allowed",
+        MAX_COMMITS
+    )]
+    TooManyCommits(u64),
+    #[error("A commit-graph file can have at most 255 base graphs, but {0} were given")]
+    TooManyBaseGraphs(usize),
+}
+
+/// The result of [`File::write_to()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Outcome {
+    /// The checksum of the written file, which is also its name in a split commit-graph chain.
+    pub checksum: ObjectId,
+    /// The amount of commits in the written file.
+    pub num_commits: u32,
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file containing `commits` to `out`, configured by `options`.
+    ///
+    /// If `base` is set, the file is written as new layer on top of all of its files, turning it into a split commit-graph chain.
+    /// Commits already contained in `base` are skipped, and parents of all other commits must either be in `base` or among `commits`.
+    ///
+    /// Generation numbers are computed as topological levels, and committer timestamps are stored as is.
+    pub fn write_to(
+        mut commits: Vec<Commit>,
+        base: Option<&Graph>,
+        mut out: impl std::io::Write,
+        Options {
+            object_hash,
+            bloom_filter_settings,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        if let Some(base) = base {
+            commits.retain(|c| base.lookup(c.id).is_none());
+        }
+        let base_files = base.map_or(&[][..], |base| &base.files);
+        let base_graph_count: u8 = base_files
+            .len()
+            .try_into()
+            .map_err(|_| Error::TooManyBaseGraphs(base_files.len()))?;
+        let num_base_commits = base.map_or(0, |base| base.num_commits());
+        let num_commits = u64::from(num_base_commits) + commits.len() as u64;
+        if num_commits > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(num_commits));
+        }
+
+        let mut parents = Vec::with_capacity(commits.len());
+        for commit in &commits {
+            let mut positions = Vec::with_capacity(commit.parents.len());
+            for parent in &commit.parents {
+                let position = match commits.binary_search_by(|c| c.id.cmp(parent)) {
+                    Ok(idx) => num_base_commits + idx as u32,
+                    Err(_) => base
+                        .and_then(|base| base.lookup(parent))
+                        .map(|p| p.0)
+                        .ok_or(Error::MissingParent {
+                            id: commit.id,
+                            parent: *parent,
+                        })?,
+                };
+                positions.push(position);
+            }
+            parents.push(positions);
+        }
+        let generations = compute_generations(&parents, num_base_commits, base);
+
+        let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = git_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if bloom_filter_settings.is_some() {
+            let num_filter_bytes: usize = commits
+                .iter()
+                .filter_map(|c| c.bloom_filter.as_ref())
+                .map(|f| f.as_bytes().len())
+                .sum();
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + num_filter_bytes) as u64,
+            );
+        }
+        if base_graph_count != 0 {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_files.len() * hash_len) as u64);
+        }
+
+        let mut out = git_features::hash::Write::new(&mut out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base_graph_count,
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for commit in &commits {
+                        fan[usize::from(commit.id.first_byte())] += 1;
+                    }
+                    let mut total = 0;
+                    for count in fan {
+                        total += count;
+                        chunk_write.write_all(&total.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for commit in &commits {
+                        chunk_write.write_all(commit.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    let mut extra_edge_index = 0;
+                    for ((commit, parents), generation) in commits.iter().zip(&parents).zip(&generations) {
+                        let (parent1, parent2) = match parents.as_slice() {
+                            [] => (NO_PARENT, NO_PARENT),
+                            [parent1] => (*parent1, NO_PARENT),
+                            [parent1, parent2] => (*parent1, *parent2),
+                            [parent1, extra_edges @ ..] => {
+                                let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                                extra_edge_index += extra_edges.len() as u32;
+                                (*parent1, edge)
+                            }
+                        };
+                        chunk_write.write_all(commit.tree.as_slice())?;
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        let timestamp =
+                            u64::try_from(commit.committer_timestamp).unwrap_or_default() & 0x0003_ffff_ffff;
+                        chunk_write.write_all(&((generation << 2) | (timestamp >> 32) as u32).to_be_bytes())?;
+                        chunk_write.write_all(&(timestamp as u32).to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for parents in parents.iter().filter(|p| p.len() > 2) {
+                        let (last, extra_edges) = parents[1..].split_last().expect("more than two parents");
+                        for edge in extra_edges {
+                            chunk_write.write_all(&edge.to_be_bytes())?;
+                        }
+                        chunk_write.write_all(&(LAST_EXTENDED_EDGE_MASK | last).to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let mut end = 0u32;
+                    for commit in &commits {
+                        end += commit.bloom_filter.as_ref().map_or(0, |f| f.as_bytes().len() as u32);
+                        chunk_write.write_all(&end.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let settings = bloom_filter_settings.expect("set if planned");
+                    for value in [settings.hash_version, settings.num_hashes, settings.bits_per_entry] {
+                        chunk_write.write_all(&value.to_be_bytes())?;
+                    }
+                    for filter in commits.iter().filter_map(|c| c.bloom_filter.as_ref()) {
+                        chunk_write.write_all(filter.as_bytes())?;
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for file in base_files {
+                        chunk_write.write_all(file.checksum().as_bytes())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+        drop(chunk_write);
+
+        let checksum = out.hash.digest();
+        out.inner.write_all(checksum.as_slice())?;
+        Ok(Outcome {
+            checksum,
+            num_commits: commits.len() as u32,
+        })
+    }
+}
+
+/// Compute the generation number of each commit, given the graph positions of the `parents` of each commit.
+///
+/// Commits to write start at graph position `num_base_commits`, all commits with lower positions are looked up in `base`.
+fn compute_generations(parents: &[Vec<u32>], num_base_commits: u32, base: Option<&Graph>) -> Vec<u32> {
+    let base_generation = |position: u32| {
+        base.expect("positions below the base commit count are in the base")
+            .commit_at(crate::graph::Position(position))
+            .generation()
+    };
+    let mut generations = vec![0; parents.len()];
+    let mut stack = Vec::new();
+    for start in 0..parents.len() {
+        if generations[start] != 0 {
+            continue;
+        }
+        stack.push(start);
+        while let Some(&idx) = stack.last() {
+            let mut max_parent_generation = 0;
+            let mut parents_are_known = true;
+            for &parent in &parents[idx] {
+                let generation = match parent.checked_sub(num_base_commits) {
+                    Some(parent_idx) => {
+                        let generation = generations[parent_idx as usize];
+                        if generation == 0 {
+                            parents_are_known = false;
+                            stack.push(parent_idx as usize);
+                        }
+                        generation
+                    }
+                    None => base_generation(parent),
+                };
+                max_parent_generation = max_parent_generation.max(generation);
+            }
+            if parents_are_known {
+                generations[idx] = max_parent_generation.saturating_add(1).min(GENERATION_NUMBER_MAX);
+                stack.pop();
+            }
+        }
+    }
+    generations
+}
@@ -0,0 +1,282 @@
+//! Auxiliary types used to write and update commit graphs.
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{self, commit, File},
+    Graph,
+};
+
+/// The error used in [`Graph::write_to_info_dir()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Open(#[from] crate::graph::init::Error),
+    #[error(transparent)]
+    Commit(#[from] commit::Error),
+    #[error(transparent)]
+    WriteFile(#[from] file::write::Error),
+    #[error("Could not write commit-graph file at '{}'", .path.display())]
+    Io {
+        #[source]
+        err: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+/// Determine how a split commit-graph chain is updated.
+///
+/// A chain consists of layers, one per file, with each layer containing only commits that aren't in the layers below it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Split {
+    /// Merge the top-most layers into the new layer as long as they contain less than `size_multiple` times the commits of the new layer,
+    /// or as long as the new layer would contain more than `max_commits` commits, similar to `git commit-graph write --split`.
+    MergeBySize {
+        /// The factor by which each layer must be larger than the layer on top of it, 2 by default.
+        size_multiple: u32,
+        /// If set, merge layers while the new layer has more than the given amount of commits.
+        max_commits: Option<u32>,
+    },
+    /// Always add a new layer on top of the existing ones, similar to `git commit-graph write --split=no-merge`.
+    NoMerge,
+    /// Replace all layers with a single one containing all commits, similar to `git commit-graph write --split=replace`.
+    Replace,
+}
+
+impl Default for Split {
+    fn default() -> Self {
+        Split::MergeBySize {
+            size_multiple: 2,
+            max_commits: None,
+        }
+    }
+}
+
+/// Options for use in [`Graph::write_to_info_dir()`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Options {
+    /// The kind of hash to use for all ids and checksums.
+    pub object_hash: git_hash::Kind,
+    /// If set, the commits are written into a split commit-graph chain in `info/commit-graphs/`, otherwise
+    /// a single `info/commit-graph` file is written.
+    pub split: Option<Split>,
+    /// If set, the bloom filters of all commits are written as well, which must have been computed with these settings.
+    ///
+    /// Bloom filters of commits in existing files are kept if they were computed with the same settings.
+    pub bloom_filter_settings: Option<bloom::Settings>,
+}
+
+/// The result of [`Graph::write_to_info_dir()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Outcome {
+    /// The checksums of all files of the commit graph after writing, from the bottom-most layer to the top-most one.
+    ///
+    /// It's empty if nothing was written as there was no commit to write.
+    pub checksums: Vec<ObjectId>,
+    /// The amount of commits written into the new file.
+    pub num_commits_written: u32,
+    /// The amount of files that were merged into the new file, and removed.
+    pub num_files_merged: usize,
+}
+
+/// Writing
+impl Graph {
+    /// Add `commits` to the commit graph in the `.git/objects/info` directory at `info_dir`, creating it if it doesn't exist yet,
+    /// and return information about the written files.
+    ///
+    /// If [`options.split`][Options::split] is `None`, all commits of the existing commit graph and `commits` are written into
+    /// a single `commit-graph` file, replacing the previous one. Otherwise `commits` that aren't in the existing chain yet are written into
+    /// a new layer, along with the commits of all layers that are merged into it according to the [`Split`] strategy. Commits of an
+    /// existing `commit-graph` file are always merged into the new layer, as it would otherwise shadow the chain.
+    ///
+    /// All parents of `commits` must either be among `commits` or in the existing commit graph, which is naturally the case if
+    /// they were obtained by traversing all commits that aren't in the commit graph yet.
+    pub fn write_to_info_dir(
+        info_dir: impl AsRef<Path>,
+        commits: impl IntoIterator<Item = file::write::Commit>,
+        Options {
+            object_hash,
+            split,
+            bloom_filter_settings,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let info_dir = info_dir.as_ref();
+        let graph_path = info_dir.join("commit-graph");
+        let graphs_dir = info_dir.join("commit-graphs");
+        let chain_path = graphs_dir.join("commit-graph-chain");
+
+        let single = graph_path
+            .is_file()
+            .then(|| Graph::from_file(&graph_path))
+            .transpose()?;
+        let chain = chain_path
+            .is_file()
+            .then(|| Graph::from_commit_graphs_dir(&graphs_dir))
+            .transpose()?;
+        let mut commits: Vec<_> = commits.into_iter().collect();
+        if let Some(single) = &single {
+            commits.extend(all_commits(single, 0, bloom_filter_settings)?);
+        }
+
+        let (base, merged_paths) = match (split, chain) {
+            (None, chain) => {
+                if let Some(chain) = &chain {
+                    commits.extend(all_commits(chain, 0, bloom_filter_settings)?);
+                }
+                (None, Vec::new())
+            }
+            (Some(_), None) => (None, Vec::new()),
+            (Some(split), Some(mut chain)) => {
+                commits.retain(|c| chain.lookup(c.id).is_none());
+                commits.sort_by_key(|c| c.id);
+                commits.dedup_by(|a, b| a.id == b.id);
+                let mut num_files_to_keep = chain.files.len();
+                let mut num_commits = commits.len() as u64;
+                while let Some(file) = num_files_to_keep.checked_sub(1).map(|idx| &chain.files[idx]) {
+                    let merge = match split {
+                        Split::Replace => true,
+                        Split::NoMerge => false,
+                        Split::MergeBySize {
+                            size_multiple,
+                            max_commits,
+                        } => {
+                            u64::from(file.num_commits()) < u64::from(size_multiple) * num_commits
+                                || max_commits.map_or(false, |max| num_commits > u64::from(max))
+                        }
+                    };
+                    if !merge {
+                        break;
+                    }
+                    num_commits += u64::from(file.num_commits());
+                    num_files_to_keep -= 1;
+                }
+                let num_files_merged = chain.files.len() - num_files_to_keep;
+                if commits.is_empty() && num_files_merged == 0 {
+                    return Ok(Outcome {
+                        checksums: chain.files.iter().map(|f| f.checksum().to_owned()).collect(),
+                        num_commits_written: 0,
+                        num_files_merged: 0,
+                    });
+                }
+                commits.extend(all_commits(&chain, num_files_to_keep, bloom_filter_settings)?);
+                let merged_paths = chain
+                    .files
+                    .split_off(num_files_to_keep)
+                    .iter()
+                    .map(|f| f.path().to_owned())
+                    .collect();
+                ((!chain.files.is_empty()).then(|| chain), merged_paths)
+            }
+        };
+        let num_files_merged = merged_paths.len();
+        if commits.is_empty() && single.is_none() {
+            return Ok(Outcome {
+                checksums: Vec::new(),
+                num_commits_written: 0,
+                num_files_merged,
+            });
+        }
+
+        let mut buf = Vec::new();
+        let outcome = File::write_to(
+            commits,
+            base.as_ref(),
+            &mut buf,
+            file::write::Options {
+                object_hash,
+                bloom_filter_settings,
+            },
+        )?;
+        let mut checksums: Vec<_> = base
+            .iter()
+            .flat_map(|base| base.files.iter().map(|f| f.checksum().to_owned()))
+            .collect();
+        checksums.push(outcome.checksum);
+        // Release all memory maps before replacing or deleting files.
+        drop(base);
+        drop(single);
+
+        match split {
+            None => write_atomically(&graph_path, &buf)?,
+            Some(_) => {
+                std::fs::create_dir_all(&graphs_dir).map_err(|err| Error::Io {
+                    err,
+                    path: graphs_dir.clone(),
+                })?;
+                write_atomically(&graphs_dir.join(format!("graph-{}.graph", outcome.checksum)), &buf)?;
+                let mut chain = Vec::new();
+                for checksum in &checksums {
+                    writeln!(chain, "{}", checksum).expect("write to memory never fails");
+                }
+                write_atomically(&chain_path, &chain)?;
+                for path in merged_paths.iter().chain(Some(&graph_path)) {
+                    remove_if_present(path)?;
+                }
+            }
+        }
+        Ok(Outcome {
+            checksums: if split.is_some() {
+                checksums
+            } else {
+                vec![outcome.checksum]
+            },
+            num_commits_written: outcome.num_commits,
+            num_files_merged,
+        })
+    }
+}
+
+/// Return all commits in the files of `graph` starting at `first_file`, along with their bloom filters if they were
+/// computed with `bloom_filter_settings`.
+fn all_commits(
+    graph: &Graph,
+    first_file: usize,
+    bloom_filter_settings: Option<bloom::Settings>,
+) -> Result<Vec<file::write::Commit>, commit::Error> {
+    let mut out = Vec::new();
+    for file in &graph.files[first_file..] {
+        let keep_bloom_filters =
+            bloom_filter_settings.is_some() && file.bloom_filter_settings() == bloom_filter_settings;
+        for commit in file.iter_commits() {
+            out.push(file::write::Commit {
+                id: commit.id().to_owned(),
+                tree: commit.root_tree_id().to_owned(),
+                parents: commit
+                    .iter_parents()
+                    .map(|parent| parent.map(|pos| graph.id_at(pos).to_owned()))
+                    .collect::<Result<_, _>>()?,
+                committer_timestamp: commit.committer_timestamp() as git_date::SecondsSinceUnixEpoch,
+                bloom_filter: keep_bloom_filters
+                    .then(|| commit.bloom_filter().map(|f| f.to_owned()))
+                    .flatten(),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn write_atomically(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let tmp_path = path.with_extension("lock");
+    std::fs::write(&tmp_path, data)
+        .and_then(|_| std::fs::rename(&tmp_path, path))
+        .map_err(|err| Error::Io {
+            err,
+            path: path.to_owned(),
+        })
+}
+
+fn remove_if_present(path: &Path) -> Result<(), Error> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+            err,
+            path: path.to_owned(),
+        }),
+        _ => Ok(()),
+    }
+}
@@ -1,4 +1,4 @@
-//! Read, verify, and traverse git commit graphs.
+//! Read, write, verify, and traverse git commit graphs.
 //!
 //! A [commit graph][Graph] is an index of commits in the git commit history.
 //! The [Graph] stores commit data in a way that accelerates lookups considerably compared to
@@ -7,6 +7,7 @@
 //! As generating the full commit graph from scratch can take some time, git may write new commits
 //! to separate [files][file::File] instead of overwriting the original file.
 //! Eventually, git will merge these files together as the number of files grows.
+//! [`Graph::write_to_info_dir()`] can do the same with configurable [merge strategies][graph::write::Split].
 //! ## Feature Flags
 #![cfg_attr(
     feature = "document-features",
@@ -15,6 +16,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+pub mod bloom;
 pub mod file;
 pub mod graph;
 
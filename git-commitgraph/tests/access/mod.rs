@@ -69,3 +69,59 @@ fn two_parents() -> crate::Result {
 
     Ok(())
 }
+
+#[test]
+fn bloom_filters() -> crate::Result {
+    let repo_dir = make_readonly_repo("bloom.sh");
+    let refs = inspect_refs(
+        &repo_dir,
+        &[
+            "root",
+            "modify-a",
+            "modify-c",
+            "delete-b",
+            "modify-non-ascii",
+            "empty",
+            "too-many-changes",
+        ],
+    );
+    let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    check_common(&cg, &refs);
+
+    let changed = |name: &str| {
+        let commit = cg.commit_at(refs[name].pos());
+        assert!(commit.bloom_filter().is_some(), "git computed a filter for each commit");
+        [
+            "a",
+            "dir",
+            "dir/b",
+            "dir/subdir",
+            "dir/subdir/c",
+            "dir/ä",
+            "many/1",
+            "missing",
+        ]
+        .into_iter()
+        .filter(|path| commit.maybe_changed_path((*path).into()))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        changed("root"),
+        ["a", "dir", "dir/b", "dir/subdir", "dir/subdir/c", "dir/ä"]
+    );
+    assert_eq!(changed("modify-a"), ["a"]);
+    assert_eq!(changed("modify-c"), ["dir", "dir/subdir", "dir/subdir/c"]);
+    assert_eq!(
+        changed("delete-b"),
+        ["dir", "dir/b", "dir/ä"],
+        "false positives are possible, particularly with small filters"
+    );
+    assert_eq!(changed("modify-non-ascii"), ["dir", "dir/ä"]);
+    assert_eq!(changed("empty"), Vec::<&str>::new());
+    assert_eq!(
+        changed("too-many-changes").len(),
+        8,
+        "filters of commits with too many changes contain everything"
+    );
+    Ok(())
+}
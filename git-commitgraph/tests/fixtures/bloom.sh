@@ -0,0 +1,43 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/subdir
+echo a > a
+echo b > dir/b
+echo c > dir/subdir/c
+echo d > "dir/ä"
+git add .
+git commit -q -m root
+git tag root
+
+echo a2 > a
+git commit -q -am modify-a
+git tag modify-a
+
+echo c2 > dir/subdir/c
+git commit -q -am modify-c
+git tag modify-c
+
+git rm -q dir/b
+git commit -q -m delete-b
+git tag delete-b
+
+echo d2 > "dir/ä"
+git commit -q -am modify-non-ascii
+git tag modify-non-ascii
+
+git commit -q --allow-empty -m empty
+git tag empty
+
+mkdir many
+for i in $(seq 600); do
+  echo $i > many/$i
+done
+git add many
+git commit -q -m too-many-changes
+git tag too-many-changes
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
@@ -0,0 +1,265 @@
+use std::{path::Path, process::Command};
+
+use git_commitgraph::{
+    file::write::Commit,
+    graph::write::{Error, Options, Split},
+    Graph,
+};
+use git_odb::FindExt;
+use git_testtools::scripted_fixture_writable;
+
+use crate::{check_common, inspect_refs};
+
+const OCTOPUS_REFS: &[&str] = &[
+    "root",
+    "parent1",
+    "parent2",
+    "parent3",
+    "parent4",
+    "three_parents",
+    "four_parents",
+];
+
+fn commits_of(graph: &Graph) -> Vec<Commit> {
+    graph
+        .iter_commits()
+        .map(|commit| Commit {
+            id: commit.id().to_owned(),
+            tree: commit.root_tree_id().to_owned(),
+            parents: commit
+                .iter_parents()
+                .map(|pos| graph.id_at(pos.expect("valid parent")).to_owned())
+                .collect(),
+            committer_timestamp: commit.committer_timestamp() as git_date::SecondsSinceUnixEpoch,
+            bloom_filter: commit.bloom_filter().map(|f| f.to_owned()),
+        })
+        .collect()
+}
+
+/// Return all commits of the graph written by `git`, and remove it.
+fn take_commits_written_by_git(info_dir: &Path) -> Result<Vec<Commit>, Box<dyn std::error::Error>> {
+    let commits = commits_of(&Graph::from_info_dir(info_dir)?);
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    Ok(commits)
+}
+
+fn options(split: Option<Split>) -> Options {
+    Options {
+        object_hash: git_hash::Kind::Sha1,
+        split,
+        bloom_filter_settings: None,
+    }
+}
+
+fn assert_git_verifies(repo_dir: &Path) {
+    let status = Command::new("git")
+        .args(["commit-graph", "verify", "--no-progress"])
+        .current_dir(repo_dir)
+        .env_remove("GIT_DIR")
+        .status()
+        .expect("git can be executed");
+    assert!(status.success(), "git considers the written commit graph valid");
+}
+
+#[test]
+fn single_file_with_octopus_merges() -> crate::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let commits = take_commits_written_by_git(&info_dir)?;
+
+    let outcome = Graph::write_to_info_dir(&info_dir, commits, options(None))?;
+    assert_eq!(outcome.num_commits_written, 7);
+    assert_eq!(outcome.checksums.len(), 1);
+    assert_eq!(outcome.num_files_merged, 0);
+
+    let refs = inspect_refs(repo.path(), OCTOPUS_REFS);
+    let cg = Graph::from_info_dir(&info_dir)?;
+    check_common(&cg, &refs);
+    for (name, generation) in [("root", 1), ("parent1", 2), ("parent4", 2), ("four_parents", 3)] {
+        assert_eq!(cg.commit_at(refs[name].pos()).generation(), generation);
+    }
+    assert_git_verifies(repo.path());
+    Ok(())
+}
+
+#[test]
+fn single_file_from_traversal() -> crate::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    let refs = inspect_refs(repo.path(), OCTOPUS_REFS);
+
+    let odb = git_odb::at(repo.path().join(".git").join("objects"))?;
+    let mut buf = Vec::new();
+    let mut commits = Vec::new();
+    for id in git_traverse::commit::Ancestors::new(
+        ["three_parents", "four_parents"]
+            .iter()
+            .map(|name| refs[*name].id().to_owned()),
+        git_traverse::commit::ancestors::State::default(),
+        |id, buf| odb.find_commit_iter(id, buf),
+    ) {
+        let id = id?;
+        commits.push(Commit::from_commit_iter(id, odb.find_commit_iter(id, &mut buf)?)?);
+    }
+
+    let outcome = Graph::write_to_info_dir(&info_dir, commits, options(None))?;
+    assert_eq!(outcome.num_commits_written, 7);
+
+    let cg = Graph::from_info_dir(&info_dir)?;
+    check_common(&cg, &refs);
+    for (name, generation) in [("root", 1), ("parent1", 2), ("parent4", 2), ("four_parents", 3)] {
+        assert_eq!(cg.commit_at(refs[name].pos()).generation(), generation);
+    }
+    assert_git_verifies(repo.path());
+    Ok(())
+}
+
+#[test]
+fn split_chains_are_extended_and_merged() -> crate::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let commits = take_commits_written_by_git(&info_dir)?;
+    let layer = |num_parents: std::ops::RangeInclusive<usize>| {
+        commits
+            .iter()
+            .filter(|c| num_parents.contains(&c.parents.len()))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let mut num_layers = Vec::new();
+    for commits in [layer(0..=0), layer(1..=1), layer(2..=4)] {
+        let outcome = Graph::write_to_info_dir(&info_dir, commits, options(Some(Split::NoMerge)))?;
+        num_layers.push(outcome.checksums.len());
+    }
+    assert_eq!(num_layers, [1, 2, 3]);
+    assert_git_verifies(repo.path());
+    let cg = Graph::from_info_dir(&info_dir)?;
+    assert_eq!(cg.num_commits(), 7);
+    assert_eq!(commits_of(&cg).len(), commits.len());
+    for commit in &commits {
+        let actual = cg.commit_by_id(commit.id).expect("all commits are present");
+        assert_eq!(actual.root_tree_id(), commit.tree);
+        assert_eq!(
+            actual
+                .iter_parents()
+                .map(|pos| cg.id_at(pos.expect("valid")).to_owned())
+                .collect::<Vec<_>>(),
+            commit.parents
+        );
+    }
+    drop(cg);
+
+    let outcome = Graph::write_to_info_dir(&info_dir, commits.clone(), options(Some(Split::default())))?;
+    assert_eq!(outcome.checksums.len(), 3, "nothing to write means nothing changes");
+    assert_eq!(outcome.num_commits_written, 0);
+
+    let outcome = Graph::write_to_info_dir(&info_dir, None, options(Some(Split::Replace)))?;
+    assert_eq!(outcome.checksums.len(), 1);
+    assert_eq!(outcome.num_files_merged, 3);
+    assert_eq!(outcome.num_commits_written, 7);
+    assert_eq!(
+        std::fs::read_dir(info_dir.join("commit-graphs"))?.count(),
+        2,
+        "merged files are removed, leaving the chain and the new file"
+    );
+    assert_git_verifies(repo.path());
+    Ok(())
+}
+
+#[test]
+fn split_chains_merge_layers_by_size() -> crate::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let commits = take_commits_written_by_git(&info_dir)?;
+    let (roots, rest): (Vec<_>, Vec<_>) = commits.into_iter().partition(|c| c.parents.is_empty());
+    let (parents, merges): (Vec<_>, Vec<_>) = rest.into_iter().partition(|c| c.parents.len() == 1);
+
+    let mut num_layers = Vec::new();
+    for commits in [roots, parents, merges] {
+        let outcome = Graph::write_to_info_dir(&info_dir, commits, options(Some(Split::default())))?;
+        num_layers.push(outcome.checksums.len());
+    }
+    assert_eq!(
+        num_layers,
+        [1, 1, 2],
+        "4 commits are merged with the layer of 1 commit, but 2 commits are put on top of 5"
+    );
+    assert_git_verifies(repo.path());
+    Ok(())
+}
+
+#[test]
+fn parents_must_be_known() -> crate::Result {
+    let repo = scripted_fixture_writable("octopus_merges.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let merges: Vec<_> = take_commits_written_by_git(&info_dir)?
+        .into_iter()
+        .filter(|c| c.parents.len() > 1)
+        .collect();
+    assert!(matches!(
+        Graph::write_to_info_dir(&info_dir, merges, options(None)),
+        Err(Error::WriteFile(
+            git_commitgraph::file::write::Error::MissingParent { .. }
+        ))
+    ));
+    Ok(())
+}
+
+#[test]
+fn bloom_filters_are_computed_like_git_and_written() -> crate::Result {
+    let repo = scripted_fixture_writable("bloom.sh")?;
+    let info_dir = repo.path().join(".git").join("objects").join("info");
+    let settings = git_commitgraph::bloom::Settings::default();
+    let commits_by_git = commits_of(&Graph::from_info_dir(&info_dir)?);
+    assert_eq!(
+        git_commitgraph::file::File::at(info_dir.join("commit-graph"))?.bloom_filter_settings(),
+        Some(settings)
+    );
+
+    let mut commits = Vec::new();
+    for commit in &commits_by_git {
+        let output = Command::new("git")
+            .args(["diff-tree", "-r", "-z", "--root", "--no-commit-id", "--name-only"])
+            .arg(commit.id.to_string())
+            .current_dir(repo.path())
+            .env_remove("GIT_DIR")
+            .output()?;
+        assert!(output.status.success());
+        let paths = output.stdout.split(|b| *b == 0).filter(|p| !p.is_empty());
+        let filter = git_commitgraph::bloom::Filter::from_changed_paths(paths.map(Into::into), &settings);
+        assert_eq!(
+            Some(&filter),
+            commit.bloom_filter.as_ref(),
+            "the filter of {} is the same as the one computed by git",
+            commit.id
+        );
+        commits.push(Commit {
+            bloom_filter: Some(filter),
+            ..commit.clone()
+        });
+    }
+
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    Graph::write_to_info_dir(
+        &info_dir,
+        commits,
+        Options {
+            bloom_filter_settings: Some(settings),
+            ..options(None)
+        },
+    )?;
+    assert_git_verifies(repo.path());
+    assert_eq!(commits_of(&Graph::from_info_dir(&info_dir)?), commits_by_git);
+
+    let outcome = Graph::write_to_info_dir(&info_dir, None, options(Some(Split::default())))?;
+    assert_eq!(outcome.num_commits_written, 7);
+    assert!(
+        commits_of(&Graph::from_info_dir(&info_dir)?)
+            .iter()
+            .all(|c| c.bloom_filter.is_none()),
+        "filters are dropped if they aren't configured"
+    );
+    Ok(())
+}
@@ -17,12 +17,17 @@ pub mod time;
 pub mod parse;
 pub use parse::function::parse;
 
+/// The amount of seconds since unix epoch, which is negative for dates before 1970.
+///
+/// Note that it's 64 bits wide to support dates well past the year 2038.
+pub type SecondsSinceUnixEpoch = i64;
+
 /// A timestamp with timezone.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
-    /// time in seconds since epoch.
-    pub seconds_since_unix_epoch: u32,
+    /// time in seconds since epoch, which may be negative for dates before 1970.
+    pub seconds_since_unix_epoch: SecondsSinceUnixEpoch,
     /// time offset in seconds, may be negative to match the `sign` field.
     pub offset_in_seconds: i32,
     /// the sign of `offset`, used to encode `-0000` which would otherwise loose sign information.
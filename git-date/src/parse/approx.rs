@@ -0,0 +1,269 @@
+//! A port of git's `approxidate`, which understands relative dates like `2 weeks ago` or `last friday noon`,
+//! along with pretty much anything that looks like a date.
+use crate::{
+    parse::{
+        basic::{match_multi_number, match_string, parse_number, MONTH_NAMES, WEEKDAY_NAMES},
+        tm::Tm,
+    },
+    SecondsSinceUnixEpoch,
+};
+
+const NUMBER_NAMES: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// Units of time along with their length in seconds.
+const TYPE_LENGTHS: [(&str, i64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 60 * 60),
+    ("days", 24 * 60 * 60),
+    ("weeks", 7 * 24 * 60 * 60),
+];
+
+type SpecialFn = fn(&mut Tm, &Tm, &mut i64);
+
+const SPECIALS: [(&str, SpecialFn); 8] = [
+    ("yesterday", date_yesterday),
+    ("noon", date_noon),
+    ("midnight", date_midnight),
+    ("tea", date_tea),
+    ("PM", date_pm),
+    ("AM", date_am),
+    ("never", date_never),
+    ("now", date_now),
+];
+
+/// Parse `input` relative to `now` like git's `approxidate_str()`, returning the seconds since epoch
+/// or `None` if nothing in `input` could be understood.
+pub(crate) fn parse(input: &[u8], now: SecondsSinceUnixEpoch) -> Option<SecondsSinceUnixEpoch> {
+    let mut number = 0;
+    let mut touched = false;
+    let now_tm = Tm::localtime(now);
+    let mut tm = Tm {
+        year: -1,
+        mon: -1,
+        mday: -1,
+        ..now_tm
+    };
+
+    let mut pos = 0;
+    while let Some(&c) = input.get(pos) {
+        if c.is_ascii_digit() {
+            pending_number(&mut tm, &mut number);
+            pos += approxidate_digit(&input[pos..], &mut tm, &mut number, now);
+            touched = true;
+        } else if c.is_ascii_alphabetic() {
+            pos += approxidate_alpha(&input[pos..], &mut tm, &now_tm, &mut number, &mut touched);
+        } else {
+            pos += 1;
+        }
+    }
+    pending_number(&mut tm, &mut number);
+    touched.then(|| update_tm(&mut tm, &now_tm, 0))
+}
+
+/// Fill in missing date fields from `now`, and move `tm` back by `seconds`.
+fn update_tm(tm: &mut Tm, now: &Tm, seconds: i64) -> SecondsSinceUnixEpoch {
+    if tm.mday < 0 {
+        tm.mday = now.mday;
+    }
+    if tm.mon < 0 {
+        tm.mon = now.mon;
+    }
+    if tm.year < 0 {
+        tm.year = now.year;
+        if tm.mon > now.mon {
+            tm.year -= 1;
+        }
+    }
+
+    let n = tm.mktime().saturating_sub(seconds);
+    *tm = Tm::localtime(n);
+    n
+}
+
+/// Do we have a pending number at the end, or when we see a new one?
+/// Let's assume it's a month day, as in "Dec 6, 1992".
+fn pending_number(tm: &mut Tm, num: &mut i64) {
+    let number = *num;
+    if number == 0 {
+        return;
+    }
+    *num = 0;
+    if tm.mday < 0 && number < 32 {
+        tm.mday = number;
+    } else if tm.mon < 0 && number < 13 {
+        tm.mon = number - 1;
+    } else if tm.year < 0 {
+        if (1000..10000).contains(&number) {
+            tm.year = number - 1900;
+        } else if number > 69 && number < 100 {
+            tm.year = number;
+        } else if number < 38 {
+            tm.year = 100 + number;
+        }
+        // We screw up for number = 00 ?
+    }
+}
+
+fn date_now(tm: &mut Tm, now: &Tm, num: &mut i64) {
+    *num = 0;
+    update_tm(tm, now, 0);
+}
+
+fn date_yesterday(tm: &mut Tm, now: &Tm, num: &mut i64) {
+    *num = 0;
+    update_tm(tm, now, 24 * 60 * 60);
+}
+
+fn date_time(tm: &mut Tm, now: &Tm, hour: i64) {
+    if tm.hour < hour {
+        update_tm(tm, now, 24 * 60 * 60);
+    }
+    tm.hour = hour;
+    tm.min = 0;
+    tm.sec = 0;
+}
+
+fn date_midnight(tm: &mut Tm, now: &Tm, num: &mut i64) {
+    pending_number(tm, num);
+    date_time(tm, now, 0);
+}
+
+fn date_noon(tm: &mut Tm, now: &Tm, num: &mut i64) {
+    pending_number(tm, num);
+    date_time(tm, now, 12);
+}
+
+fn date_tea(tm: &mut Tm, now: &Tm, num: &mut i64) {
+    pending_number(tm, num);
+    date_time(tm, now, 17);
+}
+
+fn date_pm(tm: &mut Tm, _now: &Tm, num: &mut i64) {
+    let n = std::mem::take(num);
+    let mut hour = tm.hour;
+    if n != 0 {
+        hour = n;
+        tm.min = 0;
+        tm.sec = 0;
+    }
+    tm.hour = (hour % 12) + 12;
+}
+
+fn date_am(tm: &mut Tm, _now: &Tm, num: &mut i64) {
+    let n = std::mem::take(num);
+    let mut hour = tm.hour;
+    if n != 0 {
+        hour = n;
+        tm.min = 0;
+        tm.sec = 0;
+    }
+    tm.hour = hour % 12;
+}
+
+fn date_never(tm: &mut Tm, _now: &Tm, num: &mut i64) {
+    *tm = Tm::localtime(0);
+    *num = 0;
+}
+
+/// Handle the alphabetic word at the beginning of `date` and return its length.
+fn approxidate_alpha(date: &[u8], tm: &mut Tm, now: &Tm, num: &mut i64, touched: &mut bool) -> usize {
+    let end = 1 + date.iter().skip(1).take_while(|b| b.is_ascii_alphabetic()).count();
+
+    for (i, name) in MONTH_NAMES.iter().enumerate() {
+        if match_string(date, name) >= 3 {
+            tm.mon = i as i64;
+            *touched = true;
+            return end;
+        }
+    }
+
+    for (name, special) in SPECIALS {
+        if match_string(date, name) == name.len() {
+            special(tm, now, num);
+            *touched = true;
+            return end;
+        }
+    }
+
+    if *num == 0 {
+        for (i, name) in NUMBER_NAMES.iter().enumerate().skip(1) {
+            if match_string(date, name) == name.len() {
+                *num = i as i64;
+                *touched = true;
+                return end;
+            }
+        }
+        if match_string(date, "last") == 4 {
+            *num = 1;
+            *touched = true;
+        }
+        return end;
+    }
+
+    for (name, length) in TYPE_LENGTHS {
+        if match_string(date, name) >= name.len() - 1 {
+            update_tm(tm, now, length.saturating_mul(*num));
+            *num = 0;
+            *touched = true;
+            return end;
+        }
+    }
+
+    for (i, name) in WEEKDAY_NAMES.iter().enumerate() {
+        if match_string(date, name) >= 3 {
+            let mut n = *num - 1;
+            *num = 0;
+
+            let mut diff = tm.wday - i as i64;
+            if diff <= 0 {
+                n += 1;
+            }
+            diff = diff.saturating_add(n.saturating_mul(7));
+
+            update_tm(tm, now, diff.saturating_mul(24 * 60 * 60));
+            *touched = true;
+            return end;
+        }
+    }
+
+    if match_string(date, "months") >= 5 {
+        update_tm(tm, now, 0); // fill in date fields if needed
+        let months = tm.mon.saturating_sub(std::mem::take(num));
+        tm.year = tm.year.saturating_add(months.div_euclid(12));
+        tm.mon = months.rem_euclid(12);
+        *touched = true;
+        return end;
+    }
+
+    if match_string(date, "years") >= 4 {
+        update_tm(tm, now, 0); // fill in date fields if needed
+        tm.year = tm.year.saturating_sub(std::mem::take(num));
+        *touched = true;
+        return end;
+    }
+
+    end
+}
+
+/// Handle the number at the beginning of `date` and return the amount of bytes consumed.
+fn approxidate_digit(date: &[u8], tm: &mut Tm, num: &mut i64, now: SecondsSinceUnixEpoch) -> usize {
+    let (number, end) = parse_number(date);
+
+    if let Some(&c @ (b':' | b'.' | b'/' | b'-')) = date.get(end) {
+        if date.get(end + 1).map_or(false, u8::is_ascii_digit) {
+            let matched = match_multi_number(number, c, date, end, tm, now);
+            if matched != 0 {
+                return matched;
+            }
+        }
+    }
+
+    // Accept zero-padding only for small numbers ("Dec 02", never "Dec 0002")
+    if date[0] != b'0' || end <= 2 {
+        *num = number;
+    }
+    end
+}
@@ -0,0 +1,463 @@
+//! A port of git's strict date parsing as implemented by `parse_date_basic()`, along with the utilities it shares
+//! with approxidate.
+use crate::{parse::tm::Tm, SecondsSinceUnixEpoch};
+
+pub(crate) const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+pub(crate) const WEEKDAY_NAMES: [&str; 7] = [
+    "Sundays",
+    "Mondays",
+    "Tuesdays",
+    "Wednesdays",
+    "Thursdays",
+    "Fridays",
+    "Saturdays",
+];
+
+/// Timezone names along with their offset in hours and whether or not it's daylight saving time, which is
+/// added to the offset.
+const TIMEZONE_NAMES: &[(&str, i64, i64)] = &[
+    ("IDLW", -12, 0), // International Date Line West
+    ("NT", -11, 0),   // Nome
+    ("CAT", -10, 0),  // Central Alaska
+    ("HST", -10, 0),  // Hawaii Standard
+    ("HDT", -10, 1),  // Hawaii Daylight
+    ("YST", -9, 0),   // Yukon Standard
+    ("YDT", -9, 1),   // Yukon Daylight
+    ("PST", -8, 0),   // Pacific Standard
+    ("PDT", -8, 1),   // Pacific Daylight
+    ("MST", -7, 0),   // Mountain Standard
+    ("MDT", -7, 1),   // Mountain Daylight
+    ("CST", -6, 0),   // Central Standard
+    ("CDT", -6, 1),   // Central Daylight
+    ("EST", -5, 0),   // Eastern Standard
+    ("EDT", -5, 1),   // Eastern Daylight
+    ("AST", -3, 0),   // Atlantic Standard
+    ("ADT", -3, 1),   // Atlantic Daylight
+    ("WAT", -1, 0),   // West Africa
+    ("GMT", 0, 0),    // Greenwich Mean
+    ("UTC", 0, 0),    // Universal (Coordinated)
+    ("Z", 0, 0),      // Zulu, alias for UTC
+    ("WET", 0, 0),    // Western European
+    ("BST", 0, 1),    // British Summer
+    ("CET", 1, 0),    // Central European
+    ("MET", 1, 0),    // Middle European
+    ("MEWT", 1, 0),   // Middle European Winter
+    ("MEST", 1, 1),   // Middle European Summer
+    ("CEST", 1, 1),   // Central European Summer
+    ("MESZ", 1, 1),   // Middle European Summer
+    ("FWT", 1, 0),    // French Winter
+    ("FST", 1, 1),    // French Summer
+    ("EET", 2, 0),    // Eastern Europe, USSR Zone 1
+    ("EEST", 2, 1),   // Eastern European Daylight
+    ("WAST", 7, 0),   // West Australian Standard
+    ("WADT", 7, 1),   // West Australian Daylight
+    ("CCT", 8, 0),    // China Coast, USSR Zone 7
+    ("JST", 9, 0),    // Japan Standard, USSR Zone 8
+    ("EAST", 10, 0),  // Eastern Australian Standard
+    ("EADT", 10, 1),  // Eastern Australian Daylight
+    ("GST", 10, 0),   // Guam Standard, USSR Zone 9
+    ("NZT", 12, 0),   // New Zealand
+    ("NZST", 12, 0),  // New Zealand Standard
+    ("NZDT", 12, 1),  // New Zealand Daylight
+    ("IDLE", 12, 0),  // International Date Line East
+];
+
+/// Parse `input` strictly like git's `parse_date_basic()`, returning the seconds since epoch along with
+/// the timezone offset in minutes, or `None` if the date wasn't understood.
+///
+/// `now` is used to reject ambiguous dates that would lie in the future.
+pub(crate) fn parse(input: &[u8], now: SecondsSinceUnixEpoch) -> Option<(SecondsSinceUnixEpoch, i64)> {
+    if let Some(rest) = input.strip_prefix(b"@") {
+        if let Some(res) = match_object_header_date(rest) {
+            return Some(res);
+        }
+    }
+
+    let mut tm = Tm::unset();
+    let mut offset = None;
+    let mut tm_gmt = false;
+    let mut date = input;
+    while let Some(&c) = date.first() {
+        if c == b'\n' {
+            break;
+        }
+        let matched = if c.is_ascii_alphabetic() {
+            match_alpha(date, &mut tm, &mut offset)
+        } else if c.is_ascii_digit() {
+            match_digit(date, &mut tm, &mut offset, &mut tm_gmt, now)
+        } else if (c == b'-' || c == b'+') && date.get(1).map_or(false, u8::is_ascii_digit) {
+            match_tz(date, &mut offset)
+        } else {
+            0
+        };
+        date = &date[matched.max(1).min(date.len())..];
+    }
+
+    let mut timestamp = tm.to_time_t()?;
+    let offset = offset.unwrap_or_else(|| crate::parse::tm::local_offset_in_seconds(timestamp) / 60);
+    if !tm_gmt {
+        timestamp = timestamp.checked_sub(offset * 60)?;
+    }
+    Some((timestamp, offset))
+}
+
+/// Compare `date` to `name` case-insensitively, and return the amount of matching bytes if `date` stops matching
+/// at a non-alphanumeric character, or `0` otherwise.
+pub(crate) fn match_string(date: &[u8], name: &str) -> usize {
+    let name = name.as_bytes();
+    for (i, &c) in date.iter().enumerate() {
+        let expected = name.get(i).copied().unwrap_or(0);
+        if c.eq_ignore_ascii_case(&expected) {
+            continue;
+        }
+        if !c.is_ascii_alphanumeric() {
+            return i;
+        }
+        return 0;
+    }
+    date.len()
+}
+
+/// Return the amount of consecutive alphabetic characters, assuming the first one is one.
+fn skip_alpha(date: &[u8]) -> usize {
+    1 + date.iter().skip(1).take_while(|b| b.is_ascii_alphabetic()).count()
+}
+
+/// Parse the number at the beginning of `date`, saturating on overflow, and return it along with
+/// the amount of digits that were consumed.
+pub(crate) fn parse_number(date: &[u8]) -> (i64, usize) {
+    let digits = date.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = date[..digits].iter().fold(0i64, |acc, b| {
+        acc.saturating_mul(10).saturating_add(i64::from(b - b'0'))
+    });
+    (number, digits)
+}
+
+fn is_digit_at(date: &[u8], pos: usize) -> bool {
+    date.get(pos).map_or(false, u8::is_ascii_digit)
+}
+
+/// Parse month, weekday, or timezone name.
+fn match_alpha(date: &[u8], tm: &mut Tm, offset: &mut Option<i64>) -> usize {
+    for (i, name) in MONTH_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.mon = i as i64;
+            return matched;
+        }
+    }
+
+    for (i, name) in WEEKDAY_NAMES.iter().enumerate() {
+        let matched = match_string(date, name);
+        if matched >= 3 {
+            tm.wday = i as i64;
+            return matched;
+        }
+    }
+
+    for (name, hours, dst) in TIMEZONE_NAMES {
+        let matched = match_string(date, name);
+        if matched >= 3 || matched == name.len() {
+            // Only use the timezone name offset if we don't have anything better.
+            // Note that adding daylight saving time like this is bogus, but that's what git does.
+            offset.get_or_insert(60 * (hours + dst));
+            return matched;
+        }
+    }
+
+    if match_string(date, "PM") == 2 {
+        tm.hour = (tm.hour % 12) + 12;
+        return 2;
+    }
+
+    if match_string(date, "AM") == 2 {
+        tm.hour %= 12;
+        return 2;
+    }
+
+    // ISO-8601 allows yyyymmDD'T'HHMMSS, with less precision
+    if date[0] == b'T' && is_digit_at(date, 1) && tm.hour == -1 {
+        tm.min = 0;
+        tm.sec = 0;
+        return 1;
+    }
+
+    skip_alpha(date)
+}
+
+/// Set the date in `tm` to the given values if they are valid, and return `true` in that case.
+///
+/// If `now_tm` is set, a missing `year` is taken from it and dates more than ten days past `now` are refused.
+fn set_date(year: i64, month: i64, day: i64, now_tm: Option<&Tm>, now: SecondsSinceUnixEpoch, tm: &mut Tm) -> bool {
+    if !(month > 0 && month < 13 && day > 0 && day < 32) {
+        return false;
+    }
+    let mut check = *tm;
+    let r = if now_tm.is_some() { &mut check } else { &mut *tm };
+
+    r.mon = month - 1;
+    r.mday = day;
+    if year == -1 {
+        match now_tm {
+            Some(now_tm) => r.year = now_tm.year,
+            None => return false,
+        }
+    } else if year >= 1000 {
+        r.year = year - 1900;
+    } else if year > 70 && year < 100 {
+        r.year = year;
+    } else if year < 38 {
+        r.year = year + 100;
+    } else {
+        return false;
+    }
+    if now_tm.is_none() {
+        return true;
+    }
+
+    // Be it commit time or author time, it does not make sense to specify a timestamp way into the future.
+    // Make sure it is not later than ten days from now.
+    if let Some(specified) = check.to_time_t() {
+        if now.saturating_add(10 * 24 * 3600) < specified {
+            return false;
+        }
+    }
+    tm.mon = check.mon;
+    tm.mday = check.mday;
+    if year != -1 {
+        tm.year = check.year;
+    }
+    true
+}
+
+/// Set the time in `tm` if the values are valid, and return `true` in that case.
+fn set_time(hour: i64, minute: i64, second: i64, tm: &mut Tm) -> bool {
+    // We accept the 61st second because of leap seconds.
+    if (0..=24).contains(&hour) && (0..60).contains(&minute) && (0..=60).contains(&second) {
+        tm.hour = hour;
+        tm.min = minute;
+        tm.sec = second;
+        return true;
+    }
+    false
+}
+
+/// Match `num[c]num2[c]num3` where `num` was already parsed from `date` and `end` is the position of the first `c`,
+/// and return the amount of bytes consumed from `date`, or `0` if it couldn't be matched.
+pub(crate) fn match_multi_number(
+    num: i64,
+    c: u8,
+    date: &[u8],
+    mut end: usize,
+    tm: &mut Tm,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num2, digits) = parse_number(&date[end + 1..]);
+    end += 1 + digits;
+    let mut num3 = -1;
+    if date.get(end) == Some(&c) && is_digit_at(date, end + 1) {
+        let (num, digits) = parse_number(&date[end + 1..]);
+        num3 = num;
+        end += 1 + digits;
+    }
+
+    match c {
+        b':' => {
+            if num3 < 0 {
+                num3 = 0;
+            }
+            if !set_time(num, num2, num3, tm) {
+                return 0;
+            }
+            // If %H:%M:%S was just parsed followed by .<num4>, consider (and discard) it as fractional second
+            // if %Y%m%d was parsed before.
+            if date.get(end) == Some(&b'.') && is_digit_at(date, end + 1) && tm.is_date_known() {
+                end += 1 + parse_number(&date[end + 1..]).1;
+            }
+        }
+        b'-' | b'/' | b'.' => {
+            let now_tm = Tm::gmtime(now);
+            let refuse_future = Some(&now_tm);
+            let parsed = (num > 70
+                && (set_date(num, num2, num3, None, now, tm) || set_date(num, num3, num2, None, now, tm)))
+                // Our eastern European friends say dd.mm.yy[yy] is the norm there, so giving precedence
+                // to mm/dd/yy[yy] form only when separator is not '.'
+                || (c != b'.' && set_date(num3, num, num2, refuse_future, now, tm))
+                // European dd.mm.yy[yy] or funny US dd/mm/yy[yy]
+                || set_date(num3, num2, num, refuse_future, now, tm)
+                // Funny European mm.dd.yy
+                || (c == b'.' && set_date(num3, num, num2, refuse_future, now, tm));
+            if !parsed {
+                return 0;
+            }
+        }
+        _ => unreachable!("BUG: only called with known separators"),
+    }
+    end
+}
+
+/// We've seen a digit. Time? Year? Date?
+fn match_digit(
+    date: &[u8],
+    tm: &mut Tm,
+    offset: &mut Option<i64>,
+    tm_gmt: &mut bool,
+    now: SecondsSinceUnixEpoch,
+) -> usize {
+    let (num, mut end) = parse_number(date);
+
+    // Seconds since 1970? We trigger on that for any numbers with more than 8 digits.
+    // This is because we don't want to rule out numbers like 20070606 as a YYYYMMDD date.
+    if num >= 100_000_000 && tm.is_unset() {
+        *tm = Tm::gmtime(num);
+        *tm_gmt = true;
+        return end;
+    }
+
+    // Check for special formats: num[-.:/]num[same]num
+    if let Some(&c @ (b':' | b'.' | b'/' | b'-')) = date.get(end) {
+        if is_digit_at(date, end + 1) {
+            let matched = match_multi_number(num, c, date, end, tm, now);
+            if matched != 0 {
+                return matched;
+            }
+        }
+    }
+
+    // None of the special formats? Try to guess what the number meant.
+    // We use the number of digits to make a more educated guess.
+    let n = end;
+
+    // 8 digits, compact style of ISO-8601's date: YYYYmmDD
+    // 6 digits, compact style of ISO-8601's time: HHMMSS
+    if n == 8 || n == 6 {
+        let num1 = num / 10000;
+        let num2 = (num % 10000) / 100;
+        let num3 = num % 100;
+        if n == 8 {
+            set_date(num1, num2, num3, None, 0, tm);
+        } else if set_time(num1, num2, num3, tm) && date.get(end) == Some(&b'.') && is_digit_at(date, end + 1) {
+            end += 1 + parse_number(&date[end + 1..]).1;
+        }
+        return end;
+    }
+
+    // Four-digit year or a timezone?
+    if n == 4 {
+        if num <= 1400 && offset.is_none() {
+            let minutes = num % 100;
+            let hours = num / 100;
+            *offset = Some(hours * 60 + minutes);
+        } else if num > 1400 {
+            tm.year = num - 1900;
+        }
+        return n;
+    }
+
+    // Ignore lots of numerals. We took care of 4-digit years above. Days or months must be one or two digits.
+    if n > 2 {
+        return n;
+    }
+
+    // NOTE! We will give precedence to day-of-month over month or year numbers in the 1-12 range.
+    // So 05 is always "mday 5", unless we already have a mday.
+    //
+    // IOW, 01 Apr 05 parses as "April 1st, 2005".
+    if num > 0 && num < 32 && tm.mday < 0 {
+        tm.mday = num;
+        return n;
+    }
+
+    // Two-digit year?
+    if n == 2 && tm.year < 0 {
+        if num < 10 && tm.mday >= 0 {
+            tm.year = num + 100;
+            return n;
+        }
+        if num >= 70 {
+            tm.year = num;
+            return n;
+        }
+    }
+
+    if num > 0 && num < 13 && tm.mon < 0 {
+        tm.mon = num - 1;
+    }
+    n
+}
+
+/// Match a timezone offset like `+0200`, `-02:00` or `+02` and set `offset` in minutes if it was valid.
+fn match_tz(date: &[u8], offset: &mut Option<i64>) -> usize {
+    let (mut hour, n) = parse_number(&date[1..]);
+    let mut end = 1 + n;
+    let mut min = 0;
+
+    if n == 4 {
+        // hhmm
+        min = hour % 100;
+        hour /= 100;
+    } else if n != 2 {
+        min = 99; // random crap
+    } else if date.get(end) == Some(&b':') {
+        // hh:mm?
+        let (minutes, digits) = parse_number(&date[end + 1..]);
+        min = minutes;
+        end += 1 + digits;
+        if end - 1 != 5 {
+            min = 99; // random crap
+        }
+    } // otherwise we parsed "hh"
+
+    // Don't accept any random crap. Even though some places have offset larger than 12 hours
+    // (e.g. Pacific/Kiritimati is at UTC+14), there is something wrong if hour part is much larger than that.
+    if min < 60 && hour < 24 {
+        let minutes = hour * 60 + min;
+        *offset = Some(if date[0] == b'-' { -minutes } else { minutes });
+    }
+    end
+}
+
+/// Parse a string like `0 +0000` as ancient timestamp near epoch, but only when it appears not as part of any other string.
+///
+/// Unlike git, negative timestamps like `-3600 +0100` are supported as well.
+fn match_object_header_date(date: &[u8]) -> Option<(SecondsSinceUnixEpoch, i64)> {
+    let (is_negative, date) = match date.strip_prefix(b"-") {
+        Some(date) => (true, date),
+        None => (false, date),
+    };
+    if !date.first()?.is_ascii_digit() {
+        return None;
+    }
+    let (stamp, end) = parse_number(date);
+    if stamp == i64::MAX || date.get(end) != Some(&b' ') {
+        return None;
+    }
+    let sign = *date.get(end + 1)?;
+    if sign != b'+' && sign != b'-' {
+        return None;
+    }
+    let date = &date[end + 2..];
+    let (ofs, digits) = parse_number(date);
+    if digits != 4 || !matches!(date.get(digits), None | Some(b'\n')) {
+        return None;
+    }
+    let ofs = (ofs / 100) * 60 + (ofs % 100);
+    Some((
+        if is_negative { -stamp } else { stamp },
+        if sign == b'-' { -ofs } else { ofs },
+    ))
+}
@@ -0,0 +1,66 @@
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Unknown date format: {input:?}")]
+    InvalidDateString { input: String },
+    #[error("Current time is missing but required to handle relative date {input:?}")]
+    MissingCurrentTime { input: String },
+}
+
+pub(crate) mod function {
+    use std::time::SystemTime;
+
+    use crate::{
+        parse::{approx, basic, tm::local_offset_in_seconds, Error},
+        SecondsSinceUnixEpoch, Time,
+    };
+
+    /// Parse `input` as any date that git understands, similar to `approxidate_careful()`, which is what git uses when
+    /// parsing dates on the command-line or in revision specifications.
+    ///
+    /// Strict dates as understood by [`strict()`][crate::parse::strict()] are tried first and retain their timezone offset.
+    /// Otherwise, dates may be relative to `now`, like `2 weeks ago` or `yesterday noon`, and are returned in the
+    /// local timezone, or UTC if it can't be determined.
+    /// Just like in git, missing parts of a date are taken from `now`, so `2022-08-22` refers to the current time of
+    /// day on that date.
+    ///
+    /// Note that `now` is only needed for relative dates, and if it's `None` these will fail to parse.
+    /// Absolute dates with missing parts still parse in that case, and take these parts from the current system time instead.
+    pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
+        let current_time = seconds(now.unwrap_or_else(SystemTime::now));
+        if let Some((seconds, offset_in_minutes)) = basic::parse(input.as_bytes(), current_time) {
+            return Ok(Time::new(seconds, offset_in_minutes as i32 * 60));
+        }
+
+        match now {
+            Some(now) => approx::parse(input.as_bytes(), seconds(now))
+                .map(|seconds| Time::new(seconds, local_offset_in_seconds(seconds) as i32))
+                .ok_or_else(|| Error::InvalidDateString { input: input.into() }),
+            None => Err(match approx::parse(input.as_bytes(), current_time) {
+                Some(_) => Error::MissingCurrentTime { input: input.into() },
+                None => Error::InvalidDateString { input: input.into() },
+            }),
+        }
+    }
+
+    /// Parse `input` strictly as a date with all of its components present, similar to `parse_date()` in git which is
+    /// used for dates in `GIT_AUTHOR_DATE` and `GIT_COMMITTER_DATE`.
+    ///
+    /// Supported are all formats that git can write, like `Thu, 18 Aug 2022 12:45:06 +0800`,
+    /// `2022-08-17T21:43:13+08:00` or `@1660874655 +0800`, along with plenty of variations of these.
+    /// If no timezone is given, the local one is assumed, or UTC if it can't be determined.
+    pub fn strict(input: &str) -> Result<Time, Error> {
+        basic::parse(input.as_bytes(), seconds(SystemTime::now()))
+            .map(|(seconds, offset_in_minutes)| Time::new(seconds, offset_in_minutes as i32 * 60))
+            .ok_or_else(|| Error::InvalidDateString { input: input.into() })
+    }
+
+    fn seconds(time: SystemTime) -> SecondsSinceUnixEpoch {
+        time::OffsetDateTime::from(time).unix_timestamp()
+    }
+}
+pub use function::strict;
+
+mod approx;
+mod basic;
+mod tm;
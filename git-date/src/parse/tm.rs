@@ -0,0 +1,165 @@
+use crate::SecondsSinceUnixEpoch;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A broken-down time similar to `struct tm` in C, which is what git's date parsing operates on.
+///
+/// Fields are `-1` if they are unset, and like in C, `year` is relative to `1900` and `mon` is zero-based.
+/// Unlike `struct tm`, years aren't limited to any particular range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Tm {
+    pub year: i64,
+    pub mon: i64,
+    pub mday: i64,
+    pub hour: i64,
+    pub min: i64,
+    pub sec: i64,
+    pub wday: i64,
+}
+
+impl Tm {
+    /// A time with all date and time fields unset.
+    pub fn unset() -> Self {
+        Tm {
+            year: -1,
+            mon: -1,
+            mday: -1,
+            hour: -1,
+            min: -1,
+            sec: -1,
+            wday: 0,
+        }
+    }
+
+    /// Like `gmtime()`, break down `seconds` in UTC.
+    pub fn gmtime(seconds: SecondsSinceUnixEpoch) -> Self {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        Tm {
+            year: year - 1900,
+            mon: month - 1,
+            mday: day,
+            hour: seconds_of_day / 3600,
+            min: seconds_of_day % 3600 / 60,
+            sec: seconds_of_day % 60,
+            wday: (days + 4).rem_euclid(7),
+        }
+    }
+
+    /// Like `localtime()`, break down `seconds` in the local timezone.
+    pub fn localtime(seconds: SecondsSinceUnixEpoch) -> Self {
+        Self::gmtime(seconds.saturating_add(local_offset_in_seconds(seconds)))
+    }
+
+    /// Convert this instance into seconds since epoch assuming it is in UTC, or `None` if the fields are out of range.
+    ///
+    /// This is `tm_to_time_t()` in git, which, unlike `mktime()`, doesn't normalize fields, but isn't limited to
+    /// years between 1970 and 2099 either.
+    pub fn to_time_t(self) -> Option<SecondsSinceUnixEpoch> {
+        if self.year == -1 || !(0..12).contains(&self.mon) {
+            return None;
+        }
+        if self.hour < 0 || self.min < 0 || self.sec < 0 {
+            return None;
+        }
+        let days = days_from_civil(self.year.checked_add(1900)?, self.mon + 1, self.mday);
+        days.checked_mul(SECONDS_PER_DAY)?
+            .checked_add(self.hour * 3600 + self.min * 60 + self.sec)
+    }
+
+    /// Like `mktime()`, convert this instance in the local timezone into seconds since epoch,
+    /// normalizing fields that are out of range.
+    pub fn mktime(&self) -> SecondsSinceUnixEpoch {
+        let months = self
+            .year
+            .saturating_add(1900)
+            .saturating_mul(12)
+            .saturating_add(self.mon);
+        let days = days_from_civil(months.div_euclid(12), months.rem_euclid(12) + 1, 1).saturating_add(self.mday - 1);
+        let seconds = days
+            .saturating_mul(SECONDS_PER_DAY)
+            .saturating_add(self.hour.saturating_mul(3600))
+            .saturating_add(self.min.saturating_mul(60))
+            .saturating_add(self.sec);
+        seconds.saturating_sub(local_offset_in_seconds(seconds))
+    }
+
+    /// Return true if none of the date or time fields were set yet.
+    pub fn is_unset(&self) -> bool {
+        self.year < 0 && self.mon < 0 && self.mday < 0 && self.hour < 0 && self.min < 0 && self.sec < 0
+    }
+
+    /// Return true if year, month and day are known.
+    pub fn is_date_known(&self) -> bool {
+        self.year != -1 && self.mon != -1 && self.mday != -1
+    }
+}
+
+/// Return the offset of the local timezone at `seconds` since epoch, or `0` if it can't be determined.
+pub(crate) fn local_offset_in_seconds(seconds: SecondsSinceUnixEpoch) -> i64 {
+    time::OffsetDateTime::from_unix_timestamp(seconds)
+        .ok()
+        .and_then(|time| time::UtcOffset::local_offset_at(time).ok())
+        .map_or(0, |offset| offset.whole_seconds().into())
+}
+
+/// The amount of days since 1970-01-01 for the given proleptic gregorian date, with `month` and `day` being one-based.
+///
+/// Days that are out of range for the given month are counted as offset from its first day.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.saturating_mul(146097)
+        .saturating_add(day_of_era - 719468)
+        .saturating_add(day - 1)
+}
+
+/// The `(year, month, day)` of the given amount of `days` since 1970-01-01, with `month` and `day` being one-based.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days.saturating_add(719468);
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gmtime_and_to_time_t_round_trip() {
+        for seconds in [0, -1, 951782400, -2208988800, 4102444800, 253402300799, 1660874655] {
+            assert_eq!(Tm::gmtime(seconds).to_time_t(), Some(seconds), "{seconds}");
+        }
+    }
+
+    #[test]
+    fn gmtime_fields() {
+        let tm = Tm::gmtime(-1);
+        assert_eq!(
+            (tm.year, tm.mon, tm.mday, tm.hour, tm.min, tm.sec),
+            (69, 11, 31, 23, 59, 59)
+        );
+        assert_eq!(tm.wday, 3, "1969-12-31 was a Wednesday");
+        let tm = Tm::gmtime(951782400);
+        assert_eq!((tm.year, tm.mon, tm.mday), (100, 1, 29), "2000-02-29 is a leap day");
+    }
+
+    #[test]
+    fn extreme_values_do_not_overflow() {
+        for seconds in [i64::MIN, i64::MAX] {
+            Tm::gmtime(seconds).to_time_t();
+            Tm::gmtime(seconds).mktime();
+        }
+    }
+}
@@ -1,13 +1,15 @@
 use time::{format_description::FormatItem, macros::format_description};
 
-use crate::{time::Format, Time};
+use std::time::SystemTime;
+
+use crate::{time::Format, SecondsSinceUnixEpoch, Time};
 
 /// E.g. `2018-12-24`
 pub const SHORT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
 
 /// E.g. `Thu, 18 Aug 2022 12:45:06 +0800`
 pub const RFC2822: &[FormatItem<'_>] = format_description!(
-    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+    "[weekday repr:short], [day padding:none] [month repr:short] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
 );
 
 /// E.g. `2022-08-17 22:04:58 +0200`
@@ -24,9 +26,9 @@ pub const UNIX: Format<'static> = Format::Unix;
 /// E.g. `1660874655 +0800`
 pub const RAW: Format<'static> = Format::Raw;
 
-/// E.g. `Thu Sep 04 2022 10:45:06 -0400`
+/// E.g. `Sun Sep 4 10:45:06 2022 -0400`
 pub const DEFAULT: &[FormatItem<'_>] = format_description!(
-    "[weekday repr:short] [month repr:short] [day] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+    "[weekday repr:short] [month repr:short] [day padding:none] [hour]:[minute]:[second] [year] [offset_hour sign:mandatory][offset_minute]"
 );
 
 mod format_impls {
@@ -47,22 +49,140 @@ impl Time {
     ///
     /// Use the [`format_description`](https://time-rs.github.io/book/api/format-description.html) macro to create and
     /// validate formats at compile time, courtesy of the [`time`] crate.
+    ///
+    /// Note that times which can't be represented by the [`time`] crate, like those with out-of-range offsets,
+    /// are formatted as [raw][Format::Raw] for custom formats.
     pub fn format<'a>(&self, format: impl Into<Format<'a>>) -> String {
         match format.into() {
-            Format::Custom(format) => self
-                .to_time()
-                .format(&format)
-                .expect("well-known format into memory never fails"),
+            Format::Custom(format) => match self.to_time() {
+                Some(time) => time.format(&format).expect("well-known format into memory never fails"),
+                None => self.to_bstring().to_string(),
+            },
             Format::Unix => self.seconds_since_unix_epoch.to_string(),
             Format::Raw => self.to_bstring().to_string(),
+            Format::Relative(now) => relative(self.seconds_since_unix_epoch, seconds(now)),
+            Format::Human(now) => self.human(now),
         }
     }
 }
 
 impl Time {
-    fn to_time(self) -> time::OffsetDateTime {
-        time::OffsetDateTime::from_unix_timestamp(self.seconds_since_unix_epoch as i64)
-            .expect("always valid unix time")
-            .to_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).expect("valid offset"))
+    fn to_time(self) -> Option<time::OffsetDateTime> {
+        Some(
+            time::OffsetDateTime::from_unix_timestamp(self.seconds_since_unix_epoch)
+                .ok()?
+                .to_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).ok()?),
+        )
+    }
+
+    /// Like `show_date_normal()` in git for the `human` date mode.
+    fn human(&self, now: SystemTime) -> String {
+        let now = time::OffsetDateTime::from(now);
+        let human_offset = time::UtcOffset::local_offset_at(now).unwrap_or(time::UtcOffset::UTC);
+        let now_tm = now.to_offset(human_offset);
+        let tm = match self.to_time() {
+            Some(time) => time,
+            None => return self.to_bstring().to_string(),
+        };
+
+        let hide_year = tm.year() == now_tm.year();
+        let (mut hide_date, mut hide_wday) = (false, false);
+        if hide_year && tm.month() == now_tm.month() {
+            if tm.day() > now_tm.day() {
+                // Future date: think timezones
+            } else if tm.day() == now_tm.day() {
+                hide_date = true;
+                hide_wday = true;
+            } else if tm.day() + 5 > now_tm.day() {
+                // Leave just weekday if it was a few days ago
+                hide_date = true;
+            }
+        }
+
+        // Show "today" times as just relative times
+        if hide_wday {
+            return relative(self.seconds_since_unix_epoch, now.unix_timestamp());
+        }
+
+        let hide_tz = self.offset_in_seconds == human_offset.whole_seconds() || !hide_date;
+        let (hide_wday, hide_time) = (!hide_year, !hide_year);
+
+        let mut out = String::new();
+        if !hide_wday {
+            out.push_str(&tm.weekday().to_string()[..3]);
+            out.push(' ');
+        }
+        if !hide_date {
+            out.push_str(&format!("{} {} ", &tm.month().to_string()[..3], tm.day()));
+        }
+        if hide_time {
+            out.truncate(out.trim_end().len());
+        } else {
+            out.push_str(&format!("{:02}:{:02}", tm.hour(), tm.minute()));
+        }
+        if !hide_year {
+            out.push_str(&format!(" {}", tm.year()));
+        }
+        if !hide_tz {
+            let mut offset = Vec::new();
+            self.write_offset_to(&mut offset).expect("write to memory cannot fail");
+            out.push(' ');
+            out.push_str(std::str::from_utf8(&offset).expect("ascii only"));
+        }
+        out
+    }
+}
+
+fn seconds(time: SystemTime) -> SecondsSinceUnixEpoch {
+    time::OffsetDateTime::from(time).unix_timestamp()
+}
+
+/// Like `show_date_relative()` in git, describe how long ago `time` was as seen from `now`.
+fn relative(time: SecondsSinceUnixEpoch, now: SecondsSinceUnixEpoch) -> String {
+    fn plural(amount: i64, unit: &str) -> String {
+        format!("{amount} {unit}{}", if amount == 1 { "" } else { "s" })
+    }
+    if now < time {
+        return "in the future".into();
+    }
+    let mut diff = now.saturating_sub(time);
+    if diff < 90 {
+        return format!("{} ago", plural(diff, "second"));
+    }
+    // Turn it into minutes
+    diff = (diff + 30) / 60;
+    if diff < 90 {
+        return format!("{} ago", plural(diff, "minute"));
+    }
+    // Turn it into hours
+    diff = (diff + 30) / 60;
+    if diff < 36 {
+        return format!("{} ago", plural(diff, "hour"));
+    }
+    // We deal with number of days from here on
+    diff = (diff + 12) / 24;
+    if diff < 14 {
+        return format!("{} ago", plural(diff, "day"));
+    }
+    // Say weeks for the past 10 weeks or so
+    if diff < 70 {
+        return format!("{} ago", plural((diff + 3) / 7, "week"));
+    }
+    // Say months for the past 12 months or so
+    if diff < 365 {
+        return format!("{} ago", plural((diff + 15) / 30, "month"));
+    }
+    // Give years and months for 5 years or so
+    if diff < 1825 {
+        let total_months = (diff * 12 * 2 + 365) / (365 * 2);
+        let years = total_months / 12;
+        let months = total_months % 12;
+        return if months != 0 {
+            format!("{}, {} ago", plural(years, "year"), plural(months, "month"))
+        } else {
+            format!("{} ago", plural(years, "year"))
+        };
     }
+    // Otherwise, just years. Centuries is probably overkill.
+    format!("{} ago", plural((diff + 183) / 365, "year"))
 }
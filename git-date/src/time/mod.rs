@@ -1,4 +1,4 @@
-use crate::Time;
+use crate::{SecondsSinceUnixEpoch, Time};
 
 /// Access
 impl Time {
@@ -8,7 +8,7 @@ impl Time {
     }
 
     /// Return the passed seconds since epoch since this signature was made.
-    pub fn seconds(&self) -> u32 {
+    pub fn seconds(&self) -> SecondsSinceUnixEpoch {
         self.seconds_since_unix_epoch
     }
 }
@@ -31,6 +31,11 @@ pub enum Format<'a> {
     Unix,
     /// The seconds since 1970, followed by the offset, like `1660874655 +0800`
     Raw,
+    /// The time relative to the given current time, like `2 hours ago` or `1 year, 2 months ago`.
+    Relative(std::time::SystemTime),
+    /// A format relative to the given current time that omits what's obvious from it, like `Aug 18 12:45` for
+    /// times in the current year, `Thu 12:45` for times within the last few days, or `2 hours ago` for times of the same day.
+    Human(std::time::SystemTime),
 }
 
 ///
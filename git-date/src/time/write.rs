@@ -16,6 +16,12 @@ impl Time {
         let mut itoa = itoa::Buffer::new();
         out.write_all(itoa.format(self.seconds_since_unix_epoch).as_bytes())?;
         out.write_all(b" ")?;
+        self.write_offset_to(out)
+    }
+
+    /// Serialize only the offset of this instance to `out`, like `+0230`.
+    pub(crate) fn write_offset_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        let mut itoa = itoa::Buffer::new();
         out.write_all(match self.sign {
             Sign::Plus => b"+",
             Sign::Minus => b"-",
@@ -42,28 +48,14 @@ impl Time {
 
     /// Computes the number of bytes necessary to render this time.
     pub fn size(&self) -> usize {
-        // TODO: this is not year 2038 safe…but we also can't parse larger numbers (or represent them) anyway. It's a trap nonetheless
-        //       that can be fixed by increasing the size to usize.
-        (if self.seconds_since_unix_epoch >= 1_000_000_000 {
-            10
-        } else if self.seconds_since_unix_epoch >= 100_000_000 {
-            9
-        } else if self.seconds_since_unix_epoch >= 10_000_000 {
-            8
-        } else if self.seconds_since_unix_epoch >= 1_000_000 {
-            7
-        } else if self.seconds_since_unix_epoch >= 100_000 {
-            6
-        } else if self.seconds_since_unix_epoch >= 10_000 {
-            5
-        } else if self.seconds_since_unix_epoch >= 1_000 {
-            4
-        } else if self.seconds_since_unix_epoch >= 100 {
-            3
-        } else if self.seconds_since_unix_epoch >= 10 {
-            2
-        } else {
-            1
-        }) + 2 /*space + sign*/ + 2 /*hours*/ + 2 /*minutes*/
+        let seconds = self.seconds_since_unix_epoch;
+        let mut digits = 1;
+        let mut remaining = seconds.unsigned_abs();
+        while remaining >= 10 {
+            remaining /= 10;
+            digits += 1;
+        }
+        usize::from(seconds < 0) + digits + 2 /*space + sign*/ + 2 /*hours*/ + 2
+        /*minutes*/
     }
 }
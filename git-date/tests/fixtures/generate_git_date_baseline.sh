@@ -25,20 +25,38 @@ function baseline() {
 
 # date formats following to https://git-scm.com/docs/git-log#Documentation/git-log.txt---dateltformatgt
 
-# short
-# ODO
-#baseline '2022-08-22'
 # rfc2822
 baseline 'Thu, 18 Aug 2022 12:45:06 +0800' 'RFC2822'
+baseline 'Thu, 4 Aug 2022 12:45:06 +0800' 'RFC2822'
 # iso8601
 baseline '2022-08-17 22:04:58 +0200' 'ISO8601'
 # iso8601_strict
 baseline '2022-08-17T21:43:13+08:00' 'ISO8601_STRICT'
 # default
 baseline 'Thu Sep 04 2022 10:45:06 -0400' '' # cannot round-trip, incorrect day-of-week
-baseline 'Sun Sep 04 2022 10:45:06 -0400' 'DEFAULT'
+baseline 'Sun Sep 04 2022 10:45:06 -0400' '' # cannot round-trip, not the order git writes
+baseline 'Sun Sep 4 10:45:06 2022 -0400' 'DEFAULT'
 # unix
 baseline '123456789' 'UNIX'
 # raw
 baseline '1660874655 +0800' 'RAW'
 
+# variations git understands as well
+baseline '@1660874655 +0800' ''
+baseline '@0 +0000' ''
+baseline '2022.08.17 22:04:58 +0200' ''
+baseline '17.08.2022 22:04:58 +0200' ''
+baseline '08/17/2022 22:04:58 +0200' ''
+baseline '2022-08-17 22:04:58.123 +0200' ''
+baseline '2022-08-17 22:04:58 CEST' ''
+baseline '2022-08-17 22:04:58 PST' ''
+baseline '2022-08-17 10:04:58 PM +02:00' ''
+baseline '20220817T220458 +0200' ''
+baseline '2022-08-17T22:04:58Z' ''
+baseline 'Wed, 17 Aug 22 22:04:58 +0200' ''
+baseline 'August 17 2022 22:04:58 -0130' ''
+baseline '17 Aug 2022 22:04 +0200' ''
+
+
+# failure
+baseline 'foobar' ''
@@ -8,7 +8,7 @@ type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 struct Sample {
     format_name: Option<String>,
     exit_code: usize,
-    time_in_seconds_since_unix_epoch: u32,
+    time_in_seconds_since_unix_epoch: git_date::SecondsSinceUnixEpoch,
 }
 
 static BASELINE: Lazy<HashMap<String, Sample>> = Lazy::new(|| {
@@ -21,7 +21,7 @@ static BASELINE: Lazy<HashMap<String, Sample>> = Lazy::new(|| {
         while let Some(date_str) = lines.next() {
             let format_name = lines.next().expect("four lines per baseline").to_string();
             let exit_code = lines.next().expect("four lines per baseline").parse()?;
-            let time_in_seconds_since_unix_epoch: u32 = lines
+            let time_in_seconds_since_unix_epoch: git_date::SecondsSinceUnixEpoch = lines
                 .next()
                 .expect("four lines per baseline")
                 .parse()
@@ -42,7 +42,61 @@ fn rfc2822() {
 fn default() {
     assert_eq!(
         time().format(git_date::time::format::DEFAULT),
-        "Fri Nov 30 1973 00:03:09 +0230"
+        "Fri Nov 30 00:03:09 1973 +0230"
+    );
+}
+
+#[test]
+fn relative() {
+    for (seconds_ago, expected) in [
+        (-1, "in the future"),
+        (0, "0 seconds ago"),
+        (1, "1 second ago"),
+        (89, "89 seconds ago"),
+        (90, "2 minutes ago"),
+        (2 * 60 * 60, "2 hours ago"),
+        (35 * 60 * 60, "35 hours ago"),
+        (36 * 60 * 60, "2 days ago"),
+        (14 * 24 * 60 * 60, "2 weeks ago"),
+        (70 * 24 * 60 * 60, "2 months ago"),
+        (365 * 24 * 60 * 60, "1 year ago"),
+        (400 * 24 * 60 * 60, "1 year, 1 month ago"),
+        (2000 * 24 * 60 * 60, "5 years ago"),
+    ] {
+        assert_eq!(time().format(Format::Relative(now(seconds_ago))), expected);
+    }
+}
+
+#[test]
+fn human() {
+    let utc = |seconds| Time::new(seconds, 0);
+    let now = now(0);
+    let in_seconds = time().seconds_since_unix_epoch;
+    assert_eq!(utc(in_seconds - 2 * 60 * 60).format(Format::Human(now)), "2 hours ago");
+    assert_eq!(
+        utc(in_seconds - 2 * 24 * 60 * 60).format(Format::Human(now)),
+        "Tue 21:33",
+        "just the weekday for the last few days"
+    );
+    assert_eq!(
+        Time::new(in_seconds - 2 * 24 * 60 * 60, 9000).format(Format::Human(now)),
+        "Wed 00:03 +0230",
+        "the offset is shown along with the weekday if it differs from the local one"
+    );
+    assert_eq!(
+        time().format(Format::Human(now)),
+        "Fri Nov 30 00:03",
+        "the date is shown as the day differs in the local timezone, but never the offset along with it"
+    );
+    assert_eq!(
+        utc(in_seconds - 10 * 24 * 60 * 60).format(Format::Human(now)),
+        "Mon Nov 19 21:33",
+        "the year is omitted for dates in the current year"
+    );
+    assert_eq!(
+        utc(in_seconds - 365 * 24 * 60 * 60).format(Format::Human(now)),
+        "Nov 29 1972",
+        "older dates omit the time"
     );
 }
 
@@ -54,6 +108,11 @@ fn custom_compile_time() {
     );
 }
 
+fn now(seconds_after_time: i64) -> std::time::SystemTime {
+    std::time::UNIX_EPOCH
+        + std::time::Duration::from_secs((time().seconds_since_unix_epoch + seconds_after_time) as u64)
+}
+
 fn time() -> Time {
     Time {
         seconds_since_unix_epoch: 123456789,
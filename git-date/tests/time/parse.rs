@@ -1,29 +1,35 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use git_date::{time::Sign, Time};
 
+/// 2022-08-22 12:34:56 UTC
+fn now() -> SystemTime {
+    std::time::UNIX_EPOCH + Duration::from_secs(1661171696)
+}
+
 #[test]
-fn special_time_is_ok_for_now() {
+fn short_takes_time_of_day_from_now() {
     assert_eq!(
-        git_date::parse("1979-02-26 18:30:00", Some(SystemTime::now())).unwrap(),
+        git_date::parse("1979-02-26", Some(now())).expect("parsed date"),
         Time {
-            seconds_since_unix_epoch: 42,
-            offset_in_seconds: 1800,
+            seconds_since_unix_epoch: 288880496,
+            offset_in_seconds: 0,
             sign: Sign::Plus,
-        }
+        },
+        "like git, the time of day is taken from now"
     );
 }
 
 #[test]
-fn short() {
+fn iso8601_with_and_without_offset() {
     assert_eq!(
-        git_date::parse("1979-02-26", Some(SystemTime::now())).expect("parsed date"),
-        Time {
-            seconds_since_unix_epoch: 288835200,
-            offset_in_seconds: 0,
-            sign: Sign::Plus,
-        },
-        "could not parse with SHORT format"
+        git_date::parse("1979-02-26 18:30:00 +0030", None).expect("parsed date"),
+        Time::new(288900000, 1800)
+    );
+    assert_eq!(
+        git_date::parse("1979-02-26 18:30:00", None).expect("parsed date"),
+        Time::new(288901800, 0),
+        "without offset, the local timezone is used which is UTC during testing"
     );
 }
 
@@ -61,52 +67,122 @@ fn raw() {
         },
         "could not parse with raw format"
     );
+
+    assert_eq!(
+        git_date::parse("@42 +0030", None).expect("parsed raw string"),
+        Time::new(42, 1800),
+        "small timestamps need the '@' prefix"
+    );
+}
+
+#[test]
+fn before_unix_epoch() {
+    for (input, expected) in [
+        ("1960-01-01 00:00:00 +0000", Time::new(-315619200, 0)),
+        ("Sun Jul 20 20:17:40 1969 +0000", Time::new(-14182940, 0)),
+        ("@-315619200 +0100", Time::new(-315619200, 3600)),
+    ] {
+        assert_eq!(git_date::parse(input, None).expect("parsed date"), expected, "{input}");
+    }
+}
+
+#[test]
+fn past_2038() {
+    for (input, expected) in [
+        ("2150-01-01 00:00:00 +0000", Time::new(5680281600, 0)),
+        ("5680281600 +0000", Time::new(5680281600, 0)),
+        ("@5680281600 -0100", Time::new(5680281600, -3600)),
+    ] {
+        assert_eq!(git_date::parse(input, None).expect("parsed date"), expected, "{input}");
+    }
+}
+
+#[test]
+fn strict_does_not_take_anything_from_now() {
+    assert_eq!(
+        git_date::parse::strict("Thu, 18 Aug 2022 12:45:06 +0800").expect("parsed"),
+        Time::new(1660797906, 28800)
+    );
+    for input in ["1979-02-26", "yesterday", "2 weeks ago"] {
+        assert!(matches!(
+            git_date::parse::strict(input).unwrap_err(),
+            git_date::parse::Error::InvalidDateString { .. }
+        ));
+    }
 }
 
 #[test]
 fn invalid_dates_can_be_produced_without_current_time() {
     assert!(matches!(
         git_date::parse("foobar", None).unwrap_err(),
-        git_date::parse::Error::InvalidDateString
+        git_date::parse::Error::InvalidDateString { input } if input == "foobar"
     ));
 }
 
 mod relative {
-    use std::time::SystemTime;
+    use std::time::Duration;
+
+    use git_date::{parse::Error, Time};
+    use time::OffsetDateTime;
 
-    use git_date::{parse::Error, time::Sign};
-    use time::{Duration, OffsetDateTime};
+    use super::now;
+
+    fn parse(input: &str) -> Time {
+        git_date::parse(input, Some(now())).expect("valid time")
+    }
 
     #[test]
     fn large_offsets() {
         git_date::parse("999999999999999 weeks ago", Some(std::time::UNIX_EPOCH)).ok();
+        git_date::parse("9999999999 weeks ago", Some(std::time::UNIX_EPOCH)).ok();
+        git_date::parse("999999999999999999999999 years ago", Some(std::time::UNIX_EPOCH)).ok();
     }
 
     #[test]
-    #[should_panic] // TODO: fix
-    fn large_offsets_can_panic_elsewhere() {
-        git_date::parse("9999999999 weeks ago", Some(std::time::UNIX_EPOCH)).ok();
+    fn offset_leading_to_before_unix_epoch_can_be_represented() {
+        let time = git_date::parse("1 second ago", Some(std::time::UNIX_EPOCH)).unwrap();
+        assert_eq!(time.seconds_since_unix_epoch, -1);
     }
 
     #[test]
-    fn offset_leading_to_before_unix_epoch_cannot_be_represented() {
-        let err = git_date::parse("1 second ago", Some(std::time::UNIX_EPOCH)).unwrap_err();
-        assert!(matches!(err, Error::TooEarly{timestamp} if timestamp == -1));
+    fn relative_dates_need_the_current_time() {
+        assert!(matches!(
+            git_date::parse("2 weeks ago", None).unwrap_err(),
+            Error::MissingCurrentTime { .. }
+        ));
     }
 
     #[test]
     fn various() {
-        let now = Some(SystemTime::now());
-        let two_weeks_ago = git_date::parse("2 weeks ago", now).expect("valid time");
-        assert_eq!(Sign::Plus, two_weeks_ago.sign);
-        assert_eq!(0, two_weeks_ago.offset_in_seconds);
-        let expected = OffsetDateTime::from(now.unwrap()).saturating_sub(Duration::weeks(2));
-        // account for the loss of precision when creating `Time` with seconds
-        let expected = expected.replace_nanosecond(0).unwrap();
+        let now = now();
+        let two_weeks_ago = parse("2 weeks ago");
+        assert_eq!(two_weeks_ago.sign, two_weeks_ago.offset_in_seconds.into());
+        let expected = OffsetDateTime::from(now - Duration::from_secs(14 * 24 * 60 * 60));
         assert_eq!(
-            OffsetDateTime::from_unix_timestamp(two_weeks_ago.seconds_since_unix_epoch as i64).expect("valid datetime"),
+            OffsetDateTime::from_unix_timestamp(two_weeks_ago.seconds_since_unix_epoch).expect("valid datetime"),
             expected,
             "relative times differ"
         );
+
+        let now = 1661171696;
+        for (input, expected) in [
+            ("now", now),
+            ("yesterday", now - 24 * 60 * 60),
+            ("3.days.ago", now - 3 * 24 * 60 * 60),
+            ("two hours ago", now - 2 * 60 * 60),
+            ("last week", now - 7 * 24 * 60 * 60),
+            ("5 minutes 10 seconds ago", now - 5 * 60 - 10),
+            ("noon today", 1661169600),
+            ("yesterday noon", 1661083200),
+            ("midnight", 1661126400),
+            ("last friday", 1660912496),
+            ("2 months ago", 1655901296),
+            ("1 year ago", 1629635696),
+            ("Dec 6, 1992", 723645296),
+            ("Aug 1st 10:30pm", 1659393000),
+            ("never", 0),
+        ] {
+            assert_eq!(parse(input).seconds_since_unix_epoch, expected, "{input}");
+        }
     }
 }
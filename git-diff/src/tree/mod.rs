@@ -45,3 +45,6 @@ pub use visit::Visit;
 pub mod recorder;
 #[doc(inline)]
 pub use recorder::Recorder;
+
+///
+pub mod rewrites;
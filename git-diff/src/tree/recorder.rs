@@ -32,6 +32,22 @@ pub enum Change {
 
         path: BString,
     },
+    /// An entry was renamed or copied from `source_path` to `path`, possibly along with a modification.
+    ///
+    /// It's never produced by the [`Recorder`], but by [`rewrites::detect()`][crate::tree::rewrites::detect()] to replace
+    /// an [`Addition`][Change::Addition] and, if it's a rename, the [`Deletion`][Change::Deletion] of its source.
+    Rewrite {
+        source_entry_mode: tree::EntryMode,
+        source_oid: ObjectId,
+        source_path: BString,
+
+        entry_mode: tree::EntryMode,
+        oid: ObjectId,
+        path: BString,
+
+        /// If true, the source still exists and the entry was copied, otherwise it was renamed.
+        copy: bool,
+    },
 }
 
 /// A [Visit][visit::Visit] implementation to record every observed change and keep track of the changed paths.
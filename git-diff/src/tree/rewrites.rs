@@ -0,0 +1,321 @@
+use std::{collections::HashMap, ops::Range};
+
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+};
+
+use crate::{blob, tree::recorder::Change};
+
+/// The error returned by [`detect()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The blob {oid} to compute its similarity with could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+}
+
+/// Configure how copies are detected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Copies {
+    /// The minimal similarity between 0.0 and 1.0 of a source and its copy, like `--find-copies=50%` in `git`,
+    /// or `None` to only detect copies with identical content.
+    pub percentage: Option<f32>,
+}
+
+impl Default for Copies {
+    fn default() -> Self {
+        Copies { percentage: Some(0.5) }
+    }
+}
+
+/// Options for use with [`detect()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// The minimal similarity between 0.0 and 1.0 of a deleted and an added file to be considered a rename, like
+    /// `--find-renames=50%` in `git`, or `None` to only detect renames of files with identical content.
+    pub percentage: Option<f32>,
+    /// If set, additions are also matched with files that remain in place to detect copies.
+    ///
+    /// Only deleted and modified files are used as sources of copies, similar to `--find-copies` in `git`.
+    pub copies: Option<Copies>,
+    /// The maximum amount of sources and destinations to compare with each other when finding similar files, like `diff.renameLimit`.
+    ///
+    /// If there are more than `limit * limit` pairs of sources and destinations, only files with identical content are detected.
+    /// If 0, there is no limit.
+    pub limit: usize,
+    /// The algorithm to use for computing the similarity of files.
+    pub algorithm: blob::Algorithm,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            percentage: Some(0.5),
+            copies: None,
+            limit: 1000,
+            algorithm: blob::Algorithm::Myers,
+        }
+    }
+}
+
+/// The outcome of [`detect()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The input changes, with additions replaced by [rewrites][Change::Rewrite] and the deletion of renamed files removed.
+    pub changes: Vec<Change>,
+    /// If true, there were too many possible pairs of files to compare according to [`Options::limit`], and
+    /// only rewrites of files with identical content were detected.
+    pub limit_exceeded: bool,
+}
+
+/// A possible source of a rewrite.
+struct Source {
+    /// The index into the changes.
+    change_index: usize,
+    /// If true, the source is a deleted file which can be renamed, otherwise it's the previous state of a modified file.
+    is_deletion: bool,
+    /// If true, the source was renamed already, so it can only serve as source of copies from now on.
+    renamed: bool,
+}
+
+/// Pair additions with deletions in `changes` to detect renames, and possibly with deletions and modifications to detect copies as well,
+/// as configured by `options`.
+///
+/// Files with identical content are matched first, preferring sources with the same file name. Then the remaining files are compared
+/// with each other using the [diff algorithm][Options::algorithm] to compute their similarity, which is the fraction of bytes
+/// of the source which remain unchanged relative to the size of the larger file. Pairs with the highest similarity are matched first.
+///
+/// `find(oid, buf) -> Result<data, E>` is used to obtain the data of blobs to compare.
+///
+/// Note that only blobs and symbolic links are considered, and each deleted file is the source of only one rename,
+/// but may be the source of multiple copies.
+pub fn detect<FindFn, E>(changes: Vec<Change>, mut find: FindFn, options: Options) -> Result<Outcome, Error>
+where
+    FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<&'b [u8], E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let copies = options.copies.is_some();
+    let mut sources = Vec::new();
+    let mut destinations = Vec::new();
+    for (change_index, change) in changes.iter().enumerate() {
+        match change {
+            Change::Addition { entry_mode, .. } if is_rewritable(*entry_mode) => destinations.push(change_index),
+            Change::Deletion { entry_mode, .. } if is_rewritable(*entry_mode) => sources.push(Source {
+                change_index,
+                is_deletion: true,
+                renamed: false,
+            }),
+            Change::Modification {
+                previous_entry_mode, ..
+            } if copies && is_rewritable(*previous_entry_mode) => sources.push(Source {
+                change_index,
+                is_deletion: false,
+                renamed: false,
+            }),
+            _ => {}
+        }
+    }
+
+    // (destination change index, source index, is copy)
+    let mut rewrites = Vec::new();
+    let mut sources_by_id = HashMap::<ObjectId, Vec<usize>>::new();
+    for (source_index, source) in sources.iter().enumerate() {
+        sources_by_id
+            .entry(source_side(&changes[source.change_index]).1)
+            .or_default()
+            .push(source_index);
+    }
+    destinations.retain(|&destination| {
+        let (mode, id, path) = destination_side(&changes[destination]);
+        let candidates = match sources_by_id.get(&id) {
+            Some(candidates) => candidates,
+            None => return true,
+        };
+        let compatible = |source: &&usize| is_compatible(source_side(&changes[sources[**source].change_index]).0, mode);
+        let renamable = candidates
+            .iter()
+            .filter(compatible)
+            .filter(|s| sources[**s].is_deletion && !sources[**s].renamed)
+            .max_by_key(|s| file_name(source_side(&changes[sources[**s].change_index]).2) == file_name(path));
+        if let Some(&source) = renamable {
+            sources[source].renamed = true;
+            rewrites.push((destination, source, false));
+            return false;
+        }
+        match candidates.iter().find(compatible) {
+            Some(&source) if copies => {
+                rewrites.push((destination, source, true));
+                false
+            }
+            _ => true,
+        }
+    });
+
+    let copy_percentage = options.copies.and_then(|c| c.percentage);
+    let mut limit_exceeded = false;
+    let min_percentage = match (options.percentage, copy_percentage) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    if let Some(min_percentage) = min_percentage.filter(|_| !destinations.is_empty()) {
+        let candidate_sources: Vec<_> = (0..sources.len())
+            .filter(|s| copy_percentage.is_some() || (sources[*s].is_deletion && !sources[*s].renamed))
+            .collect();
+        if options.limit != 0 && destinations.len() * candidate_sources.len() > options.limit * options.limit {
+            limit_exceeded = true;
+        } else if !candidate_sources.is_empty() {
+            let mut blobs = HashMap::<ObjectId, Vec<u8>>::new();
+            let mut load = |id: ObjectId| -> Result<(), Error> {
+                if let std::collections::hash_map::Entry::Vacant(entry) = blobs.entry(id) {
+                    let mut buf = Vec::new();
+                    let data = find(&id, &mut buf).map_err(|err| Error::FindExisting {
+                        oid: id,
+                        source: err.into(),
+                    })?;
+                    entry.insert(data.to_owned());
+                }
+                Ok(())
+            };
+            for destination in &destinations {
+                load(destination_side(&changes[*destination]).1)?;
+            }
+            for source in &candidate_sources {
+                load(source_side(&changes[sources[*source].change_index]).1)?;
+            }
+
+            // (similarity, destination position, source index)
+            let mut pairs = Vec::new();
+            for (destination_pos, destination) in destinations.iter().enumerate() {
+                let (mode, id, _path) = destination_side(&changes[*destination]);
+                let new = &blobs[&id];
+                for source in &candidate_sources {
+                    let (source_mode, source_id, _) = source_side(&changes[sources[*source].change_index]);
+                    if !is_compatible(source_mode, mode) {
+                        continue;
+                    }
+                    let old = &blobs[&source_id];
+                    let (min, max) = (old.len().min(new.len()), old.len().max(new.len()));
+                    if max != 0 && (min as f32 / max as f32) < min_percentage {
+                        continue;
+                    }
+                    let similarity = similarity(options.algorithm, old, new);
+                    if similarity >= min_percentage {
+                        pairs.push((similarity, destination_pos, *source));
+                    }
+                }
+            }
+            pairs.sort_by(|a, b| {
+                b.0.partial_cmp(&a.0)
+                    .expect("no NaN")
+                    .then(a.1.cmp(&b.1))
+                    .then(a.2.cmp(&b.2))
+            });
+
+            let mut is_rewritten = vec![false; destinations.len()];
+            for (similarity, destination_pos, source) in pairs {
+                if is_rewritten[destination_pos] {
+                    continue;
+                }
+                let can_rename = sources[source].is_deletion
+                    && !sources[source].renamed
+                    && options.percentage.map_or(false, |p| similarity >= p);
+                let can_copy = copy_percentage.map_or(false, |p| similarity >= p);
+                if can_rename {
+                    sources[source].renamed = true;
+                } else if !can_copy {
+                    continue;
+                }
+                is_rewritten[destination_pos] = true;
+                rewrites.push((destinations[destination_pos], source, !can_rename));
+            }
+        }
+    }
+
+    let mut is_renamed_source = vec![false; changes.len()];
+    let mut rewritten = Vec::with_capacity(rewrites.len());
+    for (destination, source, copy) in rewrites {
+        let source_change_index = sources[source].change_index;
+        let (source_entry_mode, source_oid, source_path) = source_side(&changes[source_change_index]);
+        let (entry_mode, oid, path) = destination_side(&changes[destination]);
+        rewritten.push((
+            destination,
+            Change::Rewrite {
+                source_entry_mode,
+                source_oid,
+                source_path: source_path.to_owned(),
+                entry_mode,
+                oid,
+                path: path.to_owned(),
+                copy,
+            },
+        ));
+        is_renamed_source[source_change_index] |= !copy;
+    }
+    let mut changes: Vec<_> = changes.into_iter().map(Some).collect();
+    for (destination, rewrite) in rewritten {
+        changes[destination] = Some(rewrite);
+    }
+    for (change, is_renamed_source) in changes.iter_mut().zip(is_renamed_source) {
+        if is_renamed_source {
+            *change = None;
+        }
+    }
+    Ok(Outcome {
+        changes: changes.into_iter().flatten().collect(),
+        limit_exceeded,
+    })
+}
+
+/// Return the fraction of bytes in `old` that remain unchanged in `new`, relative to the size of the larger one.
+fn similarity(algorithm: blob::Algorithm, old: &[u8], new: &[u8]) -> f32 {
+    let max = old.len().max(new.len());
+    if max == 0 {
+        return 1.0;
+    }
+    let input = blob::intern::InternedInput::new(blob::sources::byte_lines(old), blob::sources::byte_lines(new));
+    let mut removed_bytes = 0;
+    blob::diff(algorithm, &input, |before: Range<u32>, _after: Range<u32>| {
+        removed_bytes += input.before[before.start as usize..before.end as usize]
+            .iter()
+            .map(|token| input.interner[*token].len())
+            .sum::<usize>();
+    });
+    (old.len() - removed_bytes) as f32 / max as f32
+}
+
+fn is_rewritable(mode: EntryMode) -> bool {
+    matches!(mode, EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link)
+}
+
+fn is_compatible(lhs: EntryMode, rhs: EntryMode) -> bool {
+    (lhs == EntryMode::Link) == (rhs == EntryMode::Link)
+}
+
+fn file_name(path: &BStr) -> &BStr {
+    path.rfind_byte(b'/').map_or(path, |pos| path[pos + 1..].as_bstr())
+}
+
+fn source_side(change: &Change) -> (EntryMode, ObjectId, &BStr) {
+    match change {
+        Change::Deletion { entry_mode, oid, path } => (*entry_mode, *oid, path.as_ref()),
+        Change::Modification {
+            previous_entry_mode,
+            previous_oid,
+            path,
+            ..
+        } => (*previous_entry_mode, *previous_oid, path.as_ref()),
+        _ => unreachable!("only deletions and modifications are sources"),
+    }
+}
+
+fn destination_side(change: &Change) -> (EntryMode, ObjectId, &BStr) {
+    match change {
+        Change::Addition { entry_mode, oid, path } => (*entry_mode, *oid, path.as_ref()),
+        _ => unreachable!("only additions are destinations"),
+    }
+}
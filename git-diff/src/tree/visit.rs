@@ -80,8 +80,8 @@ mod tests {
     fn size_of_change() {
         let actual = std::mem::size_of::<Change>();
         assert!(
-            actual <= 46,
-            "{} <= 46: this type shouldn't grow without us knowing",
+            actual <= 70,
+            "{} <= 70: this type shouldn't grow without us knowing",
             actual
         )
     }
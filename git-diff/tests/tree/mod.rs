@@ -613,3 +613,228 @@ mod changes {
         }
     }
 }
+
+mod rewrites {
+    use std::collections::HashMap;
+
+    use git_diff::tree::{
+        recorder::Change::{self, *},
+        rewrites::{self, Copies, Options},
+    };
+    use git_hash::ObjectId;
+    use git_object::tree::EntryMode;
+
+    use crate::hex_to_id;
+
+    fn id(n: u8) -> ObjectId {
+        hex_to_id(&format!("{:040x}", n))
+    }
+
+    fn lines(range: std::ops::Range<usize>) -> Vec<u8> {
+        range.map(|n| format!("line {}\n", n)).collect::<String>().into_bytes()
+    }
+
+    fn detect(
+        changes: Vec<Change>,
+        blobs: &HashMap<ObjectId, Vec<u8>>,
+        options: Options,
+    ) -> Result<rewrites::Outcome, rewrites::Error> {
+        rewrites::detect(
+            changes,
+            |oid, buf| {
+                buf.clear();
+                buf.extend_from_slice(
+                    blobs
+                        .get(&oid.to_owned())
+                        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "missing"))?,
+                );
+                Ok::<_, std::io::Error>(buf.as_slice())
+            },
+            options,
+        )
+    }
+
+    fn deletion(oid: ObjectId, path: &str) -> Change {
+        Deletion {
+            entry_mode: EntryMode::Blob,
+            oid,
+            path: path.into(),
+        }
+    }
+
+    fn addition(oid: ObjectId, path: &str) -> Change {
+        Addition {
+            entry_mode: EntryMode::Blob,
+            oid,
+            path: path.into(),
+        }
+    }
+
+    fn rewrite(source_oid: ObjectId, source_path: &str, oid: ObjectId, path: &str, copy: bool) -> Change {
+        Rewrite {
+            source_entry_mode: EntryMode::Blob,
+            source_oid,
+            source_path: source_path.into(),
+            entry_mode: EntryMode::Blob,
+            oid,
+            path: path.into(),
+            copy,
+        }
+    }
+
+    #[test]
+    fn identical_content_is_matched_without_reading_blobs_preferring_the_same_file_name() -> crate::Result {
+        let out = detect(
+            vec![
+                deletion(id(1), "a/file"),
+                deletion(id(1), "b/other"),
+                addition(id(1), "c/other"),
+                addition(id(2), "unrelated"),
+            ],
+            &HashMap::new(),
+            Options {
+                percentage: None,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            out.changes,
+            vec![
+                deletion(id(1), "a/file"),
+                rewrite(id(1), "b/other", id(1), "c/other", false),
+                addition(id(2), "unrelated"),
+            ]
+        );
+        assert!(!out.limit_exceeded);
+        Ok(())
+    }
+
+    #[test]
+    fn similar_content_is_matched_if_above_the_threshold() -> crate::Result {
+        let blobs: HashMap<_, _> = [
+            (id(1), lines(0..10)),
+            (id(2), lines(0..9)),
+            (id(3), lines(100..110)),
+            (id(4), lines(200..210)),
+        ]
+        .into_iter()
+        .collect();
+        let changes = vec![
+            deletion(id(1), "old"),
+            deletion(id(3), "gone"),
+            addition(id(2), "new"),
+            addition(id(4), "fresh"),
+        ];
+        let out = detect(changes.clone(), &blobs, Options::default())?;
+        assert_eq!(
+            out.changes,
+            vec![
+                deletion(id(3), "gone"),
+                rewrite(id(1), "old", id(2), "new", false),
+                addition(id(4), "fresh"),
+            ]
+        );
+
+        let out = detect(
+            changes.clone(),
+            &blobs,
+            Options {
+                percentage: Some(0.95),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.changes, changes, "a 90% similarity isn't enough");
+        Ok(())
+    }
+
+    #[test]
+    fn the_limit_prevents_comparing_content() -> crate::Result {
+        let changes = vec![deletion(id(1), "old"), addition(id(2), "new"), addition(id(3), "other")];
+        let out = detect(
+            changes.clone(),
+            &HashMap::new(),
+            Options {
+                limit: 1,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.changes, changes);
+        assert!(out.limit_exceeded);
+
+        assert!(
+            matches!(
+                detect(changes, &HashMap::new(), Options::default()),
+                Err(rewrites::Error::FindExisting { .. })
+            ),
+            "without limit, blobs are read"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn copies_are_detected_from_modified_and_renamed_files() -> crate::Result {
+        let blobs: HashMap<_, _> = [
+            (id(1), lines(0..10)),
+            (id(2), lines(0..11)),
+            (id(3), lines(0..9)),
+            (id(4), lines(50..60)),
+        ]
+        .into_iter()
+        .collect();
+        let modification = Modification {
+            previous_entry_mode: EntryMode::Blob,
+            previous_oid: id(4),
+            entry_mode: EntryMode::Blob,
+            oid: id(5),
+            path: "modified".into(),
+        };
+        let out = detect(
+            vec![
+                deletion(id(1), "old"),
+                modification.clone(),
+                addition(id(1), "renamed"),
+                addition(id(4), "copy-of-modified"),
+                addition(id(2), "copy-of-old"),
+                addition(id(3), "similar-copy-of-old"),
+            ],
+            &blobs,
+            Options {
+                copies: Some(Copies::default()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            out.changes,
+            vec![
+                modification,
+                rewrite(id(1), "old", id(1), "renamed", false),
+                rewrite(id(4), "modified", id(4), "copy-of-modified", true),
+                rewrite(id(1), "old", id(2), "copy-of-old", true),
+                rewrite(id(1), "old", id(3), "similar-copy-of-old", true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn links_are_only_matched_with_links() -> crate::Result {
+        let changes = vec![
+            Deletion {
+                entry_mode: EntryMode::Link,
+                oid: id(1),
+                path: "link".into(),
+            },
+            addition(id(1), "file"),
+        ];
+        let out = detect(
+            changes.clone(),
+            &HashMap::new(),
+            Options {
+                percentage: None,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.changes, changes);
+        Ok(())
+    }
+}
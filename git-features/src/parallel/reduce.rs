@@ -267,6 +267,37 @@ impl<Input, Error> Reduce for IdentityWithResult<Input, Error> {
     }
 }
 
+/// A reducer which collects all items of the vectors produced in threads into a single vector, in the order they are fed.
+pub struct Flatten<Item, Error> {
+    items: Vec<Item>,
+    _error: PhantomData<Error>,
+}
+
+impl<Item, Error> Default for Flatten<Item, Error> {
+    fn default() -> Self {
+        Flatten {
+            items: Vec::new(),
+            _error: Default::default(),
+        }
+    }
+}
+
+impl<Item, Error> Reduce for Flatten<Item, Error> {
+    type Input = Result<Vec<Item>, Self::Error>;
+    type FeedProduce = ();
+    type Output = Vec<Item>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.items.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.items)
+    }
+}
+
 /// A trait reflecting the `finalize()` method of [`Reduce`] implementations
 pub trait Finalize {
     /// An implementation of [`Reduce`]
@@ -11,3 +11,21 @@ fn size_of_sha1() {
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 104)
 }
+
+#[test]
+fn hasher_of_each_kind_produces_ids_of_that_kind() {
+    for (kind, expected) in [
+        (git_hash::Kind::Sha1, "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+        (
+            git_hash::Kind::Sha256,
+            "6ef19b41225c5369f1c104d45d8d85efa9b057b53b14b4b9b939dd74decc5321",
+        ),
+    ] {
+        let mut hasher = git_features::hash::hasher(kind);
+        hasher.update(b"tree 0\0");
+        let id = hasher.digest();
+        assert_eq!(id.kind(), kind);
+        assert_eq!(id.to_string(), expected, "the empty tree of each kind");
+        assert_eq!(id, git_hash::ObjectId::empty_tree(kind));
+    }
+}
@@ -0,0 +1,57 @@
+//! A filter which applies rot13 to its input, both as single-shot `clean` or `smudge` command reading from stdin,
+//! or as long-running filter `process`.
+//!
+//! In `process` mode, files named `error.txt` fail with an error, and files named `abort.txt` abort the capability.
+//! Files named `no-status.txt` receive a response without any status, which violates the protocol.
+use std::io::{Read, Write};
+
+use bstr::ByteSlice;
+use git_filter::driver::process;
+
+fn rot13(input: &[u8]) -> Vec<u8> {
+    input
+        .iter()
+        .map(|b| match b {
+            b'a'..=b'z' => (b - b'a' + 13) % 26 + b'a',
+            b'A'..=b'Z' => (b - b'A' + 13) % 26 + b'A',
+            _ => *b,
+        })
+        .collect()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mode = std::env::args()
+        .nth(1)
+        .ok_or("First argument must be 'clean', 'smudge' or 'process'")?;
+    match mode.as_str() {
+        "clean" | "smudge" => {
+            let mut buf = Vec::new();
+            std::io::stdin().read_to_end(&mut buf)?;
+            std::io::stdout().write_all(&rot13(&buf))?;
+        }
+        "process" => {
+            let stdin = std::io::stdin();
+            let stdout = std::io::stdout();
+            let mut server =
+                process::Server::handshake(stdin.lock(), stdout.lock(), "git-filter", &[2], &["clean", "smudge"])?;
+            let mut buf = Vec::new();
+            while let Some(mut request) = server.next_request()? {
+                request.read_content(&mut buf)?;
+                match request.meta("pathname").map(|p| p.as_bytes()) {
+                    Some(b"error.txt") => request.write_error(process::Status::Error)?,
+                    Some(b"abort.txt") => request.write_error(process::Status::Abort)?,
+                    Some(b"no-status.txt") => {
+                        drop(request);
+                        let mut out = std::io::stdout();
+                        git_packetline::encode::flush_to_write(&mut out)?;
+                        out.flush()?;
+                    }
+                    _ => request.write_content(&rot13(&buf))?,
+                }
+            }
+        }
+        "fail" => std::process::exit(1),
+        unknown => return Err(format!("Unknown mode: '{}'", unknown).into()),
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use std::io::{Read, Write};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::Driver;
+
+/// The way content is converted by a driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Convert content from the worktree for storage in the object database.
+    Clean,
+    /// Convert content from the object database for use in the worktree.
+    Smudge,
+}
+
+impl Operation {
+    /// Return the name of the operation as used in configuration and in the filter protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Clean => "clean",
+            Operation::Smudge => "smudge",
+        }
+    }
+}
+
+///
+pub mod apply {
+    use bstr::BString;
+
+    /// The error returned by [`Driver::apply()`][crate::Driver::apply()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not spawn filter command '{command}'")]
+        SpawnCommand { source: std::io::Error, command: BString },
+        #[error("Could not write content to filter command '{command}'")]
+        WriteSource { source: std::io::Error, command: BString },
+        #[error("Could not read the output of filter command '{command}'")]
+        ReadOutput { source: std::io::Error, command: BString },
+        #[error("Filter command '{command}' failed with exit code {code:?}")]
+        Status { code: Option<i32>, command: BString },
+    }
+}
+
+///
+pub mod process;
+
+/// Single-shot filter invocation
+impl Driver {
+    /// Return the command configured for `operation`, if there is one.
+    pub fn command(&self, operation: Operation) -> Option<&BStr> {
+        match operation {
+            Operation::Clean => self.clean.as_ref(),
+            Operation::Smudge => self.smudge.as_ref(),
+        }
+        .map(|cmd| cmd.as_bstr())
+        .filter(|cmd| !cmd.is_empty())
+    }
+
+    /// Run the command for `operation` once to convert `src`, as found at the repository-relative `rela_path`, and write its
+    /// output to `out`. Return `false` if there is no such command, leaving `out` untouched.
+    ///
+    /// The command is run through a shell, with `%f` substituted by the quoted `rela_path`.
+    pub fn apply(
+        &self,
+        operation: Operation,
+        src: &[u8],
+        rela_path: &BStr,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, apply::Error> {
+        let command = match self.command(operation) {
+            Some(cmd) => substitute_path(cmd, rela_path),
+            None => return Ok(false),
+        };
+        let mut child = git_command::prepare(git_path::from_bstr(command.as_bstr()).as_ref())
+            .with_shell()
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|source| apply::Error::SpawnCommand {
+                source,
+                command: command.clone(),
+            })?;
+
+        let mut stdin = child.stdin.take().expect("configured");
+        let input = src.to_owned();
+        // Write in parallel to reading to prevent deadlocks on large inputs.
+        let writer = std::thread::spawn(move || match stdin.write_all(&input) {
+            // The filter may not consume all input, which is fine as long as it succeeds.
+            Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            res => res,
+        });
+        out.clear();
+        let read_res = child.stdout.take().expect("configured").read_to_end(out);
+        let write_res = writer.join().expect("no panic in writer");
+        let status = child.wait().map_err(|source| apply::Error::ReadOutput {
+            source,
+            command: command.clone(),
+        })?;
+        read_res.map_err(|source| apply::Error::ReadOutput {
+            source,
+            command: command.clone(),
+        })?;
+        write_res.map_err(|source| apply::Error::WriteSource {
+            source,
+            command: command.clone(),
+        })?;
+        if !status.success() {
+            return Err(apply::Error::Status {
+                code: status.code(),
+                command,
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// Replace `%f` in `command` with `rela_path` in single quotes, and `%%` with `%`.
+fn substitute_path(command: &BStr, rela_path: &BStr) -> BString {
+    let mut out = BString::from(Vec::with_capacity(command.len() + rela_path.len()));
+    let mut bytes = command.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte != b'%' {
+            out.push(byte);
+            continue;
+        }
+        match bytes.as_slice().first() {
+            Some(b'f') => {
+                bytes.next();
+                out.push(b'\'');
+                for &byte in rela_path.iter() {
+                    if byte == b'\'' || byte == b'!' {
+                        out.push_str(b"'\\");
+                        out.push(byte);
+                        out.push(b'\'');
+                    } else {
+                        out.push(byte);
+                    }
+                }
+                out.push(b'\'');
+            }
+            Some(b'%') => {
+                bytes.next();
+                out.push(b'%');
+            }
+            _ => out.push(byte),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::substitute_path;
+
+    #[test]
+    fn path_substitution_quotes_the_path() {
+        assert_eq!(substitute_path("cmd %f".into(), "a b".into()), "cmd 'a b'");
+        assert_eq!(substitute_path("cmd %f".into(), "it's".into()), "cmd 'it'\\''s'");
+        assert_eq!(substitute_path("100%% %x".into(), "p".into()), "100% %x");
+    }
+}
@@ -0,0 +1,205 @@
+use std::io::Write;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::driver::process::{
+    read_data, read_text_lines, split_key_value, write_data, write_text_lines, Client, Status, WaitOnDrop,
+};
+
+///
+pub mod handshake {
+    use bstr::BString;
+
+    /// The error returned by [`Client::handshake()`][crate::driver::process::Client::handshake()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not spawn the filter process")]
+        SpawnCommand(#[source] std::io::Error),
+        #[error("Failed to communicate with the filter process")]
+        Io(#[from] std::io::Error),
+        #[error("{msg}: '{actual}'")]
+        Protocol { msg: String, actual: BString },
+        #[error("The filter process doesn't support any of the versions {versions:?}")]
+        UnsupportedVersion { versions: Vec<usize> },
+    }
+}
+
+///
+pub mod invoke {
+    use bstr::BString;
+
+    /// The error returned by [`Client::invoke()`][crate::driver::process::Client::invoke()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to communicate with the filter process")]
+        Io(#[from] std::io::Error),
+        #[error("The filter process responded with an invalid status: '{actual}'")]
+        Protocol { actual: BString },
+        #[error("The filter process responded without a status")]
+        MissingStatus,
+    }
+}
+
+/// Protocol implementation
+impl Client {
+    /// Spawn `process` and perform the handshake, introducing ourselves as `<welcome_prefix>-client` and expecting
+    /// `<welcome_prefix>-server` in return, using one of the given protocol `versions` and announcing our
+    /// `desired_capabilities`, of which the process may support a subset.
+    ///
+    /// For filter processes, `welcome_prefix` is `git-filter`, `versions` is `[2]` and `desired_capabilities`
+    /// are `clean` and `smudge`.
+    pub fn handshake(
+        mut process: std::process::Command,
+        welcome_prefix: &str,
+        versions: &[usize],
+        desired_capabilities: &[&str],
+    ) -> Result<Self, handshake::Error> {
+        let mut child = process
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .map_err(handshake::Error::SpawnCommand)?;
+        let mut input = std::io::BufWriter::new(child.stdin.take().expect("configured"));
+        let mut out = git_packetline::StreamingPeekableIter::new(
+            child.stdout.take().expect("configured"),
+            &[git_packetline::PacketLineRef::Flush],
+        );
+
+        let welcome = format!("{}-client", welcome_prefix);
+        let version_lines: Vec<_> = versions.iter().map(|v| format!("version={}", v)).collect();
+        write_text_lines(
+            &mut input,
+            std::iter::once(welcome.as_str())
+                .chain(version_lines.iter().map(String::as_str))
+                .map(Into::into),
+        )?;
+        input.flush()?;
+
+        let lines = read_text_lines(&mut out)?.unwrap_or_default();
+        let mut lines = lines.iter();
+        let expected_welcome = format!("{}-server", welcome_prefix);
+        match lines.next() {
+            Some(line) if line == expected_welcome.as_str() => {}
+            actual => {
+                return Err(handshake::Error::Protocol {
+                    msg: format!("Expected '{}'", expected_welcome),
+                    actual: actual.cloned().unwrap_or_default(),
+                })
+            }
+        }
+        let version = lines
+            .filter_map(|line| split_key_value(line.as_bstr()))
+            .find_map(|(key, value)| {
+                (key == "version")
+                    .then(|| value.to_str().ok()?.parse::<usize>().ok())
+                    .flatten()
+            })
+            .filter(|v| versions.contains(v))
+            .ok_or_else(|| handshake::Error::UnsupportedVersion {
+                versions: versions.to_owned(),
+            })?;
+
+        let capability_lines: Vec<_> = desired_capabilities
+            .iter()
+            .map(|c| format!("capability={}", c))
+            .collect();
+        write_text_lines(&mut input, capability_lines.iter().map(|l| l.as_str().into()))?;
+        input.flush()?;
+
+        let capabilities = read_text_lines(&mut out)?
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| split_key_value(line.as_bstr()))
+            .filter(|(key, value)| *key == "capability" && desired_capabilities.iter().any(|c| value == c))
+            .map(|(_, value)| value.to_owned())
+            .collect();
+
+        Ok(Client {
+            input,
+            out,
+            capabilities,
+            version,
+            _child: WaitOnDrop(child),
+        })
+    }
+
+    /// Send `command` along with `meta`-data like `("pathname", "path/to/file")` and `content` to the process and write its
+    /// response into `out` if it was processed successfully.
+    ///
+    /// Return the status of the request. Note that `out` is only written if the status is [`Success`][Status::Success].
+    pub fn invoke<'a>(
+        &mut self,
+        command: &str,
+        meta: impl IntoIterator<Item = (&'a str, &'a BStr)>,
+        content: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<Status, invoke::Error> {
+        let command = format!("command={}", command);
+        let meta: Vec<BString> = meta
+            .into_iter()
+            .map(|(key, value)| {
+                let mut line = BString::from(format!("{}=", key));
+                line.extend_from_slice(value);
+                line
+            })
+            .collect();
+        write_text_lines(
+            &mut self.input,
+            std::iter::once(command.as_str().into()).chain(meta.iter().map(|l| l.as_bstr())),
+        )?;
+        write_data(&mut self.input, content)?;
+        self.input.flush()?;
+
+        // Like `git`, only an explicit status counts as success, but it doesn't have to be repeated after the content.
+        let status = self.read_status()?.ok_or(invoke::Error::MissingStatus)?;
+        if status != Status::Success {
+            return Ok(status);
+        }
+        out.clear();
+        read_data(&mut self.out, out)?;
+        // The status may be sent again after the content, overriding the previous one.
+        Ok(self.read_status()?.unwrap_or(status))
+    }
+
+    fn read_status(&mut self) -> Result<Option<Status>, invoke::Error> {
+        let lines = read_text_lines(&mut self.out)?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "filter process terminated unexpectedly",
+            )
+        })?;
+        // The last status wins.
+        lines
+            .iter()
+            .rev()
+            .filter_map(|line| split_key_value(line.as_bstr()))
+            .find(|(key, _)| *key == "status")
+            .map(|(_, value)| Status::from_bytes(value).ok_or_else(|| invoke::Error::Protocol { actual: value.into() }))
+            .transpose()
+    }
+}
+
+/// Access
+impl Client {
+    /// Return the capabilities the process agreed to support.
+    pub fn capabilities(&self) -> &[BString] {
+        &self.capabilities
+    }
+
+    /// Return true if the process supports `capability`.
+    pub fn has_capability(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+
+    /// Forget about `capability` so it won't be used anymore, which is useful if the process asked to abort it.
+    pub fn remove_capability(&mut self, capability: &str) {
+        self.capabilities.retain(|c| c != capability);
+    }
+
+    /// Return the protocol version negotiated with the process.
+    pub fn version(&self) -> usize {
+        self.version
+    }
+}
@@ -0,0 +1,134 @@
+//! An implementation of the long-running process protocol as used by `filter.<driver>.process`, both as client
+//! to talk to filter programs, and as server to facilitate writing them.
+use bstr::{BStr, BString, ByteSlice};
+
+/// The status a filter process reports for a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The request was processed successfully.
+    Success,
+    /// The request failed, but the process is able to handle more requests.
+    Error,
+    /// The request failed, and the process doesn't want to receive any more requests of the same kind.
+    Abort,
+}
+
+impl Status {
+    /// Return the status as used in the protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Success => "success",
+            Status::Error => "error",
+            Status::Abort => "abort",
+        }
+    }
+
+    fn from_bytes(value: &[u8]) -> Option<Self> {
+        Some(match value {
+            b"success" => Status::Success,
+            b"error" => Status::Error,
+            b"abort" => Status::Abort,
+            _ => return None,
+        })
+    }
+}
+
+/// A client to communicate with a long-running filter process.
+///
+/// Dropping it closes the input of the process, which signals it to shut down, and waits for it to terminate.
+pub struct Client {
+    // Note that the field order matters as `input` must be dropped before waiting for `_child`.
+    input: std::io::BufWriter<std::process::ChildStdin>,
+    out: git_packetline::StreamingPeekableIter<std::process::ChildStdout>,
+    capabilities: Vec<BString>,
+    version: usize,
+    _child: WaitOnDrop,
+}
+
+/// A child process which is awaited when dropped, to not leave zombies behind.
+struct WaitOnDrop(std::process::Child);
+
+impl Drop for WaitOnDrop {
+    fn drop(&mut self) {
+        self.0.wait().ok();
+    }
+}
+
+/// A server to implement long-running filter processes that communicate with `git` or a [`Client`].
+pub struct Server<R, W> {
+    input: git_packetline::StreamingPeekableIter<R>,
+    out: W,
+    capabilities: Vec<BString>,
+    version: usize,
+}
+
+///
+pub mod client;
+
+///
+pub mod server;
+
+/// Read text lines until the next flush packet and reset `input` to read more, returning `None` on EOF.
+fn read_text_lines<R: std::io::Read>(
+    input: &mut git_packetline::StreamingPeekableIter<R>,
+) -> std::io::Result<Option<Vec<BString>>> {
+    let mut lines = Vec::new();
+    while let Some(line) = input.read_line() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof && lines.is_empty() => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        lines.push(
+            line.as_text()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "expected a text line"))?
+                .as_bstr()
+                .to_owned(),
+        );
+    }
+    input.reset();
+    Ok(Some(lines))
+}
+
+/// Read data lines until the next flush packet into `out` and reset `input` to read more.
+fn read_data<R: std::io::Read>(
+    input: &mut git_packetline::StreamingPeekableIter<R>,
+    out: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    while let Some(line) = input.read_line() {
+        let line = line?.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        if let Some(data) = line.as_slice() {
+            out.extend_from_slice(data);
+        }
+    }
+    input.reset();
+    Ok(())
+}
+
+/// Write all `lines` as text lines, followed by a flush packet.
+fn write_text_lines<'a>(
+    mut out: impl std::io::Write,
+    lines: impl IntoIterator<Item = &'a BStr>,
+) -> std::io::Result<()> {
+    for line in lines {
+        git_packetline::encode::text_to_write(line, &mut out)?;
+    }
+    git_packetline::encode::flush_to_write(&mut out)?;
+    Ok(())
+}
+
+/// Write `data` as data lines, followed by a flush packet.
+fn write_data(mut out: impl std::io::Write, data: &[u8]) -> std::io::Result<()> {
+    if !data.is_empty() {
+        std::io::Write::write_all(&mut git_packetline::Writer::new(&mut out), data)?;
+    }
+    git_packetline::encode::flush_to_write(&mut out)?;
+    Ok(())
+}
+
+/// Split `line` at the first `=` into key and value.
+fn split_key_value(line: &BStr) -> Option<(&BStr, &BStr)> {
+    line.find_byte(b'=')
+        .map(|pos| (line[..pos].as_bstr(), line[pos + 1..].as_bstr()))
+}
@@ -0,0 +1,195 @@
+use std::io::{Read, Write};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::driver::process::{
+    read_data, read_text_lines, split_key_value, write_data, write_text_lines, Server, Status,
+};
+
+///
+pub mod handshake {
+    use bstr::BString;
+
+    /// The error returned by [`Server::handshake()`][crate::driver::process::Server::handshake()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to communicate with the client")]
+        Io(#[from] std::io::Error),
+        #[error("{msg}: '{actual}'")]
+        Protocol { msg: String, actual: BString },
+        #[error("None of the versions offered by the client are supported: {offered:?}")]
+        UnsupportedVersion { offered: Vec<usize> },
+    }
+}
+
+///
+pub mod next_request {
+    use bstr::BString;
+
+    /// The error returned by [`Server::next_request()`][crate::driver::process::Server::next_request()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to communicate with the client")]
+        Io(#[from] std::io::Error),
+        #[error("The request didn't start with a command: '{actual}'")]
+        MissingCommand { actual: BString },
+    }
+}
+
+/// A request sent by the client, as obtained by [`Server::next_request()`].
+pub struct Request<'a, R, W> {
+    parent: &'a mut Server<R, W>,
+    /// The command to perform, like `clean` or `smudge`.
+    pub command: BString,
+    /// All other key-value pairs sent along with the command, like `("pathname", "path/to/file")`.
+    pub meta: Vec<(BString, BString)>,
+}
+
+/// Protocol implementation
+impl<R, W> Server<R, W>
+where
+    R: Read,
+    W: Write,
+{
+    /// Perform the handshake with the client reading from `read` and writing to `write`, expecting it to introduce itself
+    /// as `<welcome_prefix>-client`. The highest of the offered versions that is also contained in `versions` is chosen,
+    /// and of the capabilities requested by the client only those in `capabilities` are agreed to.
+    pub fn handshake(
+        read: R,
+        mut write: W,
+        welcome_prefix: &str,
+        versions: &[usize],
+        capabilities: &[&str],
+    ) -> Result<Self, handshake::Error> {
+        let mut input = git_packetline::StreamingPeekableIter::new(read, &[git_packetline::PacketLineRef::Flush]);
+        let lines = read_text_lines(&mut input)?.unwrap_or_default();
+        let mut lines = lines.iter();
+        let expected_welcome = format!("{}-client", welcome_prefix);
+        match lines.next() {
+            Some(line) if line == expected_welcome.as_str() => {}
+            actual => {
+                return Err(handshake::Error::Protocol {
+                    msg: format!("Expected '{}'", expected_welcome),
+                    actual: actual.cloned().unwrap_or_default(),
+                })
+            }
+        }
+        let offered: Vec<usize> = lines
+            .filter_map(|line| split_key_value(line.as_bstr()))
+            .filter(|(key, _)| *key == "version")
+            .filter_map(|(_, value)| value.to_str().ok()?.parse().ok())
+            .collect();
+        let version = offered
+            .iter()
+            .filter(|v| versions.contains(v))
+            .max()
+            .copied()
+            .ok_or(handshake::Error::UnsupportedVersion { offered })?;
+
+        let welcome = format!("{}-server", welcome_prefix);
+        let version_line = format!("version={}", version);
+        write_text_lines(&mut write, [welcome.as_str().into(), version_line.as_str().into()])?;
+        write.flush()?;
+
+        let agreed: Vec<BString> = read_text_lines(&mut input)?
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|line| split_key_value(line.as_bstr()))
+            .filter(|(key, value)| *key == "capability" && capabilities.iter().any(|c| value == c))
+            .map(|(_, value)| value.to_owned())
+            .collect();
+        let capability_lines: Vec<BString> = agreed
+            .iter()
+            .map(|c| {
+                let mut line = BString::from("capability=");
+                line.extend_from_slice(c);
+                line
+            })
+            .collect();
+        write_text_lines(&mut write, capability_lines.iter().map(|l| l.as_bstr()))?;
+        write.flush()?;
+
+        Ok(Server {
+            input,
+            out: write,
+            capabilities: agreed,
+            version,
+        })
+    }
+
+    /// Read the next request, or return `None` if the client closed the connection.
+    ///
+    /// The request must be answered by reading its content and writing a response before the next one can be obtained.
+    pub fn next_request(&mut self) -> Result<Option<Request<'_, R, W>>, next_request::Error> {
+        let lines = match read_text_lines(&mut self.input)? {
+            Some(lines) => lines,
+            None => return Ok(None),
+        };
+        let mut lines = lines.into_iter();
+        let command = match lines.next() {
+            Some(line) if line.starts_with(b"command=") => line[b"command=".len()..].into(),
+            actual => {
+                return Err(next_request::Error::MissingCommand {
+                    actual: actual.unwrap_or_default(),
+                })
+            }
+        };
+        let meta = lines
+            .filter_map(|line| split_key_value(line.as_bstr()).map(|(k, v)| (k.to_owned(), v.to_owned())))
+            .collect();
+        Ok(Some(Request {
+            parent: self,
+            command,
+            meta,
+        }))
+    }
+}
+
+/// Access
+impl<R, W> Server<R, W> {
+    /// Return the capabilities agreed on with the client.
+    pub fn capabilities(&self) -> &[BString] {
+        &self.capabilities
+    }
+
+    /// Return the protocol version agreed on with the client.
+    pub fn version(&self) -> usize {
+        self.version
+    }
+}
+
+impl<'a, R, W> Request<'a, R, W>
+where
+    R: Read,
+    W: Write,
+{
+    /// Return the value of the meta-data with `key`, like the `pathname`.
+    pub fn meta(&self, key: &str) -> Option<&BStr> {
+        self.meta.iter().find_map(|(k, v)| (k == key).then(|| v.as_bstr()))
+    }
+
+    /// Read the entire content sent along with the request into `out`.
+    pub fn read_content(&mut self, out: &mut Vec<u8>) -> std::io::Result<()> {
+        out.clear();
+        read_data(&mut self.parent.input, out)
+    }
+
+    /// Respond with `status`, which must not be [`Success`][Status::Success], after the content was read.
+    pub fn write_error(self, status: Status) -> std::io::Result<()> {
+        let line = format!("status={}", status.as_str());
+        write_text_lines(&mut self.parent.out, [line.as_str().into()])?;
+        self.parent.out.flush()
+    }
+
+    /// Respond with a successful status along with `content`, after the content was read.
+    pub fn write_content(self, content: &[u8]) -> std::io::Result<()> {
+        let out = &mut self.parent.out;
+        write_text_lines(&mut *out, ["status=success".into()])?;
+        write_data(&mut *out, content)?;
+        // An empty list means that the status remains unchanged.
+        write_text_lines(&mut *out, [])?;
+        out.flush()
+    }
+}
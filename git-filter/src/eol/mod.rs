@@ -0,0 +1,293 @@
+use bstr::ByteSlice;
+
+/// The kind of end of lines to use when converting text files, as configured by `core.eol`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Use a line feed, `\n`.
+    Lf,
+    /// Use a carriage return followed by a line feed, `\r\n`.
+    CrLf,
+}
+
+impl Default for Mode {
+    /// The native line ending of the current platform.
+    fn default() -> Self {
+        if cfg!(windows) {
+            Mode::CrLf
+        } else {
+            Mode::Lf
+        }
+    }
+}
+
+/// The value of `core.autocrlf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutoCrlf {
+    /// Convert line endings to `CRLF` on checkout and to `LF` when adding files with automatic text detection, `true`.
+    Enabled,
+    /// Convert line endings to `LF` when adding files with automatic text detection, but never on checkout, `input`.
+    Input,
+    /// Don't convert line endings unless attributes say so, `false`.
+    Disabled,
+}
+
+impl Default for AutoCrlf {
+    fn default() -> Self {
+        AutoCrlf::Disabled
+    }
+}
+
+/// The repository configuration that affects end-of-line conversions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Configuration {
+    /// The value of `core.autocrlf`.
+    pub auto_crlf: AutoCrlf,
+    /// The value of `core.eol`, or `None` to use the native line ending.
+    pub eol: Option<Mode>,
+}
+
+impl Configuration {
+    /// Return the line ending to use for text files in the worktree if no attribute says otherwise.
+    pub fn worktree_eol(&self) -> Mode {
+        match self.auto_crlf {
+            AutoCrlf::Enabled => Mode::CrLf,
+            AutoCrlf::Input => Mode::Lf,
+            AutoCrlf::Disabled => self.eol.unwrap_or_default(),
+        }
+    }
+}
+
+/// The way end-of-line conversions should be performed for a path, as derived from its `text`, `crlf` and `eol` attributes
+/// along with `core.autocrlf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttributesDigest {
+    /// The file is binary and must never be converted, like with `-text`.
+    Binary,
+    /// The file is text and uses the configured line endings in the worktree, like with `text`.
+    Text,
+    /// The file is text and uses `LF` in the worktree, like with `text eol=lf`.
+    TextInput,
+    /// The file is text and uses `CRLF` in the worktree, like with `text eol=crlf`.
+    TextCrlf,
+    /// The file is text if it doesn't look binary, and uses the configured line endings in the worktree, like with `text=auto`.
+    TextAuto,
+    /// The file is text if it doesn't look binary, and uses `LF` in the worktree, like with `text=auto eol=lf`.
+    TextAutoInput,
+    /// The file is text if it doesn't look binary, and uses `CRLF` in the worktree, like with `text=auto eol=crlf`.
+    TextAutoCrlf,
+}
+
+impl AttributesDigest {
+    /// Interpret the state of the `text`, `crlf` and `eol` attributes of a path along with the repository `config`
+    /// to determine how its line endings should be converted.
+    pub fn from_attributes(
+        text: Option<git_attributes::StateRef<'_>>,
+        crlf: Option<git_attributes::StateRef<'_>>,
+        eol: Option<git_attributes::StateRef<'_>>,
+        config: Configuration,
+    ) -> Self {
+        use git_attributes::StateRef;
+        fn text_or_crlf(state: Option<StateRef<'_>>) -> Option<AttributesDigest> {
+            Some(match state? {
+                StateRef::Set => AttributesDigest::Text,
+                StateRef::Unset => AttributesDigest::Binary,
+                StateRef::Value(v) if v.as_bstr() == "input" => AttributesDigest::TextInput,
+                StateRef::Value(v) if v.as_bstr() == "auto" => AttributesDigest::TextAuto,
+                StateRef::Value(_) | StateRef::Unspecified => return None,
+            })
+        }
+        let mut digest = text_or_crlf(text).or_else(|| text_or_crlf(crlf));
+        if digest != Some(AttributesDigest::Binary) {
+            let eol = match eol {
+                Some(StateRef::Value(v)) if v.as_bstr() == "lf" => Some(Mode::Lf),
+                Some(StateRef::Value(v)) if v.as_bstr() == "crlf" => Some(Mode::CrLf),
+                _ => None,
+            };
+            digest = match (digest, eol) {
+                (Some(AttributesDigest::TextAuto), Some(Mode::Lf)) => Some(AttributesDigest::TextAutoInput),
+                (Some(AttributesDigest::TextAuto), Some(Mode::CrLf)) => Some(AttributesDigest::TextAutoCrlf),
+                (_, Some(Mode::Lf)) => Some(AttributesDigest::TextInput),
+                (_, Some(Mode::CrLf)) => Some(AttributesDigest::TextCrlf),
+                (digest, None) => digest,
+            };
+        }
+        match digest {
+            Some(AttributesDigest::Text) => match config.worktree_eol() {
+                Mode::Lf => AttributesDigest::TextInput,
+                Mode::CrLf => AttributesDigest::TextCrlf,
+            },
+            Some(digest) => digest,
+            None => match config.auto_crlf {
+                AutoCrlf::Disabled => AttributesDigest::Binary,
+                AutoCrlf::Enabled => AttributesDigest::TextAutoCrlf,
+                AutoCrlf::Input => AttributesDigest::TextAutoInput,
+            },
+        }
+    }
+
+    /// Return the line ending to use in the worktree, or `None` if the file is binary.
+    fn worktree_eol(&self, config: Configuration) -> Option<Mode> {
+        Some(match self {
+            AttributesDigest::Binary => return None,
+            AttributesDigest::TextInput | AttributesDigest::TextAutoInput => Mode::Lf,
+            AttributesDigest::TextCrlf | AttributesDigest::TextAutoCrlf => Mode::CrLf,
+            AttributesDigest::Text | AttributesDigest::TextAuto => config.worktree_eol(),
+        })
+    }
+
+    fn is_auto(&self) -> bool {
+        matches!(
+            self,
+            AttributesDigest::TextAuto | AttributesDigest::TextAutoInput | AttributesDigest::TextAutoCrlf
+        )
+    }
+}
+
+/// Statistics about a buffer that help to determine if it's binary and which line endings it uses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of null bytes.
+    pub null: usize,
+    /// The amount of carriage returns that aren't followed by a line feed.
+    pub lone_cr: usize,
+    /// The amount of line feeds that aren't preceded by a carriage return.
+    pub lone_lf: usize,
+    /// The amount of carriage returns followed by a line feed.
+    pub crlf: usize,
+    /// The amount of printable characters.
+    pub printable: usize,
+    /// The amount of non-printable characters.
+    pub non_printable: usize,
+}
+
+impl Stats {
+    /// Gather statistics about `data`.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut stats = Stats::default();
+        let mut bytes = data.iter().peekable();
+        while let Some(&byte) = bytes.next() {
+            match byte {
+                b'\r' => {
+                    if bytes.next_if_eq(&&b'\n').is_some() {
+                        stats.crlf += 1;
+                    } else {
+                        stats.lone_cr += 1;
+                    }
+                }
+                b'\n' => stats.lone_lf += 1,
+                127 => stats.non_printable += 1,
+                // BS, HT, ESC and FF
+                b'\x08' | b'\t' | b'\x1b' | b'\x0c' => stats.printable += 1,
+                0 => {
+                    stats.null += 1;
+                    stats.non_printable += 1;
+                }
+                byte if byte < 32 => stats.non_printable += 1,
+                _ => stats.printable += 1,
+            }
+        }
+        // An EOF character at the end of the data doesn't count as non-printable.
+        if data.last() == Some(&b'\x1a') {
+            stats.non_printable -= 1;
+        }
+        stats
+    }
+
+    /// Return true if the data these statistics were gathered from is considered binary, which is the case if there are
+    /// null bytes, lone carriage returns or too many non-printable characters.
+    pub fn is_binary(&self) -> bool {
+        self.lone_cr > 0 || self.null > 0 || (self.printable >> 7) < self.non_printable
+    }
+
+    fn will_convert_lf_to_crlf(&self, digest: AttributesDigest, config: Configuration) -> bool {
+        if digest.worktree_eol(config) != Some(Mode::CrLf) || self.lone_lf == 0 {
+            return false;
+        }
+        if digest.is_auto() && (self.lone_cr > 0 || self.crlf > 0 || self.is_binary()) {
+            return false;
+        }
+        true
+    }
+}
+
+///
+pub mod convert_to_git {
+    /// The error returned by [`convert_to_git()`][super::convert_to_git()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not obtain the object in the index to see if it has CRLF line endings")]
+        FetchObjectFromIndex(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    }
+}
+
+/// Convert all `CRLF` line endings in `src` to `LF` and write the result into `out`, if the end-of-line conversions
+/// in `digest` require it. Return `true` if `out` was written, or `false` if `src` remains unchanged.
+///
+/// `index_object(buf)` is called to write the object currently in the index for the same path into `buf`, returning
+/// `None` if there is no such object. If it already has `CRLF` line endings, the conversion is skipped for files with
+/// automatic text detection, just like `git` does to avoid changing them on each commit.
+pub fn convert_to_git(
+    src: &[u8],
+    digest: AttributesDigest,
+    out: &mut Vec<u8>,
+    index_object: impl FnOnce(&mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync + 'static>>,
+) -> Result<bool, convert_to_git::Error> {
+    if digest == AttributesDigest::Binary || src.is_empty() {
+        return Ok(false);
+    }
+    let stats = Stats::from_bytes(src);
+    if digest.is_auto() {
+        if stats.is_binary() {
+            return Ok(false);
+        }
+        let mut buf = Vec::new();
+        if stats.crlf > 0
+            && index_object(&mut buf)
+                .map_err(convert_to_git::Error::FetchObjectFromIndex)?
+                .map_or(false, |_| {
+                    let stats = Stats::from_bytes(&buf);
+                    !stats.is_binary() && stats.crlf > 0
+                })
+        {
+            return Ok(false);
+        }
+    }
+    if stats.crlf == 0 {
+        return Ok(false);
+    }
+
+    out.clear();
+    out.reserve(src.len() - stats.crlf);
+    let mut bytes = src.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if !(byte == b'\r' && bytes.peek() == Some(&&b'\n')) {
+            out.push(byte);
+        }
+    }
+    Ok(true)
+}
+
+/// Convert all `LF` line endings in `src` to `CRLF` and write the result into `out`, if the end-of-line conversions
+/// in `digest` along with `config` require it. Return `true` if `out` was written, or `false` if `src` remains unchanged.
+pub fn convert_to_worktree(src: &[u8], digest: AttributesDigest, out: &mut Vec<u8>, config: Configuration) -> bool {
+    if src.is_empty() || digest.worktree_eol(config) != Some(Mode::CrLf) {
+        return false;
+    }
+    let stats = Stats::from_bytes(src);
+    if !stats.will_convert_lf_to_crlf(digest, config) {
+        return false;
+    }
+
+    out.clear();
+    out.reserve(src.len() + stats.lone_lf);
+    let mut previous = None;
+    for &byte in src {
+        if byte == b'\n' && previous != Some(b'\r') {
+            out.push(b'\r');
+        }
+        out.push(byte);
+        previous = Some(byte);
+    }
+    true
+}
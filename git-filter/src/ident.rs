@@ -0,0 +1,72 @@
+use bstr::ByteSlice;
+
+/// Find the byte range of the next `$Id…$` keyword in `src`, either unexpanded as `$Id$` or expanded as `$Id: …$`,
+/// as long as it doesn't span multiple lines.
+fn find_keyword(src: &[u8]) -> Option<std::ops::Range<usize>> {
+    let mut pos = 0;
+    while let Some(start) = src[pos..].find(b"$Id").map(|ofs| pos + ofs) {
+        let after_keyword = start + 3;
+        match src.get(after_keyword) {
+            Some(b'$') => return Some(start..after_keyword + 1),
+            Some(b':') => {
+                let rest = &src[after_keyword + 1..];
+                match rest.find_byteset(b"$\n") {
+                    Some(end) if rest[end] == b'$' => return Some(start..after_keyword + 1 + end + 1),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        pos = after_keyword;
+    }
+    None
+}
+
+/// Replace all expanded `$Id: <hex>$` keywords in `src` with `$Id$` and write the result into `out`, as is done for files
+/// with the `ident` attribute when they are added to the object database.
+/// Return `true` if `out` was written, or `false` if `src` remains unchanged.
+pub fn undo(src: &[u8], out: &mut Vec<u8>) -> bool {
+    let mut written = false;
+    let (mut pos, mut copied) = (0, 0);
+    while let Some(range) = find_keyword(&src[pos..]).map(|r| (r.start + pos)..(r.end + pos)) {
+        pos = range.end;
+        if range.len() == b"$Id$".len() {
+            continue;
+        }
+        if !written {
+            out.clear();
+            written = true;
+        }
+        out.extend_from_slice(&src[copied..range.start]);
+        out.extend_from_slice(b"$Id$");
+        copied = range.end;
+    }
+    if written {
+        out.extend_from_slice(&src[copied..]);
+    }
+    written
+}
+
+/// Replace all `$Id$` keywords, and all expanded ones as well, with `$Id: <id>$`, where `id` is the hash of the
+/// object whose data is in `src`, and write the result into `out`. This is done for files with the `ident` attribute
+/// when they are checked out.
+/// Return `true` if `out` was written, or `false` if `src` remains unchanged.
+pub fn apply(src: &[u8], id: &git_hash::oid, out: &mut Vec<u8>) -> bool {
+    let mut written = false;
+    let mut pos = 0;
+    while let Some(range) = find_keyword(&src[pos..]).map(|r| (r.start + pos)..(r.end + pos)) {
+        if !written {
+            out.clear();
+            written = true;
+        }
+        out.extend_from_slice(&src[pos..range.start]);
+        out.extend_from_slice(b"$Id: ");
+        out.extend_from_slice(id.to_hex().to_string().as_bytes());
+        out.extend_from_slice(b" $");
+        pos = range.end;
+    }
+    if written {
+        out.extend_from_slice(&src[pos..]);
+    }
+    written
+}
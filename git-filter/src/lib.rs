@@ -1,2 +1,56 @@
-#![deny(rust_2018_idioms)]
+//! A library for implementing everything needed to deal with git filters, which convert the content of files when they
+//! are written into the object database (_clean_) or checked out into the worktree (_smudge_).
+//!
+//! This includes end-of-line conversions, `ident` substitution, `working-tree-encoding` as well as user-defined
+//! filter drivers, which are either invoked once per file or as long-running process using the filter protocol.
+//! All of these are driven by attributes and combined in a [`Pipeline`].
+#![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
+
+use bstr::BString;
+
+///
+pub mod eol;
+
+///
+pub mod ident;
+
+///
+pub mod worktree;
+
+///
+pub mod driver;
+
+///
+pub mod pipeline;
+
+/// A user-defined filter driver as configured in `filter.<name>`, which is activated for paths with the `filter=<name>`
+/// attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Driver {
+    /// The name of the driver as used in the `filter` attribute.
+    pub name: BString,
+    /// The command to run to convert content from the worktree into the object database, if set.
+    ///
+    /// `%f` in the command is substituted with the quoted path of the file being filtered.
+    pub clean: Option<BString>,
+    /// The command to run to convert content from the object database into the worktree, if set.
+    ///
+    /// `%f` in the command is substituted with the quoted path of the file being filtered.
+    pub smudge: Option<BString>,
+    /// The command to launch a long-running filter process, which takes precedence over `clean` and `smudge` if set.
+    pub process: Option<BString>,
+    /// If true, a failure to run the filter is an error, instead of passing the content through unchanged.
+    pub required: bool,
+}
+
+/// A combination of all filters that are applied in a certain order to convert content between the worktree and
+/// the object database, as driven by the attributes of each path.
+///
+/// It's meant to be kept alive for multiple files as it keeps long-running filter processes alive between them.
+pub struct Pipeline {
+    options: pipeline::Options,
+    drivers: Vec<Driver>,
+    processes: Vec<(BString, driver::process::Client)>,
+    buf: Vec<u8>,
+}
@@ -0,0 +1,286 @@
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{driver, eol, ident, worktree, Driver, Pipeline};
+
+/// Options for use in a [`Pipeline`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The repository configuration affecting end-of-line conversions.
+    pub eol_config: eol::Configuration,
+}
+
+///
+pub mod convert {
+    use bstr::BString;
+
+    /// The error returned by [`Pipeline::convert_to_git()`][crate::Pipeline::convert_to_git()] and
+    /// [`Pipeline::convert_to_worktree()`][crate::Pipeline::convert_to_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Eol(#[from] crate::eol::convert_to_git::Error),
+        #[error("The working-tree-encoding '{label}' of '{rela_path}' is unknown")]
+        UnknownWorktreeEncoding { label: BString, rela_path: BString },
+        #[error(transparent)]
+        EncodeToGit(#[from] crate::worktree::encode_to_git::Error),
+        #[error(transparent)]
+        EncodeToWorktree(#[from] crate::worktree::encode_to_worktree::Error),
+        #[error("The required {operation} filter '{name}' failed for '{rela_path}'")]
+        RequiredDriverFailed {
+            name: BString,
+            operation: &'static str,
+            rela_path: BString,
+            #[source]
+            source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+        },
+    }
+}
+
+/// The name of the capability-welcome prefix and versions of the filter process protocol.
+const PROCESS_WELCOME_PREFIX: &str = "git-filter";
+const PROCESS_VERSIONS: &[usize] = &[2];
+const PROCESS_CAPABILITIES: &[&str] = &["clean", "smudge"];
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Pipeline::new(Default::default(), Vec::new())
+    }
+}
+
+impl Clone for Pipeline {
+    /// Clone the configuration of this instance, but not the running filter processes, which are started on demand.
+    fn clone(&self) -> Self {
+        Pipeline::new(self.options, self.drivers.clone())
+    }
+}
+
+/// Lifecycle
+impl Pipeline {
+    /// Create a new instance with `options` and filter `drivers` as configured in `filter.<name>`.
+    pub fn new(options: Options, drivers: Vec<Driver>) -> Self {
+        Pipeline {
+            options,
+            drivers,
+            processes: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+/// Access
+impl Pipeline {
+    /// Return the options this instance was created with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Return the filter drivers this instance was created with.
+    pub fn drivers(&self) -> &[Driver] {
+        &self.drivers
+    }
+}
+
+/// Conversions
+impl Pipeline {
+    /// Convert `src`, the content of the file at the repository-relative `rela_path` in the worktree, so it can be stored
+    /// in the object database, applying the filters as configured by the `attributes` of `rela_path`.
+    ///
+    /// `index_object(buf)` is called to write the object currently stored in the index for `rela_path` into `buf`,
+    /// returning `None` if there is none. It's used to see if the file already had `CRLF` line endings.
+    ///
+    /// Return `true` if the converted content was written to `out`, or `false` if `src` is unchanged and `out` wasn't touched.
+    pub fn convert_to_git(
+        &mut self,
+        src: &[u8],
+        rela_path: &BStr,
+        attributes: &git_attributes::Outcome,
+        index_object: impl FnOnce(&mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync + 'static>>,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, convert::Error> {
+        let mut changed = false;
+        if let Some(name) = driver_name(attributes) {
+            let input = if changed { out.as_slice() } else { src };
+            if self.apply_driver(name, driver::Operation::Clean, input, rela_path)? {
+                std::mem::swap(out, &mut self.buf);
+                changed = true;
+            }
+        }
+        if let Some(encoding) = worktree_encoding(attributes, rela_path)? {
+            let input = if changed { out.as_slice() } else { src };
+            worktree::encode_to_git(input, encoding, &mut self.buf)?;
+            std::mem::swap(out, &mut self.buf);
+            changed = true;
+        }
+        let digest = self.eol_digest(attributes);
+        let input = if changed { out.as_slice() } else { src };
+        if eol::convert_to_git(input, digest, &mut self.buf, index_object)? {
+            std::mem::swap(out, &mut self.buf);
+            changed = true;
+        }
+        if is_ident(attributes) {
+            let input = if changed { out.as_slice() } else { src };
+            if ident::undo(input, &mut self.buf) {
+                std::mem::swap(out, &mut self.buf);
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Convert `src`, the content of the object with `id` that is to be written to the repository-relative `rela_path`
+    /// in the worktree, applying the filters as configured by the `attributes` of `rela_path`.
+    ///
+    /// Return `true` if the converted content was written to `out`, or `false` if `src` is unchanged and `out` wasn't touched.
+    pub fn convert_to_worktree(
+        &mut self,
+        src: &[u8],
+        rela_path: &BStr,
+        id: &git_hash::oid,
+        attributes: &git_attributes::Outcome,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, convert::Error> {
+        let mut changed = false;
+        if is_ident(attributes) && ident::apply(src, id, &mut self.buf) {
+            std::mem::swap(out, &mut self.buf);
+            changed = true;
+        }
+        let digest = self.eol_digest(attributes);
+        let input = if changed { out.as_slice() } else { src };
+        if eol::convert_to_worktree(input, digest, &mut self.buf, self.options.eol_config) {
+            std::mem::swap(out, &mut self.buf);
+            changed = true;
+        }
+        if let Some(encoding) = worktree_encoding(attributes, rela_path)? {
+            let input = if changed { out.as_slice() } else { src };
+            worktree::encode_to_worktree(input, encoding, &mut self.buf)?;
+            std::mem::swap(out, &mut self.buf);
+            changed = true;
+        }
+        if let Some(name) = driver_name(attributes) {
+            let input = if changed { out.as_slice() } else { src };
+            if self.apply_driver(name, driver::Operation::Smudge, input, rela_path)? {
+                std::mem::swap(out, &mut self.buf);
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn eol_digest(&self, attributes: &git_attributes::Outcome) -> eol::AttributesDigest {
+        eol::AttributesDigest::from_attributes(
+            attributes.get("text"),
+            attributes.get("crlf"),
+            attributes.get("eol"),
+            self.options.eol_config,
+        )
+    }
+
+    /// Apply the driver named `name` for `operation` to `input` and write the result into `self.buf`,
+    /// returning `true` if that happened.
+    fn apply_driver(
+        &mut self,
+        name: &BStr,
+        operation: driver::Operation,
+        input: &[u8],
+        rela_path: &BStr,
+    ) -> Result<bool, convert::Error> {
+        let driver = match self.drivers.iter().find(|d| d.name == name) {
+            Some(driver) => driver,
+            None => return Ok(false),
+        };
+        let failed = |source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>| {
+            if driver.required {
+                Err(convert::Error::RequiredDriverFailed {
+                    name: driver.name.clone(),
+                    operation: operation.as_str(),
+                    rela_path: rela_path.to_owned(),
+                    source,
+                })
+            } else {
+                Ok(false)
+            }
+        };
+
+        let process = match driver.process.as_ref().filter(|p| !p.is_empty()) {
+            Some(process) => process,
+            None => {
+                return match driver.apply(operation, input, rela_path, &mut self.buf) {
+                    Ok(true) => Ok(true),
+                    Ok(false) => failed(None),
+                    Err(err) => failed(Some(err.into())),
+                }
+            }
+        };
+
+        let client_idx = match self.processes.iter().position(|(n, _)| n == name) {
+            Some(idx) => idx,
+            None => {
+                let cmd = git_command::prepare(git_path::from_bstr(process.as_bstr()).as_ref())
+                    .with_shell()
+                    .into();
+                match driver::process::Client::handshake(
+                    cmd,
+                    PROCESS_WELCOME_PREFIX,
+                    PROCESS_VERSIONS,
+                    PROCESS_CAPABILITIES,
+                ) {
+                    Ok(client) => {
+                        self.processes.push((driver.name.clone(), client));
+                        self.processes.len() - 1
+                    }
+                    Err(err) => return failed(Some(err.into())),
+                }
+            }
+        };
+        let client = &mut self.processes[client_idx].1;
+        if !client.has_capability(operation.as_str()) {
+            return failed(None);
+        }
+        match client.invoke(operation.as_str(), Some(("pathname", rela_path)), input, &mut self.buf) {
+            Ok(driver::process::Status::Success) => Ok(true),
+            Ok(driver::process::Status::Error) => failed(None),
+            Ok(driver::process::Status::Abort) => {
+                client.remove_capability(operation.as_str());
+                failed(None)
+            }
+            Err(err) => {
+                // The process is in an unknown state, so it's shut down and possibly restarted on the next invocation.
+                self.processes.remove(client_idx);
+                failed(Some(err.into()))
+            }
+        }
+    }
+}
+
+fn is_ident(attributes: &git_attributes::Outcome) -> bool {
+    attributes.get("ident") == Some(git_attributes::StateRef::Set)
+}
+
+fn driver_name(attributes: &git_attributes::Outcome) -> Option<&BStr> {
+    match attributes.get("filter")? {
+        git_attributes::StateRef::Value(name) => Some(name.as_bstr()),
+        _ => None,
+    }
+}
+
+fn worktree_encoding(
+    attributes: &git_attributes::Outcome,
+    rela_path: &BStr,
+) -> Result<Option<worktree::WorktreeEncoding>, convert::Error> {
+    match attributes.get("working-tree-encoding") {
+        Some(git_attributes::StateRef::Value(label)) => {
+            let label = label.as_bytes();
+            if label.eq_ignore_ascii_case(b"utf-8") || label.eq_ignore_ascii_case(b"utf8") {
+                return Ok(None);
+            }
+            worktree::WorktreeEncoding::for_label(label).map(Some).ok_or_else(|| {
+                convert::Error::UnknownWorktreeEncoding {
+                    label: BString::from(label),
+                    rela_path: rela_path.to_owned(),
+                }
+            })
+        }
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,121 @@
+//! Conversions for the `working-tree-encoding` attribute, which allows files to be stored as UTF-8 in the object database
+//! while using a different encoding in the worktree.
+use bstr::ByteSlice;
+use encoding_rs::Encoding;
+
+/// An encoding as named in the `working-tree-encoding` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorktreeEncoding {
+    encoding: &'static Encoding,
+    /// If true, a byte-order mark is written when encoding, and expected when decoding, as the encoding doesn't specify the
+    /// byte order like `UTF-16`.
+    with_bom: bool,
+}
+
+impl WorktreeEncoding {
+    /// Return the encoding for `label`, like `UTF-16LE` or `ISO-8859-1`, or `None` if it is unknown or if it is UTF-8,
+    /// which doesn't need any conversion.
+    pub fn for_label(label: &[u8]) -> Option<Self> {
+        let encoding = Encoding::for_label(label.trim())?;
+        if encoding == encoding_rs::UTF_8 {
+            return None;
+        }
+        Some(WorktreeEncoding {
+            encoding,
+            with_bom: label.trim().eq_ignore_ascii_case(b"utf-16"),
+        })
+    }
+
+    /// The canonical name of the encoding.
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+}
+
+///
+pub mod encode_to_git {
+    /// The error returned by [`encode_to_git()`][super::encode_to_git()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not decode content from the worktree as {encoding}")]
+        Malformed { encoding: &'static str },
+    }
+}
+
+/// Decode `src` from the worktree `encoding` and write it as UTF-8 into `out`.
+pub fn encode_to_git(src: &[u8], encoding: WorktreeEncoding, out: &mut Vec<u8>) -> Result<(), encode_to_git::Error> {
+    let err = || encode_to_git::Error::Malformed {
+        encoding: encoding.name(),
+    };
+    let (decoder, src) = match Encoding::for_bom(src) {
+        Some((bom_encoding, bom_len))
+            if bom_encoding == encoding.encoding
+                || (encoding.with_bom && [encoding_rs::UTF_16LE, encoding_rs::UTF_16BE].contains(&bom_encoding)) =>
+        {
+            (bom_encoding, &src[bom_len..])
+        }
+        _ if encoding.with_bom => return Err(err()),
+        _ => (encoding.encoding, src),
+    };
+    let decoded = decoder
+        .decode_without_bom_handling_and_without_replacement(src)
+        .ok_or_else(err)?;
+    out.clear();
+    out.extend_from_slice(decoded.as_bytes());
+    Ok(())
+}
+
+///
+pub mod encode_to_worktree {
+    /// The error returned by [`encode_to_worktree()`][super::encode_to_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Content isn't valid UTF-8 and can't be encoded as {encoding}")]
+        InputNotUtf8 { encoding: &'static str },
+        #[error("Content contains characters that can't be encoded as {encoding}")]
+        Unmappable { encoding: &'static str },
+    }
+}
+
+/// Encode the UTF-8 in `src` with the worktree `encoding` and write it into `out`.
+pub fn encode_to_worktree(
+    src: &[u8],
+    encoding: WorktreeEncoding,
+    out: &mut Vec<u8>,
+) -> Result<(), encode_to_worktree::Error> {
+    let src = std::str::from_utf8(src).map_err(|_| encode_to_worktree::Error::InputNotUtf8 {
+        encoding: encoding.name(),
+    })?;
+    out.clear();
+    // UTF-16 can only be decoded by `encoding_rs`, so it's encoded by hand.
+    let utf16_to_bytes: Option<fn(u16) -> [u8; 2]> = if encoding.encoding == encoding_rs::UTF_16LE {
+        Some(u16::to_le_bytes)
+    } else if encoding.encoding == encoding_rs::UTF_16BE {
+        Some(u16::to_be_bytes)
+    } else {
+        None
+    };
+    match utf16_to_bytes {
+        Some(to_bytes) => {
+            if encoding.with_bom {
+                out.extend_from_slice(&to_bytes(0xfeff));
+            }
+            out.reserve(src.len() * 2);
+            for unit in src.encode_utf16() {
+                out.extend_from_slice(&to_bytes(unit));
+            }
+        }
+        None => {
+            let (encoded, _, had_unmappable_characters) = encoding.encoding.encode(src);
+            if had_unmappable_characters {
+                return Err(encode_to_worktree::Error::Unmappable {
+                    encoding: encoding.name(),
+                });
+            }
+            out.extend_from_slice(&encoded);
+        }
+    }
+    Ok(())
+}
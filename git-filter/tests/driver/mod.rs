@@ -0,0 +1,180 @@
+use bstr::ByteSlice;
+use git_filter::{
+    driver::{process, Operation},
+    Driver,
+};
+
+use crate::rot13_program;
+
+fn driver(clean: Option<&str>, smudge: Option<&str>) -> Driver {
+    let program = rot13_program().to_str().expect("valid UTF-8").to_owned();
+    Driver {
+        name: "rot13".into(),
+        clean: clean.map(|args| format!("{} {}", program, args).into()),
+        smudge: smudge.map(|args| format!("{} {}", program, args).into()),
+        ..Default::default()
+    }
+}
+
+mod single_shot {
+    use bstr::ByteSlice;
+    use git_filter::driver::Operation;
+
+    use super::driver;
+
+    #[test]
+    fn apply_runs_the_configured_command() -> crate::Result {
+        let driver = driver(Some("clean"), None);
+        let mut out = Vec::new();
+        assert!(driver.apply(Operation::Clean, b"Hello", "file".into(), &mut out)?);
+        assert_eq!(out.as_bstr(), "Uryyb");
+
+        assert!(
+            !driver.apply(Operation::Smudge, b"Hello", "file".into(), &mut out)?,
+            "there is no smudge command"
+        );
+        assert_eq!(out.as_bstr(), "Uryyb", "out isn't touched if no command was run");
+        Ok(())
+    }
+
+    #[test]
+    fn apply_substitutes_the_path() -> crate::Result {
+        let driver = git_filter::Driver {
+            name: "echo".into(),
+            smudge: Some("printf '%%s' %f".into()),
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        assert!(driver.apply(Operation::Smudge, b"", "a b'c".into(), &mut out)?);
+        assert_eq!(out.as_bstr(), "a b'c");
+        Ok(())
+    }
+
+    #[test]
+    fn failing_commands_are_errors() {
+        let driver = driver(Some("fail"), None);
+        let mut out = Vec::new();
+        assert!(matches!(
+            driver.apply(Operation::Clean, b"Hello", "file".into(), &mut out),
+            Err(git_filter::driver::apply::Error::Status { code: Some(1), .. })
+        ));
+    }
+}
+
+mod process_client {
+    use super::*;
+
+    fn client() -> process::Client {
+        let mut cmd = std::process::Command::new(rot13_program());
+        cmd.arg("process");
+        process::Client::handshake(cmd, "git-filter", &[2], &["clean", "smudge", "delay"]).expect("handshake succeeds")
+    }
+
+    #[test]
+    fn handshake_negotiates_version_and_capabilities() {
+        let client = client();
+        assert_eq!(client.version(), 2);
+        assert_eq!(
+            client.capabilities(),
+            ["clean", "smudge"],
+            "the server only agrees to what it supports"
+        );
+    }
+
+    #[test]
+    fn invoke_multiple_times_and_handle_failures() -> crate::Result {
+        let mut client = client();
+        let mut out = Vec::new();
+        for (content, expected) in [("Hello", "Uryyb"), ("", ""), ("World", "Jbeyq")] {
+            let status = client.invoke(
+                Operation::Smudge.as_str(),
+                Some(("pathname", "a.txt".into())),
+                content.as_bytes(),
+                &mut out,
+            )?;
+            assert_eq!(status, process::Status::Success);
+            assert_eq!(out.as_bstr(), expected);
+        }
+
+        let large = "a".repeat(200_000);
+        let status = client.invoke("clean", Some(("pathname", "large".into())), large.as_bytes(), &mut out)?;
+        assert_eq!(status, process::Status::Success);
+        assert_eq!(
+            out,
+            "n".repeat(200_000).as_bytes(),
+            "content is split into multiple packet lines"
+        );
+
+        for (path, expected) in [
+            ("error.txt", process::Status::Error),
+            ("abort.txt", process::Status::Abort),
+        ] {
+            let status = client.invoke("clean", Some(("pathname", path.into())), b"content", &mut out)?;
+            assert_eq!(status, expected);
+        }
+        let status = client.invoke("clean", Some(("pathname", "a.txt".into())), b"Hi", &mut out)?;
+        assert_eq!(
+            status,
+            process::Status::Success,
+            "the process is still usable after errors"
+        );
+        assert_eq!(out.as_bstr(), "Uv");
+        Ok(())
+    }
+
+    #[test]
+    fn invoke_fails_if_the_status_is_missing() {
+        let mut client = client();
+        let mut out = Vec::new();
+        assert!(matches!(
+            client.invoke(
+                "clean",
+                Some(("pathname", "no-status.txt".into())),
+                b"content",
+                &mut out
+            ),
+            Err(process::client::invoke::Error::MissingStatus)
+        ));
+        assert!(out.is_empty(), "nothing is written without success");
+    }
+
+    #[test]
+    fn handshake_fails_for_programs_not_speaking_the_protocol() {
+        let mut cmd = std::process::Command::new(rot13_program());
+        cmd.arg("fail");
+        assert!(process::Client::handshake(cmd, "git-filter", &[2], &["clean"]).is_err());
+    }
+}
+
+mod process_server {
+    use super::*;
+
+    #[test]
+    fn git_uses_it_as_filter_process() -> crate::Result {
+        let dir = git_testtools::tempfile::tempdir()?;
+        let git = |args: &[&str]| -> crate::Result<Vec<u8>> {
+            let out = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .output()?;
+            assert!(out.status.success(), "git {:?} failed: {}", args, out.stderr.as_bstr());
+            Ok(out.stdout)
+        };
+        git(&["init", "-q"])?;
+        git(&[
+            "config",
+            "filter.rot13.process",
+            &format!("{} process", rot13_program().display()),
+        ])?;
+        git(&["config", "filter.rot13.required", "true"])?;
+        std::fs::write(dir.path().join(".gitattributes"), "*.txt filter=rot13\n")?;
+        std::fs::write(dir.path().join("a.txt"), "Hello\n")?;
+        git(&["add", "a.txt"])?;
+        assert_eq!(git(&["cat-file", "-p", ":a.txt"])?.as_bstr(), "Uryyb\n");
+
+        std::fs::remove_file(dir.path().join("a.txt"))?;
+        git(&["checkout", "a.txt"])?;
+        assert_eq!(std::fs::read(dir.path().join("a.txt"))?.as_bstr(), "Hello\n");
+        Ok(())
+    }
+}
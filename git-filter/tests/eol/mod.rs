@@ -0,0 +1,186 @@
+use git_filter::eol::{self, AttributesDigest, AutoCrlf, Configuration, Mode, Stats};
+
+fn no_index_object(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    Ok(None)
+}
+
+mod stats {
+    use super::Stats;
+
+    #[test]
+    fn counts_line_endings() {
+        let stats = Stats::from_bytes(b"a\r\nb\nc\rd\n");
+        assert_eq!(stats.crlf, 1);
+        assert_eq!(stats.lone_lf, 2);
+        assert_eq!(stats.lone_cr, 1);
+        assert!(stats.is_binary(), "lone CRs make it binary");
+    }
+
+    #[test]
+    fn nul_bytes_are_binary() {
+        assert!(Stats::from_bytes(b"a\0b\n").is_binary());
+        assert!(!Stats::from_bytes(b"hello\r\nworld\n").is_binary());
+    }
+}
+
+mod attributes_digest {
+    use git_attributes::StateRef;
+
+    use super::{AttributesDigest, AutoCrlf, Configuration, Mode};
+
+    #[test]
+    fn text_attributes_take_precedence_over_autocrlf() {
+        let config = Configuration {
+            auto_crlf: AutoCrlf::Enabled,
+            eol: None,
+        };
+        assert_eq!(
+            AttributesDigest::from_attributes(Some(StateRef::Unset), None, None, config),
+            AttributesDigest::Binary
+        );
+        assert_eq!(
+            AttributesDigest::from_attributes(Some(StateRef::Set), None, Some(StateRef::Value("lf".into())), config),
+            AttributesDigest::TextInput
+        );
+        assert_eq!(
+            AttributesDigest::from_attributes(Some(StateRef::Value("auto".into())), None, None, config),
+            AttributesDigest::TextAuto
+        );
+    }
+
+    #[test]
+    fn unspecified_text_is_decided_by_autocrlf() {
+        let config = |auto_crlf| Configuration {
+            auto_crlf,
+            eol: Some(Mode::Lf),
+        };
+        assert_eq!(
+            AttributesDigest::from_attributes(None, None, None, config(AutoCrlf::Disabled)),
+            AttributesDigest::Binary
+        );
+        assert_eq!(
+            AttributesDigest::from_attributes(None, None, None, config(AutoCrlf::Enabled)),
+            AttributesDigest::TextAutoCrlf
+        );
+        assert_eq!(
+            AttributesDigest::from_attributes(None, None, None, config(AutoCrlf::Input)),
+            AttributesDigest::TextAutoInput
+        );
+    }
+
+    #[test]
+    fn legacy_crlf_attribute_is_used_if_text_is_unspecified() {
+        let config = Configuration::default();
+        assert_eq!(
+            AttributesDigest::from_attributes(None, Some(StateRef::Value("input".into())), None, config),
+            AttributesDigest::TextInput
+        );
+        assert_eq!(
+            AttributesDigest::from_attributes(None, Some(StateRef::Unset), None, config),
+            AttributesDigest::Binary
+        );
+    }
+}
+
+#[test]
+fn to_git_removes_crlf_in_text_files() -> crate::Result {
+    let mut out = Vec::new();
+    assert!(eol::convert_to_git(
+        b"a\r\nb\r\n",
+        AttributesDigest::Text,
+        &mut out,
+        no_index_object
+    )?);
+    assert_eq!(out, b"a\nb\n");
+
+    assert!(
+        !eol::convert_to_git(b"a\r\nb\r\n", AttributesDigest::Binary, &mut out, no_index_object)?,
+        "binary files are never converted"
+    );
+    assert!(
+        !eol::convert_to_git(b"a\nb\n", AttributesDigest::Text, &mut out, no_index_object)?,
+        "nothing to do"
+    );
+    Ok(())
+}
+
+#[test]
+fn to_git_auto_keeps_crlf_if_the_index_has_them_too() -> crate::Result {
+    let mut out = Vec::new();
+    assert!(!eol::convert_to_git(
+        b"a\r\nb\r\n",
+        AttributesDigest::TextAuto,
+        &mut out,
+        |buf| {
+            buf.extend_from_slice(b"a\r\n");
+            Ok(Some(()))
+        }
+    )?);
+    assert!(
+        !eol::convert_to_git(b"a\r\n\0", AttributesDigest::TextAuto, &mut out, no_index_object)?,
+        "binary files are detected"
+    );
+    assert!(eol::convert_to_git(
+        b"a\r\nb\r\n",
+        AttributesDigest::TextAuto,
+        &mut out,
+        no_index_object
+    )?);
+    assert_eq!(out, b"a\nb\n");
+    Ok(())
+}
+
+#[test]
+fn to_worktree_adds_crlf_only_if_configured() {
+    let crlf = Configuration {
+        auto_crlf: AutoCrlf::Disabled,
+        eol: Some(Mode::CrLf),
+    };
+    let mut out = Vec::new();
+    assert!(eol::convert_to_worktree(
+        b"a\nb\r\n",
+        AttributesDigest::Text,
+        &mut out,
+        crlf
+    ));
+    assert_eq!(out, b"a\r\nb\r\n");
+
+    assert!(!eol::convert_to_worktree(
+        b"a\nb\n",
+        AttributesDigest::TextInput,
+        &mut out,
+        crlf
+    ));
+    assert!(!eol::convert_to_worktree(
+        b"a\nb\n",
+        AttributesDigest::Text,
+        &mut out,
+        Configuration {
+            eol: Some(Mode::Lf),
+            ..crlf
+        }
+    ));
+    assert!(eol::convert_to_worktree(
+        b"a\nb\n",
+        AttributesDigest::TextCrlf,
+        &mut out,
+        Configuration::default()
+    ));
+}
+
+#[test]
+fn to_worktree_auto_leaves_files_with_crlf_alone() {
+    let mut out = Vec::new();
+    let config = Configuration::default();
+    assert!(
+        !eol::convert_to_worktree(b"a\r\nb\n", AttributesDigest::TextAutoCrlf, &mut out, config),
+        "mixed line endings are left as is"
+    );
+    assert!(eol::convert_to_worktree(
+        b"a\nb\n",
+        AttributesDigest::TextAutoCrlf,
+        &mut out,
+        config
+    ));
+    assert_eq!(out, b"a\r\nb\r\n");
+}
@@ -0,0 +1,39 @@
+mod driver;
+mod eol;
+mod ident;
+mod pipeline;
+mod worktree;
+
+/// Build the `rot13` example filter program once and return the path to it.
+fn rot13_program() -> &'static std::path::Path {
+    static PROGRAM: git_testtools::once_cell::sync::Lazy<std::path::PathBuf> =
+        git_testtools::once_cell::sync::Lazy::new(|| {
+            let mut cmd = std::process::Command::new(env!("CARGO"));
+            cmd.args(["build", "--example", "rot13"]);
+            assert!(
+                cmd.spawn().unwrap().wait().expect("example builds OK").success(),
+                "example must build"
+            );
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("target")
+                .join("debug")
+                .join("examples")
+                .join("rot13")
+                .canonicalize()
+                .expect("example was built")
+        });
+    PROGRAM.as_path()
+}
+
+/// Return the attributes assigned to `file` by the attribute `line`, like `* text eol=crlf`.
+fn attributes(line: &str) -> git_attributes::Outcome {
+    let mut group = git_attributes::MatchGroup::<git_attributes::Attributes>::default();
+    group.add_patterns_buffer(line.as_bytes(), "<memory>", None);
+    let mut out = git_attributes::Outcome::default();
+    out.initialize_macros(group.patterns.iter());
+    group.attributes_matching_relative_path("file", Some(false), git_glob::pattern::Case::Sensitive, &mut out);
+    out
+}
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -0,0 +1,25 @@
+use git_filter::ident;
+
+#[test]
+fn apply_substitutes_all_keywords() {
+    let id = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    assert!(ident::apply(b"$Id$ and $Id: old $\n$Id", &id, &mut out));
+    assert_eq!(
+        out,
+        format!("$Id: {id} $ and $Id: {id} $\n$Id", id = id.to_hex()).as_bytes()
+    );
+    assert!(!ident::apply(b"no keyword", &id, &mut out));
+}
+
+#[test]
+fn undo_removes_expanded_ids() {
+    let mut out = Vec::new();
+    assert!(ident::undo(b"a $Id: abc $ b $Id$ c", &mut out));
+    assert_eq!(out, b"a $Id$ b $Id$ c");
+    assert!(
+        !ident::undo(b"$Id: not\nterminated $", &mut out),
+        "expansions don't span lines"
+    );
+    assert!(!ident::undo(b"$Id$", &mut out), "nothing to do");
+}
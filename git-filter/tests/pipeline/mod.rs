@@ -0,0 +1,182 @@
+use bstr::ByteSlice;
+use git_filter::{eol, pipeline, Driver, Pipeline};
+
+use crate::{attributes, rot13_program};
+
+fn no_index_object(_buf: &mut Vec<u8>) -> Result<Option<()>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    Ok(None)
+}
+
+fn pipeline(drivers: Vec<Driver>) -> Pipeline {
+    Pipeline::new(
+        pipeline::Options {
+            eol_config: eol::Configuration {
+                auto_crlf: eol::AutoCrlf::Disabled,
+                eol: Some(eol::Mode::CrLf),
+            },
+        },
+        drivers,
+    )
+}
+
+fn rot13_driver(process: bool, required: bool) -> Driver {
+    let program = rot13_program().to_str().expect("valid UTF-8");
+    let cmd = |mode: &str| Some(format!("{} {}", program, mode).into());
+    Driver {
+        name: "rot13".into(),
+        clean: (!process).then(|| cmd("clean")).flatten(),
+        smudge: (!process).then(|| cmd("smudge")).flatten(),
+        process: process.then(|| cmd("process")).flatten(),
+        required,
+    }
+}
+
+fn round_trip(pipeline: &mut Pipeline, attrs: &str, worktree: &str, git: &str) -> crate::Result {
+    let attrs = attributes(attrs);
+    let id = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    let changed = pipeline.convert_to_git(worktree.as_bytes(), "file".into(), &attrs, no_index_object, &mut out)?;
+    assert_eq!(
+        if changed {
+            out.as_bstr()
+        } else {
+            worktree.as_bytes().as_bstr()
+        },
+        git,
+        "to git"
+    );
+
+    let changed = pipeline.convert_to_worktree(git.as_bytes(), "file".into(), &id, &attrs, &mut out)?;
+    assert_eq!(
+        if changed {
+            out.as_bstr()
+        } else {
+            git.as_bytes().as_bstr()
+        },
+        worktree.replace("$Id$", &format!("$Id: {} $", id)),
+        "to worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn no_attributes_means_no_conversion() -> crate::Result {
+    let mut pipeline = pipeline(Vec::new());
+    let mut out = Vec::new();
+    let attrs = attributes("");
+    assert!(!pipeline.convert_to_git(b"a\r\n", "file".into(), &attrs, no_index_object, &mut out)?);
+    assert!(!pipeline.convert_to_worktree(
+        b"a\n",
+        "file".into(),
+        &git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1),
+        &attrs,
+        &mut out
+    )?);
+    Ok(())
+}
+
+#[test]
+fn all_builtin_filters_combined() -> crate::Result {
+    let mut pipeline = pipeline(Vec::new());
+    round_trip(&mut pipeline, "* text", "a\r\nb\r\n", "a\nb\n")?;
+    round_trip(&mut pipeline, "* text ident", "$Id$\r\n", "$Id$\n")?;
+
+    let attrs = attributes("* text working-tree-encoding=ISO-8859-1");
+    let mut out = Vec::new();
+    assert!(pipeline.convert_to_git(b"\xe4\r\n", "file".into(), &attrs, no_index_object, &mut out)?);
+    assert_eq!(out.as_bstr(), "\u{e4}\n");
+    let id = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1);
+    assert!(pipeline.convert_to_worktree("\u{e4}\n".as_bytes(), "file".into(), &id, &attrs, &mut out)?);
+    assert_eq!(
+        out, b"\xe4\r\n",
+        "line endings are converted before the encoding changes"
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_working_tree_encoding_is_an_error() {
+    let mut pipeline = pipeline(Vec::new());
+    let mut out = Vec::new();
+    assert!(matches!(
+        pipeline.convert_to_git(
+            b"a",
+            "file".into(),
+            &attributes("* working-tree-encoding=no-such-thing"),
+            no_index_object,
+            &mut out
+        ),
+        Err(pipeline::convert::Error::UnknownWorktreeEncoding { .. })
+    ));
+}
+
+#[test]
+fn single_shot_drivers_with_eol_conversion() -> crate::Result {
+    let mut pipeline = pipeline(vec![rot13_driver(false, true)]);
+    round_trip(&mut pipeline, "* filter=rot13 text", "Hello\r\n", "Uryyb\n")?;
+    round_trip(&mut pipeline, "* filter=unknown", "Hello", "Hello")?;
+    Ok(())
+}
+
+#[test]
+fn process_drivers_are_kept_alive() -> crate::Result {
+    let mut pipeline = pipeline(vec![rot13_driver(true, false)]);
+    round_trip(&mut pipeline, "* filter=rot13", "Hello", "Uryyb")?;
+    round_trip(&mut pipeline, "* filter=rot13 text", "World\r\n", "Jbeyq\n")?;
+
+    let mut clone = pipeline.clone();
+    round_trip(&mut clone, "* filter=rot13", "Hello", "Uryyb")?;
+    Ok(())
+}
+
+#[test]
+fn failures_of_required_drivers_are_errors() -> crate::Result {
+    let attrs = attributes("* filter=rot13");
+    let mut out = Vec::new();
+    for required in [false, true] {
+        let mut pipeline = pipeline(vec![rot13_driver(true, required)]);
+        for path in ["error.txt", "abort.txt", "abort.txt"] {
+            let res = pipeline.convert_to_git(b"Hello", path.into(), &attrs, no_index_object, &mut out);
+            if required {
+                assert!(matches!(
+                    res,
+                    Err(pipeline::convert::Error::RequiredDriverFailed { .. })
+                ));
+            } else {
+                assert!(!res?, "failures pass the content through");
+            }
+        }
+        let res = pipeline.convert_to_git(b"Hello", "a.txt".into(), &attrs, no_index_object, &mut out);
+        if required {
+            assert!(res.is_err(), "the clean capability was aborted, but it's required");
+        } else {
+            assert!(!res?, "after abort, the clean filter isn't used anymore");
+        }
+        let id = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1);
+        assert!(
+            pipeline.convert_to_worktree(b"Uryyb", "a.txt".into(), &id, &attrs, &mut out)?,
+            "smudge still works"
+        );
+        assert_eq!(out.as_bstr(), "Hello");
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_commands_of_required_drivers_are_errors() {
+    let mut pipeline = pipeline(vec![Driver {
+        name: "rot13".into(),
+        required: true,
+        ..Default::default()
+    }]);
+    let mut out = Vec::new();
+    assert!(pipeline
+        .convert_to_git(
+            b"a",
+            "file".into(),
+            &attributes("* filter=rot13"),
+            no_index_object,
+            &mut out
+        )
+        .is_err());
+}
@@ -0,0 +1,46 @@
+use git_filter::worktree::{self, WorktreeEncoding};
+
+#[test]
+fn for_label_ignores_utf8_and_unknown_encodings() {
+    assert!(WorktreeEncoding::for_label(b"UTF-8").is_none());
+    assert!(WorktreeEncoding::for_label(b"no-such-encoding").is_none());
+    assert!(WorktreeEncoding::for_label(b"utf-16le").is_some());
+    assert!(WorktreeEncoding::for_label(b"SHIFT-JIS").is_some());
+}
+
+#[test]
+fn utf16_round_trips_with_bom() -> crate::Result {
+    let encoding = WorktreeEncoding::for_label(b"UTF-16").expect("known");
+    let mut worktree_buf = Vec::new();
+    worktree::encode_to_worktree("hä\n".as_bytes(), encoding, &mut worktree_buf)?;
+    assert_eq!(
+        &worktree_buf[..2],
+        &[0xff, 0xfe],
+        "generic UTF-16 gets a little-endian BOM"
+    );
+
+    let mut git_buf = Vec::new();
+    worktree::encode_to_git(&worktree_buf, encoding, &mut git_buf)?;
+    assert_eq!(git_buf, "hä\n".as_bytes());
+    Ok(())
+}
+
+#[test]
+fn utf16le_has_no_bom() -> crate::Result {
+    let encoding = WorktreeEncoding::for_label(b"UTF-16LE").expect("known");
+    let mut buf = Vec::new();
+    worktree::encode_to_worktree(b"a", encoding, &mut buf)?;
+    assert_eq!(buf, [b'a', 0]);
+    Ok(())
+}
+
+#[test]
+fn errors_are_reported() {
+    let encoding = WorktreeEncoding::for_label(b"UTF-16LE").expect("known");
+    let mut buf = Vec::new();
+    assert!(
+        worktree::encode_to_git(b"a", encoding, &mut buf).is_err(),
+        "odd number of bytes is malformed"
+    );
+    assert!(worktree::encode_to_worktree(&[0xff], encoding, &mut buf).is_err());
+}
@@ -9,8 +9,35 @@ mod from_hex_len {
         assert_eq!(Kind::from_hex_len(40), Some(Kind::Sha1));
     }
 
+    #[test]
+    fn some_sha256() {
+        assert_eq!(Kind::from_hex_len(41), Some(Kind::Sha256));
+        assert_eq!(Kind::from_hex_len(64), Some(Kind::Sha256));
+    }
+
     #[test]
     fn none_if_there_is_no_fit() {
         assert_eq!(Kind::from_hex_len(65), None);
     }
 }
+
+mod try_from {
+    use std::convert::TryFrom;
+
+    use git_hash::Kind;
+
+    #[test]
+    fn the_hash_format_ids_used_in_git_files() {
+        assert_eq!(Kind::try_from(1), Ok(Kind::Sha1));
+        assert_eq!(Kind::try_from(2), Ok(Kind::Sha256));
+        assert_eq!(Kind::try_from(3), Err(3));
+    }
+
+    #[test]
+    fn the_names_used_in_object_format_extension_and_capability() {
+        assert_eq!("sha1".parse::<Kind>(), Ok(Kind::Sha1));
+        assert_eq!("sha256".parse::<Kind>(), Ok(Kind::Sha256));
+        assert!("sha512".parse::<Kind>().is_err());
+        assert_eq!(Kind::Sha256.to_string(), "SHA256");
+    }
+}
@@ -96,6 +96,18 @@ mod try_from {
         let actual = Prefix::try_from(input).expect("No errors");
         assert_eq!(actual.cmp_oid(&expected), Ordering::Equal);
     }
+    #[test]
+    fn sha256_ids_longer_than_sha1() {
+        let oid_hex = "6ef19b41225c5369f1c104d45d8d85efa9b057b53b14b4b9b939dd74decc5321";
+        let input = &oid_hex[..41];
+
+        let expected = git_hash::ObjectId::from_hex(oid_hex.as_bytes()).expect("valid");
+        let actual = Prefix::try_from(input).expect("No errors");
+        assert_eq!(actual.as_oid().kind(), git_hash::Kind::Sha256);
+        assert_eq!(actual.cmp_oid(&expected), Ordering::Equal);
+        assert_eq!(actual.to_string(), input);
+    }
+
     #[test]
     fn id_to_short() {
         let input = "ab";
@@ -106,8 +118,8 @@ mod try_from {
 
     #[test]
     fn id_to_long() {
-        let input = "abcdefabcdefabcdefabcdefabcdefabcdefabcd123123123123123123";
-        let expected = Error::TooLong { hex_len: 58 };
+        let input = "abcdefabcdefabcdefabcdefabcdefabcdefabcd123123123123123123123123a";
+        let expected = Error::TooLong { hex_len: 65 };
         let actual = Prefix::try_from(input).unwrap_err();
         assert_eq!(actual, expected);
     }
@@ -1,11 +1,9 @@
+use std::ops::Range;
+
 use bstr::{BStr, ByteSlice};
 
 use crate::{entry, extension, Entry, PathStorage, State, Version};
 
-// TODO: integrate this somehow, somewhere, depending on later usage.
-#[allow(dead_code)]
-mod sparse;
-
 /// General information and entries
 impl State {
     /// Return the version used to store this state's information on disk.
@@ -13,6 +11,19 @@ impl State {
         self.version
     }
 
+    /// Set the version to use when writing this state to `version`.
+    ///
+    /// Note that only [`Version::V4`] is kept when writing, as V2 and V3 are chosen automatically depending on the entries.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
+    /// Return the time at which this state was read from disk or created, which is used to detect racily changed entries
+    /// with [`Stat::is_racy()`][entry::Stat::is_racy()].
+    pub fn timestamp(&self) -> filetime::FileTime {
+        self.timestamp
+    }
+
     /// Return the kind of hashes used in this instance.
     pub fn object_hash(&self) -> git_hash::Kind {
         self.object_hash
@@ -113,6 +124,216 @@ impl State {
     }
 }
 
+/// Mutation
+impl State {
+    /// Insert a new entry at `path` with the given information in the position matching its path and the stage
+    /// stored in `flags`, or update the existing entry with the same path and stage. Return the index of the entry
+    /// in [`entries()`][State::entries()].
+    ///
+    /// Just like `git` does, entries that can't exist alongside the new one are removed: a merged entry at stage 0
+    /// resolves all conflict stages of `path`, and entries of a directory `path` or of files at the parent directories
+    /// of `path` conflict with it. Removed conflict stages are remembered in the [resolve-undo extension][State::resolve_undo()].
+    ///
+    /// The [`tree` extension][State::tree()] is invalidated for `path`.
+    pub fn upsert_entry(
+        &mut self,
+        path: &BStr,
+        stat: entry::Stat,
+        id: git_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+    ) -> usize {
+        let stage = flags.stage();
+        let num_entries = self.entries.len();
+        let dir = path.strip_suffix(b"/").unwrap_or(path);
+        self.remove_entries(|_, entry_path, entry| {
+            if entry_path == path {
+                return stage == 0 && entry.stage() != 0;
+            }
+            let is_in_dir =
+                entry_path.len() > dir.len() && entry_path.starts_with(dir) && entry_path[dir.len()] == b'/';
+            let is_parent_file =
+                path.len() > entry_path.len() && path.starts_with(entry_path) && path[entry_path.len()] == b'/';
+            is_in_dir || is_parent_file
+        });
+        if self.entries.len() != num_entries {
+            self.compact_path_backing();
+        }
+
+        let idx = match self
+            .entries
+            .binary_search_by(|e| Entry::cmp_filepaths(e.path(self), path).then_with(|| e.stage().cmp(&stage)))
+        {
+            Ok(idx) => {
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                idx
+            }
+            Err(idx) => {
+                let entry = self.new_entry(path, stat, id, flags, mode);
+                self.entries.insert(idx, entry);
+                idx
+            }
+        };
+        self.is_sparse |= mode.is_sparse();
+        self.entry_changed(self.entries[idx].path.clone());
+        idx
+    }
+
+    /// Append a new entry at `path` with the given information without keeping entries sorted, and without checking
+    /// for an existing entry with the same path and stage.
+    ///
+    /// This is useful to add many entries at once, but [`sort_entries()`][State::sort_entries()] must be called
+    /// afterwards and duplicates must be avoided by the caller to keep the index valid.
+    pub fn dangerously_push_entry(
+        &mut self,
+        path: &BStr,
+        stat: entry::Stat,
+        id: git_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+    ) {
+        let entry = self.new_entry(path, stat, id, flags, mode);
+        self.is_sparse |= mode.is_sparse();
+        self.entry_changed(entry.path.clone());
+        self.entries.push(entry);
+    }
+
+    /// Sort all entries by path and stage, as needed after using [`dangerously_push_entry()`][State::dangerously_push_entry()].
+    pub fn sort_entries(&mut self) {
+        let path_backing = &self.path_backing;
+        self.entries.sort_by(|a, b| {
+            Entry::cmp_filepaths(a.path_in(path_backing), b.path_in(path_backing))
+                .then_with(|| a.stage().cmp(&b.stage()))
+        });
+    }
+
+    /// Remove the entry at `idx` and return it, or _panic_ if the index is out of bounds.
+    ///
+    /// The path of the returned entry can still be obtained through this instance, but should not be used for adding
+    /// it again as it will not be found anymore. Removed entries with a conflict stage are remembered in the
+    /// [resolve-undo extension][State::resolve_undo()].
+    pub fn remove_entry_at(&mut self, idx: usize) -> Entry {
+        let entry = self.entries.remove(idx);
+        self.entry_removed(&entry);
+        if entry.mode.is_sparse() {
+            self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+        }
+        entry
+    }
+
+    /// Remove all entries for which `should_remove(idx, path, entry)` returns true, with `idx` being the index of the entry
+    /// before any entry was removed.
+    pub fn remove_entries(&mut self, mut should_remove: impl FnMut(usize, &BStr, &mut Entry) -> bool) {
+        let mut removed = Vec::new();
+        let entries = std::mem::take(&mut self.entries);
+        self.entries.reserve(entries.len());
+        for (idx, mut entry) in entries.into_iter().enumerate() {
+            if should_remove(idx, entry.path_in(&self.path_backing), &mut entry) {
+                removed.push(entry);
+            } else {
+                self.entries.push(entry);
+            }
+        }
+        for entry in &removed {
+            self.entry_removed(entry);
+        }
+        if removed.iter().any(|e| e.mode.is_sparse()) {
+            self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+        }
+    }
+
+    /// Set the stage of the entry at `idx` to `stage`, between 0 and 3, moving it to the position matching its path and new stage,
+    /// and return its new index. An existing entry with the same path and `stage` is replaced by it.
+    ///
+    /// This is used to record conflicts with the base version of an entry at stage 1, ours at stage 2 and theirs at stage 3,
+    /// or to resolve them by moving the chosen entry to stage 0.
+    pub fn set_entry_stage(&mut self, idx: usize, stage: entry::Stage) -> usize {
+        let mut entry = self.entries.remove(idx);
+        self.entry_removed(&entry);
+        entry.flags.set_stage(stage);
+        let path = entry.path.clone();
+        let res = self.entries.binary_search_by(|e| {
+            Entry::cmp_filepaths(e.path(self), self.path_backing[path.clone()].as_bstr())
+                .then_with(|| e.stage().cmp(&stage))
+        });
+        let idx = match res {
+            Ok(idx) => {
+                let replaced = std::mem::replace(&mut self.entries[idx], entry);
+                self.entry_removed(&replaced);
+                idx
+            }
+            Err(idx) => {
+                self.entries.insert(idx, entry);
+                idx
+            }
+        };
+        self.entry_changed(path);
+        idx
+    }
+
+    fn new_entry(
+        &mut self,
+        path: &BStr,
+        stat: entry::Stat,
+        id: git_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+    ) -> Entry {
+        let path_start = self.path_backing.len();
+        self.path_backing.extend_from_slice(path);
+        Entry {
+            stat,
+            id,
+            flags,
+            mode,
+            path: path_start..self.path_backing.len(),
+        }
+    }
+
+    /// Rewrite our path backing to contain only the paths of our entries, dropping those of removed entries.
+    fn compact_path_backing(&mut self) {
+        let mut backing = PathStorage::with_capacity(self.entries.iter().map(|e| e.path.len()).sum());
+        for entry in &mut self.entries {
+            let start = backing.len();
+            backing.extend_from_slice(&self.path_backing[entry.path.clone()]);
+            entry.path = start..backing.len();
+        }
+        self.path_backing = backing;
+    }
+
+    fn entry_removed(&mut self, entry: &Entry) {
+        let stage = entry.stage();
+        if stage != 0 {
+            extension::resolve_undo::record(
+                self.resolve_undo.get_or_insert_with(Default::default),
+                entry.path_in(&self.path_backing),
+                stage,
+                entry.mode.bits(),
+                entry.id,
+            );
+        }
+        self.entry_changed(entry.path.clone());
+    }
+
+    /// Invalidate all extensions which depend on the entry at `path`, stored in our path backing.
+    ///
+    /// The `link` extension of a split index refers to entries of the shared index by position, and is dropped entirely.
+    fn entry_changed(&mut self, path: Range<usize>) {
+        self.link = None;
+        let path = self.path_backing[path].as_bstr();
+        if let Some(tree) = self.tree.as_mut() {
+            tree.invalidate_path(path);
+        }
+        if let Some(untracked) = self.untracked.as_mut() {
+            untracked.invalidate_path(path);
+        }
+    }
+}
+
 /// Extensions
 impl State {
     /// Access the `tree` extension.
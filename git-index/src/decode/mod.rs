@@ -206,7 +206,7 @@ impl State {
 
         let checksum = git_hash::ObjectId::from(data);
         let EntriesOutcome {
-            entries,
+            mut entries,
             path_backing,
             mut is_sparse,
         } = entries;
@@ -219,6 +219,9 @@ impl State {
             is_sparse: is_sparse_from_ext, // a marker is needed in case there are no directories
         } = ext;
         is_sparse |= is_sparse_from_ext;
+        if let Some(fs_monitor) = &fs_monitor {
+            fs_monitor.mark_valid_entries(&mut entries);
+        }
 
         Ok((
             State {
@@ -288,13 +291,13 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     Some((
         entry::Stat {
             mtime: entry::Time {
-                secs: ctime_secs,
-                nsecs: ctime_nsecs,
-            },
-            ctime: entry::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
+            ctime: entry::Time {
+                secs: ctime_secs,
+                nsecs: ctime_nsecs,
+            },
             dev,
             ino,
             uid,
@@ -62,6 +62,12 @@ impl Flags {
         (*self & Flags::STAGE_MASK).bits >> 12
     }
 
+    /// Set the stage of this instance to `stage`, which must be between 0 and 3.
+    pub fn set_stage(&mut self, stage: Stage) {
+        assert!(stage <= 3, "BUG: stages range from 0 to 3, got {}", stage);
+        self.bits = (self.bits & !Flags::STAGE_MASK.bits) | (stage << 12);
+    }
+
     /// Transform ourselves to a storage representation to keep all flags which are to be persisted,
     /// skipping all extended flags. Note that the caller has to check for the `EXTENDED` bit to be present
     /// and write extended flags as well if so.
@@ -10,6 +10,9 @@ pub use flags::Flags;
 
 mod write;
 
+///
+pub mod stat;
+
 /// The time component in a [`Stat`] struct.
 #[derive(Debug, Default, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
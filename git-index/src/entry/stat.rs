@@ -0,0 +1,132 @@
+use std::time::SystemTimeError;
+
+use filetime::FileTime;
+
+use crate::entry::{Stat, Time};
+
+/// Configure how [`Stat`] information is compared to detect changes to files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, a change of the creation time is considered a change of the file, as configured by `core.trustCTime`.
+    /// Can be set to false in case other tools alter the creation time in ways that interfere with our operation.
+    ///
+    /// Default true.
+    pub trust_ctime: bool,
+    /// If true, all stat fields will be used when checking for up-to-date'ness of the entry, which is configured
+    /// by `core.checkStat`. Otherwise nano-second parts of mtime and ctime, uid, gid, inode and device number _will not_
+    /// be used, leaving only the whole-second part of ctime and mtime and the file size to be checked.
+    ///
+    /// Default true.
+    pub check_stat: bool,
+    /// If true, the nano-second parts of mtime and ctime are compared as well if `check_stat` is enabled.
+    ///
+    /// Default true.
+    pub use_nsec: bool,
+    /// If true, the device number is compared as well if `check_stat` is enabled. It's off by default as it's
+    /// not stable on all filesystems, just like in `git`.
+    ///
+    /// Default false.
+    pub use_stdev: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            trust_ctime: true,
+            check_stat: true,
+            use_nsec: true,
+            use_stdev: false,
+        }
+    }
+}
+
+impl Stat {
+    /// Create stat information from the `meta`data of a file, as obtained without following symbolic links.
+    ///
+    /// Times that can't be obtained on this platform are defaulted.
+    pub fn from_fs(meta: &std::fs::Metadata) -> Result<Stat, SystemTimeError> {
+        let mtime = meta.modified().map_or(Ok(Time::default()), Time::from_system_time)?;
+        #[cfg(unix)]
+        let res = {
+            use std::os::unix::fs::MetadataExt;
+            Stat {
+                mtime,
+                ctime: Time {
+                    secs: meta.ctime() as u32,
+                    nsecs: meta.ctime_nsec() as u32,
+                },
+                dev: meta.dev() as u32,
+                ino: meta.ino() as u32,
+                uid: meta.uid(),
+                gid: meta.gid(),
+                size: meta.len() as u32,
+            }
+        };
+        #[cfg(not(unix))]
+        let res = Stat {
+            mtime,
+            ctime: meta.created().map_or(Ok(Time::default()), Time::from_system_time)?,
+            size: meta.len() as u32,
+            ..Default::default()
+        };
+        Ok(res)
+    }
+
+    /// Return true if this instance, typically stored in the index, matches the `other` instance obtained from the file on disk,
+    /// comparing only the fields enabled in `options`.
+    ///
+    /// Note that the file may still have changed even though the stat information matches, see [`is_racy()`][Stat::is_racy()].
+    pub fn matches(&self, other: &Stat, options: Options) -> bool {
+        let Options {
+            trust_ctime,
+            check_stat,
+            use_nsec,
+            use_stdev,
+        } = options;
+        if self.mtime.secs != other.mtime.secs || self.size != other.size {
+            return false;
+        }
+        if trust_ctime && self.ctime.secs != other.ctime.secs {
+            return false;
+        }
+        if !check_stat {
+            return true;
+        }
+        if use_nsec && (self.mtime.nsecs != other.mtime.nsecs || (trust_ctime && self.ctime.nsecs != other.ctime.nsecs))
+        {
+            return false;
+        }
+        if use_stdev && self.dev != other.dev {
+            return false;
+        }
+        self.uid == other.uid && self.gid == other.gid && self.ino == other.ino
+    }
+
+    /// Return true if the file this instance was obtained from may have changed after the index was written at `timestamp`,
+    /// but in the same time period, as determined by `options`, which means its stat information can't be trusted.
+    ///
+    /// Such entries have to be checked by comparing their content.
+    pub fn is_racy(&self, timestamp: FileTime, options: Options) -> bool {
+        let index_secs = timestamp.unix_seconds();
+        if index_secs <= 0 {
+            return false;
+        }
+        let mtime_secs = i64::from(self.mtime.secs);
+        if options.check_stat && options.use_nsec {
+            (index_secs, timestamp.nanoseconds()) <= (mtime_secs, self.mtime.nsecs)
+        } else {
+            index_secs <= mtime_secs
+        }
+    }
+}
+
+impl Time {
+    /// Convert `time` into a time since the unix epoch, truncating the seconds to 32 bits just like `git` does.
+    pub fn from_system_time(time: std::time::SystemTime) -> Result<Time, SystemTimeError> {
+        let d = time.duration_since(std::time::UNIX_EPOCH)?;
+        Ok(Time {
+            secs: d.as_secs() as u32,
+            nsecs: d.subsec_nanos(),
+        })
+    }
+}
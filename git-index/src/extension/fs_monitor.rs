@@ -1,8 +1,10 @@
 use bstr::BString;
 
 use crate::{
+    entry,
     extension::{FsMonitor, Signature},
     util::{read_u32, read_u64, split_at_byte_exclusive},
+    Entry,
 };
 
 #[derive(Clone)]
@@ -36,3 +38,48 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
 
     FsMonitor { token, entry_dirty }.into()
 }
+
+impl FsMonitor {
+    /// Mark all `entries` as valid which aren't dirty according to the filesystem monitor.
+    pub(crate) fn mark_valid_entries(&self, entries: &mut [Entry]) {
+        for entry in entries.iter_mut() {
+            entry.flags.insert(entry::Flags::FSMONITOR_VALID);
+        }
+        self.entry_dirty.for_each_set_bit(|idx| {
+            if let Some(entry) = entries.get_mut(idx) {
+                entry.flags.remove(entry::Flags::FSMONITOR_VALID);
+            }
+            Some(())
+        });
+    }
+
+    /// Serialize this instance to `out`, marking all `entries` as dirty which aren't known to be valid.
+    pub fn write_to(&self, entries: &[Entry], out: impl std::io::Write) -> std::io::Result<()> {
+        let mut entry_dirty = git_bitmap::ewah::Vec::default();
+        for (idx, _) in entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| !e.flags.contains(entry::Flags::FSMONITOR_VALID))
+        {
+            entry_dirty.set(idx);
+        }
+        crate::write::util::write_extension(out, SIGNATURE, |data| {
+            match &self.token {
+                Token::V1 { nanos_since_1970 } => {
+                    data.extend_from_slice(&1_u32.to_be_bytes());
+                    data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+                }
+                Token::V2 { token } => {
+                    data.extend_from_slice(&2_u32.to_be_bytes());
+                    data.extend_from_slice(token);
+                    data.push(0);
+                }
+            }
+            let mut bitmap = Vec::new();
+            entry_dirty.write_to(&mut bitmap)?;
+            data.extend_from_slice(&(bitmap.len() as u32).to_be_bytes());
+            data.extend_from_slice(&bitmap);
+            Ok(())
+        })
+    }
+}
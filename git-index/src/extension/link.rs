@@ -32,6 +32,20 @@ pub mod decode {
     }
 }
 
+impl Link {
+    /// Serialize this instance to `out`, writing the bitmaps as they were read.
+    pub fn write_to(&self, out: impl std::io::Write) -> std::io::Result<()> {
+        crate::write::util::write_extension(out, SIGNATURE, |data| {
+            data.extend_from_slice(self.shared_index_checksum.as_bytes());
+            if let Some(bitmaps) = &self.bitmaps {
+                bitmaps.delete.write_to(&mut *data)?;
+                bitmaps.replace.write_to(&mut *data)?;
+            }
+            Ok(())
+        })
+    }
+}
+
 pub(crate) fn decode(data: &[u8], object_hash: git_hash::Kind) -> Result<Link, decode::Error> {
     let (id, data) = split_at_pos(data, object_hash.len_in_bytes())
         .ok_or(decode::Error::Corrupt(
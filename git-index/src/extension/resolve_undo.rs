@@ -1,4 +1,4 @@
-use bstr::BString;
+use bstr::{BStr, BString};
 use git_hash::ObjectId;
 
 use crate::{
@@ -62,3 +62,44 @@ pub fn decode(mut data: &[u8], object_hash: git_hash::Kind) -> Option<Paths> {
     }
     out.into()
 }
+
+/// Serialize all resolve-undo `paths` to `out`.
+pub fn write_to(paths: &Paths, out: impl std::io::Write) -> std::io::Result<()> {
+    crate::write::util::write_extension(out, SIGNATURE, |data| {
+        for path in paths {
+            data.extend_from_slice(&path.name);
+            data.push(0);
+            for stage in &path.stages {
+                let mode = stage.map_or(0, |stage| stage.mode);
+                data.extend_from_slice(format!("{:o}", mode).as_bytes());
+                data.push(0);
+            }
+            for stage in path.stages.iter().flatten() {
+                data.extend_from_slice(stage.id.as_bytes());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Remember the entry with `mode` and `id` at `path` and `stage` (1 to 3), which is about to be removed from the index,
+/// to be able to recreate the conflict later.
+pub fn record(paths: &mut Paths, path: &BStr, stage: crate::entry::Stage, mode: u32, id: ObjectId) {
+    if stage == 0 {
+        return;
+    }
+    let idx = match paths.binary_search_by(|p| p.name.as_slice().cmp(path.as_ref())) {
+        Ok(idx) => idx,
+        Err(idx) => {
+            paths.insert(
+                idx,
+                ResolvePath {
+                    name: path.into(),
+                    stages: [None, None, None],
+                },
+            );
+            idx
+        }
+    };
+    paths[idx].stages[stage as usize - 1] = Some(Stage { mode, id });
+}
@@ -44,7 +44,7 @@ fn one_recursive(data: &[u8], hash_len: usize) -> Option<(Tree, &[u8])> {
         data = rest;
     }
 
-    subtrees.sort_by(|a, b| a.name.cmp(&b.name));
+    subtrees.sort_by(|a, b| super::update::cmp_names(&a.name, &b.name));
     let num_trees = subtrees.len();
     subtrees.dedup_by(|a, b| a.name == b.name);
     if num_trees != subtrees.len() {
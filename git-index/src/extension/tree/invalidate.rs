@@ -0,0 +1,23 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::extension::Tree;
+
+impl Tree {
+    /// Mark this tree and all sub-trees leading to the entry at the repository-relative `path` as invalid, as the entry
+    /// was added, removed or changed. If `path` names a sub-tree directly, it will be removed entirely as it was replaced
+    /// by a non-tree entry.
+    ///
+    /// Invalid trees have no [number of entries][Tree::num_entries] and need to be recomputed when writing a tree from the index.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        self.num_entries = None;
+        match path.find_byte(b'/') {
+            Some(pos) => {
+                let (name, rest) = (&path[..pos], &path[pos + 1..]);
+                if let Some(child) = self.children.iter_mut().find(|child| child.name.as_slice() == name) {
+                    child.invalidate_path(rest.as_bstr());
+                }
+            }
+            None => self.children.retain(|child| child.name.as_slice() != path.as_bytes()),
+        }
+    }
+}
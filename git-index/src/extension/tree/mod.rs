@@ -11,11 +11,16 @@ pub use decode::decode;
 
 mod write;
 
+mod invalidate;
+
+///
+pub mod update;
+
 #[cfg(test)]
 mod tests {
 
     #[test]
     fn size_of_tree() {
-        assert_eq!(std::mem::size_of::<crate::extension::Tree>(), 88);
+        assert_eq!(std::mem::size_of::<crate::extension::Tree>(), 104);
     }
 }
@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+
+use bstr::{BStr, BString, ByteSlice};
+use git_hash::ObjectId;
+use git_object::tree::EntryMode;
+
+use crate::{entry, extension::Tree, Entry, PathStorageRef, State};
+
+/// The error returned by [State::update_tree_extension()][crate::State::update_tree_extension()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Entry '{path}' is unmerged at stage {stage}, but trees can only be written from merged entries")]
+    Unmerged { path: BString, stage: entry::Stage },
+    #[error("Entry '{path}' has mode {mode:o} which can't be stored in a tree")]
+    InvalidMode { path: BString, mode: u32 },
+    #[error("Could not write tree object")]
+    WriteTree(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl State {
+    /// Compute and write all trees of the [`tree` extension][State::tree()] which were invalidated by changes to entries,
+    /// creating the extension if it doesn't exist yet, and return the id of the root tree.
+    ///
+    /// Valid trees are reused without looking at their entries, all other trees are passed to `write` to store them
+    /// and obtain their id. Entries which are only intended to be added are left out, and their trees remain invalid.
+    ///
+    /// Note that entries with a conflict stage make this fail as trees can only be created from merged entries.
+    pub fn update_tree_extension<E>(
+        &mut self,
+        write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+    ) -> Result<ObjectId, Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut root = self.tree.take().unwrap_or_else(|| empty_root(self.object_hash));
+        let res = update_root(&mut root, &self.entries, &self.path_backing, write);
+        self.tree = Some(root);
+        res
+    }
+
+    /// Like [`update_tree_extension()`][State::update_tree_extension()], pass all trees needed to represent our entries
+    /// to `write` and return the id of the root tree, but leave the [`tree` extension][State::tree()] unchanged.
+    ///
+    /// This is what `git write-tree` does, and valid trees of the extension are reused as well.
+    pub fn write_tree<E>(&self, write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>) -> Result<ObjectId, Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut root = self.tree.clone().unwrap_or_else(|| empty_root(self.object_hash));
+        update_root(&mut root, &self.entries, &self.path_backing, write)
+    }
+}
+
+fn empty_root(object_hash: git_hash::Kind) -> Tree {
+    Tree {
+        name: Default::default(),
+        id: ObjectId::null(object_hash),
+        num_entries: None,
+        children: Vec::new(),
+    }
+}
+
+fn update_root<E>(
+    root: &mut Tree,
+    entries: &[Entry],
+    backing: &PathStorageRef,
+    mut write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+) -> Result<ObjectId, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    update_recursive(root, entries, backing, BStr::new(""), &mut write).map(|_| root.id)
+}
+
+/// Update `tree` for the directory at `dir`, which has a trailing slash, from its `entries` and return its id.
+pub(crate) fn update_dir<E>(
+    tree: &mut Tree,
+    entries: &[Entry],
+    backing: &PathStorageRef,
+    dir: &BStr,
+    mut write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+) -> Result<ObjectId, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    update_recursive(tree, entries, backing, dir, &mut write).map(|_| tree.id)
+}
+
+/// The order in which `git` keeps sub-trees in its tree extension: shorter names come first.
+pub(crate) fn cmp_names(a: &[u8], b: &[u8]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+struct Outcome {
+    /// The amount of index entries which are contained in the tree, recursively.
+    num_entries: usize,
+    /// If true, the tree has no entries and isn't stored in its parent tree.
+    is_empty: bool,
+}
+
+/// Update `tree` from all `entries` whose path starts with `prefix`, assuming the first entry is the first one of the tree.
+fn update_recursive<E>(
+    tree: &mut Tree,
+    entries: &[Entry],
+    backing: &PathStorageRef,
+    prefix: &BStr,
+    write: &mut impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+) -> Result<Outcome, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if let Some(num_entries) = tree.num_entries {
+        return Ok(Outcome {
+            num_entries: num_entries as usize,
+            is_empty: num_entries == 0,
+        });
+    }
+
+    let mut tree_entries = Vec::new();
+    let mut used_children = Vec::new();
+    let mut is_valid = true;
+    let mut idx = 0;
+    while let Some(entry) = entries.get(idx) {
+        let path = entry.path_in(backing);
+        if !path.starts_with(prefix) {
+            break;
+        }
+        let rest = &path[prefix.len()..];
+        if entry.flags.contains(entry::Flags::REMOVE) {
+            idx += 1;
+            continue;
+        }
+        if entry.stage() != 0 {
+            return Err(Error::Unmerged {
+                path: path.to_owned(),
+                stage: entry.stage(),
+            });
+        }
+
+        match rest.find_byte(b'/').filter(|pos| *pos + 1 != rest.len()) {
+            Some(pos) => {
+                let name = &rest[..pos];
+                let child_idx = match tree.children.binary_search_by(|child| cmp_names(&child.name, name)) {
+                    Ok(child_idx) => child_idx,
+                    Err(child_idx) => {
+                        tree.children.insert(
+                            child_idx,
+                            Tree {
+                                name: name.as_bytes().into(),
+                                id: ObjectId::null(tree.id.kind()),
+                                num_entries: None,
+                                children: Vec::new(),
+                            },
+                        );
+                        child_idx
+                    }
+                };
+                let child = &mut tree.children[child_idx];
+                let outcome = update_recursive(
+                    child,
+                    &entries[idx..],
+                    backing,
+                    path[..prefix.len() + pos + 1].as_bstr(),
+                    write,
+                )?;
+                idx += outcome.num_entries.max(1);
+                is_valid &= child.num_entries.is_some();
+                used_children.push(name);
+                if !outcome.is_empty {
+                    tree_entries.push(git_object::tree::Entry {
+                        mode: EntryMode::Tree,
+                        filename: name.into(),
+                        oid: child.id,
+                    });
+                }
+            }
+            None => {
+                idx += 1;
+                if entry.flags.contains(entry::Flags::INTENT_TO_ADD) {
+                    is_valid = false;
+                    continue;
+                }
+                let (mode, filename) = match entry.mode {
+                    entry::Mode::FILE => (EntryMode::Blob, rest),
+                    entry::Mode::FILE_EXECUTABLE => (EntryMode::BlobExecutable, rest),
+                    entry::Mode::SYMLINK => (EntryMode::Link, rest),
+                    entry::Mode::COMMIT => (EntryMode::Commit, rest),
+                    entry::Mode::DIR => {
+                        // Sparse directories may have their tree cached as well, which remains valid.
+                        let name = &rest[..rest.len() - 1];
+                        used_children.push(name);
+                        (EntryMode::Tree, name)
+                    }
+                    mode => {
+                        return Err(Error::InvalidMode {
+                            path: path.to_owned(),
+                            mode: mode.bits(),
+                        })
+                    }
+                };
+                tree_entries.push(git_object::tree::Entry {
+                    mode,
+                    filename: filename.into(),
+                    oid: entry.id,
+                });
+            }
+        }
+    }
+    tree.children
+        .retain(|child| used_children.iter().any(|name| *name == child.name.as_slice()));
+
+    let is_empty = tree_entries.is_empty();
+    tree.id = write(&git_object::Tree { entries: tree_entries }).map_err(|err| Error::WriteTree(err.into()))?;
+    tree.num_entries = is_valid.then(|| idx as u32);
+    Ok(Outcome {
+        num_entries: idx,
+        is_empty,
+    })
+}
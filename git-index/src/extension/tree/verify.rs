@@ -61,7 +61,7 @@ impl Tree {
             for child in children {
                 entries += child.num_entries.unwrap_or(0);
                 if let Some(prev) = prev {
-                    if prev.name.cmp(&child.name) != Ordering::Less {
+                    if super::update::cmp_names(&prev.name, &child.name) != Ordering::Less {
                         return Err(Error::OutOfOrder {
                             parent_id,
                             previous_path: prev.name.as_bstr().into(),
@@ -79,7 +79,7 @@ impl Tree {
                     .filter(|e| e.mode == git_object::tree::EntryMode::Tree)
                 {
                     children
-                        .binary_search_by(|e| e.name.as_bstr().cmp(entry.filename))
+                        .binary_search_by(|e| super::update::cmp_names(&e.name, entry.filename))
                         .map_err(|_| Error::MissingTreeDirectory {
                             parent_id,
                             entry_id: entry.oid.to_owned(),
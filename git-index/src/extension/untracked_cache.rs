@@ -1,12 +1,13 @@
 use std::convert::TryInto;
 
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice};
 use git_hash::ObjectId;
 
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
     util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    write::util::write_var_int,
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
@@ -39,6 +40,26 @@ pub struct Directory {
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
+/// Access
+impl UntrackedCache {
+    /// The stat information and object id of `.git/info/exclude` at the time the cache was written, if it existed.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+    /// The stat information and object id of the file configured in `core.excludesFile`, if it existed.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+    /// The name of the per-directory exclude file, usually `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_bstr()
+    }
+    /// The flags used for the directory traversal that filled the cache.
+    pub fn dir_flags(&self) -> u32 {
+        self.dir_flags
+    }
+}
+
 // #[allow(unused)]
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<UntrackedCache> {
@@ -49,9 +70,19 @@ pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<UntrackedCache
     let (identifier, data) = split_at_pos(data, identifier_len.try_into().ok()?)?;
 
     let hash_len = object_hash.len_in_bytes();
-    let (info_exclude, data) = decode_oid_stat(data, hash_len)?;
-    let (excludes_file, data) = decode_oid_stat(data, hash_len)?;
+    let (info_exclude_stat, data) = crate::decode::stat(data)?;
+    let (excludes_file_stat, data) = crate::decode::stat(data)?;
     let (dir_flags, data) = read_u32(data)?;
+    let (info_exclude_id, data) = split_at_pos(data, hash_len)?;
+    let (excludes_file_id, data) = split_at_pos(data, hash_len)?;
+    let info_exclude = OidStat {
+        stat: info_exclude_stat,
+        id: ObjectId::from(info_exclude_id),
+    };
+    let excludes_file = OidStat {
+        stat: excludes_file_stat,
+        id: ObjectId::from(excludes_file_id),
+    };
     let (exclude_filename_per_dir, data) = split_at_byte_exclusive(data, 0)?;
 
     let (num_directory_blocks, data) = var_int(data)?;
@@ -143,14 +174,148 @@ fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>)
     data.into()
 }
 
-fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
-    let (stat, data) = crate::decode::stat(data)?;
-    let (hash, data) = split_at_pos(data, hash_len)?;
-    Some((
-        OidStat {
-            stat,
-            id: ObjectId::from(hash),
-        },
-        data,
-    ))
+/// The flag in [`UntrackedCache::dir_flags`] indicating that untracked directories are shown as directory, instead
+/// of listing all their untracked files.
+const DIR_SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+
+impl UntrackedCache {
+    /// Invalidate the directory containing the repository-relative `path` as an entry was added or removed there,
+    /// along with its parent directories if these would show it as untracked directory.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        if self.directories.is_empty() {
+            return;
+        }
+        self.invalidate_component(0, path);
+    }
+
+    /// Return true if the parent directory needs to be invalidated as well.
+    fn invalidate_component(&mut self, dir_index: usize, path: &BStr) -> bool {
+        let needs_invalidation = match path.find_byte(b'/') {
+            Some(pos) => {
+                let (name, rest) = (&path[..pos], &path[pos + 1..]);
+                let dir = &self.directories[dir_index];
+                match dir
+                    .sub_directories
+                    .iter()
+                    .copied()
+                    .find(|idx| self.directories[*idx].name == name)
+                {
+                    Some(sub_dir_index) => self.invalidate_component(sub_dir_index, rest.as_bstr()),
+                    None => self.dir_flags & DIR_SHOW_OTHER_DIRECTORIES != 0,
+                }
+            }
+            None => true,
+        };
+        if needs_invalidation {
+            let dir = &mut self.directories[dir_index];
+            dir.stat = None;
+            dir.check_only = false;
+            dir.untracked_entries.clear();
+        }
+        needs_invalidation && self.dir_flags & DIR_SHOW_OTHER_DIRECTORIES != 0
+    }
+}
+
+/// Serialize the untracked `cache` to `out`, assuming object ids are of type `object_hash` if missing.
+pub fn write_to(cache: &UntrackedCache, object_hash: git_hash::Kind, out: impl std::io::Write) -> std::io::Result<()> {
+    crate::write::util::write_extension(out, SIGNATURE, |data| {
+        write_var_int(&mut *data, cache.identifier.len() as u64)?;
+        data.extend_from_slice(&cache.identifier);
+        let null_oid_stat = |oid_stat: &Option<OidStat>| match oid_stat {
+            Some(oid_stat) => (oid_stat.stat, oid_stat.id.as_bytes().to_owned()),
+            None => (entry::Stat::default(), vec![0; object_hash.len_in_bytes()]),
+        };
+        let (info_exclude_stat, info_exclude_id) = null_oid_stat(&cache.info_exclude);
+        let (excludes_file_stat, excludes_file_id) = null_oid_stat(&cache.excludes_file);
+        write_stat(&mut *data, &info_exclude_stat)?;
+        write_stat(&mut *data, &excludes_file_stat)?;
+        data.extend_from_slice(&cache.dir_flags.to_be_bytes());
+        data.extend_from_slice(&info_exclude_id);
+        data.extend_from_slice(&excludes_file_id);
+        data.extend_from_slice(&cache.exclude_filename_per_dir);
+        data.push(0);
+
+        write_var_int(&mut *data, cache.directories.len() as u64)?;
+        if cache.directories.is_empty() {
+            return Ok(());
+        }
+
+        let mut bitmaps = Bitmaps::default();
+        let mut index = 0;
+        write_directory_block(&cache.directories, 0, &mut index, &mut bitmaps, data)?;
+        bitmaps.valid.write_to(&mut *data)?;
+        bitmaps.check_only.write_to(&mut *data)?;
+        bitmaps.hash_valid.write_to(&mut *data)?;
+        data.extend_from_slice(&bitmaps.stats);
+        data.extend_from_slice(&bitmaps.hashes);
+        data.push(0);
+        Ok(())
+    })
+}
+
+#[derive(Default)]
+struct Bitmaps {
+    valid: git_bitmap::ewah::Vec,
+    check_only: git_bitmap::ewah::Vec,
+    hash_valid: git_bitmap::ewah::Vec,
+    stats: Vec<u8>,
+    hashes: Vec<u8>,
+}
+
+fn write_directory_block(
+    directories: &[Directory],
+    dir_index: usize,
+    index: &mut usize,
+    bitmaps: &mut Bitmaps,
+    out: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    let dir = &directories[dir_index];
+    let bit = *index;
+    *index += 1;
+    let untracked_entries: &[BString] = if dir.stat.is_some() {
+        &dir.untracked_entries
+    } else {
+        &[]
+    };
+    if dir.check_only && dir.stat.is_some() {
+        bitmaps.check_only.set(bit);
+    }
+    if let Some(stat) = &dir.stat {
+        bitmaps.valid.set(bit);
+        write_stat(&mut bitmaps.stats, stat)?;
+    }
+    if let Some(id) = dir.exclude_file_oid.filter(|id| !id.is_null()) {
+        bitmaps.hash_valid.set(bit);
+        bitmaps.hashes.extend_from_slice(id.as_bytes());
+    }
+
+    write_var_int(&mut *out, untracked_entries.len() as u64)?;
+    write_var_int(&mut *out, dir.sub_directories.len() as u64)?;
+    out.extend_from_slice(&dir.name);
+    out.push(0);
+    for name in untracked_entries {
+        out.extend_from_slice(name);
+        out.push(0);
+    }
+    for sub_dir_index in &dir.sub_directories {
+        write_directory_block(directories, *sub_dir_index, index, bitmaps, out)?;
+    }
+    Ok(())
+}
+
+fn write_stat(mut out: impl std::io::Write, stat: &entry::Stat) -> std::io::Result<()> {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.write_all(&field.to_be_bytes())?;
+    }
+    Ok(())
 }
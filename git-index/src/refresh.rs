@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    entry::{self, stat, Stat},
+    Entry, State,
+};
+
+/// The error returned by [State::refresh()][crate::State::refresh()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain the metadata of '{}'", path.display())]
+    Metadata { path: PathBuf, source: std::io::Error },
+    #[error("Could not check whether the content of '{}' changed", path.display())]
+    ContentCheck { path: PathBuf, source: std::io::Error },
+    #[error("The modification time of '{}' could not be converted", path.display())]
+    Time {
+        path: PathBuf,
+        source: std::time::SystemTimeError,
+    },
+}
+
+/// The outcome of [State::refresh()][crate::State::refresh()].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The indices of entries whose file in the worktree differs from what's recorded in the index.
+    pub modified: Vec<usize>,
+    /// The indices of entries which don't have a file in the worktree anymore.
+    pub deleted: Vec<usize>,
+    /// The amount of entries whose stat information was updated as their content didn't change.
+    pub updated: usize,
+}
+
+impl State {
+    /// Compare the stat information of all entries to their files in the `worktree` directory using `options`, and update it
+    /// for all files whose content didn't actually change. This is what `git update-index --refresh` does.
+    ///
+    /// If the stat information differs or can't be trusted as the file was [changed racily][Stat::is_racy()],
+    /// `content_matches(path, entry)` is called with the path to the file on disk to determine if its content still matches the
+    /// [entry's id][Entry::id], typically by hashing it.
+    ///
+    /// Entries with conflicts, submodules, sparse directories and those which are marked to be assumed valid, skip the worktree
+    /// or which are only intended to be added are ignored. Changes to the executable bit are not detected.
+    pub fn refresh(
+        &mut self,
+        worktree: &Path,
+        options: stat::Options,
+        mut content_matches: impl FnMut(&Path, &Entry) -> std::io::Result<bool>,
+    ) -> Result<Outcome, Error> {
+        let mut out = Outcome::default();
+        let timestamp = self.timestamp;
+        for (idx, (entry, path)) in self.entries_mut_with_paths().enumerate() {
+            if entry.stage() != 0
+                || matches!(entry.mode, entry::Mode::COMMIT | entry::Mode::DIR)
+                || entry
+                    .flags
+                    .intersects(entry::Flags::ASSUME_VALID | entry::Flags::SKIP_WORKTREE | entry::Flags::INTENT_TO_ADD)
+            {
+                continue;
+            }
+            let file_path = worktree.join(git_path::from_bstr(path));
+            let meta = match std::fs::symlink_metadata(&file_path) {
+                Ok(meta) => meta,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    out.deleted.push(idx);
+                    continue;
+                }
+                Err(source) => {
+                    return Err(Error::Metadata {
+                        path: file_path,
+                        source,
+                    })
+                }
+            };
+            let kind_matches = if entry.mode == entry::Mode::SYMLINK {
+                meta.file_type().is_symlink()
+            } else {
+                meta.is_file()
+            };
+            if !kind_matches {
+                out.modified.push(idx);
+                continue;
+            }
+            let stat = Stat::from_fs(&meta).map_err(|source| Error::Time {
+                path: file_path.clone(),
+                source,
+            })?;
+            if entry.stat.matches(&stat, options) && !entry.stat.is_racy(timestamp, options) {
+                entry.flags.insert(entry::Flags::UPTODATE);
+                continue;
+            }
+            if content_matches(&file_path, entry).map_err(|source| Error::ContentCheck {
+                path: file_path.clone(),
+                source,
+            })? {
+                entry.stat = stat;
+                entry.flags.insert(entry::Flags::UPTODATE);
+                out.updated += 1;
+            } else {
+                out.modified.push(idx);
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,262 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_hash::ObjectId;
+use git_object::TreeRefIter;
+
+use crate::{
+    entry::{self, Flags, Stat},
+    extension, Entry, State,
+};
+
+/// Configuration related to sparse indexes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If true, certain entries in the index will be excluded / skipped for certain operations,
+    /// based on the ignore patterns in the `.git/info/sparse-checkout` file. These entries will
+    /// carry the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This typically is the value of `core.sparseCheckout` in the git configuration.
+    pub sparse_checkout: bool,
+
+    /// Interpret the `.git/info/sparse-checkout` file using _cone mode_.
+    ///
+    /// If true, _cone mode_ is active and entire directories will be included in the checkout, as well as files in the root
+    /// of the repository.
+    /// If false, non-cone mode is active and entries to _include_ will be matched with patterns like those found in `.gitignore` files.
+    ///
+    /// This typically is the value of `core.sparseCheckoutCone` in the git configuration.
+    pub directory_patterns_only: bool,
+
+    /// If true, will attempt to write a sparse index file which only works in cone mode.
+    ///
+    /// A sparse index has [`DIR` entries][crate::entry::Mode::DIR] that represent entire directories to be skipped
+    /// during checkout and other operations due to the added presence of
+    /// the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This is typically the value of `index.sparse` in the git configuration.
+    pub write_sparse_index: bool,
+}
+
+impl Options {
+    /// Derive a valid mode from all parameters that affect the 'sparseness' of the index.
+    ///
+    /// Some combinations of them degenerate to one particular mode.
+    pub fn sparse_mode(&self) -> Mode {
+        match (
+            self.sparse_checkout,
+            self.directory_patterns_only,
+            self.write_sparse_index,
+        ) {
+            (true, true, true) => Mode::IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+            (true, true, false) => Mode::IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+            (true, false, _) => Mode::IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+            (false, _, _) => Mode::Disabled,
+        }
+    }
+}
+
+/// Describes the configuration how a sparse index should be written, or if one should be written at all.
+#[derive(Debug)]
+pub enum Mode {
+    /// index with DIR entries for exclusion and included entries, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+    /// index with all file entries and skip worktree flags for exclusion, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+    /// index with all file entries and skip-worktree flags for exclusion, `ignore` patterns to include entries in `.git/info/sparse-checkout` file.
+    IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+    /// index with all entries, non is excluded, `.git/info/sparse-checkout` file is not considered, a regular index.
+    Disabled,
+}
+
+///
+pub mod expand {
+    use bstr::BString;
+
+    /// The error returned by [State::expand_sparse_directories()][crate::State::expand_sparse_directories()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not traverse the tree of sparse directory '{path}'")]
+        Traverse {
+            path: BString,
+            source: git_traverse::tree::breadthfirst::Error,
+        },
+    }
+}
+
+/// Sparse directories
+impl State {
+    /// Replace all [sparse directory entries][entry::Mode::DIR] for which `should_expand(dir_path)` returns true with the entries
+    /// of their tree, which are obtained using `find`, and return the amount of expanded directories.
+    /// Note that `dir_path` has a trailing slash.
+    ///
+    /// The new entries are marked to [skip the worktree][Flags::SKIP_WORKTREE] if the directory entry was.
+    pub fn expand_sparse_directories<Find>(
+        &mut self,
+        mut should_expand: impl FnMut(&BStr) -> bool,
+        mut find: Find,
+    ) -> Result<usize, expand::Error>
+    where
+        Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+    {
+        if !self.is_sparse {
+            return Ok(0);
+        }
+        let mut dirs = Vec::new();
+        self.remove_entries(|_, path, entry| {
+            let expand = entry.mode.is_sparse() && should_expand(path);
+            if expand {
+                dirs.push((path.to_owned(), entry.id, entry.flags & Flags::SKIP_WORKTREE));
+            }
+            expand
+        });
+        for (dir, id, skip_worktree) in &dirs {
+            let tree = State::from_tree(id, &mut find).map_err(|source| expand::Error::Traverse {
+                path: dir.clone(),
+                source,
+            })?;
+            let mut path = dir.clone();
+            for entry in tree.entries() {
+                path.truncate(dir.len());
+                path.push_str(entry.path(&tree));
+                self.dangerously_push_entry(
+                    path.as_bstr(),
+                    entry.stat,
+                    entry.id,
+                    entry.flags | *skip_worktree,
+                    entry.mode,
+                );
+            }
+        }
+        self.sort_entries();
+        self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+        Ok(dirs.len())
+    }
+
+    /// Replace all entries within directories for which `is_excluded(dir_path)` returns true with a single
+    /// [sparse directory entry][entry::Mode::DIR] for the tree of the directory, and return the amount of created directory entries.
+    /// Note that `dir_path` has a trailing slash.
+    ///
+    /// Only directories whose entries are all merged and marked to [skip the worktree][Flags::SKIP_WORKTREE] are collapsed,
+    /// otherwise their sub-directories are tried instead.
+    /// The trees of these directories are passed to `write` to obtain their ids, reusing valid trees of the [`tree` extension][State::tree()].
+    /// The extension is updated afterwards, unless unmerged entries remain in which case only the trees containing collapsed
+    /// directories are invalidated.
+    pub fn collapse_sparse_directories<E>(
+        &mut self,
+        mut is_excluded: impl FnMut(&BStr) -> bool,
+        mut write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+    ) -> Result<usize, extension::tree::update::Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut dirs = Vec::new();
+        let mut prefix = BString::default();
+        self.collect_collapsible_dirs(&mut prefix, &mut is_excluded, &mut write, &mut dirs)?;
+        if dirs.is_empty() {
+            return Ok(0);
+        }
+
+        self.remove_entries(|_, path, _| {
+            let idx = dirs.partition_point(|(dir, _)| dir.as_bstr() <= path);
+            idx > 0 && path.starts_with(&dirs[idx - 1].0)
+        });
+        for (dir, id) in &dirs {
+            self.dangerously_push_entry(
+                dir.as_bstr(),
+                Stat::default(),
+                *id,
+                Flags::SKIP_WORKTREE,
+                entry::Mode::DIR,
+            );
+        }
+        self.sort_entries();
+        if let Some(tree) = self.tree.as_mut() {
+            for (dir, _) in &dirs {
+                tree.invalidate_path(dir[..dir.len() - 1].as_bstr());
+            }
+        }
+        if self.entries.iter().all(|e| e.stage() == 0) {
+            self.update_tree_extension(write)?;
+        }
+        Ok(dirs.len())
+    }
+
+    /// Collect all directories below `prefix` which can be collapsed into `out`, along with the id of their tree, in the order of their paths.
+    fn collect_collapsible_dirs<E>(
+        &self,
+        prefix: &mut BString,
+        is_excluded: &mut impl FnMut(&BStr) -> bool,
+        write: &mut impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+        out: &mut Vec<(BString, ObjectId)>,
+    ) -> Result<(), extension::tree::update::Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let entries = self.entries_in_dir(prefix.as_bstr());
+        let prefix_len = prefix.len();
+        let mut idx = 0;
+        while let Some(entry) = entries.get(idx) {
+            let rest = &entry.path(self)[prefix_len..];
+            let name_len = match rest.find_byte(b'/').filter(|pos| pos + 1 != rest.len()) {
+                Some(pos) => pos,
+                None => {
+                    // files and directories which are already collapsed
+                    idx += 1;
+                    continue;
+                }
+            };
+            prefix.extend_from_slice(&rest[..name_len]);
+            prefix.push_byte(b'/');
+            let dir_entries = self.entries_in_dir(prefix.as_bstr());
+            idx += dir_entries.len();
+            if is_excluded(prefix.as_bstr())
+                && dir_entries
+                    .iter()
+                    .all(|e| e.stage() == 0 && e.flags.contains(Flags::SKIP_WORKTREE))
+            {
+                let id = self.write_dir_tree(prefix.as_bstr(), dir_entries, &mut *write)?;
+                out.push((prefix.clone(), id));
+            } else {
+                self.collect_collapsible_dirs(prefix, is_excluded, write, out)?;
+            }
+            prefix.truncate(prefix_len);
+        }
+        Ok(())
+    }
+
+    /// Write the tree of the directory at `dir` with all of its `entries` using `write`, starting from its tree in the
+    /// [`tree` extension][State::tree()] if there is one, and return its id.
+    fn write_dir_tree<E>(
+        &self,
+        dir: &BStr,
+        entries: &[Entry],
+        write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+    ) -> Result<ObjectId, extension::tree::update::Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let cached = self.tree.as_ref().and_then(|mut tree| {
+            for name in dir[..dir.len() - 1].split_str("/") {
+                tree = tree.children.iter().find(|child| child.name.as_slice() == name)?;
+            }
+            Some(tree.clone())
+        });
+        let mut tree = cached.unwrap_or_else(|| extension::Tree {
+            name: Default::default(),
+            id: ObjectId::null(self.object_hash),
+            num_entries: None,
+            children: Vec::new(),
+        });
+        extension::tree::update::update_dir(&mut tree, entries, &self.path_backing, dir, write)
+    }
+
+    /// Return all entries in the directory at `dir`, which has a trailing slash.
+    fn entries_in_dir(&self, dir: &BStr) -> &[Entry] {
+        let start = self.entries.partition_point(|e| e.path(self) < dir);
+        let len = self.entries[start..]
+            .iter()
+            .take_while(|e| e.path(self).starts_with(dir))
+            .count();
+        &self.entries[start..][..len]
+    }
+}
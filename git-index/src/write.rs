@@ -14,8 +14,16 @@ pub enum Extensions {
     /// Mandatory extensions, like `sdir` or other lower-case ones, may not be configured here as they need to be present
     /// or absent depending on the state of the index itself and for it to be valid.
     Given {
+        /// Write the link extension of a split index, if present.
+        link: bool,
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
     },
@@ -36,10 +44,18 @@ impl Extensions {
             Extensions::None => None,
             Extensions::All => Some(signature),
             Extensions::Given {
+                link,
                 tree_cache,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
             } => match signature {
+                extension::link::SIGNATURE => link,
                 extension::tree::SIGNATURE => tree_cache,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
                 _ => &false,
             }
@@ -50,7 +66,8 @@ impl Extensions {
 
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options write either index V2 or V3 depending on the content of the entries, unless the state is
+/// [set to V4][State::set_version()] which will be kept.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write
@@ -70,7 +87,7 @@ impl State {
             .expect("definitely not 4billion entries");
 
         let offset_to_entries = header(&mut write, version, num_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
+        let offset_to_extensions = entries(&mut write, self, offset_to_entries, version)?;
         let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
 
         if num_entries > 0
@@ -96,11 +113,41 @@ impl State {
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                extensions
+                    .should_write(extension::link::SIGNATURE)
+                    .and_then(|signature| self.link().map(|link| link.write_to(write).map(|_| signature)))
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(paths, write).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked().map(|cache| {
+                            extension::untracked_cache::write_to(cache, self.object_hash, write).map(|_| signature)
+                        })
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor()
+                            .map(|fs_monitor| fs_monitor.write_to(&self.entries, write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -108,7 +155,7 @@ impl State {
         ];
 
         let mut offset_to_previous_ext = offset_to_extensions;
-        let mut out = Vec::with_capacity(5);
+        let mut out = Vec::with_capacity(extensions.len());
         for write_ext in extensions {
             if let Some(signature) = write_ext(&mut write).transpose()? {
                 let offset_past_ext = write.count;
@@ -123,6 +170,9 @@ impl State {
 
 impl State {
     fn detect_required_version(&self) -> Version {
+        if self.version == Version::V4 {
+            return Version::V4;
+        }
         self.entries
             .iter()
             .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then(|| Version::V3))
@@ -148,7 +198,15 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    header_size: u32,
+    version: Version,
+) -> Result<u32, std::io::Error> {
+    if version == Version::V4 {
+        return entries_with_path_compression(out, state);
+    }
     for entry in state.entries() {
         entry.write_to(&mut *out, state)?;
         match (out.count - header_size) % 8 {
@@ -163,7 +221,30 @@ fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_siz
     Ok(out.count)
 }
 
-mod util {
+/// Write entries with their path stored as the amount of bytes to remove from the end of the previous path, followed by
+/// the bytes to append to it, without padding.
+fn entries_with_path_compression<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+) -> Result<u32, std::io::Error> {
+    let mut previous_path: &[u8] = &[];
+    for entry in state.entries() {
+        let path = entry.path(state);
+        let common_len = previous_path
+            .iter()
+            .zip(path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        entry.write_without_path_to(&mut *out, path)?;
+        util::write_var_int(&mut *out, (previous_path.len() - common_len) as u64)?;
+        out.write_all(&path[common_len..])?;
+        out.write_all(b"\0")?;
+        previous_path = path;
+    }
+    Ok(out.count)
+}
+
+pub(crate) mod util {
     use std::convert::TryFrom;
 
     pub struct CountBytes<T> {
@@ -202,4 +283,34 @@ mod util {
             self.inner.flush()
         }
     }
+
+    /// Write `value` as variable-length integer to `out`, the way `git` encodes them in the index.
+    pub fn write_var_int(mut out: impl std::io::Write, mut value: u64) -> std::io::Result<()> {
+        let mut buf = [0u8; 10];
+        let mut pos = buf.len() - 1;
+        buf[pos] = value as u8 & 0x7f;
+        loop {
+            value >>= 7;
+            if value == 0 {
+                break;
+            }
+            value -= 1;
+            pos -= 1;
+            buf[pos] = 0x80 | (value as u8 & 0x7f);
+        }
+        out.write_all(&buf[pos..])
+    }
+
+    /// Write an extension with `signature` whose data is produced by `write_data`, prefixed with its size.
+    pub fn write_extension(
+        mut out: impl std::io::Write,
+        signature: crate::extension::Signature,
+        write_data: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        let mut data = Vec::new();
+        write_data(&mut data)?;
+        out.write_all(&signature)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
 }
@@ -0,0 +1,10 @@
+#!/bin/bash
+set -eu -o pipefail
+
+export GIT_INDEX_VERSION=2
+git init -q
+
+echo content > a
+touch -m -d @1234567890 a
+git add a
+git commit -m "a with modification time in the past"
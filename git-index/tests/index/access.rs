@@ -0,0 +1,203 @@
+use bstr::{BStr, ByteSlice};
+use git_index::{
+    entry::{Flags, Mode, Stat},
+    State,
+};
+use git_testtools::hex_to_id;
+
+fn paths_and_stages(state: &State) -> Vec<(&BStr, u32)> {
+    state.entries().iter().map(|e| (e.path(state), e.stage())).collect()
+}
+
+fn id() -> git_hash::ObjectId {
+    hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")
+}
+
+#[test]
+fn upsert_entry_inserts_new_entries_in_order_and_updates_existing_ones() {
+    let mut state = State::new(git_hash::Kind::Sha1);
+    for path in ["b", "d/a", "a", "c", "d.b"] {
+        state.upsert_entry(path.into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    }
+    state.verify_entries().expect("entries are sorted");
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![
+            ("a".into(), 0),
+            ("b".into(), 0),
+            ("c".into(), 0),
+            ("d.b".into(), 0),
+            ("d/a".into(), 0)
+        ]
+    );
+
+    let idx = state.upsert_entry("c".into(), Stat::default(), id(), Flags::empty(), Mode::FILE_EXECUTABLE);
+    assert_eq!(idx, 2);
+    assert_eq!(state.entries().len(), 5, "existing entries are updated");
+    assert_eq!(state.entry(idx).mode, Mode::FILE_EXECUTABLE);
+    assert!(!state.is_sparse());
+}
+
+#[test]
+fn upsert_entry_at_stage_0_resolves_conflicts() {
+    let mut state = State::new(git_hash::Kind::Sha1);
+    for (path, stage) in [("a", 0), ("b", 1), ("b", 2), ("b", 3), ("c", 0)] {
+        let mut flags = Flags::empty();
+        flags.set_stage(stage);
+        state.upsert_entry(path.into(), Stat::default(), id(), flags, Mode::FILE);
+    }
+    assert_eq!(state.entries().len(), 5);
+
+    let idx = state.upsert_entry("b".into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    assert_eq!(idx, 1);
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 0), ("c".into(), 0)]
+    );
+    assert_eq!(
+        state.resolve_undo().expect("conflicts are remembered").len(),
+        1,
+        "all stages of 'b' can be restored"
+    );
+    assert_eq!(state.path_backing().as_slice(), b"acb", "removed paths are dropped");
+    state.verify_entries().expect("sorted");
+}
+
+#[test]
+fn upsert_entry_removes_entries_conflicting_as_directory_or_file() {
+    let mut state = State::new(git_hash::Kind::Sha1);
+    for path in ["a", "b/a", "b/c/d", "b-c", "c"] {
+        state.upsert_entry(path.into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    }
+
+    state.upsert_entry("b".into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 0), ("b-c".into(), 0), ("c".into(), 0)],
+        "the file replaces the directory of the same name"
+    );
+
+    state.upsert_entry("c/d/e".into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 0), ("b-c".into(), 0), ("c/d/e".into(), 0)],
+        "files at the location of parent directories are replaced"
+    );
+    assert_eq!(
+        state.path_backing().as_slice(),
+        b"abb-cc/d/e",
+        "paths of removed entries are dropped"
+    );
+    state.verify_entries().expect("sorted");
+}
+
+#[test]
+fn dangerously_push_entry_needs_sorting_afterwards() {
+    let mut state = State::new(git_hash::Kind::Sha1);
+    for path in ["c", "b", "a"] {
+        state.dangerously_push_entry(path.into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    }
+    assert!(state.verify_entries().is_err());
+    state.sort_entries();
+    state.verify_entries().expect("sorted");
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 0), ("c".into(), 0)]
+    );
+}
+
+#[test]
+fn entries_can_be_removed_and_invalidate_the_tree_extension() {
+    let mut file = git_index::File::at(
+        crate::fixture_index_path("v2_more_files"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        file.tree().expect("present").num_entries,
+        Some(6),
+        "the tree extension is valid initially"
+    );
+
+    let removed = file.remove_entry_at(0);
+    assert_eq!(removed.path(&file), "a");
+    let tree = file.tree().expect("still present");
+    assert_eq!(tree.num_entries, None, "the root is invalidated");
+    assert_eq!(tree.children[0].num_entries, Some(3), "sub-trees are left alone");
+
+    let mut seen = Vec::new();
+    file.remove_entries(|idx, path, _entry| {
+        seen.push(idx);
+        path.starts_with_str("d/")
+    });
+    assert_eq!(seen, vec![0, 1, 2, 3, 4], "all entries are offered for removal");
+    assert_eq!(paths_and_stages(&file), vec![("b".into(), 0), ("c".into(), 0)]);
+    assert_eq!(
+        file.tree().expect("present").children[0].num_entries,
+        None,
+        "the sub-tree is invalidated"
+    );
+    file.verify_entries().expect("still sorted");
+}
+
+#[test]
+fn set_entry_stage_moves_entries_into_place() {
+    let mut state = State::new(git_hash::Kind::Sha1);
+    for path in ["a", "b", "c"] {
+        state.upsert_entry(path.into(), Stat::default(), id(), Flags::empty(), Mode::FILE);
+    }
+    let mut flags = Flags::empty();
+    flags.set_stage(3);
+    let theirs = state.upsert_entry("b".into(), Stat::default(), id(), flags, Mode::FILE);
+    assert_eq!(theirs, 2);
+
+    let ours = state.set_entry_stage(1, 2);
+    assert_eq!(ours, 1);
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 2), ("b".into(), 3), ("c".into(), 0)]
+    );
+    state.verify_entries().expect("sorted");
+    assert_eq!(state.entry_index_by_path_and_stage("b".into(), 3), Some(2));
+
+    let resolved = state.set_entry_stage(2, 0);
+    assert_eq!(resolved, 1);
+    let idx = state.set_entry_stage(2, 0);
+    assert_eq!(idx, 1, "an existing entry in the same stage is replaced");
+    assert_eq!(
+        paths_and_stages(&state),
+        vec![("a".into(), 0), ("b".into(), 0), ("c".into(), 0)]
+    );
+}
+
+mod refresh {
+    use std::path::Path;
+
+    use git_index::entry::stat;
+
+    #[test]
+    fn updates_stat_of_unchanged_files_and_reports_changes() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_writable("make_index/v2_more_files.sh")?;
+        let mut index = git_index::File::at(
+            dir.path().join(".git").join("index"),
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )?;
+
+        std::fs::write(dir.path().join("d").join("a"), b"changed")?;
+        std::fs::remove_file(dir.path().join("b"))?;
+        let mut checked = 0;
+        let outcome = index.refresh(dir.path(), stat::Options::default(), |path: &Path, entry| {
+            checked += 1;
+            let data = std::fs::read(path)?;
+            Ok(git_object::compute_hash(git_hash::Kind::Sha1, git_object::Kind::Blob, &data) == entry.id)
+        })?;
+        assert_eq!(outcome.modified, vec![3], "d/a was changed");
+        assert_eq!(outcome.deleted, vec![1], "b was deleted");
+        assert!(checked >= 1, "at least the changed file needs its content checked");
+        assert_eq!(outcome.updated, checked - 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+mod stat {
+    use git_index::entry::{stat, Stat, Time};
+
+    #[test]
+    fn from_fs_matches_itself_until_the_file_changes() -> crate::Result {
+        let tmp = git_testtools::tempfile::tempdir()?;
+        let path = tmp.path().join("file");
+        std::fs::write(&path, b"content")?;
+        let stat = Stat::from_fs(&std::fs::symlink_metadata(&path)?)?;
+        assert_eq!(stat.size, 7);
+        assert!(stat.matches(&Stat::from_fs(&std::fs::symlink_metadata(&path)?)?, Default::default()));
+
+        std::fs::write(&path, b"more content")?;
+        let changed = Stat::from_fs(&std::fs::symlink_metadata(&path)?)?;
+        assert!(!stat.matches(&changed, Default::default()), "the size differs");
+        Ok(())
+    }
+
+    #[test]
+    fn check_stat_and_trust_ctime_limit_the_fields_to_compare() {
+        let index = Stat {
+            mtime: Time { secs: 10, nsecs: 5 },
+            ctime: Time { secs: 10, nsecs: 5 },
+            ino: 1,
+            size: 42,
+            ..Default::default()
+        };
+        let mut worktree = index;
+        worktree.ino = 2;
+        worktree.mtime.nsecs = 0;
+        assert!(!index.matches(&worktree, Default::default()));
+        let minimal = stat::Options {
+            check_stat: false,
+            ..Default::default()
+        };
+        assert!(index.matches(&worktree, minimal));
+
+        worktree.ctime.secs = 11;
+        assert!(!index.matches(&worktree, minimal));
+        assert!(index.matches(
+            &worktree,
+            stat::Options {
+                trust_ctime: false,
+                ..minimal
+            }
+        ));
+    }
+
+    #[test]
+    fn is_racy_if_modified_at_or_after_the_index_timestamp() {
+        let stat = Stat {
+            mtime: Time { secs: 10, nsecs: 5 },
+            ..Default::default()
+        };
+        let opts = stat::Options::default();
+        assert!(stat.is_racy(filetime::FileTime::from_unix_time(10, 5), opts));
+        assert!(stat.is_racy(filetime::FileTime::from_unix_time(9, 0), opts));
+        assert!(!stat.is_racy(filetime::FileTime::from_unix_time(10, 6), opts));
+        assert!(
+            stat.is_racy(
+                filetime::FileTime::from_unix_time(10, 6),
+                stat::Options {
+                    use_nsec: false,
+                    ..opts
+                }
+            ),
+            "only seconds are compared without nano-seconds"
+        );
+        assert!(
+            !stat.is_racy(filetime::FileTime::from_unix_time(0, 0), opts),
+            "unknown timestamps never make entries racy"
+        );
+    }
+}
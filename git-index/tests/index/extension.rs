@@ -0,0 +1,174 @@
+mod tree {
+    use git_index::{entry, State};
+
+    fn write_tree(tree: &git_object::Tree) -> Result<git_hash::ObjectId, std::convert::Infallible> {
+        let mut buf = Vec::new();
+        git_object::WriteTo::write_to(tree, &mut buf).expect("in-memory write");
+        Ok(git_object::compute_hash(
+            git_hash::Kind::Sha1,
+            git_object::Kind::Tree,
+            &buf,
+        ))
+    }
+
+    fn v2_more_files() -> State {
+        git_index::File::at(
+            crate::fixture_index_path("v2_more_files"),
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )
+        .unwrap()
+        .into_state()
+    }
+
+    #[test]
+    fn update_reuses_valid_trees_and_computes_the_same_ids_as_git() -> crate::Result {
+        let mut state = v2_more_files();
+        let expected_root = state.tree().expect("present").id;
+        let mut writes = 0;
+        let root = state.update_tree_extension(|tree| {
+            writes += 1;
+            write_tree(tree)
+        })?;
+        assert_eq!(root, expected_root);
+        assert_eq!(writes, 0, "nothing needs to be written if the tree extension is valid");
+
+        let mut fresh = State::new(git_hash::Kind::Sha1);
+        for entry in state.entries() {
+            fresh.dangerously_push_entry(entry.path(&state), entry.stat, entry.id, entry.flags, entry.mode);
+        }
+        let mut writes = 0;
+        let root = fresh.update_tree_extension(|tree| {
+            writes += 1;
+            write_tree(tree)
+        })?;
+        assert_eq!(root, expected_root, "trees are computed just like git does it");
+        assert_eq!(writes, 2, "the root tree and d/");
+        assert_eq!(
+            fresh.tree(),
+            state.tree(),
+            "the extension is the same as the one written by git"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_after_changes_only_writes_invalidated_trees() -> crate::Result {
+        let mut state = v2_more_files();
+        let id = state.entries()[0].id;
+        state.upsert_entry(
+            "e/f".into(),
+            entry::Stat::default(),
+            id,
+            entry::Flags::empty(),
+            entry::Mode::FILE,
+        );
+        state.remove_entry_at(0);
+
+        let mut written = Vec::new();
+        state.update_tree_extension(|tree| {
+            written.push(tree.entries.iter().map(|e| e.filename.to_string()).collect::<Vec<_>>());
+            write_tree(tree)
+        })?;
+        assert_eq!(
+            written,
+            vec![
+                vec!["f".to_string()],
+                vec!["b".into(), "c".into(), "d".into(), "e".into()]
+            ],
+            "d/ is reused and e/ is created"
+        );
+        let tree = state.tree().expect("present");
+        assert_eq!(tree.num_entries, Some(6));
+        assert_eq!(
+            tree.children.iter().map(|c| c.num_entries).collect::<Vec<_>>(),
+            vec![Some(3), Some(1)]
+        );
+        state.verify_extensions(false, git_index::verify::extensions::no_find)?;
+        Ok(())
+    }
+
+    #[test]
+    fn write_tree_leaves_the_extension_unchanged() -> crate::Result {
+        let state = v2_more_files();
+        let expected_root = state.tree().expect("present").id;
+        let mut writes = 0;
+        let root = state.write_tree(|tree| {
+            writes += 1;
+            write_tree(tree)
+        })?;
+        assert_eq!(root, expected_root);
+        assert_eq!(writes, 0, "valid trees are reused");
+
+        let source = state;
+        let mut state = State::new(git_hash::Kind::Sha1);
+        for entry in source.entries() {
+            state.dangerously_push_entry(entry.path(&source), entry.stat, entry.id, entry.flags, entry.mode);
+        }
+        let mut writes = 0;
+        let root = state.write_tree(|tree| {
+            writes += 1;
+            write_tree(tree)
+        })?;
+        assert_eq!(root, expected_root);
+        assert_eq!(writes, 2, "the root tree and d/");
+        assert!(state.tree().is_none(), "the extension isn't created");
+
+        let empty = State::new(git_hash::Kind::Sha1);
+        assert_eq!(
+            empty.write_tree(write_tree)?,
+            git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1),
+            "an empty index yields the empty tree"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_fails_on_unmerged_entries() {
+        let mut state = git_index::File::at(
+            crate::loose_file_path("conflicting-file"),
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )
+        .unwrap()
+        .into_state();
+        state.upsert_entry(
+            "new".into(),
+            entry::Stat::default(),
+            git_hash::Kind::Sha1.null(),
+            entry::Flags::empty(),
+            entry::Mode::FILE,
+        );
+        let err = state.update_tree_extension(write_tree).unwrap_err();
+        assert!(matches!(
+            err,
+            git_index::extension::tree::update::Error::Unmerged { stage: 1, .. }
+        ));
+    }
+}
+
+mod resolve_undo {
+    #[test]
+    fn removing_conflicting_entries_records_them() -> crate::Result {
+        let mut file = git_index::File::at(
+            crate::loose_file_path("conflicting-file"),
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )?;
+        assert!(file.resolve_undo().is_none());
+        file.remove_entries(|_, _, entry| entry.stage() != 0);
+        assert_eq!(file.entries().len(), 0);
+        assert_eq!(file.resolve_undo().expect("created").len(), 1, "one path with 3 stages");
+
+        let mut out = Vec::new();
+        file.write_to(&mut out, Default::default())?;
+        let (actual, _) = git_index::State::from_bytes(
+            &out,
+            filetime::FileTime::now(),
+            git_hash::Kind::Sha1,
+            Default::default(),
+        )?;
+        assert_eq!(actual.resolve_undo().expect("written").len(), 1);
+        Ok(())
+    }
+}
@@ -102,6 +102,20 @@ fn v2_with_multiple_entries_without_eoie_ext() {
     assert_eq!(tree.name.as_bstr(), "d");
 }
 
+#[test]
+fn v2_entry_stat_keeps_mtime_and_ctime_apart() {
+    let file = file("v2_mtime_differs_from_ctime");
+    assert_eq!(file.entries().len(), 1);
+
+    let stat = file.entries()[0].stat;
+    assert_eq!(stat.mtime.secs, 1234567890, "the modification time was set explicitly");
+    assert_eq!(stat.mtime.nsecs, 0);
+    assert!(
+        stat.ctime.secs > stat.mtime.secs,
+        "the status change time is the time the file was touched, which is long after its modification time"
+    );
+}
+
 fn find_shared_index_for(index: impl AsRef<Path>) -> PathBuf {
     let mut matches = std::fs::read_dir(index.as_ref().parent().unwrap())
         .unwrap()
@@ -174,7 +188,17 @@ fn untr_extension() {
     let file = loose_file("UNTR");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.untracked().is_some());
+    let untracked = file.untracked().expect("present");
+    assert_eq!(untracked.dir_flags(), 6, "show other directories and hide empty ones");
+    assert_eq!(untracked.exclude_filename_per_dir(), ".gitignore");
+    let info_exclude = untracked.info_exclude().expect("info/exclude was present");
+    assert_eq!(
+        info_exclude.id,
+        hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        "it's empty"
+    );
+    assert_eq!(info_exclude.stat.size, 0);
+    assert!(untracked.excludes_file().is_none(), "no excludes file is configured");
 }
 
 #[test]
@@ -182,7 +206,17 @@ fn untr_extension_with_oids() {
     let file = loose_file("UNTR-with-oids");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.untracked().is_some());
+    let untracked = file.untracked().expect("present");
+    assert_eq!(untracked.dir_flags(), 6, "show other directories and hide empty ones");
+    assert_eq!(untracked.exclude_filename_per_dir(), ".gitignore");
+    let info_exclude = untracked.info_exclude().expect("info/exclude was present");
+    assert_eq!(
+        info_exclude.id,
+        hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        "it's empty"
+    );
+    assert_eq!(info_exclude.stat.size, 0);
+    assert!(untracked.excludes_file().is_none(), "no excludes file is configured");
 }
 
 #[test]
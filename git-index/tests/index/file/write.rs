@@ -13,13 +13,26 @@ fn roundtrips() -> crate::Result {
         (
             Generated("v2"),
             options_with(write::Extensions::Given {
+                link: false,
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: true,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Loose("REUC"), all_ext_but_eoie()),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
+        (Generated("v2_split_index"), all_ext_but_eoie()),
+        (Generated("v3_skip_worktree"), only_tree_ext()),
+        (Generated("v3_sparse_index_non_cone"), only_tree_ext()),
+        (Generated("v3_sparse_index"), only_tree_ext()),
+        (Generated("v2_sparse_index_no_dirs"), only_tree_ext()),
     ];
 
     for (fixture, options) in input {
@@ -40,46 +53,6 @@ fn roundtrips() -> crate::Result {
     Ok(())
 }
 
-#[test]
-fn roundtrips_sparse_index() -> crate::Result {
-    // NOTE: I initially tried putting these fixtures into the main roundtrip test above,
-    // but the call to `compare_raw_bytes` panics. It seems like git is using a different
-    // ordering when it comes to writing the tree extension. Need to investigate more, hence
-    // the separate test for now.
-    //
-    //          git                     gitoxide
-    //
-    //          treeroot                treeroot
-    //            | d                     | c1
-    //            | d/c4                  | c1/c2
-    //            | c1                    | c1/c3
-    //            | c1/c2                 | d
-    //            | c1/c3                 | d/c4
-    //
-
-    let input = [
-        ("v3_skip_worktree", only_tree_ext()),
-        ("v3_sparse_index_non_cone", only_tree_ext()),
-        ("v3_sparse_index", only_tree_ext()),
-        ("v2_sparse_index_no_dirs", only_tree_ext()),
-    ];
-
-    for (fixture, options) in input {
-        let path = fixture_index_path(fixture);
-        let expected = git_index::File::at(&path, git_hash::Kind::Sha1, Default::default())?;
-        let _expected_bytes = std::fs::read(&path)?;
-        let mut out_bytes = Vec::new();
-
-        let (actual_version, _) = expected.write_to(&mut out_bytes, options)?;
-        let (actual, _) = State::from_bytes(&out_bytes, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
-
-        compare_states_against_baseline(&actual, actual_version, &expected, options, fixture);
-        // TODO: make this work and re-enable it, once this is done the fixtures can be merged into the main "roundtrip" test
-        // compare_raw_bytes(&out_bytes, &_expected_bytes, fixture);
-    }
-    Ok(())
-}
-
 #[test]
 fn state_comparisons_with_various_extension_configurations() {
     for fixture in [
@@ -95,9 +68,7 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this failes because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required verison, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files_IEOT"),
         Generated("v3_skip_worktree"),
         Generated("v3_sparse_index_non_cone"),
         Generated("v3_sparse_index"),
@@ -108,11 +79,19 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::None),
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
+                link: false,
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: false,
             }),
             options_with(write::Extensions::Given {
+                link: true,
                 tree_cache: false,
+                resolve_undo: true,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: true,
             }),
         ] {
@@ -130,6 +109,30 @@ fn state_comparisons_with_various_extension_configurations() {
     }
 }
 
+#[test]
+fn split_index_link_is_dropped_once_entries_change() -> crate::Result {
+    let mut file = git_index::File::at(
+        fixture_index_path("v2_split_index"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    assert!(file.link().is_some());
+
+    let entry = file.entry(0).clone();
+    let path = entry.path(&file).to_owned();
+    file.upsert_entry(path.as_ref(), entry.stat, entry.id, entry.flags, entry.mode);
+    assert!(
+        file.link().is_none(),
+        "the link extension refers to shared index entries by position, which may have changed"
+    );
+
+    let mut buf = Vec::new();
+    file.write_to(&mut buf, Default::default())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
+    assert!(actual.link().is_none(), "hence it isn't written anymore");
+    Ok(())
+}
+
 #[test]
 fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crate::Result {
     let mut expected = git_index::File::at(fixture_index_path("v2"), git_hash::Kind::Sha1, Default::default())?;
@@ -143,6 +146,41 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn v4_is_kept_and_compresses_paths() -> crate::Result {
+    let path = fixture_index_path("v4_more_files_IEOT");
+    let expected = git_index::File::at(&path, git_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(expected.version(), Version::V4);
+
+    let mut out_bytes = Vec::new();
+    let (actual_version, _digest) = expected.write_to(&mut out_bytes, options_with(write::Extensions::None))?;
+    assert_eq!(actual_version, Version::V4, "the version is retained");
+    let (actual, _) = State::from_bytes(&out_bytes, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(actual.version(), Version::V4);
+    assert_eq!(actual.entries(), expected.entries());
+    assert_eq!(actual.path_backing(), expected.path_backing());
+
+    let mut file = git_index::File::at(
+        fixture_index_path("v2_more_files"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    let mut v2_bytes = Vec::new();
+    file.write_to(&mut v2_bytes, options_with(write::Extensions::None))?;
+    file.set_version(Version::V4);
+    let mut v4_bytes = Vec::new();
+    file.write_to(&mut v4_bytes, options_with(write::Extensions::None))?;
+    assert!(
+        v4_bytes.len() < v2_bytes.len(),
+        "paths are compressed and entries aren't padded"
+    );
+    let (actual, _) = State::from_bytes(&v4_bytes, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
+    assert_eq!(actual.version(), Version::V4);
+    assert_eq!(actual.entries(), file.entries());
+    assert_eq!(actual.path_backing(), file.path_backing());
+    Ok(())
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -179,6 +217,16 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
         "tree extension mismatch, actual vs option in {:?}",
         fixture
     );
+    assert_eq!(
+        actual.link().map(|link| link.shared_index_checksum),
+        options
+            .extensions
+            .should_write(extension::link::SIGNATURE)
+            .and_then(|_| expected.link())
+            .map(|link| link.shared_index_checksum),
+        "link extension mismatch, actual vs option in {:?}",
+        fixture
+    );
 
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing againt baseline
@@ -237,7 +285,24 @@ fn only_tree_ext() -> Options {
     Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
+            link: false,
+            tree_cache: true,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
+        },
+    }
+}
+
+fn all_ext_but_eoie() -> Options {
+    Options {
+        extensions: write::Extensions::Given {
+            end_of_index_entry: false,
+            link: true,
             tree_cache: true,
+            resolve_undo: true,
+            untracked_cache: true,
+            fs_monitor: true,
         },
     }
 }
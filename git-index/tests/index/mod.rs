@@ -1,7 +1,11 @@
 use std::path::{Path, PathBuf};
 
+mod access;
+mod entry;
+mod extension;
 mod file;
 mod init;
+mod sparse;
 
 pub fn fixture_index_path(name: &str) -> PathBuf {
     let dir = git_testtools::scripted_fixture_read_only(Path::new("make_index").join(name).with_extension("sh"))
@@ -15,7 +19,7 @@ pub fn loose_file_path(name: &str) -> PathBuf {
 
 #[test]
 fn size_of_entry() {
-    assert_eq!(std::mem::size_of::<git_index::Entry>(), 80);
+    assert_eq!(std::mem::size_of::<git_index::Entry>(), 96);
 
     // the reason we have our own time is half the size.
     assert_eq!(std::mem::size_of::<git_index::entry::Time>(), 8);
@@ -0,0 +1,129 @@
+use git_index::{entry, State};
+use git_repository::odb::FindExt;
+
+fn write_tree(tree: &git_object::Tree) -> Result<git_hash::ObjectId, std::convert::Infallible> {
+    let mut buf = Vec::new();
+    git_object::WriteTo::write_to(tree, &mut buf).expect("in-memory write");
+    Ok(git_object::compute_hash(
+        git_hash::Kind::Sha1,
+        git_object::Kind::Tree,
+        &buf,
+    ))
+}
+
+fn paths_modes_and_ids(state: &State) -> Vec<(String, entry::Mode, git_hash::ObjectId)> {
+    state
+        .entries()
+        .iter()
+        .map(|e| (e.path(state).to_string(), e.mode, e.id))
+        .collect()
+}
+
+#[test]
+fn expand_and_collapse_sparse_directories() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_read_only("make_index/v3_sparse_index.sh")?;
+    let repo = git_repository::open(&dir)?;
+    let mut state =
+        git_index::File::at(dir.join(".git").join("index"), git_hash::Kind::Sha1, Default::default())?.into_state();
+    assert!(state.is_sparse());
+    let expected_entries = paths_modes_and_ids(&state);
+    let expected_root = state.write_tree(write_tree)?;
+    assert_eq!(
+        expected_root,
+        repo.head_commit()?.tree_id()?,
+        "sparse directories are trees"
+    );
+
+    let num_expanded =
+        state.expand_sparse_directories(|dir| dir == "d/", |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    assert_eq!(num_expanded, 1);
+    assert!(state.is_sparse(), "c1/c3/ is still a sparse directory");
+    let num_expanded =
+        state.expand_sparse_directories(|_| true, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    assert_eq!(num_expanded, 1);
+    assert!(!state.is_sparse());
+    state.verify_entries()?;
+    let paths: Vec<_> = state.entries().iter().map(|e| e.path(&state).to_string()).collect();
+    assert_eq!(
+        paths,
+        [
+            "a", "b", "c1/a", "c1/b", "c1/c2/a", "c1/c2/b", "c1/c3/a", "c1/c3/b", "d/a", "d/b", "d/c4/a", "d/c4/b",
+            "d/c4/c5"
+        ]
+    );
+    for entry in state.entries() {
+        let path = entry.path(&state);
+        assert_eq!(
+            entry.flags.contains(entry::Flags::SKIP_WORKTREE),
+            path.starts_with(b"c1/c3/") || path.starts_with(b"d/"),
+            "{}: expanded entries skip the worktree just like their directory",
+            path
+        );
+    }
+    assert_eq!(state.write_tree(write_tree)?, expected_root, "the content is unchanged");
+
+    let num_collapsed =
+        state.collapse_sparse_directories(|dir| dir == "c1/c3/" || dir.starts_with(b"d/"), write_tree)?;
+    assert_eq!(num_collapsed, 2);
+    assert!(state.is_sparse());
+    assert_eq!(
+        paths_modes_and_ids(&state),
+        expected_entries,
+        "we collapse like git does"
+    );
+    assert_eq!(state.tree().expect("updated").id, expected_root);
+    state.verify_extensions(false, git_index::verify::extensions::no_find)?;
+
+    let num_collapsed = state.collapse_sparse_directories(|_| true, write_tree)?;
+    assert_eq!(
+        num_collapsed, 0,
+        "directories with entries that don't skip the worktree stay"
+    );
+    Ok(())
+}
+
+#[test]
+fn collapse_sparse_directories_skips_those_with_unmerged_entries() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_read_only("make_index/v3_sparse_index.sh")?;
+    let repo = git_repository::open(&dir)?;
+    let mut state =
+        git_index::File::at(dir.join(".git").join("index"), git_hash::Kind::Sha1, Default::default())?.into_state();
+    let expected_entries = paths_modes_and_ids(&state);
+    state.expand_sparse_directories(|_| true, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    for (entry, path) in state.entries_mut_with_paths() {
+        if path == "a" || path == "d/c4/a" {
+            entry.flags.set_stage(1);
+        }
+    }
+
+    let num_collapsed =
+        state.collapse_sparse_directories(|dir| dir == "c1/c3/" || dir.starts_with(b"d/"), write_tree)?;
+    assert_eq!(num_collapsed, 1, "only d/ and d/c4/ contain an unmerged entry");
+    assert!(state.is_sparse());
+    let paths: Vec<_> = state
+        .entries()
+        .iter()
+        .map(|e| (e.path(&state).to_string(), e.mode))
+        .collect();
+    assert_eq!(
+        paths.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(),
+        ["a", "b", "c1/a", "c1/b", "c1/c2/a", "c1/c2/b", "c1/c3/", "d/a", "d/b", "d/c4/a", "d/c4/b", "d/c4/c5"]
+    );
+    let collapsed = state.entry_by_path_and_stage("c1/c3/".into(), 0).expect("present");
+    let expected = expected_entries
+        .iter()
+        .find(|(path, _, _)| path == "c1/c3/")
+        .expect("sparse in fixture");
+    assert_eq!((collapsed.mode, collapsed.id), (expected.1, expected.2));
+    assert!(
+        state.tree().map_or(true, |tree| tree.num_entries.is_none()),
+        "the tree extension can't be updated with unmerged entries, and is invalidated instead"
+    );
+
+    state.expand_sparse_directories(|_| true, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    assert!(
+        !state.is_sparse(),
+        "without sparse directories, the index isn't sparse anymore"
+    );
+    Ok(())
+}
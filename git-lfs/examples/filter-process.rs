@@ -0,0 +1,14 @@
+//! A long-running LFS filter process to be configured as `filter.lfs.process`, which stores objects in `$GIT_DIR/lfs`
+//! or `.git/lfs` of the current directory.
+//!
+//! Objects that aren't present locally can't be fetched, as no LFS server is configured.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let git_dir = std::env::var_os("GIT_DIR").unwrap_or_else(|| ".git".into());
+    let storage = git_lfs::Storage::at_git_dir(git_dir);
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    git_lfs::filter::process(stdin.lock(), stdout.lock(), &storage, &mut |pointer, _storage| {
+        Err(format!("LFS object {} isn't available locally", pointer.oid).into())
+    })?;
+    Ok(())
+}
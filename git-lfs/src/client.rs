@@ -0,0 +1,325 @@
+use std::io::BufRead;
+
+use git_transport::client::http::{GetResponse, Http, PostResponse};
+
+use crate::{Client, Pointer, Storage};
+
+/// The media type used by the LFS batch API for both requests and responses.
+const MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+const USER_AGENT_HEADER: &str = concat!("User-Agent: git/oxide-", env!("CARGO_PKG_VERSION"));
+
+///
+pub mod batch {
+    use std::collections::BTreeMap;
+
+    /// The error returned by [`Client::batch()`][crate::Client::batch()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Http(#[from] git_transport::client::http::Error),
+        #[error("Failed to send the request or to receive the response")]
+        Io(#[from] std::io::Error),
+        #[error("Could not decode the response of the LFS server")]
+        Decode(#[from] serde_json::Error),
+        #[error("The LFS server chose the unsupported transfer adapter '{name}'")]
+        UnsupportedTransfer { name: String },
+    }
+
+    impl Error {
+        /// Return `true` if the server requires authentication or rejected the credentials we sent.
+        pub fn is_permission_denied(&self) -> bool {
+            matches!(self, Error::Io(err) if err.kind() == std::io::ErrorKind::PermissionDenied)
+        }
+    }
+
+    /// The kind of operation to perform in a batch request.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Operation {
+        /// Obtain the location of objects to download.
+        Download,
+        /// Obtain the location to which to upload objects.
+        Upload,
+    }
+
+    /// An object as sent in a batch request.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+    pub struct RequestObject {
+        /// The sha256 hash of the object in hexadecimal notation.
+        pub oid: String,
+        /// The size of the object in bytes.
+        pub size: u64,
+    }
+
+    /// A batch request as sent to `<endpoint>/objects/batch`.
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+    pub struct Request {
+        /// The operation to perform.
+        pub operation: Operation,
+        /// The transfer adapters we support.
+        pub transfers: Vec<String>,
+        /// The objects to obtain actions for.
+        pub objects: Vec<RequestObject>,
+        /// The hash algorithm of object ids.
+        pub hash_algo: String,
+    }
+
+    /// The response to a batch [`Request`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+    pub struct Response {
+        /// The transfer adapter chosen by the server, which is `basic` if unset.
+        pub transfer: Option<String>,
+        /// The objects for which actions or errors were returned.
+        pub objects: Vec<Object>,
+    }
+
+    /// An object as returned by the server in a [`Response`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+    pub struct Object {
+        /// The sha256 hash of the object in hexadecimal notation.
+        pub oid: String,
+        /// The size of the object in bytes.
+        pub size: u64,
+        /// The actions to perform to transfer the object, if there are any.
+        #[serde(default)]
+        pub actions: BTreeMap<String, Action>,
+        /// The error if the object can't be transferred.
+        pub error: Option<ObjectError>,
+    }
+
+    /// An action to perform to transfer an [`Object`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+    pub struct Action {
+        /// The url to send the request to.
+        pub href: String,
+        /// Additional headers to send along with the request.
+        #[serde(default)]
+        pub header: BTreeMap<String, String>,
+    }
+
+    /// The error for an individual [`Object`].
+    #[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+    pub struct ObjectError {
+        /// The error code, similar to an http status code.
+        pub code: u32,
+        /// A description of the error.
+        pub message: String,
+    }
+}
+
+///
+pub mod download {
+    /// The error returned by [`Client::download()`][crate::Client::download()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Batch(#[from] super::batch::Error),
+        #[error("The LFS server didn't provide LFS object {}", pointer.oid)]
+        MissingObject { pointer: crate::Pointer },
+        #[error("The LFS server refused to provide LFS object {} with code {code}: {message}", pointer.oid)]
+        Object {
+            pointer: crate::Pointer,
+            code: u32,
+            message: String,
+        },
+        #[error("Could not download LFS object {} from '{url}'", pointer.oid)]
+        Http {
+            source: git_transport::client::http::Error,
+            pointer: crate::Pointer,
+            url: String,
+        },
+        #[error("Could not receive the headers of LFS object {}", pointer.oid)]
+        Io {
+            source: std::io::Error,
+            pointer: crate::Pointer,
+        },
+        #[error(transparent)]
+        Write(#[from] crate::storage::write::Error),
+    }
+}
+
+/// Return the url of the LFS server to use with a repository at the `remote` url, following the conventions of `git-lfs`,
+/// or `None` if the remote isn't reachable via http.
+///
+/// The url of the LFS server is `<remote>.git/info/lfs`, with `ssh` urls being turned into `https` ones.
+/// Note that the `lfs.url` and `remote.<name>.lfsurl` configuration takes precedence, if set.
+pub fn endpoint_url(remote: &git_url::Url) -> Option<String> {
+    use git_url::Scheme;
+    let scheme = match remote.scheme {
+        Scheme::Http => "http",
+        Scheme::Https | Scheme::Ssh => "https",
+        _ => return None,
+    };
+    let mut url = format!("{}://{}", scheme, remote.host()?);
+    if let (Some(port), Scheme::Http | Scheme::Https) = (remote.port, &remote.scheme) {
+        url.push_str(&format!(":{}", port));
+    }
+    let path = remote.path.to_string();
+    let path = path.trim_end_matches('/');
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+    url.push_str(path);
+    if !url.ends_with(".git") {
+        url.push_str(".git");
+    }
+    url.push_str("/info/lfs");
+    Some(url)
+}
+
+/// Initialization
+impl<H: Http> Client<H> {
+    /// Create a new instance which uses `http` to talk to the LFS server at `url`, like `https://example.com/repo.git/info/lfs`.
+    pub fn new_http(http: H, url: impl Into<String>) -> Self {
+        Client {
+            http,
+            url: url.into().trim_end_matches('/').to_owned(),
+            identity: None,
+            extra_headers: Vec::new(),
+        }
+    }
+
+    /// Send the `extra_headers`, like `Authorization: …`, along with each request to the LFS server.
+    pub fn with_extra_headers(mut self, extra_headers: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extra_headers = extra_headers.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+#[cfg(any(feature = "http-client-curl", feature = "http-client-reqwest"))]
+impl Client<git_transport::client::http::Impl> {
+    /// Create a new instance which talks to the LFS server at `url` with the http backend of `git-transport`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::new_http(Default::default(), url)
+    }
+}
+
+/// Access
+impl<H> Client<H> {
+    /// The url of the LFS server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Use `identity` to authenticate with the LFS server, which is useful after a request failed as
+    /// [permission was denied][batch::Error::is_permission_denied()].
+    pub fn set_identity(&mut self, identity: git_sec::identity::Account) {
+        self.identity = Some(identity);
+    }
+
+    /// Return the underlying http implementation.
+    pub fn http_mut(&mut self) -> &mut H {
+        &mut self.http
+    }
+}
+
+/// Operations
+impl<H: Http> Client<H> {
+    /// Ask the server how to perform `operation` with the objects of all `pointers`.
+    pub fn batch(
+        &mut self,
+        operation: batch::Operation,
+        pointers: &[Pointer],
+    ) -> Result<batch::Response, batch::Error> {
+        let request = batch::Request {
+            operation,
+            transfers: vec!["basic".into()],
+            objects: pointers
+                .iter()
+                .map(|p| batch::RequestObject {
+                    oid: p.oid.to_string(),
+                    size: p.size,
+                })
+                .collect(),
+            hash_algo: "sha256".into(),
+        };
+        let mut headers = vec![
+            USER_AGENT_HEADER.to_owned(),
+            format!("Accept: {}", MEDIA_TYPE),
+            format!("Content-Type: {}", MEDIA_TYPE),
+        ];
+        headers.extend(self.authentication_headers());
+
+        let url = format!("{}/objects/batch", self.url);
+        let PostResponse {
+            post_body,
+            headers,
+            body,
+        } = self.http.post(&url, &self.url, headers)?;
+        {
+            let mut post_body = post_body;
+            serde_json::to_writer(&mut post_body, &request)?;
+        }
+        consume_headers(headers)?;
+        let response: batch::Response = serde_json::from_reader(body)?;
+        match response.transfer.as_deref() {
+            None | Some("basic") => Ok(response),
+            Some(name) => Err(batch::Error::UnsupportedTransfer { name: name.into() }),
+        }
+    }
+
+    /// Download the objects of all `pointers` which aren't yet contained in `storage` and place them there.
+    ///
+    /// Objects are verified to match their pointer before they are made available in the `storage`.
+    pub fn download(&mut self, pointers: &[Pointer], storage: &Storage) -> Result<(), download::Error> {
+        let pointers: Vec<_> = pointers.iter().filter(|p| !storage.contains(p)).copied().collect();
+        if pointers.is_empty() {
+            return Ok(());
+        }
+        let response = self.batch(batch::Operation::Download, &pointers)?;
+        for pointer in pointers {
+            let oid = pointer.oid.to_string();
+            let object = response
+                .objects
+                .iter()
+                .find(|o| o.oid == oid && o.size == pointer.size)
+                .ok_or(download::Error::MissingObject { pointer })?;
+            if let Some(err) = &object.error {
+                return Err(download::Error::Object {
+                    pointer,
+                    code: err.code,
+                    message: err.message.clone(),
+                });
+            }
+            let action = object
+                .actions
+                .get("download")
+                .ok_or(download::Error::MissingObject { pointer })?;
+
+            let mut headers = vec![USER_AGENT_HEADER.to_owned()];
+            headers.extend(action.header.iter().map(|(name, value)| format!("{}: {}", name, value)));
+            let GetResponse { headers, body } =
+                self.http
+                    .get(&action.href, &action.href, headers)
+                    .map_err(|source| download::Error::Http {
+                        source,
+                        pointer,
+                        url: action.href.clone(),
+                    })?;
+            consume_headers(headers).map_err(|source| download::Error::Io { source, pointer })?;
+            storage.write_verified(&pointer, body)?;
+        }
+        Ok(())
+    }
+
+    /// Return the headers needed to authenticate with the LFS server, which aren't sent to the urls of download actions
+    /// as these bring their own headers.
+    fn authentication_headers(&self) -> Vec<String> {
+        let mut headers = Vec::new();
+        if let Some(git_sec::identity::Account { username, password }) = &self.identity {
+            headers.push(format!(
+                "Authorization: Basic {}",
+                base64::encode(format!("{}:{}", username, password))
+            ));
+        }
+        headers.extend(self.extra_headers.iter().cloned());
+        headers
+    }
+}
+
+/// Read all headers, which is where failed requests are reported by the http backends.
+fn consume_headers(mut headers: impl BufRead) -> std::io::Result<()> {
+    std::io::copy(&mut headers, &mut std::io::sink()).map(|_| ())
+}
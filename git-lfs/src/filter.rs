@@ -0,0 +1,132 @@
+//! The `clean` and `smudge` conversions of LFS, to be used as filter driver.
+use std::io::{Read, Write};
+
+use git_filter::driver::process::{Server, Status};
+
+use crate::{Pointer, Storage};
+
+/// The type of function to obtain the object of a pointer that isn't present in the local storage, by placing it into
+/// the storage.
+pub type FetchFn<'a> =
+    dyn FnMut(&Pointer, &Storage) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> + 'a;
+
+///
+pub mod clean {
+    /// The error returned by [`clean()`][super::clean()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Write(#[from] crate::storage::write::Error),
+    }
+}
+
+///
+pub mod smudge {
+    /// The error returned by [`smudge()`][super::smudge()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read LFS object {}", pointer.oid)]
+        Read {
+            source: std::io::Error,
+            pointer: crate::Pointer,
+        },
+        #[error("Could not obtain LFS object {}", pointer.oid)]
+        Fetch {
+            source: Box<dyn std::error::Error + Send + Sync + 'static>,
+            pointer: crate::Pointer,
+        },
+        #[error("LFS object {} wasn't present in the local storage after fetching it", pointer.oid)]
+        Missing { pointer: crate::Pointer },
+    }
+}
+
+///
+pub mod process {
+    /// The error returned by [`process()`][super::process()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Handshake(#[from] git_filter::driver::process::server::handshake::Error),
+        #[error(transparent)]
+        NextRequest(#[from] git_filter::driver::process::server::next_request::Error),
+        #[error("Failed to communicate with the client")]
+        Io(#[from] std::io::Error),
+    }
+}
+
+/// Convert the file content `src` into a pointer written to `out`, after placing `src` into `storage`.
+///
+/// Return `true` if `out` was written, or `false` if `src` is already a pointer or is empty, to be used as is.
+pub fn clean(storage: &Storage, src: &[u8], out: &mut Vec<u8>) -> Result<bool, clean::Error> {
+    if src.is_empty() || Pointer::from_bytes(src).is_ok() {
+        return Ok(false);
+    }
+    let pointer = storage.write(src)?;
+    out.clear();
+    pointer.write_to(out).expect("writing to a vector never fails");
+    Ok(true)
+}
+
+/// Convert the pointer in `src` into the file content it points to, written to `out`.
+/// If the object isn't present in `storage`, `fetch(pointer, storage)` is called to place it there.
+///
+/// Return `true` if `out` was written, or `false` if `src` isn't a pointer and should be used as is.
+pub fn smudge(
+    storage: &Storage,
+    src: &[u8],
+    fetch: &mut FetchFn<'_>,
+    out: &mut Vec<u8>,
+) -> Result<bool, smudge::Error> {
+    let pointer = match Pointer::from_bytes(src) {
+        Ok(pointer) => pointer,
+        Err(_) => return Ok(false),
+    };
+    let read = |out: &mut Vec<u8>| {
+        storage
+            .read(&pointer, out)
+            .map_err(|source| smudge::Error::Read { source, pointer })
+    };
+    if read(out)? {
+        return Ok(true);
+    }
+    fetch(&pointer, storage).map_err(|source| smudge::Error::Fetch { source, pointer })?;
+    if read(out)? {
+        Ok(true)
+    } else {
+        Err(smudge::Error::Missing { pointer })
+    }
+}
+
+/// Serve the long-running filter process protocol as `filter.<driver>.process`, reading requests from `read`
+/// and writing responses to `write` until the client disconnects.
+///
+/// Both `clean` and `smudge` are supported as described in [`clean()`] and [`smudge()`], with objects being stored in
+/// and read from `storage`, and with `fetch` being used to obtain objects which are missing locally.
+/// Failed conversions are answered with an error status, leaving it to the client to decide what to do.
+pub fn process(
+    read: impl Read,
+    write: impl Write,
+    storage: &Storage,
+    fetch: &mut FetchFn<'_>,
+) -> Result<(), process::Error> {
+    let mut server = Server::handshake(read, write, "git-filter", &[2], &["clean", "smudge"])?;
+    let mut src = Vec::new();
+    let mut out = Vec::new();
+    while let Some(mut request) = server.next_request()? {
+        request.read_content(&mut src)?;
+        let converted = match request.command.as_slice() {
+            b"clean" => clean(storage, &src, &mut out).ok(),
+            b"smudge" => smudge(storage, &src, fetch, &mut out).ok(),
+            _ => None,
+        };
+        match converted {
+            Some(true) => request.write_content(&out)?,
+            Some(false) => request.write_content(&src)?,
+            None => request.write_error(Status::Error)?,
+        }
+    }
+    Ok(())
+}
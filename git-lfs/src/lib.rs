@@ -1,2 +1,64 @@
-#![deny(rust_2018_idioms)]
+//! Support for git large file storage (LFS), which replaces the content of large files with small [pointers][Pointer]
+//! in the object database while keeping the actual content in a [local storage][Storage] and on a remote LFS server.
+//!
+//! * [`filter`] implements the `clean` and `smudge` conversions, along with the server side of the long-running
+//!   `filter.<driver>.process` protocol to be used as `filter.lfs.process`.
+//! * [`Client`] talks to the LFS [batch API](https://github.com/git-lfs/git-lfs/blob/main/docs/api/batch.md) to download
+//!   objects into the local storage, using the http backends of `git-transport`.
+//! ## Feature Flags
+#![cfg_attr(
+    feature = "document-features",
+    cfg_attr(doc, doc = ::document_features::document_features!())
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+pub use bstr;
+
+/// A pointer to a file kept in LFS storage, which is what gets stored in the object database instead of the file itself.
+///
+/// Its serialized form looks like this:
+///
+/// ```text
+/// version https://git-lfs.github.com/spec/v1
+/// oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+/// size 12345
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Pointer {
+    /// The `sha256` hash of the file content.
+    pub oid: git_hash::ObjectId,
+    /// The size of the file content in bytes.
+    pub size: u64,
+}
+
+/// The local storage of LFS objects, typically located at `.git/lfs`.
+///
+/// Objects are stored by their [`oid`][Pointer::oid] at `objects/<oid[0..2]>/<oid[2..4]>/<oid>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+    root: PathBuf,
+}
+
+/// A client for the LFS batch API to download objects into a [`Storage`].
+pub struct Client<H> {
+    http: H,
+    url: String,
+    identity: Option<git_sec::identity::Account>,
+    extra_headers: Vec<String>,
+}
+
+///
+pub mod pointer;
+
+///
+pub mod storage;
+
+///
+pub mod filter;
+
+///
+pub mod client;
@@ -0,0 +1,128 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::Pointer;
+
+/// The version of the pointer specification we write, and which is expected in the first line of each pointer.
+pub const VERSION: &str = "https://git-lfs.github.com/spec/v1";
+/// The version string of pointers written by pre-release versions of `git-lfs`, which we read as well.
+const VERSION_PRE_RELEASE: &str = "https://hawser.github.com/spec/v1";
+/// Pointers larger than this amount of bytes are not considered pointers at all.
+pub const MAX_SIZE: usize = 1024;
+
+///
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`Pointer::from_bytes()`][crate::Pointer::from_bytes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Pointers must not be larger than {} bytes, got {size} bytes", super::MAX_SIZE)]
+        TooLarge { size: usize },
+        #[error("Expected the first line to be the version of the pointer specification, got '{actual}'")]
+        Version { actual: BString },
+        #[error("Lines must be of the form '<key> <value>' with keys sorted alphabetically and ending in a newline: '{line}'")]
+        Malformed { line: BString },
+        #[error("The required key '{key}' was missing")]
+        MissingKey { key: &'static str },
+        #[error("Expected the object id to be a hexadecimal sha256 hash prefixed with 'sha256:', got '{actual}'")]
+        Oid { actual: BString },
+        #[error("Expected the size to be a non-negative integer, got '{actual}'")]
+        Size { actual: BString },
+    }
+}
+
+/// Initialization
+impl Pointer {
+    /// Parse `data` as pointer, or fail if it isn't a valid pointer.
+    ///
+    /// Keys other than `oid` and `size`, like those of extensions, are accepted but ignored.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, decode::Error> {
+        use decode::Error;
+        if data.len() > MAX_SIZE {
+            return Err(Error::TooLarge { size: data.len() });
+        }
+        let mut lines = data.lines_with_terminator();
+        match lines.next().and_then(|line| line.strip_suffix(b"\n")) {
+            Some(line)
+                if line.strip_prefix(b"version ").map_or(false, |version| {
+                    version == VERSION.as_bytes() || version == VERSION_PRE_RELEASE.as_bytes()
+                }) => {}
+            actual => {
+                return Err(Error::Version {
+                    actual: actual.unwrap_or_default().into(),
+                })
+            }
+        }
+
+        let mut oid = None;
+        let mut size = None;
+        let mut previous_key: Option<&[u8]> = None;
+        for line in lines {
+            let malformed = || Error::Malformed { line: line.into() };
+            let (key, value) = line
+                .strip_suffix(b"\n")
+                .and_then(|line| line.split_once_str(b" "))
+                .ok_or_else(malformed)?;
+            if key.is_empty() || previous_key.map_or(false, |previous| previous >= key) {
+                return Err(malformed());
+            }
+            previous_key = Some(key);
+            match key {
+                b"oid" => oid = Some(parse_oid(value.as_bstr())?),
+                b"size" => {
+                    size = Some(
+                        value
+                            .to_str()
+                            .ok()
+                            .filter(|size| size.bytes().all(|b| b.is_ascii_digit()))
+                            .and_then(|size| size.parse().ok())
+                            .ok_or_else(|| Error::Size { actual: value.into() })?,
+                    )
+                }
+                _ => {}
+            }
+        }
+        Ok(Pointer {
+            oid: oid.ok_or(Error::MissingKey { key: "oid" })?,
+            size: size.ok_or(Error::MissingKey { key: "size" })?,
+        })
+    }
+
+    /// Create a pointer for the file content `data`.
+    pub fn from_data(data: &[u8]) -> Self {
+        let mut hasher = git_features::hash::Sha256::default();
+        hasher.update(data);
+        Pointer {
+            oid: hasher.digest().into(),
+            size: data.len() as u64,
+        }
+    }
+}
+
+/// Serialization
+impl Pointer {
+    /// Write this pointer in its canonical form into `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        write!(
+            out,
+            "version {}\noid sha256:{}\nsize {}\n",
+            VERSION, self.oid, self.size
+        )
+    }
+
+    /// Return this pointer in its canonical form.
+    pub fn to_bstring(&self) -> bstr::BString {
+        let mut buf = Vec::with_capacity(MAX_SIZE / 8);
+        self.write_to(&mut buf).expect("writing to a vector never fails");
+        buf.into()
+    }
+}
+
+fn parse_oid(value: &BStr) -> Result<git_hash::ObjectId, decode::Error> {
+    value
+        .strip_prefix(b"sha256:")
+        .filter(|hex| hex.len() == 64 && hex.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')))
+        .and_then(|hex| git_hash::ObjectId::from_hex(hex).ok())
+        .ok_or_else(|| decode::Error::Oid { actual: value.into() })
+}
@@ -0,0 +1,135 @@
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{Pointer, Storage};
+
+///
+pub mod write {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Storage::write()`][crate::Storage::write()] and [`Storage::write_verified()`][crate::Storage::write_verified()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not write LFS object to '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("Could not read the content of the LFS object")]
+        Read(#[source] std::io::Error),
+        #[error(
+            "The object content didn't match its pointer: expected {} with {} bytes, got {} with {} bytes",
+            expected.oid,
+            expected.size,
+            actual.oid,
+            actual.size
+        )]
+        Mismatch {
+            expected: crate::Pointer,
+            actual: crate::Pointer,
+        },
+    }
+}
+
+/// Initialization
+impl Storage {
+    /// Create an instance for the storage directory of the repository with the given `git_dir`, i.e. `<git_dir>/lfs`.
+    pub fn at_git_dir(git_dir: impl AsRef<Path>) -> Self {
+        Self::at(git_dir.as_ref().join("lfs"))
+    }
+
+    /// Create an instance with `root` as storage directory, typically `.git/lfs` or the value of `lfs.storage`.
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Storage { root: root.into() }
+    }
+}
+
+/// Access
+impl Storage {
+    /// The directory containing all objects and temporary files.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Return the path at which the object of `pointer` is stored, whether it exists or not.
+    pub fn object_path(&self, pointer: &Pointer) -> PathBuf {
+        let hex = pointer.oid.to_hex().to_string();
+        self.root.join("objects").join(&hex[..2]).join(&hex[2..4]).join(hex)
+    }
+
+    /// Return `true` if the object of `pointer` exists and has the expected size.
+    ///
+    /// Note that the content isn't hashed for performance reasons, just like `git-lfs` does.
+    pub fn contains(&self, pointer: &Pointer) -> bool {
+        std::fs::metadata(self.object_path(pointer)).map_or(false, |meta| meta.is_file() && meta.len() == pointer.size)
+    }
+
+    /// Read the object of `pointer` into `out`, returning `false` if it doesn't exist or has an unexpected size.
+    pub fn read(&self, pointer: &Pointer, out: &mut Vec<u8>) -> std::io::Result<bool> {
+        out.clear();
+        match std::fs::File::open(self.object_path(pointer)) {
+            Ok(mut file) => {
+                file.read_to_end(out)?;
+                Ok(out.len() as u64 == pointer.size)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Modification
+impl Storage {
+    /// Store `data` unless it already exists and return its pointer.
+    pub fn write(&self, data: &[u8]) -> Result<Pointer, write::Error> {
+        let pointer = Pointer::from_data(data);
+        if !self.contains(&pointer) {
+            self.write_verified(&pointer, data)?;
+        }
+        Ok(pointer)
+    }
+
+    /// Store all data read from `content` as the object of `pointer`, but only if its hash and size match `pointer`.
+    ///
+    /// The data is written to a temporary file first, so the object only becomes visible once it is complete.
+    pub fn write_verified(&self, pointer: &Pointer, mut content: impl Read) -> Result<(), write::Error> {
+        let tmp_dir = self.root.join("tmp");
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| write::Error::Io { source, path }
+        };
+        std::fs::create_dir_all(&tmp_dir).map_err(io_err(&tmp_dir))?;
+        let mut tmp = tempfile::NamedTempFile::new_in(&tmp_dir).map_err(io_err(&tmp_dir))?;
+
+        let mut hasher = git_features::hash::Sha256::default();
+        let mut size = 0;
+        let mut buf = [0; 64 * 1024];
+        loop {
+            let bytes_read = match content.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(write::Error::Read(err)),
+            };
+            hasher.update(&buf[..bytes_read]);
+            size += bytes_read as u64;
+            tmp.write_all(&buf[..bytes_read]).map_err(io_err(tmp.path()))?;
+        }
+        let actual = Pointer {
+            oid: hasher.digest().into(),
+            size,
+        };
+        if actual != *pointer {
+            return Err(write::Error::Mismatch {
+                expected: *pointer,
+                actual,
+            });
+        }
+
+        let path = self.object_path(pointer);
+        let dir = path.parent().expect("objects are always in a directory");
+        std::fs::create_dir_all(dir).map_err(io_err(dir))?;
+        tmp.persist(&path).map_err(|err| io_err(&path)(err.error))?;
+        Ok(())
+    }
+}
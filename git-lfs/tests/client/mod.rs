@@ -0,0 +1,324 @@
+use std::{cell::RefCell, collections::HashMap, io::BufRead, rc::Rc};
+
+use git_lfs::{client, Client, Pointer, Storage};
+use git_transport::client::http::{self, GetResponse, PostResponse};
+
+use crate::hello_pointer;
+
+/// A stand-in for an LFS server which serves the batch API and downloads from memory.
+#[derive(Default)]
+struct Server {
+    /// The content of objects by their hexadecimal oid.
+    objects: HashMap<String, Vec<u8>>,
+    /// If set, the batch API requires this `Authorization` header.
+    authorization: Option<String>,
+    /// All requests received so far, as method and url.
+    requests: Vec<(&'static str, String)>,
+}
+
+/// A response which is produced only once it's read, so the request body can be written first.
+struct Lazy {
+    content: Option<Box<dyn FnOnce() -> std::io::Result<Vec<u8>>>>,
+    buf: std::io::Cursor<Vec<u8>>,
+}
+
+impl Lazy {
+    fn new(content: impl FnOnce() -> std::io::Result<Vec<u8>> + 'static) -> Self {
+        Lazy {
+            content: Some(Box::new(content)),
+            buf: Default::default(),
+        }
+    }
+}
+
+impl std::io::Read for Lazy {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = std::io::Read::read(&mut self.fill_buf()?, buf)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for Lazy {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if let Some(content) = self.content.take() {
+            self.buf = std::io::Cursor::new(content()?);
+        }
+        self.buf.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.buf.consume(amt)
+    }
+}
+
+#[derive(Clone, Default)]
+struct Http {
+    server: Rc<RefCell<Server>>,
+}
+
+impl http::Http for Http {
+    type Headers = Lazy;
+    type ResponseBody = Lazy;
+    type PostBody = SharedBuf;
+
+    fn get(
+        &mut self,
+        url: &str,
+        _base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        let mut server = self.server.borrow_mut();
+        server.requests.push(("GET", url.into()));
+        let authorized = headers.into_iter().any(|h| h.as_ref() == "X-Token: secret");
+        let content = url
+            .strip_prefix("https://storage.example.com/")
+            .and_then(|oid| server.objects.get(oid))
+            .cloned();
+        let status = match (authorized, content.is_some()) {
+            (false, _) => Err(std::io::ErrorKind::PermissionDenied),
+            (true, false) => Err(std::io::ErrorKind::NotFound),
+            (true, true) => Ok(()),
+        };
+        Ok(GetResponse {
+            headers: Lazy::new(move || {
+                status
+                    .map(|()| b"content-type: application/octet-stream\n".to_vec())
+                    .map_err(Into::into)
+            }),
+            body: Lazy::new(move || Ok(content.unwrap_or_default())),
+        })
+    }
+
+    fn post(
+        &mut self,
+        url: &str,
+        _base_url: &str,
+        headers: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<PostResponse<Self::Headers, Self::ResponseBody, Self::PostBody>, http::Error> {
+        self.server.borrow_mut().requests.push(("POST", url.into()));
+        let headers: Vec<String> = headers.into_iter().map(|h| h.as_ref().to_owned()).collect();
+        assert!(headers.iter().any(|h| h == "Accept: application/vnd.git-lfs+json"));
+        assert!(headers
+            .iter()
+            .any(|h| h == "Content-Type: application/vnd.git-lfs+json"));
+        assert_eq!(url, "https://example.com/repo.git/info/lfs/objects/batch");
+
+        let authorized = {
+            let server = self.server.borrow();
+            server
+                .authorization
+                .as_ref()
+                .map_or(true, |expected| headers.iter().any(|h| h == expected))
+        };
+        let post_body = SharedBuf::default();
+        let request = post_body.clone();
+        let server = self.server.clone();
+        Ok(PostResponse {
+            post_body,
+            headers: Lazy::new(move || {
+                if authorized {
+                    Ok(b"content-type: application/vnd.git-lfs+json\n".to_vec())
+                } else {
+                    Err(std::io::ErrorKind::PermissionDenied.into())
+                }
+            }),
+            body: Lazy::new(move || {
+                let request: serde_json::Value = serde_json::from_slice(&request.0.borrow())?;
+                assert_eq!(request["operation"], "download");
+                assert_eq!(request["hash_algo"], "sha256");
+                let server = server.borrow();
+                let objects: Vec<_> = request["objects"]
+                    .as_array()
+                    .expect("objects")
+                    .iter()
+                    .map(|object| {
+                        let oid = object["oid"].as_str().expect("oid");
+                        match server.objects.get(oid) {
+                            Some(_) => serde_json::json!({
+                                "oid": oid,
+                                "size": object["size"],
+                                "actions": {
+                                    "download": {
+                                        "href": format!("https://storage.example.com/{}", oid),
+                                        "header": { "X-Token": "secret" }
+                                    }
+                                }
+                            }),
+                            None => serde_json::json!({
+                                "oid": oid,
+                                "size": object["size"],
+                                "error": { "code": 404, "message": "Object does not exist" }
+                            }),
+                        }
+                    })
+                    .collect();
+                Ok(serde_json::to_vec(
+                    &serde_json::json!({ "transfer": "basic", "objects": objects }),
+                )?)
+            }),
+        })
+    }
+
+    fn configure(
+        &mut self,
+        _config: &dyn std::any::Any,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+impl std::io::Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn client_with_objects(objects: &[&[u8]]) -> (Client<Http>, Http) {
+    let http = Http::default();
+    http.server.borrow_mut().objects = objects
+        .iter()
+        .map(|content| (Pointer::from_data(content).oid.to_string(), content.to_vec()))
+        .collect();
+    (
+        Client::new_http(http.clone(), "https://example.com/repo.git/info/lfs/"),
+        http,
+    )
+}
+
+#[test]
+fn download_places_missing_objects_into_storage() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let (mut client, http) = client_with_objects(&[b"hello", b"world"]);
+    let world = Pointer::from_data(b"world");
+
+    client.download(&[hello_pointer(), world], &storage)?;
+    assert!(storage.contains(&hello_pointer()));
+    assert!(storage.contains(&world));
+    assert_eq!(
+        http.server.borrow().requests,
+        [
+            (
+                "POST",
+                "https://example.com/repo.git/info/lfs/objects/batch".to_string()
+            ),
+            ("GET", format!("https://storage.example.com/{}", hello_pointer().oid)),
+            ("GET", format!("https://storage.example.com/{}", world.oid)),
+        ]
+    );
+
+    client.download(&[hello_pointer(), world], &storage)?;
+    assert_eq!(
+        http.server.borrow().requests.len(),
+        3,
+        "objects that are present already aren't requested"
+    );
+    Ok(())
+}
+
+#[test]
+fn download_fails_for_objects_unknown_to_the_server() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let (mut client, _http) = client_with_objects(&[]);
+    let err = client.download(&[hello_pointer()], &storage).unwrap_err();
+    assert!(
+        matches!(err, client::download::Error::Object { pointer, code: 404, .. } if pointer == hello_pointer()),
+        "{:?}",
+        err
+    );
+    Ok(())
+}
+
+#[test]
+fn download_rejects_objects_not_matching_their_pointer() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let (mut client, http) = client_with_objects(&[b"hello"]);
+    let oid = hello_pointer().oid.to_string();
+    http.server.borrow_mut().objects.insert(oid, b"hellO".to_vec());
+
+    let err = client.download(&[hello_pointer()], &storage).unwrap_err();
+    assert!(matches!(
+        err,
+        client::download::Error::Write(git_lfs::storage::write::Error::Mismatch { .. })
+    ));
+    assert!(!storage.contains(&hello_pointer()));
+    Ok(())
+}
+
+#[test]
+fn batch_requests_can_be_authenticated() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let (mut client, http) = client_with_objects(&[b"hello"]);
+    http.server.borrow_mut().authorization = Some("Authorization: Basic dXNlcjpwYXNz".into());
+
+    let err = client
+        .batch(client::batch::Operation::Download, &[hello_pointer()])
+        .unwrap_err();
+    assert!(err.is_permission_denied());
+
+    client.set_identity(git_sec::identity::Account {
+        username: "user".into(),
+        password: "pass".into(),
+    });
+    client.download(&[hello_pointer()], &storage)?;
+    assert!(storage.contains(&hello_pointer()));
+    Ok(())
+}
+
+#[test]
+fn smudge_can_fetch_with_the_client() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let (mut client, _http) = client_with_objects(&[b"hello"]);
+    let mut out = Vec::new();
+    assert!(git_lfs::filter::smudge(
+        &storage,
+        &hello_pointer().to_bstring(),
+        &mut |pointer, storage| Ok(client.download(&[*pointer], storage)?),
+        &mut out
+    )?);
+    assert_eq!(out, b"hello");
+    Ok(())
+}
+
+#[test]
+fn endpoint_url() -> crate::Result {
+    for (remote, expected) in [
+        (
+            "https://example.com/org/repo",
+            Some("https://example.com/org/repo.git/info/lfs"),
+        ),
+        (
+            "https://example.com/org/repo.git/",
+            Some("https://example.com/org/repo.git/info/lfs"),
+        ),
+        (
+            "http://example.com:8080/repo.git",
+            Some("http://example.com:8080/repo.git/info/lfs"),
+        ),
+        (
+            "git@example.com:org/repo.git",
+            Some("https://example.com/org/repo.git/info/lfs"),
+        ),
+        (
+            "ssh://git@example.com:2222/org/repo",
+            Some("https://example.com/org/repo.git/info/lfs"),
+        ),
+        ("file:///path/to/repo", None),
+    ] {
+        let url = git_url::parse(remote.into())?;
+        assert_eq!(client::endpoint_url(&url).as_deref(), expected, "{}", remote);
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use git_lfs::bstr::ByteSlice;
+use git_lfs::{filter, Storage};
+
+use crate::{filter_process_program, hello_pointer};
+
+fn fetch_unreachable(
+) -> impl FnMut(&git_lfs::Pointer, &Storage) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    |_, _| unreachable!("objects are expected to be present")
+}
+
+#[test]
+fn clean_stores_content_and_produces_pointers() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let mut out = Vec::new();
+    assert!(filter::clean(&storage, b"hello", &mut out)?);
+    assert_eq!(out.as_bstr(), hello_pointer().to_bstring());
+    assert!(storage.contains(&hello_pointer()));
+
+    let pointer = hello_pointer().to_bstring();
+    assert!(
+        !filter::clean(&storage, &pointer, &mut out)?,
+        "pointers remain unchanged"
+    );
+    assert!(!filter::clean(&storage, b"", &mut out)?, "empty files remain unchanged");
+    Ok(())
+}
+
+#[test]
+fn smudge_replaces_pointers_with_content() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    storage.write(b"hello")?;
+    let mut out = Vec::new();
+    assert!(filter::smudge(
+        &storage,
+        &hello_pointer().to_bstring(),
+        &mut fetch_unreachable(),
+        &mut out
+    )?);
+    assert_eq!(out.as_bstr(), "hello");
+
+    assert!(
+        !filter::smudge(&storage, b"not a pointer", &mut fetch_unreachable(), &mut out)?,
+        "anything that isn't a pointer remains unchanged"
+    );
+    Ok(())
+}
+
+#[test]
+fn smudge_fetches_missing_objects() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let pointer = hello_pointer().to_bstring();
+    let mut out = Vec::new();
+
+    let mut calls = 0;
+    assert!(filter::smudge(
+        &storage,
+        &pointer,
+        &mut |p, storage| {
+            calls += 1;
+            assert_eq!(*p, hello_pointer());
+            storage.write_verified(p, &b"hello"[..])?;
+            Ok(())
+        },
+        &mut out
+    )?);
+    assert_eq!(calls, 1);
+    assert_eq!(out.as_bstr(), "hello");
+
+    let err = filter::smudge(
+        &Storage::at(dir.path().join("empty")),
+        &pointer,
+        &mut |_, _| Ok(()),
+        &mut out,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, filter::smudge::Error::Missing { .. }),
+        "fetching that doesn't produce the object is an error"
+    );
+
+    let err = filter::smudge(
+        &Storage::at(dir.path().join("empty")),
+        &pointer,
+        &mut |_, _| Err("unreachable".into()),
+        &mut out,
+    )
+    .unwrap_err();
+    assert!(matches!(err, filter::smudge::Error::Fetch { .. }));
+    Ok(())
+}
+
+#[test]
+fn git_uses_it_as_filter_process() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let git = |args: &[&str]| -> crate::Result<std::process::Output> {
+        Ok(std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .output()?)
+    };
+    let git_ok = |args: &[&str]| -> crate::Result<Vec<u8>> {
+        let out = git(args)?;
+        assert!(out.status.success(), "git {:?} failed: {}", args, out.stderr.as_bstr());
+        Ok(out.stdout)
+    };
+    git_ok(&["init", "-q"])?;
+    git_ok(&[
+        "config",
+        "filter.lfs.process",
+        &filter_process_program().display().to_string(),
+    ])?;
+    git_ok(&["config", "filter.lfs.required", "true"])?;
+    std::fs::write(dir.path().join(".gitattributes"), "*.bin filter=lfs\n")?;
+    std::fs::write(dir.path().join("a.bin"), "hello")?;
+    git_ok(&["add", "a.bin"])?;
+    assert_eq!(
+        git_ok(&["cat-file", "-p", ":a.bin"])?.as_bstr(),
+        hello_pointer().to_bstring(),
+        "the object database only sees the pointer"
+    );
+    let storage = Storage::at_git_dir(dir.path().join(".git"));
+    assert!(storage.contains(&hello_pointer()));
+
+    std::fs::remove_file(dir.path().join("a.bin"))?;
+    git_ok(&["checkout", "a.bin"])?;
+    assert_eq!(std::fs::read(dir.path().join("a.bin"))?.as_bstr(), "hello");
+
+    std::fs::remove_file(dir.path().join("a.bin"))?;
+    std::fs::remove_file(storage.object_path(&hello_pointer()))?;
+    assert!(
+        !git(&["checkout", "a.bin"])?.status.success(),
+        "objects that can't be obtained fail the required filter"
+    );
+    Ok(())
+}
@@ -0,0 +1,36 @@
+mod client;
+mod filter;
+mod pointer;
+mod storage;
+
+/// Build the `filter-process` example program once and return the path to it.
+fn filter_process_program() -> &'static std::path::Path {
+    static PROGRAM: git_testtools::once_cell::sync::Lazy<std::path::PathBuf> =
+        git_testtools::once_cell::sync::Lazy::new(|| {
+            let mut cmd = std::process::Command::new(env!("CARGO"));
+            cmd.args(["build", "--example", "filter-process"]);
+            assert!(
+                cmd.spawn().unwrap().wait().expect("example builds OK").success(),
+                "example must build"
+            );
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join("target")
+                .join("debug")
+                .join("examples")
+                .join("filter-process")
+                .canonicalize()
+                .expect("example was built")
+        });
+    PROGRAM.as_path()
+}
+
+/// The pointer for the content `hello`.
+fn hello_pointer() -> git_lfs::Pointer {
+    git_lfs::Pointer {
+        oid: git_testtools::hex_to_id("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"),
+        size: 5,
+    }
+}
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
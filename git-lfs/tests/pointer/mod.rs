@@ -0,0 +1,75 @@
+use git_lfs::{pointer::decode::Error, Pointer};
+
+use crate::hello_pointer;
+
+const HELLO: &str = "version https://git-lfs.github.com/spec/v1
+oid sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824
+size 5
+";
+
+#[test]
+fn from_bytes_parses_canonical_pointers() -> crate::Result {
+    assert_eq!(Pointer::from_bytes(HELLO.as_bytes())?, hello_pointer());
+    Ok(())
+}
+
+#[test]
+fn from_bytes_accepts_extensions_and_pre_release_versions() -> crate::Result {
+    let pointer = "version https://hawser.github.com/spec/v1
+ext-0-foo sha256:0000000000000000000000000000000000000000000000000000000000000000
+oid sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824
+size 5
+";
+    assert_eq!(Pointer::from_bytes(pointer.as_bytes())?, hello_pointer());
+    Ok(())
+}
+
+#[test]
+fn from_bytes_rejects_invalid_pointers() {
+    let oid = "oid sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\n";
+    let version = "version https://git-lfs.github.com/spec/v1\n";
+    for (input, expected) in [
+        (String::new(), "Version"),
+        ("hello".into(), "Version"),
+        (format!("version https://example.com/v2\n{}size 5\n", oid), "Version"),
+        (format!("{}size 5\n{}", version, oid), "Malformed"),
+        (format!("{}{}size 5", version, oid), "Malformed"),
+        (format!("{}{}size  5\n", version, oid), "Size"),
+        (format!("{}{}size -5\n", version, oid), "Size"),
+        (format!("{}{}", version, oid), "MissingKey"),
+        (format!("{}size 5\n", version), "MissingKey"),
+        (
+            format!(
+                "{}{}size 5\n",
+                version,
+                oid.to_uppercase().replace("OID SHA256", "oid sha256")
+            ),
+            "Oid",
+        ),
+        (
+            format!("{}oid sha1:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c\nsize 5\n", version),
+            "Oid",
+        ),
+        (format!("{}{}size 5\n{}", version, oid, "x".repeat(1024)), "TooLarge"),
+    ] {
+        let err = Pointer::from_bytes(input.as_bytes()).expect_err(&input);
+        let actual = match err {
+            Error::TooLarge { .. } => "TooLarge",
+            Error::Version { .. } => "Version",
+            Error::Malformed { .. } => "Malformed",
+            Error::MissingKey { .. } => "MissingKey",
+            Error::Oid { .. } => "Oid",
+            Error::Size { .. } => "Size",
+        };
+        assert_eq!(actual, expected, "{:?}", input);
+    }
+}
+
+#[test]
+fn from_data_and_write_to_round_trip() -> crate::Result {
+    let pointer = Pointer::from_data(b"hello");
+    assert_eq!(pointer, hello_pointer());
+    assert_eq!(pointer.to_bstring(), HELLO);
+    assert_eq!(Pointer::from_bytes(&pointer.to_bstring())?, pointer);
+    Ok(())
+}
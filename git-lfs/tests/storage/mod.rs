@@ -0,0 +1,50 @@
+use git_lfs::{storage, Pointer, Storage};
+
+use crate::hello_pointer;
+
+#[test]
+fn object_path_follows_the_git_lfs_layout() {
+    let storage = Storage::at_git_dir(".git");
+    assert_eq!(
+        storage.object_path(&hello_pointer()),
+        std::path::Path::new(".git/lfs/objects/2c/f2/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824")
+    );
+}
+
+#[test]
+fn write_and_read() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let pointer = hello_pointer();
+    let mut buf = Vec::new();
+    assert!(!storage.contains(&pointer));
+    assert!(!storage.read(&pointer, &mut buf)?, "missing objects can't be read");
+
+    assert_eq!(storage.write(b"hello")?, pointer);
+    assert!(storage.contains(&pointer));
+    assert!(storage.read(&pointer, &mut buf)?);
+    assert_eq!(buf, b"hello");
+
+    assert_eq!(storage.write(b"hello")?, pointer, "writing an existing object is fine");
+    Ok(())
+}
+
+#[test]
+fn write_verified_rejects_content_not_matching_the_pointer() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let storage = Storage::at(dir.path());
+    let pointer = hello_pointer();
+    for content in [&b"hellO"[..], b"hello world"] {
+        let err = storage.write_verified(&pointer, content).unwrap_err();
+        assert!(
+            matches!(err, storage::write::Error::Mismatch { expected, actual } if expected == pointer && actual == Pointer::from_data(content))
+        );
+    }
+    assert!(!storage.contains(&pointer), "mismatching objects aren't stored");
+    assert_eq!(
+        std::fs::read_dir(dir.path().join("tmp"))?.count(),
+        0,
+        "temporary files are cleaned up"
+    );
+    Ok(())
+}
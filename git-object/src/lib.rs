@@ -222,6 +222,8 @@ pub struct TreeRef<'a> {
 pub struct TreeRefIter<'a> {
     /// The directories and files contained in this tree.
     data: &'a [u8],
+    /// The kind of hash used for the ids of all entries.
+    object_hash: git_hash::Kind,
 }
 
 /// A mutable Tree, containing other trees, blobs or commits.
@@ -244,6 +246,8 @@ impl Tree {
 pub struct Data<'a> {
     /// kind of object
     pub kind: Kind,
+    /// The kind of hash used by the repository the object is from, which is needed to decode the entries of trees.
+    pub object_hash: git_hash::Kind,
     /// decoded, decompressed data, owned by a backing store.
     pub data: &'a [u8],
 }
@@ -375,3 +379,14 @@ pub mod decode {
         Ok((kind, size, size_end + 1))
     }
 }
+
+/// A function to compute a hash of kind `hash_kind` for an object of `object_kind` and its `data`.
+pub fn compute_hash(hash_kind: git_hash::Kind, object_kind: Kind, data: &[u8]) -> git_hash::ObjectId {
+    let header = encode::loose_header(object_kind, data.len());
+
+    let mut hasher = git_features::hash::hasher(hash_kind);
+    hasher.update(&header);
+    hasher.update(data);
+
+    hasher.digest()
+}
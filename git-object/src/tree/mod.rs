@@ -105,12 +105,21 @@ impl PartialOrd for Entry {
 }
 
 impl Ord for Entry {
-    /// Entries compare by the common portion of the filename. This is critical for proper functioning of algorithms working on trees.
+    /// Entries compare by their filename just like `git` does, which is as if trees had a trailing slash in their name.
+    /// This is critical for proper functioning of algorithms working on trees.
     fn cmp(&self, other: &Self) -> Ordering {
         let common_len = self.filename.len().min(other.filename.len());
         self.filename[..common_len]
             .cmp(&other.filename[..common_len])
-            .then_with(|| self.filename.len().cmp(&other.filename.len()))
+            .then_with(|| {
+                let byte_after_common = |e: &Entry| {
+                    e.filename
+                        .get(common_len)
+                        .copied()
+                        .or_else(|| e.mode.is_tree().then(|| b'/'))
+                };
+                byte_after_common(self).cmp(&byte_after_common(other))
+            })
     }
 }
 
@@ -23,7 +23,7 @@ fn fixture_bytes(kind: &str, path: &str) -> Vec<u8> {
     fixup(super::fixture_bytes(PathBuf::from(kind).join(path).to_str().unwrap()))
 }
 
-fn signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn signature(time: i64) -> git_actor::SignatureRef<'static> {
     use git_object::bstr::ByteSlice;
     git_actor::SignatureRef {
         name: b"Sebastian Thiel".as_bstr(),
@@ -36,7 +36,7 @@ fn signature(time: u32) -> git_actor::SignatureRef<'static> {
     }
 }
 
-fn linus_signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn linus_signature(time: i64) -> git_actor::SignatureRef<'static> {
     use git_object::bstr::ByteSlice;
     git_actor::SignatureRef {
         name: b"Linus Torvalds".as_bstr(),
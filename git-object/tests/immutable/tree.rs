@@ -5,13 +5,17 @@ mod iter {
 
     #[test]
     fn empty() {
-        assert_eq!(TreeRefIter::from_bytes(&[]).count(), 0, "empty trees are definitely ok");
+        assert_eq!(
+            TreeRefIter::from_bytes(&[], git_hash::Kind::Sha1).count(),
+            0,
+            "empty trees are definitely ok"
+        );
     }
 
     #[test]
     fn error_handling() {
         let data = fixture_bytes("tree", "everything.tree");
-        let iter = TreeRefIter::from_bytes(&data[..data.len() / 2]);
+        let iter = TreeRefIter::from_bytes(&data[..data.len() / 2], git_hash::Kind::Sha1);
         let entries = iter.collect::<Vec<_>>();
         assert!(
             entries.last().expect("at least one token").is_err(),
@@ -22,7 +26,8 @@ mod iter {
     #[test]
     fn everything() -> crate::Result {
         assert_eq!(
-            TreeRefIter::from_bytes(&fixture_bytes("tree", "everything.tree")).collect::<Result<Vec<_>, _>>()?,
+            TreeRefIter::from_bytes(&fixture_bytes("tree", "everything.tree"), git_hash::Kind::Sha1)
+                .collect::<Result<Vec<_>, _>>()?,
             vec![
                 EntryRef {
                     mode: tree::EntryMode::BlobExecutable,
@@ -63,7 +68,7 @@ mod from_bytes {
     #[test]
     fn empty() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&[])?,
+            TreeRef::from_bytes(&[], git_hash::Kind::Sha1)?,
             TreeRef { entries: vec![] },
             "empty trees are valid despite usually rare in the wild"
         );
@@ -73,7 +78,7 @@ mod from_bytes {
     #[test]
     fn everything() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "everything.tree"))?,
+            TreeRef::from_bytes(&fixture_bytes("tree", "everything.tree"), git_hash::Kind::Sha1)?,
             TreeRef {
                 entries: vec![
                     EntryRef {
@@ -107,10 +112,45 @@ mod from_bytes {
         Ok(())
     }
 
+    #[test]
+    fn sha256_ids() -> crate::Result {
+        let id = git_hash::ObjectId::empty_tree(git_hash::Kind::Sha256);
+        let mut data = b"40000 dir\0".to_vec();
+        data.extend_from_slice(id.as_slice());
+        data.extend_from_slice(b"100644 file\0");
+        data.extend_from_slice(id.as_slice());
+        assert_eq!(
+            TreeRef::from_bytes(&data, git_hash::Kind::Sha256)?,
+            TreeRef {
+                entries: vec![
+                    EntryRef {
+                        mode: tree::EntryMode::Tree,
+                        filename: b"dir".as_bstr(),
+                        oid: &id
+                    },
+                    EntryRef {
+                        mode: tree::EntryMode::Blob,
+                        filename: b"file".as_bstr(),
+                        oid: &id
+                    }
+                ]
+            }
+        );
+        assert!(
+            TreeRef::from_bytes(&data, git_hash::Kind::Sha1).is_err(),
+            "the hash kind must match the ids in the tree"
+        );
+        assert_eq!(
+            git_object::TreeRefIter::from_bytes(&data, git_hash::Kind::Sha256).count(),
+            2
+        );
+        Ok(())
+    }
+
     #[test]
     fn maybe_special() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "maybe-special.tree"))?
+            TreeRef::from_bytes(&fixture_bytes("tree", "maybe-special.tree"), git_hash::Kind::Sha1)?
                 .entries
                 .len(),
             160
@@ -121,7 +161,7 @@ mod from_bytes {
     #[test]
     fn definitely_special() -> crate::Result {
         assert_eq!(
-            TreeRef::from_bytes(&fixture_bytes("tree", "definitely-special.tree"))?
+            TreeRef::from_bytes(&fixture_bytes("tree", "definitely-special.tree"), git_hash::Kind::Sha1)?
                 .entries
                 .len(),
             19
@@ -142,3 +182,31 @@ mod entry_mode {
         );
     }
 }
+
+mod entry {
+    use git_object::tree::{Entry, EntryMode};
+
+    #[test]
+    fn ordering_is_the_one_of_git() {
+        let entry = |mode, name: &str| Entry {
+            mode,
+            filename: name.into(),
+            oid: git_hash::Kind::Sha1.null(),
+        };
+        let mut entries = [
+            entry(EntryMode::Tree, "a"),
+            entry(EntryMode::Blob, "a.txt"),
+            entry(EntryMode::Blob, "a0"),
+            entry(EntryMode::Blob, "a"),
+            entry(EntryMode::Tree, "a-b"),
+        ];
+        entries.sort();
+        assert_eq!(
+            entries.iter().map(|e| e.filename.to_string()).collect::<Vec<_>>(),
+            vec!["a", "a-b", "a.txt", "a", "a0"],
+            "trees sort as if they had a trailing slash"
+        );
+        assert_eq!(entries[0].mode, EntryMode::Blob);
+        assert_eq!(entries[3].mode, EntryMode::Tree);
+    }
+}
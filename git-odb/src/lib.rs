@@ -131,6 +131,8 @@ pub struct Store {
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: git_hash::Kind,
+    /// If set, it's called to obtain objects that couldn't be found before retrying the lookup.
+    pub(crate) lazy_fetch: Option<Box<store::LazyFetchFn>>,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -17,6 +17,11 @@ impl Store {
         self.use_multi_pack_index
     }
 
+    /// Return true if a function to [obtain missing objects][Store::with_lazy_fetch()] was installed.
+    pub fn has_lazy_fetch(&self) -> bool {
+        self.lazy_fetch.is_some()
+    }
+
     /// An iterator over replacements from object-ids `X` to `X-replaced` as `(X, X-replaced)`, sorted by the original id `X`.
     pub fn replacements(&self) -> impl Iterator<Item = (git_hash::ObjectId, git_hash::ObjectId)> + '_ {
         self.replacements.iter().cloned()
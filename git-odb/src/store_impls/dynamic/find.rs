@@ -107,6 +107,11 @@ where
             }
         }
 
+        let mut lazy_fetch = recursion
+            .is_none()
+            .then(|| self.store.lazy_fetch.as_deref())
+            .flatten()
+            .filter(|_| !self.skip_lazy_fetch);
         'outer: loop {
             {
                 let marker = snapshot.marker;
@@ -157,6 +162,7 @@ where
                             Ok(r) => Ok((
                                 git_object::Data {
                                     kind: r.kind,
+                                    object_hash: self.store.object_hash,
                                     data: buffer.as_slice(),
                                 },
                                 Some(git_pack::data::entry::Location {
@@ -256,6 +262,7 @@ where
                                     (
                                         git_object::Data {
                                             kind: r.kind,
+                                            object_hash: self.store.object_hash,
                                             data: buffer.as_slice(),
                                         },
                                         Some(git_pack::data::entry::Location {
@@ -292,7 +299,15 @@ where
                     *snapshot = new_snapshot;
                     self.clear_cache();
                 }
-                None => return Ok(None),
+                None => match lazy_fetch.take() {
+                    // Like git, we consider the object missing if it can't be fetched, and look again only once if it was.
+                    Some(lazy_fetch) => {
+                        if lazy_fetch(id).is_err() {
+                            return Ok(None);
+                        }
+                    }
+                    None => return Ok(None),
+                },
             }
         }
     }
@@ -121,6 +121,26 @@ impl Store {
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            lazy_fetch: None,
         })
     }
+
+    /// Install `fetch` to be called with the id of each object that can't be found, giving it a chance to obtain the object
+    /// from elsewhere and write it into this object database, like from the promisor remote of a partial clone.
+    /// If `fetch` succeeds, the lookup is retried once, and if it fails the object is considered missing like `git` does.
+    ///
+    /// Note that only [`try_find()`][git_pack::Find::try_find()] and its variants call `fetch`, whereas
+    /// [`contains()`][git_pack::Find::contains()] and header lookups don't, and that handles which [never refresh][super::RefreshMode::Never]
+    /// won't see the newly written objects.
+    /// Handles can [skip lazy fetching][super::Handle::skip_lazy_fetch] if they expect objects to be missing.
+    pub fn with_lazy_fetch(
+        mut self,
+        fetch: impl Fn(&git_hash::oid) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        self.lazy_fetch = Some(Box::new(fetch));
+        self
+    }
 }
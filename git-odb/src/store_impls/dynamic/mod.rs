@@ -21,6 +21,10 @@ where
 
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
+    /// If true, objects that can't be found won't be obtained [lazily][crate::Store::with_lazy_fetch()] even if this is configured.
+    ///
+    /// This is useful if missing objects are expected, for instance when figuring out which objects to fetch.
+    pub skip_lazy_fetch: bool,
 
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
@@ -52,6 +56,13 @@ impl RefreshMode {
     }
 }
 
+/// A function to obtain the object with the given id from elsewhere, like the promisor remote of a partial clone, and to write it
+/// into the object database so that the lookup which couldn't find it can be retried.
+///
+/// It's installed with [`Store::with_lazy_fetch()`][crate::Store::with_lazy_fetch()].
+pub type LazyFetchFn =
+    dyn Fn(&git_hash::oid) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> + Send + Sync;
+
 ///
 pub mod find;
 
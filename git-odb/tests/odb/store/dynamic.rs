@@ -691,6 +691,70 @@ fn missing_objects_triggers_everything_is_loaded() {
     );
 }
 
+#[test]
+fn lazy_fetch_is_called_for_missing_objects_only() -> crate::Result {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let dir = tempfile::tempdir()?;
+    let objects_dir = dir.path().to_owned();
+    let calls = Arc::new(AtomicUsize::default());
+    let store = git_odb::Store::at_opts(dir.path(), Vec::new(), Default::default())?.with_lazy_fetch({
+        let calls = Arc::clone(&calls);
+        move |id| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            if id == hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f") {
+                git_odb::loose::Store::at(&objects_dir, git_hash::Kind::Sha1)
+                    .write_buf(git_object::Kind::Blob, b"hello world")?;
+                Ok(())
+            } else {
+                Err("the remote doesn't have it".into())
+            }
+        }
+    });
+    assert!(store.has_lazy_fetch());
+    let handle = git_features::threading::OwnShared::new(store).to_cache();
+
+    let mut buf = Vec::new();
+    let blob = handle.find_blob(hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f"), &mut buf)?;
+    assert_eq!(blob.data, b"hello world");
+    assert_eq!(calls.load(Ordering::SeqCst), 1, "the object was fetched once");
+
+    handle.find_blob(hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f"), &mut buf)?;
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        1,
+        "objects that are present don't trigger a fetch"
+    );
+
+    assert!(
+        handle
+            .try_find(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+            .is_none(),
+        "objects that can't be fetched are missing"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2, "there is only one attempt to fetch");
+    assert!(
+        !handle.contains(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+        "contains() doesn't fetch"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let mut handle = handle.clone();
+    handle.skip_lazy_fetch = true;
+    assert!(handle
+        .try_find(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"), &mut buf)?
+        .is_none());
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        2,
+        "handles can opt out of fetching missing objects"
+    );
+    Ok(())
+}
+
 #[test]
 fn iterate_over_a_bunch_of_loose_and_packed_objects() -> crate::Result {
     let (db, _tmp) = db_with_all_object_sources()?;
@@ -353,7 +353,7 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
     }
 }
 
-fn signature(time: u32) -> git_actor::SignatureRef<'static> {
+fn signature(time: i64) -> git_actor::SignatureRef<'static> {
     git_actor::SignatureRef {
         name: b"Sebastian Thiel".as_bstr(),
         email: b"byronimo@gmail.com".as_bstr(),
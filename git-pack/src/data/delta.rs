@@ -68,3 +68,255 @@ pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     assert_eq!(i, data.len());
     assert_eq!(target.len(), 0);
 }
+
+/// The amount of bytes in a block of the base object that is indexed, and thus the minimal length of a copy instruction
+/// that can be found.
+const BLOCK_SIZE: usize = 16;
+/// The maximum amount of blocks in a single hash bucket, to prevent degenerate inputs from slowing down the search.
+const MAX_BUCKET_LEN: usize = 64;
+/// The maximum amount of bytes a single copy instruction may produce to remain compatible with all pack readers.
+const MAX_COPY_SIZE: usize = 0x10000;
+/// The maximum amount of bytes a single insert instruction may produce.
+const MAX_INSERT_SIZE: usize = 0x7f;
+/// The multiplier of the rolling hash over `BLOCK_SIZE` bytes.
+const HASH_BASE: u32 = 0x0100_0193;
+
+/// An index over blocks of a base object to quickly find portions of a target object which can be copied from it.
+///
+/// It's the equivalent of the index produced by [this canonical git function](https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/diff-delta.c#L132),
+/// even though the hash function and the layout are simpler.
+pub(crate) struct Index {
+    base_len: usize,
+    mask: u32,
+    /// The index into `blocks` of the first block with a hash, or `u32::MAX` if there is none.
+    buckets: Vec<u32>,
+    /// The offset of each block in the base object along with the index of the next block in the same bucket.
+    blocks: Vec<(u32, u32)>,
+}
+
+fn block_hash(block: &[u8]) -> u32 {
+    block
+        .iter()
+        .fold(0u32, |h, b| h.wrapping_mul(HASH_BASE).wrapping_add(*b as u32))
+}
+
+impl Index {
+    /// Create a new index over `base`, the object which serves as base for deltas.
+    ///
+    /// Returns `None` if `base` is too large to be referred to by copy instructions.
+    pub fn new(base: &[u8]) -> Option<Self> {
+        if base.len() > u32::MAX as usize {
+            return None;
+        }
+        let num_blocks = base.len() / BLOCK_SIZE;
+        let num_buckets = num_blocks.max(1).next_power_of_two();
+        let mut index = Index {
+            base_len: base.len(),
+            mask: num_buckets as u32 - 1,
+            buckets: vec![u32::MAX; num_buckets],
+            blocks: Vec::with_capacity(num_blocks),
+        };
+        let mut bucket_lens = vec![0u8; num_buckets];
+        // Insert from the back so that blocks earlier in the base are found first.
+        for block_index in (0..num_blocks).rev() {
+            let ofs = block_index * BLOCK_SIZE;
+            let bucket = (block_hash(&base[ofs..][..BLOCK_SIZE]) & index.mask) as usize;
+            if bucket_lens[bucket] as usize == MAX_BUCKET_LEN {
+                continue;
+            }
+            bucket_lens[bucket] += 1;
+            index.blocks.push((ofs as u32, index.buckets[bucket]));
+            index.buckets[bucket] = index.blocks.len() as u32 - 1;
+        }
+        Some(index)
+    }
+
+    /// Encode a delta which turns the indexed `base` into `target` into `out`, which is cleared beforehand.
+    ///
+    /// Return `false` if the delta would be larger than `max_size`, leaving `out` in an unspecified state.
+    pub fn encode(&self, base: &[u8], target: &[u8], max_size: usize, out: &mut Vec<u8>) -> bool {
+        assert_eq!(
+            base.len(),
+            self.base_len,
+            "the index must have been created from `base`"
+        );
+        out.clear();
+        encode_header_size(base.len() as u64, out);
+        encode_header_size(target.len() as u64, out);
+
+        let high_factor = (1..BLOCK_SIZE).fold(1u32, |f, _| f.wrapping_mul(HASH_BASE));
+        let mut insert_start = 0;
+        let mut pos = 0;
+        let mut hash = None;
+        while pos + BLOCK_SIZE <= target.len() {
+            let h = match hash {
+                Some(h) => h,
+                None => block_hash(&target[pos..][..BLOCK_SIZE]),
+            };
+            let (mut copy_ofs, mut copy_len) = (0, 0);
+            let mut block = self.buckets[(h & self.mask) as usize];
+            while block != u32::MAX {
+                let (ofs, next) = self.blocks[block as usize];
+                let ofs = ofs as usize;
+                let len = base[ofs..]
+                    .iter()
+                    .zip(&target[pos..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if len > copy_len {
+                    copy_ofs = ofs;
+                    copy_len = len;
+                }
+                block = next;
+            }
+
+            if copy_len < BLOCK_SIZE {
+                if pos + BLOCK_SIZE < target.len() {
+                    hash = Some(
+                        h.wrapping_sub((target[pos] as u32).wrapping_mul(high_factor))
+                            .wrapping_mul(HASH_BASE)
+                            .wrapping_add(target[pos + BLOCK_SIZE] as u32),
+                    );
+                }
+                pos += 1;
+                continue;
+            }
+
+            while copy_ofs > 0 && pos > insert_start && base[copy_ofs - 1] == target[pos - 1] {
+                copy_ofs -= 1;
+                pos -= 1;
+                copy_len += 1;
+            }
+            encode_insert(&target[insert_start..pos], out);
+            pos += copy_len;
+            insert_start = pos;
+            while copy_len > 0 {
+                let len = copy_len.min(MAX_COPY_SIZE);
+                encode_copy(copy_ofs, len, out);
+                copy_ofs += len;
+                copy_len -= len;
+            }
+            if out.len() > max_size {
+                return false;
+            }
+            hash = None;
+        }
+        encode_insert(&target[insert_start..], out);
+        out.len() <= max_size
+    }
+}
+
+/// Encode `size` the way [`decode_header_size()`] expects it.
+fn encode_header_size(mut size: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn encode_insert(mut data: &[u8], out: &mut Vec<u8>) {
+    while !data.is_empty() {
+        let len = data.len().min(MAX_INSERT_SIZE);
+        out.push(len as u8);
+        out.extend_from_slice(&data[..len]);
+        data = &data[len..];
+    }
+}
+
+fn encode_copy(ofs: usize, size: usize, out: &mut Vec<u8>) {
+    let cmd_pos = out.len();
+    let mut cmd = 0b1000_0000;
+    out.push(cmd);
+    for (byte_index, flag) in [0b0000_0001, 0b0000_0010, 0b0000_0100, 0b0000_1000].iter().enumerate() {
+        let byte = (ofs >> (byte_index * 8)) as u8;
+        if byte != 0 {
+            cmd |= *flag;
+            out.push(byte);
+        }
+    }
+    let size = if size == MAX_COPY_SIZE { 0 } else { size };
+    for (byte_index, flag) in [0b0001_0000, 0b0010_0000, 0b0100_0000].iter().enumerate() {
+        let byte = (size >> (byte_index * 8)) as u8;
+        if byte != 0 {
+            cmd |= *flag;
+            out.push(byte);
+        }
+    }
+    out[cmd_pos] = cmd;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(base: &[u8], target: &[u8]) -> usize {
+        let mut delta = Vec::new();
+        assert!(Index::new(base)
+            .expect("small enough")
+            .encode(base, target, usize::MAX, &mut delta));
+        let (base_size, consumed) = decode_header_size(&delta);
+        assert_eq!(base_size, base.len() as u64);
+        let (target_size, consumed_2) = decode_header_size(&delta[consumed..]);
+        assert_eq!(target_size, target.len() as u64);
+        let mut actual = vec![0; target.len()];
+        apply(base, &mut actual, &delta[consumed + consumed_2..]);
+        assert_eq!(actual, target, "applying the delta to the base yields the target");
+        delta.len()
+    }
+
+    fn pseudo_random_bytes(len: usize, mut seed: u32) -> Vec<u8> {
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_and_tiny_objects() {
+        roundtrip(b"", b"");
+        roundtrip(b"", b"hello");
+        roundtrip(b"hello", b"");
+        roundtrip(b"hello", b"hello");
+    }
+
+    #[test]
+    fn similar_objects_produce_small_deltas() {
+        let base = pseudo_random_bytes(10_000, 42);
+        let mut target = base.clone();
+        target[5_000..5_010].copy_from_slice(b"0123456789");
+        target.splice(100..100, b"inserted".iter().copied());
+        target.extend_from_slice(b"appended at the end");
+        let delta_len = roundtrip(&base, &target);
+        assert!(delta_len < 100, "only changes are stored, got {} bytes", delta_len);
+    }
+
+    #[test]
+    fn copies_larger_than_the_maximum_copy_size_are_split() {
+        let base = pseudo_random_bytes(MAX_COPY_SIZE * 2 + 1_000, 7);
+        let mut target = base.clone();
+        target.insert(0, b'x');
+        roundtrip(&base, &target);
+    }
+
+    #[test]
+    fn unrelated_objects_are_inserted_entirely() {
+        let base = pseudo_random_bytes(1_000, 1);
+        let target = pseudo_random_bytes(1_000, 2);
+        assert!(roundtrip(&base, &target) > 1_000);
+    }
+
+    #[test]
+    fn max_size_is_respected() {
+        let base = pseudo_random_bytes(1_000, 1);
+        let target = pseudo_random_bytes(1_000, 2);
+        let mut delta = Vec::new();
+        assert!(!Index::new(&base).unwrap().encode(&base, &target, 500, &mut delta));
+    }
+}
@@ -1,4 +1,5 @@
 use git_hash::ObjectId;
+use git_object::bstr::BStr;
 
 use crate::data::output::Count;
 
@@ -35,10 +36,22 @@ impl Count {
         Count {
             id: oid.into(),
             entry_pack_location: PackLocation::LookedUp(location),
+            name_hash: 0,
         }
     }
 }
 
+/// Compute a hash of the `name` of a tree entry in which its last characters are most significant, so that objects with similar names,
+/// like ones with the same file extension, produce similar hashes.
+///
+/// Equivalent to [this canonical git function](https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/pack-objects.h#L209),
+/// which is used on the full path though.
+pub fn name_hash(name: &BStr) -> u32 {
+    name.iter()
+        .filter(|b| !b.is_ascii_whitespace())
+        .fold(0u32, |hash, b| (hash >> 2).wrapping_add((*b as u32) << 24))
+}
+
 #[path = "objects/mod.rs"]
 mod objects_impl;
 pub use objects_impl::{objects, objects_unthreaded};
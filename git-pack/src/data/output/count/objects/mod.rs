@@ -229,7 +229,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, progress, stats, true,
                                     );
-                                    git_object::TreeRefIter::from_bytes(obj.data)
+                                    git_object::TreeRefIter::from_bytes(obj.data, obj.object_hash)
                                 };
 
                                 let objects = if parent_commit_ids.is_empty() {
@@ -276,7 +276,10 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            git_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            git_object::TreeRefIter::from_bytes(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.object_hash,
+                                            )
                                         };
 
                                         changes_delegate.clear();
@@ -294,8 +297,16 @@ mod expand {
                                     }
                                     &changes_delegate.objects
                                 };
-                                for id in objects.iter() {
-                                    out.push(id_to_count(db, buf2, id, progress, stats, allow_pack_lookups));
+                                for (id, name_hash) in objects.iter() {
+                                    out.push(id_to_count(
+                                        db,
+                                        buf2,
+                                        id,
+                                        *name_hash,
+                                        progress,
+                                        stats,
+                                        allow_pack_lookups,
+                                    ));
                                 }
                                 break;
                             }
@@ -312,7 +323,7 @@ mod expand {
                             Tree => {
                                 traverse_delegate.clear();
                                 git_traverse::tree::breadthfirst(
-                                    git_object::TreeRefIter::from_bytes(obj.0.data),
+                                    git_object::TreeRefIter::from_bytes(obj.0.data, obj.0.object_hash),
                                     &mut tree_traversal_state,
                                     |oid, buf| {
                                         stats.decoded_objects += 1;
@@ -329,8 +340,16 @@ mod expand {
                                     &mut traverse_delegate,
                                 )
                                 .map_err(Error::TreeTraverse)?;
-                                for id in traverse_delegate.non_trees.iter() {
-                                    out.push(id_to_count(db, buf1, id, progress, stats, allow_pack_lookups));
+                                for (id, name_hash) in traverse_delegate.non_trees.iter() {
+                                    out.push(id_to_count(
+                                        db,
+                                        buf1,
+                                        id,
+                                        *name_hash,
+                                        progress,
+                                        stats,
+                                        allow_pack_lookups,
+                                    ));
                                 }
                                 break;
                             }
@@ -387,6 +406,7 @@ mod expand {
         db: &Find,
         buf: &mut Vec<u8>,
         id: &oid,
+        name_hash: u32,
         progress: &mut impl Progress,
         statistics: &mut Outcome,
         allow_pack_lookups: bool,
@@ -400,6 +420,7 @@ mod expand {
             } else {
                 PackLocation::NotLookedUp
             },
+            name_hash,
         }
     }
 }
@@ -4,13 +4,19 @@ pub mod changes {
         Visit,
     };
     use git_hash::ObjectId;
-    use git_object::{bstr::BStr, tree::EntryMode};
+    use git_object::{
+        bstr::{BStr, BString},
+        tree::EntryMode,
+    };
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use crate::data::output::count::{name_hash, objects_impl::util::InsertImmutable};
 
     pub struct AllNew<'a, H> {
-        pub objects: Vec<ObjectId>,
+        /// All new objects along with the hash of their name
+        pub objects: Vec<(ObjectId, u32)>,
         all_seen: &'a H,
+        /// The name of the entry which is visited next
+        name: BString,
     }
 
     impl<'a, H> AllNew<'a, H>
@@ -21,6 +27,7 @@ pub mod changes {
             AllNew {
                 objects: Default::default(),
                 all_seen,
+                name: Default::default(),
             }
         }
         pub fn clear(&mut self) {
@@ -36,7 +43,10 @@ pub mod changes {
 
         fn push_back_tracked_path_component(&mut self, _component: &BStr) {}
 
-        fn push_path_component(&mut self, _component: &BStr) {}
+        fn push_path_component(&mut self, component: &BStr) {
+            self.name.clear();
+            self.name.extend_from_slice(component);
+        }
 
         fn pop_path_component(&mut self) {}
 
@@ -48,7 +58,7 @@ pub mod changes {
                     }
                     let inserted = self.all_seen.insert(oid);
                     if inserted {
-                        self.objects.push(oid);
+                        self.objects.push((oid, name_hash(self.name.as_ref())));
                     }
                 }
                 Change::Deletion { .. } => {}
@@ -66,10 +76,11 @@ pub mod traverse {
     };
     use git_traverse::tree::{visit::Action, Visit};
 
-    use crate::data::output::count::objects_impl::util::InsertImmutable;
+    use crate::data::output::count::{name_hash, objects_impl::util::InsertImmutable};
 
     pub struct AllUnseen<'a, H> {
-        pub non_trees: Vec<ObjectId>,
+        /// All unseen objects that aren't trees along with the hash of their name
+        pub non_trees: Vec<(ObjectId, u32)>,
         all_seen: &'a H,
     }
 
@@ -116,7 +127,7 @@ pub mod traverse {
             }
             let inserted = self.all_seen.insert(entry.oid.to_owned());
             if inserted {
-                self.non_trees.push(entry.oid.to_owned());
+                self.non_trees.push((entry.oid.to_owned(), name_hash(entry.filename)));
             }
             Action::Continue
         }
@@ -0,0 +1,261 @@
+use std::{cmp::Reverse, collections::VecDeque, ops::Range, sync::Arc};
+
+use git_features::{parallel, progress::Progress};
+
+use crate::data::{self, output};
+
+/// The amount of candidates that are searched together, independently of all other candidates.
+///
+/// It's fixed to assure the resulting pack only depends on its input, but not on the amount of threads used to produce it.
+const CANDIDATES_PER_SEGMENT: usize = 1_000;
+
+/// An object which may be stored as delta against another object, or serve as base for other deltas.
+struct Candidate {
+    /// The index into the counts.
+    index: usize,
+    kind: git_object::Kind,
+    size: usize,
+    name_hash: u32,
+}
+
+/// An object in the window of possible bases of the current candidate.
+struct Base {
+    index: usize,
+    kind: git_object::Kind,
+    /// The length of the chain of deltas that have to be resolved to obtain this object.
+    depth: usize,
+    data: Vec<u8>,
+    delta_index: Option<data::delta::Index>,
+}
+
+/// Compute new deltas for all `counts` whose entries can't be copied from an existing pack as delta, and return them
+/// as entries along with the index of their count, ordered by index.
+///
+/// All objects that are candidates for delta compression are sorted by kind, name-hash and size (largest first), and each one is
+/// compared with the `window` candidates right before it, each of which may serve as its base. As bases are always searched
+/// before the candidates using them, deltas can't form cycles, but bases may have a higher index than their deltas, which
+/// is why entries have to be written in [`write_order()`]. No delta chain created here is longer than `max_depth`, but chains
+/// of copied deltas may become longer if their base is turned into a delta.
+///
+/// Objects that can't be read are ignored as errors will be reported once their entries are created.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn compute<Find>(
+    counts: &[output::Count],
+    counts_range_by_pack_id: &[(u32, Range<usize>)],
+    db: Find,
+    allow_thin_pack: bool,
+    window: usize,
+    max_depth: usize,
+    thread_limit: Option<usize>,
+    chunk_size: usize,
+    progress: &mut impl Progress,
+) -> Vec<(usize, output::Entry)>
+where
+    Find: crate::Find + Send + Clone,
+    <Find as crate::Find>::Error: Send,
+{
+    if window == 0 || max_depth == 0 {
+        return Vec::new();
+    }
+    let mut candidates = {
+        let mut progress = progress.add_child_with_id("finding candidates", *b"ECDF"); /* Entries from Counts Delta candidates Found */
+        progress.init(Some(counts.len()), git_features::progress::count("counts"));
+        let progress = Arc::new(parking_lot::Mutex::new(progress));
+        parallel::in_parallel_if(
+            || counts.len() > 4_000,
+            counts.chunks(chunk_size).enumerate(),
+            thread_limit,
+            |_n| Vec::<u8>::new(),
+            {
+                let db = db.clone();
+                let progress = Arc::clone(&progress);
+                move |(chunk_index, chunk): (usize, &[output::Count]), buf| {
+                    let mut out = Vec::new();
+                    for (offset, count) in chunk.iter().enumerate() {
+                        let index = chunk_index * chunk_size + offset;
+                        if let Some((kind, size)) = kind_and_size_if_candidate(
+                            count,
+                            counts,
+                            counts_range_by_pack_id,
+                            &db,
+                            allow_thin_pack,
+                            buf,
+                        ) {
+                            out.push(Candidate {
+                                index,
+                                kind,
+                                size,
+                                name_hash: count.name_hash,
+                            });
+                        }
+                    }
+                    progress.lock().inc_by(chunk.len());
+                    Ok::<_, ()>(out)
+                }
+            },
+            parallel::reduce::Flatten::default(),
+        )
+        .expect("infallible - we ignore objects that can't be read")
+    };
+    candidates.sort_by_key(|c| (c.kind, c.name_hash, Reverse(c.size), c.index));
+
+    let mut progress = progress.add_child_with_id("delta compression", *b"ECDC"); /* Entries from Counts Delta Compression */
+    progress.init(Some(candidates.len()), git_features::progress::count("objects"));
+    let progress = Arc::new(parking_lot::Mutex::new(progress));
+    let mut deltas = parallel::in_parallel_if(
+        || candidates.len() > CANDIDATES_PER_SEGMENT,
+        candidates.chunks(CANDIDATES_PER_SEGMENT),
+        thread_limit,
+        |_n| (Vec::<u8>::new(), Vec::<u8>::new(), Vec::<u8>::new()),
+        move |segment: &[Candidate], (buf, delta, best_delta)| {
+            let mut out = Vec::new();
+            let mut bases = VecDeque::<Base>::with_capacity(window + 1);
+            for candidate in segment {
+                let count = &counts[candidate.index];
+                let object = match db.try_find(count.id, buf).ok().flatten() {
+                    Some((obj, _location)) => obj.data,
+                    None => continue,
+                };
+                let hash_len = count.id.as_slice().len();
+                let mut max_size = (object.len() / 2).saturating_sub(hash_len);
+                let mut best_base = None;
+                for base in bases.iter().rev() {
+                    if base.kind != candidate.kind || base.depth >= max_depth {
+                        continue;
+                    }
+                    if base.data.len() < object.len() / 32 || object.len().saturating_sub(base.data.len()) >= max_size {
+                        continue;
+                    }
+                    if let Some(delta_index) = &base.delta_index {
+                        if delta_index.encode(&base.data, object, max_size, delta) {
+                            max_size = delta.len().saturating_sub(1);
+                            std::mem::swap(delta, best_delta);
+                            best_base = Some((base.index, base.depth));
+                        }
+                    }
+                }
+                let depth = match best_base.and_then(|(base_index, base_depth)| {
+                    output::Entry::from_delta(count, base_index, best_delta)
+                        .ok()
+                        .map(|entry| (entry, base_depth))
+                }) {
+                    Some((entry, base_depth)) => {
+                        out.push((candidate.index, entry));
+                        base_depth + 1
+                    }
+                    None => 0,
+                };
+                if bases.len() == window {
+                    bases.pop_front();
+                }
+                bases.push_back(Base {
+                    index: candidate.index,
+                    kind: candidate.kind,
+                    depth,
+                    delta_index: data::delta::Index::new(object),
+                    data: object.to_owned(),
+                });
+            }
+            progress.lock().inc_by(segment.len());
+            Ok::<_, ()>(out)
+        },
+        parallel::reduce::Flatten::default(),
+    )
+    .expect("infallible - we ignore objects that can't be read");
+    deltas.sort_by_key(|(index, _)| *index);
+    deltas
+}
+
+/// Return the indices of all `num_counts` counts in the order their entries have to be written so that each base is written
+/// before the `deltas` using it, keeping the order of the counts otherwise.
+pub(crate) fn write_order(num_counts: usize, deltas: &[(usize, output::Entry)]) -> Vec<usize> {
+    let base_of = |index: usize| {
+        deltas
+            .binary_search_by_key(&index, |e| e.0)
+            .ok()
+            .and_then(|idx| match deltas[idx].1.kind {
+                output::entry::Kind::DeltaRef { object_index } => Some(object_index),
+                _ => None,
+            })
+    };
+    let mut is_written = vec![false; num_counts];
+    let mut order = Vec::with_capacity(num_counts);
+    let mut chain = Vec::new();
+    for index in 0..num_counts {
+        let mut cursor = Some(index);
+        while let Some(index) = cursor.filter(|index| !is_written[*index]) {
+            chain.push(index);
+            cursor = base_of(index);
+        }
+        for index in chain.drain(..).rev() {
+            is_written[index] = true;
+            order.push(index);
+        }
+    }
+    order
+}
+
+/// Return the kind and size of the object represented by `count` if it's a candidate for delta compression, which is the case
+/// if it exists and isn't a delta in a pack that can be copied as is.
+fn kind_and_size_if_candidate<Find>(
+    count: &output::Count,
+    counts: &[output::Count],
+    counts_range_by_pack_id: &[(u32, Range<usize>)],
+    db: &Find,
+    allow_thin_pack: bool,
+    buf: &mut Vec<u8>,
+) -> Option<(git_object::Kind, usize)>
+where
+    Find: crate::Find,
+{
+    if let Some((location, entry)) = count
+        .entry_pack_location
+        .as_ref()
+        .and_then(|l| db.entry_by_location(l).map(|e| (l, e)))
+    {
+        if entry.version == data::Version::V2 {
+            let pack_entry = data::Entry::from_bytes(&entry.data, 0, count.id.as_slice().len());
+            use data::entry::Header::*;
+            let kind = match pack_entry.header {
+                Commit => Some(git_object::Kind::Commit),
+                Tree => Some(git_object::Kind::Tree),
+                Blob => Some(git_object::Kind::Blob),
+                Tag => Some(git_object::Kind::Tag),
+                OfsDelta { base_distance } => {
+                    let base_offset = location.pack_offset.saturating_sub(base_distance);
+                    let base_is_in_pack = counts_range_by_pack_id
+                        .binary_search_by_key(&location.pack_id, |e| e.0)
+                        .ok()
+                        .map_or(false, |idx| {
+                            counts[counts_range_by_pack_id[idx].1.clone()]
+                                .binary_search_by(|c| {
+                                    c.entry_pack_location
+                                        .as_ref()
+                                        .expect("packed")
+                                        .pack_offset
+                                        .cmp(&base_offset)
+                                })
+                                .is_ok()
+                        });
+                    if base_is_in_pack || allow_thin_pack {
+                        // Not a candidate: the delta is copied as is, either against its base in this pack or, for thin packs,
+                        // against a base the receiver already has, which `Entry::from_pack_entry()` turns into a `DeltaOid`.
+                        return None;
+                    }
+                    // A candidate: the delta's base won't be available to the receiver, so decode the object below
+                    // to search for a new base.
+                    None
+                }
+                // A candidate: decode the object below as its base is only known by id.
+                RefDelta { .. } => None,
+            };
+            if let Some(kind) = kind {
+                return Some((kind, pack_entry.decompressed_size as usize));
+            }
+        }
+    }
+    db.try_find(count.id, buf)
+        .ok()
+        .flatten()
+        .map(|(obj, _location)| (obj.kind, obj.data.len()))
+}
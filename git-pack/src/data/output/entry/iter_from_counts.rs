@@ -2,7 +2,7 @@ use std::{cmp::Ordering, sync::Arc};
 
 use git_features::{parallel, parallel::SequenceId, progress::Progress};
 
-use crate::data::output;
+use crate::data::{output, output::entry::delta_search};
 
 /// Given a known list of object `counts`, calculate entries ready to be put into a data pack.
 ///
@@ -43,6 +43,8 @@ pub fn iter_from_counts<Find>(
         allow_thin_pack,
         thread_limit,
         chunk_size,
+        delta_window,
+        delta_depth,
     }: Options,
 ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error<Find::Error>>>
        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<Find::Error>>>
@@ -90,7 +92,7 @@ where
         progress.lock().show_throughput(start);
     }
     let counts_range_by_pack_id = match mode {
-        Mode::PackCopyAndBaseObjects => {
+        Mode::PackCopyAndBaseObjects | Mode::PackCopyAndDeltaCompression => {
             let mut progress = progress.add_child_with_id("sorting", *b"ECSE"); /* Entries from Counts Sorting Entries */
             progress.init(Some(counts.len()), git_features::progress::count("counts"));
             let start = std::time::Instant::now();
@@ -127,7 +129,30 @@ where
         }
     };
 
+    let deltas = match mode {
+        Mode::PackCopyAndBaseObjects => Vec::new(),
+        Mode::PackCopyAndDeltaCompression => delta_search::compute(
+            &counts,
+            &counts_range_by_pack_id,
+            db.clone(),
+            allow_thin_pack,
+            delta_window,
+            delta_depth,
+            thread_limit,
+            chunk_size,
+            &mut progress,
+        ),
+    };
+
+    let order = delta_search::write_order(counts.len(), &deltas);
+    let mut position_by_index = vec![0; order.len()];
+    for (position, index) in order.iter().enumerate() {
+        position_by_index[*index] = position;
+    }
+    let order = Arc::new(order);
+    let position_by_index = Arc::new(position_by_index);
     let counts = Arc::new(counts);
+    let deltas = Arc::new(deltas);
     let progress = Arc::new(parking_lot::Mutex::new(progress));
     let chunks = util::ChunkRanges::new(chunk_size, counts.len());
 
@@ -149,12 +174,18 @@ where
             let counts = Arc::clone(&counts);
             move |(chunk_id, chunk_range): (SequenceId, std::ops::Range<usize>), (buf, progress)| {
                 let mut out = Vec::new();
-                let chunk = &counts[chunk_range];
                 let mut stats = Outcome::default();
                 let mut pack_offsets_to_id = None;
-                progress.init(Some(chunk.len()), git_features::progress::count("objects"));
+                progress.init(Some(chunk_range.len()), git_features::progress::count("objects"));
 
-                for count in chunk.iter() {
+                for index in order[chunk_range].iter().copied() {
+                    let count = &counts[index];
+                    if let Ok(delta_idx) = deltas.binary_search_by_key(&index, |e| e.0) {
+                        stats.delta_compressed_objects += 1;
+                        out.push(deltas[delta_idx].1.clone());
+                        progress.inc();
+                        continue;
+                    }
                     out.push(match count
                         .entry_pack_location
                         .as_ref()
@@ -227,6 +258,11 @@ where
                     }?);
                     progress.inc();
                 }
+                for entry in &mut out {
+                    if let output::entry::Kind::DeltaRef { object_index } = &mut entry.kind {
+                        *object_index = position_by_index[*object_index];
+                    }
+                }
                 Ok((chunk_id, out, stats))
             }
         },
@@ -325,6 +361,9 @@ mod types {
         pub objects_copied_from_pack: usize,
         /// The amount of objects that ref to their base as ref-delta, an indication for a thin back being created.
         pub ref_delta_objects: usize,
+        /// The amount of objects that were newly delta-compressed against another object in the pack, which can only happen
+        /// in [`Mode::PackCopyAndDeltaCompression`].
+        pub delta_compressed_objects: usize,
     }
 
     impl Outcome {
@@ -335,12 +374,14 @@ mod types {
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             }: Self,
         ) {
             self.decoded_and_recompressed_objects += decoded_objects;
             self.missing_objects += missing_objects;
             self.objects_copied_from_pack += objects_copied_from_pack;
             self.ref_delta_objects += ref_delta_objects;
+            self.delta_compressed_objects += delta_compressed_objects;
         }
     }
 
@@ -353,6 +394,14 @@ mod types {
         /// from existing pack compression and spending the smallest possible time on compressing unpacked objects at
         /// the cost of bandwidth.
         PackCopyAndBaseObjects,
+        /// Like [`PackCopyAndBaseObjects`][Mode::PackCopyAndBaseObjects], but all objects that would be written as base objects
+        /// are compared to similar objects to find a delta that is smaller than the object itself, similar to what `git pack-objects`
+        /// does. Only packed deltas whose base is part of the pack are copied as is.
+        ///
+        /// Finding deltas requires decoding all of these objects and happens before the first entry is produced, making it
+        /// considerably slower while producing much smaller packs.
+        /// See [`Options::delta_window`] and [`Options::delta_depth`] for configuration.
+        PackCopyAndDeltaCompression,
     }
 
     /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
@@ -375,6 +424,15 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// The amount of similar objects each object is compared to when searching for deltas in
+        /// [`Mode::PackCopyAndDeltaCompression`], like `pack.window` in `git`.
+        ///
+        /// Larger windows yield smaller packs at the cost of more time spent. If 0, no new deltas are computed.
+        pub delta_window: usize,
+        /// The maximum length of a chain of newly computed deltas in [`Mode::PackCopyAndDeltaCompression`], like `pack.depth` in `git`.
+        ///
+        /// Longer chains yield smaller packs, but make it more expensive to decode objects from it.
+        pub delta_depth: usize,
     }
 
     impl Default for Options {
@@ -385,6 +443,8 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                delta_window: 10,
+                delta_depth: 50,
             }
         }
     }
@@ -14,6 +14,10 @@ pub struct Count {
     pub id: ObjectId,
     /// A way to locate a pack entry in the object database, only available if the object is in a pack.
     pub entry_pack_location: count::PackLocation,
+    /// A hash of the name of the tree entry the object was found at, or 0 if it's unknown, as computed by [`count::name_hash()`].
+    ///
+    /// It's used to sort objects so that those likely to be similar are close to each other when searching for deltas.
+    pub name_hash: u32,
 }
 
 /// An entry to be written to a file.
@@ -90,15 +90,6 @@ impl Default for Version {
     }
 }
 
-impl Version {
-    /// The kind of hash to produce to be compatible to this kind of index
-    pub fn hash(&self) -> git_hash::Kind {
-        match self {
-            Version::V1 | Version::V2 => git_hash::Kind::Sha1,
-        }
-    }
-}
-
 /// A way to indicate if a lookup, despite successful, was ambiguous or yielded exactly
 /// one result in the particular index.
 pub type PrefixLookupResult = Result<EntryIndex, ()>;
@@ -164,7 +164,7 @@ impl crate::index::File {
                      decompressed: bytes,
                      ..
                  }| {
-                    modify_base(data, entry, bytes, version.hash());
+                    modify_base(data, entry, bytes, object_hash);
                     Ok::<_, Error>(())
                 },
                 traverse::Options {
@@ -194,7 +194,7 @@ impl crate::index::File {
                 let header = crate::data::header::encode(pack_version, 0);
                 let mut hasher = git_features::hash::hasher(object_hash);
                 hasher.update(&header);
-                git_hash::ObjectId::from(hasher.digest())
+                hasher.digest()
             }
             None => return Err(Error::IteratorInvariantTrailer),
         };
@@ -225,7 +225,7 @@ fn modify_base(entry: &mut TreeEntry, pack_entry: &crate::data::Entry, decompres
         let mut hasher = git_features::hash::hasher(object_hash);
         hasher.update(&git_object::encode::loose_header(kind, bytes.len()));
         hasher.update(bytes);
-        git_hash::ObjectId::from(hasher.digest())
+        hasher.digest()
     }
 
     let object_kind = pack_entry.header.as_kind().expect("base object as source of iteration");
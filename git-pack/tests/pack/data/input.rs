@@ -29,6 +29,7 @@ mod lookup_ref_delta_objects {
     fn entry(header: Header, data: &'static [u8]) -> input::Entry {
         let obj = git_object::Data {
             kind: header.as_kind().unwrap_or(git_object::Kind::Blob),
+            object_hash: git_hash::Kind::Sha1,
             data,
         };
         let mut entry = input::Entry::from_data_obj(&obj, 0).expect("valid object");
@@ -95,6 +96,7 @@ mod lookup_ref_delta_objects {
             buf.copy_from_slice(inserted_data);
             Some(git_object::Data {
                 kind: git_object::Kind::Blob,
+                object_hash: git_hash::Kind::Sha1,
                 data: buf.as_slice(),
             })
         });
@@ -122,6 +122,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 16,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3"),
                 None,
@@ -155,6 +156,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 103,
                     ref_delta_objects: 74,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("25114bd8820b393c402cd53ad8ec7f6a84bb0633"),
                 Some(hex_to_id("29ab9797aff1ca826afb699680356695d19c5acb")),
@@ -188,6 +190,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 29,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("d83d42128e40957c5174920189a0390b5a70f446"),
                 None,
@@ -209,6 +212,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -230,6 +234,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -321,6 +326,85 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn delta_compression_of_objects_that_would_be_base_objects() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let head = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let (counts, _) = output::count::objects(
+        db.clone(),
+        std::iter::once(Ok::<_, Infallible>(head)),
+        progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    )?;
+    assert!(
+        counts.iter().any(|c| c.name_hash != 0),
+        "blobs have a name hash to help finding deltas"
+    );
+
+    let mut entries_by_mode = Vec::new();
+    for mode in [
+        entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+        entry::iter_from_counts::Mode::PackCopyAndDeltaCompression,
+    ] {
+        let mut entries_iter = output::entry::iter_from_counts(
+            counts.clone(),
+            db.clone(),
+            progress::Discard,
+            output::entry::iter_from_counts::Options {
+                mode,
+                ..Default::default()
+            },
+        );
+        let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        entries_by_mode.push((entries, entries_iter.finalize()?));
+    }
+
+    let (base_entries, base_stats) = &entries_by_mode[0];
+    let (delta_entries, delta_stats) = &entries_by_mode[1];
+    assert_eq!(base_stats.decoded_and_recompressed_objects, 74);
+    assert_eq!(base_stats.delta_compressed_objects, 0);
+    assert_eq!(
+        delta_stats.delta_compressed_objects + delta_stats.decoded_and_recompressed_objects,
+        74,
+        "all objects that would have been base objects are candidates for delta compression"
+    );
+    assert_eq!(
+        delta_stats.delta_compressed_objects, 70,
+        "all objects in the window are tried as base, even those written after the candidate in count order"
+    );
+    assert_eq!(
+        delta_stats.objects_copied_from_pack, base_stats.objects_copied_from_pack,
+        "deltas in packs are still copied"
+    );
+
+    for (index, entry) in delta_entries.iter().enumerate() {
+        if let entry::Kind::DeltaRef { object_index } = entry.kind {
+            assert!(object_index < index, "bases are always written before their deltas");
+        }
+    }
+    let size = |entries: &[output::Entry]| entries.iter().map(|e| e.compressed_data.len()).sum::<usize>();
+    assert!(
+        size(delta_entries) < size(base_entries),
+        "delta compression makes the pack smaller"
+    );
+
+    write_and_verify(
+        db,
+        delta_entries.clone(),
+        hex_to_id("68d5adbabf51c3ec610083c07ba31bfec9069ace"),
+        None,
+    )
+}
+
 #[test]
 fn empty_pack_is_allowed() {
     write_and_verify(
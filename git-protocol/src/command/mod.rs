@@ -113,10 +113,22 @@ mod with_io {
 
         /// Turns on all modern features for V1 and all supported features for V2, returning them as a vector of features.
         /// Note that this is the basis for any fetch operation as these features fulfil basic requirements and reasonably up-to-date servers.
+        ///
+        /// If the server advertises its `object-format`, it's echoed back to let it know we are able to handle its kind of hash.
         pub fn default_features(
             &self,
             version: git_transport::Protocol,
             server_capabilities: &Capabilities,
+        ) -> Vec<Feature> {
+            let mut features = self.default_features_sans_object_format(version, server_capabilities);
+            features.extend(super::object_format(server_capabilities));
+            features
+        }
+
+        fn default_features_sans_object_format(
+            &self,
+            version: git_transport::Protocol,
+            server_capabilities: &Capabilities,
         ) -> Vec<Feature> {
             match self {
                 Command::Fetch => match version {
@@ -200,7 +212,7 @@ mod with_io {
                             continue;
                         }
                         match *feature {
-                            "agent" => {}
+                            "agent" | "object-format" => {}
                             _ => panic!("{}: V2 feature/capability {} is not supported", self.as_str(), feature),
                         }
                     }
@@ -210,5 +222,13 @@ mod with_io {
     }
 }
 
+/// Return the `object-format` feature to send to a server which advertises its object format in its `server_capabilities`.
+#[cfg(any(test, feature = "async-client", feature = "blocking-client"))]
+pub(crate) fn object_format(server_capabilities: &git_transport::client::Capabilities) -> Option<Feature> {
+    server_capabilities
+        .capability("object-format")
+        .and_then(|c| c.value().map(|v| ("object-format", Some(Cow::Owned(v.to_string())))))
+}
+
 #[cfg(test)]
 mod tests;
@@ -115,6 +115,26 @@ mod v2 {
             }
         }
 
+        mod object_format {
+            use git_transport::client::Capabilities;
+
+            use crate::Command;
+
+            #[test]
+            fn the_object_format_advertised_by_the_server_is_echoed() {
+                let capabilities = Capabilities::from_lines("version 2\nls-refs\nobject-format=sha256".into())
+                    .expect("valid input for V2 capabilities");
+                let features = Command::LsRefs.default_features(git_transport::Protocol::V2, &capabilities);
+                assert_eq!(features, &[("object-format", Some("sha256".into()))]);
+                Command::LsRefs.validate_argument_prefixes_or_panic(
+                    git_transport::Protocol::V2,
+                    &capabilities,
+                    &[],
+                    &features,
+                );
+            }
+        }
+
         mod validate {
             use bstr::ByteSlice;
 
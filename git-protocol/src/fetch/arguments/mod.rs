@@ -125,10 +125,20 @@ impl Arguments {
         }
     }
     /// Deepen the commit history in a relative instead of absolute fashion.
+    ///
+    /// Needs to be called before the first `want` is added.
     pub fn deepen_relative(&mut self) {
         debug_assert!(self.deepen_relative, "'deepen-relative' feature required");
         if self.deepen_relative {
-            self.args.push("deepen-relative".into());
+            match self.features_for_first_want.as_mut() {
+                // In V1, it's a capability which is sent along with the first want.
+                Some(features) => {
+                    if !features.iter().any(|f| f == "deepen-relative") {
+                        features.push("deepen-relative".into());
+                    }
+                }
+                None => self.args.push("deepen-relative".into()),
+            }
         }
     }
     /// Do not include commits reachable by the given `ref_path` when deepening the history.
@@ -153,7 +163,15 @@ impl Arguments {
     pub fn use_include_tag(&mut self) {
         debug_assert!(self.supports_include_tag, "'include-tag' feature required");
         if self.supports_include_tag {
-            self.args.push("include-tag".into());
+            match self.features_for_first_want.as_mut() {
+                // In V1, it's a capability which is sent along with the first want.
+                Some(features) => {
+                    if !features.iter().any(|f| f == "include-tag") {
+                        features.push("include-tag".into());
+                    }
+                }
+                None => self.args.push("include-tag".into()),
+            }
         }
     }
     fn prefixed(&mut self, prefix: &str, value: impl fmt::Display) {
@@ -180,6 +198,8 @@ impl Arguments {
                 supports_include_tag = has("include-tag");
                 let baked_features = features
                     .iter()
+                    // This one changes the meaning of 'deepen', so it's only sent if asked for.
+                    .filter(|(n, _)| *n != "deepen-relative")
                     .map(|(n, v)| match v {
                         Some(v) => format!("{}={}", n, v),
                         None => n.to_string(),
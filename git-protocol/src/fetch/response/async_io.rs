@@ -32,10 +32,26 @@ async fn parse_v2_section<T>(
 }
 
 impl Response {
-    /// Parse a response of the given `version` of the protocol from `reader`.
+    /// Parse a response of the given `version` of the protocol from `reader`, assuming the client sent `done` and expects a pack.
+    ///
+    /// Use [`from_line_reader_in_round()`][Response::from_line_reader_in_round()] to parse the response to a negotiation round.
     pub async fn from_line_reader(
         version: Protocol,
         reader: &mut (impl client::ExtendedBufRead + Unpin),
+    ) -> Result<Response, response::Error> {
+        Self::from_line_reader_in_round(version, reader, true).await
+    }
+
+    /// Parse a response of the given `version` of the protocol from `reader`.
+    ///
+    /// `client_expects_pack` is only relevant for V1 stateful connections, and if `false`, causes us to stop parsing when seeing `NAK`,
+    /// and if `true` we will keep parsing until we get a pack as the client already signalled to the server that it's done.
+    /// This way of doing things allows us to exploit knowledge about more recent versions of the protocol, which keeps code easier
+    /// and more localized without having to support all the cruft that there is.
+    pub async fn from_line_reader_in_round(
+        version: Protocol,
+        reader: &mut (impl client::ExtendedBufRead + Unpin),
+        client_expects_pack: bool,
     ) -> Result<Response, response::Error> {
         match version {
             Protocol::V1 => {
@@ -77,6 +93,10 @@ impl Response {
                         break 'lines true;
                     }
                     assert_ne!(reader.read_line(&mut line).await?, 0, "consuming a peeked line works");
+                    // Without 'done', the server concludes each round with a NAK and waits for more 'have's.
+                    if !client_expects_pack && matches!(acks.last(), Some(Acknowledgement::Nak)) {
+                        break 'lines false;
+                    }
                 };
                 Ok(Response {
                     acks,
@@ -172,13 +172,56 @@ mod arguments {
             assert_eq!(
                 out.as_bstr(),
                 b"0048want ff333369de1221f9bfbbe03a3a13e9a09bc1ffff include-tag feature-b
-0010include-tag
 00000009done
 "
                 .as_bstr()
             );
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn include_tag_is_a_capability_of_the_first_want_only() {
+            let mut out = Vec::new();
+            let mut t = transport(&mut out, false);
+            let mut arguments = arguments_v1(["include-tag"].iter().cloned());
+
+            arguments.use_include_tag();
+            arguments.use_include_tag();
+            arguments.want(id("7b333369de1221f9bfbbe03a3a13e9a09bc1c907"));
+            arguments.want(id("ff333369de1221f9bfbbe03a3a13e9a09bc1ffff"));
+            arguments.send(&mut t, true).await.expect("sending to buffer to work");
+            assert_eq!(
+                out.as_bstr(),
+                b"003ewant 7b333369de1221f9bfbbe03a3a13e9a09bc1c907 include-tag
+0032want ff333369de1221f9bfbbe03a3a13e9a09bc1ffff
+00000009done
+"
+                .as_bstr(),
+                "it's never sent as argument of its own, which V1 servers don't understand"
+            );
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn deepen_relative() {
+            let mut out = Vec::new();
+            let mut t = transport(&mut out, true);
+            let mut arguments = arguments_v1(["shallow", "deepen-relative", "feature-b"].iter().cloned());
+            assert!(arguments.can_use_deepen_relative());
+
+            arguments.deepen(2);
+            arguments.deepen_relative();
+            arguments.want(id("ff333369de1221f9bfbbe03a3a13e9a09bc1ffff"));
+            arguments.send(&mut t, true).await.expect("sending to buffer to work");
+            assert_eq!(
+                out.as_bstr(),
+                b"0054want ff333369de1221f9bfbbe03a3a13e9a09bc1ffff shallow feature-b deepen-relative
+000ddeepen 2
+00000009done
+"
+                .as_bstr(),
+                "'deepen-relative' is a capability in V1 and only sent if requested, as it changes the meaning of 'deepen'"
+            );
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn haves_and_wants_for_clone() {
             let mut out = Vec::new();
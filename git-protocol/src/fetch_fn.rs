@@ -130,7 +130,8 @@ where
         if sideband_all {
             setup_remote_progress(&mut progress, &mut reader);
         }
-        let response = Response::from_line_reader(protocol_version, &mut reader).await?;
+        let response =
+            Response::from_line_reader_in_round(protocol_version, &mut reader, action == Action::Cancel).await?;
         previous_response = if response.has_pack() {
             progress.step();
             progress.set_name("receiving pack");
@@ -154,8 +155,10 @@ where
     Ok(())
 }
 
-fn setup_remote_progress<P>(progress: &mut P, reader: &mut Box<dyn git_transport::client::ExtendedBufRead + Unpin + '_>)
-where
+pub(crate) fn setup_remote_progress<P>(
+    progress: &mut P,
+    reader: &mut Box<dyn git_transport::client::ExtendedBufRead + Unpin + '_>,
+) where
     P: Progress,
     P::SubProgress: 'static,
 {
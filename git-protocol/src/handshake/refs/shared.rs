@@ -121,6 +121,10 @@ pub(in crate::handshake::refs) fn parse_v1(
     if path.is_empty() {
         return Err(Error::MalformedV1RefLine(trimmed.to_owned().into()));
     }
+    // Repositories without any ref advertise their capabilities on a line with a null id, as done by `git-receive-pack`.
+    if path == b"capabilities^{}" {
+        return Ok(());
+    }
     match path.strip_suffix(b"^{}") {
         Some(stripped) => {
             let (previous_path, tag) =
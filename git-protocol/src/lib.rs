@@ -1,7 +1,7 @@
-//! An abstraction over [fetching][fetch()] a pack from the server.
+//! An abstraction over [fetching][fetch()] a pack from the server, and [pushing][push()] a pack to it.
 //!
-//! This implementation hides the transport layer, statefulness and the protocol version to the [fetch delegate][fetch::Delegate],
-//! the actual client implementation.
+//! This implementation hides the transport layer, statefulness and the protocol version to the [fetch delegate][fetch::Delegate]
+//! and the [push delegate][push::Delegate], the actual client implementations.
 //! ## Feature Flags
 #![cfg_attr(
     feature = "document-features",
@@ -40,6 +40,15 @@ mod fetch_fn;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use fetch_fn::{fetch, FetchConnection};
 
+///
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub mod push;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+mod push_fn;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use push_fn::push;
+
 mod remote_progress;
 pub use remote_progress::RemoteProgress;
 
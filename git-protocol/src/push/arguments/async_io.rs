@@ -0,0 +1,37 @@
+use futures_io::AsyncWrite;
+use futures_lite::io::AsyncWriteExt;
+use git_transport::client;
+
+use crate::push::Arguments;
+
+impl Arguments {
+    /// Send all ref updates and push options to the server and return a writer for the pack, along with the reader
+    /// for obtaining the server response once the pack was written and the writer was dropped.
+    ///
+    /// Note that no pack must be written if [`needs_pack()`][Arguments::needs_pack()] is false.
+    #[allow(clippy::type_complexity)]
+    pub async fn send<'a, T: client::Transport + 'a>(
+        &self,
+        transport: &'a mut T,
+    ) -> Result<
+        (
+            Box<dyn AsyncWrite + Unpin + 'a>,
+            Box<dyn client::ExtendedBufRead + Unpin + 'a>,
+        ),
+        client::Error,
+    > {
+        assert!(!self.is_empty(), "Must not send a push request without any update");
+        let mut line_writer = transport.request(client::WriteMode::Binary, client::MessageKind::Flush)?;
+        for line in self.command_lines() {
+            line_writer.write_all(&line).await?;
+        }
+        line_writer.write_message(client::MessageKind::Flush).await?;
+        if !self.push_options.is_empty() {
+            for option in self.push_options() {
+                line_writer.write_all(option).await?;
+            }
+            line_writer.write_message(client::MessageKind::Flush).await?;
+        }
+        Ok(line_writer.into_parts())
+    }
+}
@@ -0,0 +1,155 @@
+use bstr::{BStr, BString, ByteVec};
+use git_transport::client::Capabilities;
+
+use crate::{command::Feature, push::Update};
+
+/// The arguments passed to the server as part of a `push` operation, containing the ref updates to perform along with
+/// all capabilities and options that affect how they are applied.
+#[derive(Debug)]
+pub struct Arguments {
+    features: Vec<Feature>,
+    updates: Vec<Update>,
+    push_options: Vec<BString>,
+
+    atomic: bool,
+    supports_atomic: bool,
+    supports_push_options: bool,
+    supports_delete_refs: bool,
+    sideband: bool,
+    report_status: bool,
+}
+
+impl Arguments {
+    /// Create a new instance to help setting up the ref updates to send to a server with `server` capabilities as part of
+    /// a `push` operation. `agent` is the `(agent, Some(name))` feature to send to the server, as typically obtained from
+    /// a user agent configuration.
+    ///
+    /// The most capable way of reporting status and progress are chosen automatically.
+    pub fn new(server: &Capabilities, agent: Option<Feature>) -> Self {
+        let mut features = Vec::new();
+        let report_status = if server.contains("report-status-v2") {
+            features.push(("report-status-v2", None));
+            true
+        } else if server.contains("report-status") {
+            features.push(("report-status", None));
+            true
+        } else {
+            false
+        };
+        let sideband = server.contains("side-band-64k");
+        if sideband {
+            features.push(("side-band-64k", None));
+        }
+        if server.contains("ofs-delta") {
+            features.push(("ofs-delta", None));
+        }
+        features.extend(crate::command::object_format(server));
+        features.extend(agent);
+        Arguments {
+            features,
+            updates: Vec::new(),
+            push_options: Vec::new(),
+            atomic: false,
+            supports_atomic: server.contains("atomic"),
+            supports_push_options: server.contains("push-options"),
+            supports_delete_refs: server.contains("delete-refs"),
+            sideband,
+            report_status,
+        }
+    }
+
+    /// Return true if there is no ref update to send.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+    /// Return true if the server can apply all updates atomically, so either all of them succeed or none of them.
+    pub fn can_use_atomic(&self) -> bool {
+        self.supports_atomic
+    }
+    /// Return true if the server accepts push options to pass to its hooks.
+    pub fn can_use_push_options(&self) -> bool {
+        self.supports_push_options
+    }
+    /// Return true if the server allows to delete references.
+    pub fn can_delete_refs(&self) -> bool {
+        self.supports_delete_refs
+    }
+    /// Return true if the server will send a report about the status of each ref update after receiving the pack.
+    pub fn has_report_status(&self) -> bool {
+        self.report_status
+    }
+    /// Return true if the server will use side-bands to send its report, along with progress information.
+    pub fn has_sideband(&self) -> bool {
+        self.sideband
+    }
+    /// Return all updates previously added with [`update()`][Self::update()].
+    pub fn updates(&self) -> &[Update] {
+        &self.updates
+    }
+    /// Return true if a pack must be sent to the server, which is the case unless all updates delete references.
+    pub fn needs_pack(&self) -> bool {
+        self.updates.iter().any(|u| !u.new_id.is_null())
+    }
+
+    /// Add the given `update` to the list of updates to send.
+    ///
+    /// Note that the server will reject deletions unless [`can_delete_refs()`][Self::can_delete_refs()] is true.
+    pub fn update(&mut self, update: Update) {
+        debug_assert!(
+            self.supports_delete_refs || !update.new_id.is_null(),
+            "'delete-refs' feature required to delete references"
+        );
+        self.updates.push(update);
+    }
+    /// Ask the server to apply all updates atomically, such that either all of them succeed or none of them.
+    ///
+    /// Needs to only be called once.
+    pub fn use_atomic(&mut self) {
+        debug_assert!(self.supports_atomic, "'atomic' feature required");
+        if self.supports_atomic && !self.atomic {
+            self.atomic = true;
+            self.features.push(("atomic", None));
+        }
+    }
+    /// Send `option` to the server which passes it to its `pre-receive` and `post-receive` hooks.
+    pub fn push_option(&mut self, option: impl Into<BString>) {
+        debug_assert!(self.supports_push_options, "'push-options' feature required");
+        if self.supports_push_options {
+            if self.push_options.is_empty() {
+                self.features.push(("push-options", None));
+            }
+            self.push_options.push(option.into());
+        }
+    }
+
+    /// Produce one line per update in the format the server expects, with the first line carrying all features.
+    pub(crate) fn command_lines(&self) -> impl Iterator<Item = BString> + '_ {
+        self.updates.iter().enumerate().map(move |(idx, update)| {
+            let mut line = BString::from(format!("{} {} ", update.old_id, update.new_id));
+            line.push_str(&update.ref_name);
+            if idx == 0 {
+                line.push(0);
+                line.push_str(
+                    self.features
+                        .iter()
+                        .map(|(n, v)| match v {
+                            Some(v) => format!("{}={}", n, v),
+                            None => n.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+            }
+            line
+        })
+    }
+
+    pub(crate) fn push_options(&self) -> impl Iterator<Item = &BStr> {
+        self.push_options.iter().map(|o| o.as_ref())
+    }
+}
+
+#[cfg(feature = "async-client")]
+mod async_io;
+#[cfg(feature = "blocking-client")]
+mod blocking_io;
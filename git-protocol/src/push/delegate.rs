@@ -0,0 +1,152 @@
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+};
+
+use git_transport::client::Capabilities;
+
+use crate::{handshake::Ref, push::Arguments};
+
+/// Defines what to do next after certain [`Delegate`] operations.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Action {
+    /// Continue the typical flow of operations in this flow.
+    Continue,
+    /// Return at the next possible opportunity without making further requests, possibly after closing the connection.
+    Cancel,
+}
+
+/// The non-IO protocol delegate is the bare minimal interface needed to fully control the [`push`][crate::push()] operation, sparing
+/// the IO parts.
+/// Async implementations must treat it as blocking and unblock it by evaluating it elsewhere.
+///
+/// See [Delegate] for the complete trait.
+pub trait DelegateBlocking {
+    /// Return extra parameters to be provided during the handshake.
+    ///
+    /// Note that this method is only called once and the result is reused during subsequent handshakes which may happen
+    /// if there is an authentication failure.
+    fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
+        Vec::new()
+    }
+
+    /// Called after the handshake with all `refs` the server advertised and the `server` capabilities, to populate
+    /// `arguments` with the ref updates to perform, along with push options or the request to perform the push atomically.
+    ///
+    /// Note that the `arguments` know which capabilities are supported by the server to allow adjusting the request accordingly.
+    ///
+    /// Return `Action::Cancel` to end the interaction with the server without performing any update, which is also what
+    /// happens if no update was added to `arguments`.
+    fn prepare_push(&mut self, server: &Capabilities, refs: &[Ref], arguments: &mut Arguments) -> io::Result<Action>;
+}
+
+impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
+    fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
+        self.deref().handshake_extra_parameters()
+    }
+
+    fn prepare_push(&mut self, server: &Capabilities, refs: &[Ref], arguments: &mut Arguments) -> io::Result<Action> {
+        self.deref_mut().prepare_push(server, refs, arguments)
+    }
+}
+
+impl<T: DelegateBlocking> DelegateBlocking for &mut T {
+    fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
+        self.deref().handshake_extra_parameters()
+    }
+
+    fn prepare_push(&mut self, server: &Capabilities, refs: &[Ref], arguments: &mut Arguments) -> io::Result<Action> {
+        self.deref_mut().prepare_push(server, refs, arguments)
+    }
+}
+
+#[cfg(feature = "blocking-client")]
+mod blocking_io {
+    use std::{io, ops::DerefMut};
+
+    use git_features::progress::Progress;
+
+    use crate::push::{DelegateBlocking, Update};
+
+    /// The protocol delegate is the bare minimal interface needed to fully control the [`push`][crate::push()] operation.
+    ///
+    /// Implementations of this trait are controlled by code with intricate knowledge about how pushing works,
+    /// so you don't have to.
+    pub trait Delegate: DelegateBlocking {
+        /// Write a pack with all objects needed by the server to perform all `updates` to `output`.
+        ///
+        /// This is only called if at least one of the `updates` isn't a deletion.
+        /// Use `progress` to emit your own progress messages while creating the pack.
+        fn send_pack(&mut self, output: impl io::Write, progress: impl Progress, updates: &[Update]) -> io::Result<()>;
+    }
+
+    impl<T: Delegate> Delegate for Box<T> {
+        fn send_pack(&mut self, output: impl io::Write, progress: impl Progress, updates: &[Update]) -> io::Result<()> {
+            self.deref_mut().send_pack(output, progress, updates)
+        }
+    }
+
+    impl<T: Delegate> Delegate for &mut T {
+        fn send_pack(&mut self, output: impl io::Write, progress: impl Progress, updates: &[Update]) -> io::Result<()> {
+            self.deref_mut().send_pack(output, progress, updates)
+        }
+    }
+}
+#[cfg(feature = "blocking-client")]
+pub use blocking_io::Delegate;
+
+#[cfg(feature = "async-client")]
+mod async_io {
+    use std::{io, ops::DerefMut};
+
+    use async_trait::async_trait;
+    use futures_io::AsyncWrite;
+    use git_features::progress::Progress;
+
+    use crate::push::{DelegateBlocking, Update};
+
+    /// The protocol delegate is the bare minimal interface needed to fully control the [`push`][crate::push()] operation.
+    ///
+    /// Implementations of this trait are controlled by code with intricate knowledge about how pushing works,
+    /// so you don't have to.
+    #[async_trait(?Send)]
+    pub trait Delegate: DelegateBlocking {
+        /// Write a pack with all objects needed by the server to perform all `updates` to `output`.
+        /// The caller should consider it to be blocking as most operations to create a pack are implemented in a blocking fashion.
+        ///
+        /// This is only called if at least one of the `updates` isn't a deletion.
+        /// Use `progress` to emit your own progress messages while creating the pack.
+        async fn send_pack(
+            &mut self,
+            output: impl AsyncWrite + Unpin + 'async_trait,
+            progress: impl Progress,
+            updates: &[Update],
+        ) -> io::Result<()>;
+    }
+
+    #[async_trait(?Send)]
+    impl<T: Delegate> Delegate for Box<T> {
+        async fn send_pack(
+            &mut self,
+            output: impl AsyncWrite + Unpin + 'async_trait,
+            progress: impl Progress,
+            updates: &[Update],
+        ) -> io::Result<()> {
+            self.deref_mut().send_pack(output, progress, updates).await
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl<T: Delegate> Delegate for &mut T {
+        async fn send_pack(
+            &mut self,
+            output: impl AsyncWrite + Unpin + 'async_trait,
+            progress: impl Progress,
+            updates: &[Update],
+        ) -> io::Result<()> {
+            self.deref_mut().send_pack(output, progress, updates).await
+        }
+    }
+}
+#[cfg(feature = "async-client")]
+pub use async_io::Delegate;
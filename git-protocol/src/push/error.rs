@@ -0,0 +1,33 @@
+use std::io;
+
+use git_transport::client;
+
+use crate::{handshake, push::response};
+
+/// The error used in [`push()`][crate::push()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Handshake(#[from] handshake::Error),
+    #[error("Could not access repository or failed to write the pack")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Transport(#[from] client::Error),
+    #[error(transparent)]
+    Response(#[from] response::Error),
+    #[error("The server responded with protocol {actual:?}, but pushing is only possible with the V1 protocol")]
+    UnsupportedProtocolVersion { actual: git_transport::Protocol },
+}
+
+impl git_transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) => err.is_spurious(),
+            Error::Transport(err) => err.is_spurious(),
+            Error::Handshake(err) => err.is_spurious(),
+            Error::Response(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
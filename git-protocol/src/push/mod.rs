@@ -0,0 +1,36 @@
+use bstr::BString;
+
+/// A single ref update to request from the server, which can create, update or delete a ref on the remote.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+    /// The full name of the ref to update on the remote, like `refs/heads/main`.
+    pub ref_name: BString,
+    /// The id the ref is expected to have on the remote before the update, or the null id if it should be created.
+    ///
+    /// The server will reject the update if the ref doesn't point to this id anymore.
+    pub old_id: git_hash::ObjectId,
+    /// The id the ref should point to after the update, or the null id if it should be deleted.
+    pub new_id: git_hash::ObjectId,
+}
+
+mod arguments;
+pub use arguments::Arguments;
+
+///
+pub mod delegate;
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+pub use delegate::Delegate;
+pub use delegate::{Action, DelegateBlocking};
+
+mod error;
+pub use error::Error;
+///
+pub mod response;
+pub use response::Response;
+
+mod handshake;
+pub use handshake::receive_pack as handshake;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,32 @@
+use bstr::{BString, ByteSlice};
+use futures_lite::io::AsyncReadExt;
+use git_transport::client;
+
+use crate::push::{
+    response::{data_lines, Error},
+    Response,
+};
+
+impl Response {
+    /// Read the report of the server from `reader`, which either sends it as is or within the data band if `sideband` is true.
+    ///
+    /// Note that a progress handler should be set on `reader` beforehand if `sideband` is true.
+    pub async fn from_line_reader(
+        reader: &mut (impl client::ExtendedBufRead + Unpin),
+        sideband: bool,
+    ) -> Result<Response, Error> {
+        if sideband {
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).await?;
+            Response::from_lines(data_lines(&buf)?)
+        } else {
+            let mut lines = Vec::<BString>::new();
+            while let Some(line) = reader.readline().await {
+                if let Some(line) = line??.as_bstr() {
+                    lines.push(line.into());
+                }
+            }
+            Response::from_lines(lines.iter().map(|l| l.as_bstr()))
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned in the [response module][crate::push::response].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to read from line reader")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Transport(#[from] git_transport::client::Error),
+    #[error(transparent)]
+    DecodePacketline(#[from] git_transport::packetline::decode::Error),
+    #[error("The report did not start with an 'unpack' line")]
+    MissingUnpackStatus,
+    #[error("Encountered an unknown line prefix in {line:?}")]
+    UnknownLineType { line: BString },
+    #[error("Option line {line:?} appeared before the status of any reference")]
+    OptionWithoutRef { line: BString },
+    #[error("Could not decode object id in option line {line:?}")]
+    InvalidObjectId { line: BString },
+}
+
+impl git_transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) => err.is_spurious(),
+            Error::Transport(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// The result of the server unpacking the pack we sent.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unpack {
+    /// The pack was received and unpacked successfully.
+    Ok,
+    /// The pack could not be unpacked, which causes all ref updates to fail.
+    Failed {
+        /// The reason for the failure as provided by the server.
+        reason: BString,
+    },
+}
+
+/// The outcome of a single ref update as reported by the server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Status {
+    /// The ref was updated successfully.
+    Ok,
+    /// The ref was not updated.
+    Rejected {
+        /// The reason for the rejection, like `non-fast-forward` or `atomic push failed`.
+        reason: BString,
+    },
+}
+
+/// Additional information about how a ref update was performed, as provided with `report-status-v2` only.
+///
+/// It is sent if a hook on the server side altered the update, or if the update was forced.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefOptions {
+    /// The name of the ref that was actually updated, if it differs from the one we sent.
+    pub ref_name: Option<BString>,
+    /// The id the ref had before the update, as seen by the server.
+    pub old_id: Option<git_hash::ObjectId>,
+    /// The id the ref has after the update, as seen by the server.
+    pub new_id: Option<git_hash::ObjectId>,
+    /// If true, the update was not a fast-forward.
+    pub forced_update: bool,
+}
+
+/// The status of a single ref update as reported by the server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefStatus {
+    /// The name of the ref as sent to the server, like `refs/heads/main`.
+    pub ref_name: BString,
+    /// Whether the update succeeded or why it didn't.
+    pub status: Status,
+    /// Information about how the update was performed, with one entry per `option` line which is only
+    /// present if `report-status-v2` is used.
+    ///
+    /// Note that in theory, servers may report multiple updates for a single ref, which is why there can be more than one.
+    pub options: Vec<RefOptions>,
+}
+
+/// A `report-status` or `report-status-v2` response received from the server after sending a pack.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Response {
+    unpack: Unpack,
+    refs: Vec<RefStatus>,
+}
+
+impl Response {
+    /// Parse the report from all of its `lines`, without packet line framing but with or without trailing newline.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, Error> {
+        let mut lines = lines.into_iter().map(|l| l.trim_end_with(|c| c == '\n').as_bstr());
+        let unpack = match lines.next().and_then(|l| l.strip_prefix(b"unpack ")) {
+            Some(status) if status == b"ok" => Unpack::Ok,
+            Some(reason) => Unpack::Failed { reason: reason.into() },
+            None => return Err(Error::MissingUnpackStatus),
+        };
+        let mut refs = Vec::<RefStatus>::new();
+        for line in lines {
+            let (kind, rest) = line
+                .split_once_str(b" ")
+                .ok_or_else(|| Error::UnknownLineType { line: line.into() })?;
+            match kind {
+                b"ok" => refs.push(RefStatus {
+                    ref_name: rest.into(),
+                    status: Status::Ok,
+                    options: Vec::new(),
+                }),
+                b"ng" => {
+                    let (ref_name, reason) = rest.split_once_str(b" ").unwrap_or((rest, b"".as_slice()));
+                    refs.push(RefStatus {
+                        ref_name: ref_name.into(),
+                        status: Status::Rejected { reason: reason.into() },
+                        options: Vec::new(),
+                    })
+                }
+                b"option" => {
+                    let last = refs
+                        .last_mut()
+                        .ok_or_else(|| Error::OptionWithoutRef { line: line.into() })?;
+                    let (key, value) = match rest.split_once_str(b" ") {
+                        Some((key, value)) => (key, Some(value)),
+                        None => (rest, None),
+                    };
+                    let parse_id = |value: Option<&[u8]>| {
+                        value
+                            .and_then(|v| git_hash::ObjectId::from_hex(v).ok())
+                            .ok_or_else(|| Error::InvalidObjectId { line: line.into() })
+                    };
+                    // Each 'refname' option starts a new set of options, otherwise they are accumulated.
+                    if key == b"refname" || last.options.is_empty() {
+                        last.options.push(RefOptions::default());
+                    }
+                    let options = last.options.last_mut().expect("just pushed or present");
+                    match key {
+                        b"refname" => options.ref_name = value.map(Into::into),
+                        b"old-oid" => options.old_id = Some(parse_id(value)?),
+                        b"new-oid" => options.new_id = Some(parse_id(value)?),
+                        b"forced-update" => options.forced_update = true,
+                        _ => {
+                            // ignore unknown options for forward compatibility, like git does.
+                        }
+                    }
+                }
+                _ => return Err(Error::UnknownLineType { line: line.into() }),
+            }
+        }
+        Ok(Response { unpack, refs })
+    }
+
+    /// Return the result of unpacking the pack on the server side.
+    pub fn unpack(&self) -> &Unpack {
+        &self.unpack
+    }
+
+    /// Return the status of all ref updates in the order they were reported.
+    pub fn refs(&self) -> &[RefStatus] {
+        &self.refs
+    }
+
+    /// Return true if the pack was unpacked and all refs were updated successfully.
+    pub fn is_success(&self) -> bool {
+        self.unpack == Unpack::Ok && self.refs.iter().all(|r| r.status == Status::Ok)
+    }
+}
+
+/// Split `data` that consists of packet lines into their data portion, stopping at the first flush packet.
+fn data_lines(mut data: &[u8]) -> Result<Vec<&BStr>, Error> {
+    use git_transport::packetline::{decode, PacketLineRef};
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        match decode::streaming(data)? {
+            decode::Stream::Complete { line, bytes_consumed } => {
+                match line {
+                    PacketLineRef::Data(d) => out.push(d.as_bstr()),
+                    PacketLineRef::Flush => break,
+                    PacketLineRef::Delimiter | PacketLineRef::ResponseEnd => {}
+                }
+                data = &data[bytes_consumed..];
+            }
+            decode::Stream::Incomplete { bytes_needed } => {
+                return Err(decode::Error::NotEnoughData { bytes_needed }.into())
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "async-client")]
+mod async_io;
+#[cfg(feature = "blocking-client")]
+mod blocking_io;
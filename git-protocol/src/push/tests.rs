@@ -0,0 +1,253 @@
+mod response {
+    use bstr::ByteSlice;
+
+    use crate::push::{
+        response::{Error, RefOptions, RefStatus, Status, Unpack},
+        Response,
+    };
+
+    fn id(hex: &str) -> git_hash::ObjectId {
+        git_hash::ObjectId::from_hex(hex.as_bytes()).expect("expect valid hex id")
+    }
+
+    #[test]
+    fn report_status_with_accepted_and_rejected_refs() {
+        let res = Response::from_lines(
+            [
+                "unpack ok\n",
+                "ok refs/heads/main\n",
+                "ng refs/heads/feature non-fast-forward\n",
+                "ng refs/tags/v1",
+            ]
+            .iter()
+            .map(|l| l.as_bytes().as_bstr()),
+        )
+        .expect("valid report");
+        assert_eq!(res.unpack(), &Unpack::Ok);
+        assert!(!res.is_success(), "one ref was rejected");
+        assert_eq!(
+            res.refs(),
+            &[
+                RefStatus {
+                    ref_name: "refs/heads/main".into(),
+                    status: Status::Ok,
+                    options: vec![]
+                },
+                RefStatus {
+                    ref_name: "refs/heads/feature".into(),
+                    status: Status::Rejected {
+                        reason: "non-fast-forward".into()
+                    },
+                    options: vec![]
+                },
+                RefStatus {
+                    ref_name: "refs/tags/v1".into(),
+                    status: Status::Rejected { reason: "".into() },
+                    options: vec![]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn report_status_v2_with_options() {
+        let res = Response::from_lines(
+            [
+                "unpack ok",
+                "ok refs/heads/main",
+                "option old-oid 7b333369de1221f9bfbbe03a3a13e9a09bc1c907",
+                "option new-oid ff333369de1221f9bfbbe03a3a13e9a09bc1ffff",
+                "option forced-update",
+                "ok refs/for/main",
+                "option refname refs/changes/1",
+                "option new-oid ff333369de1221f9bfbbe03a3a13e9a09bc1ffff",
+                "option refname refs/changes/2",
+                "option unknown-option-is-ignored",
+            ]
+            .iter()
+            .map(|l| l.as_bytes().as_bstr()),
+        )
+        .expect("valid report");
+        assert!(res.is_success());
+        assert_eq!(
+            res.refs(),
+            &[
+                RefStatus {
+                    ref_name: "refs/heads/main".into(),
+                    status: Status::Ok,
+                    options: vec![RefOptions {
+                        ref_name: None,
+                        old_id: Some(id("7b333369de1221f9bfbbe03a3a13e9a09bc1c907")),
+                        new_id: Some(id("ff333369de1221f9bfbbe03a3a13e9a09bc1ffff")),
+                        forced_update: true
+                    }]
+                },
+                RefStatus {
+                    ref_name: "refs/for/main".into(),
+                    status: Status::Ok,
+                    options: vec![
+                        RefOptions {
+                            ref_name: Some("refs/changes/1".into()),
+                            new_id: Some(id("ff333369de1221f9bfbbe03a3a13e9a09bc1ffff")),
+                            ..Default::default()
+                        },
+                        RefOptions {
+                            ref_name: Some("refs/changes/2".into()),
+                            ..Default::default()
+                        }
+                    ]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn unpack_failure() {
+        let res = Response::from_lines(
+            ["unpack index-pack abnormal exit", "ng refs/heads/main unpacker error"]
+                .iter()
+                .map(|l| l.as_bytes().as_bstr()),
+        )
+        .expect("valid report");
+        assert_eq!(
+            res.unpack(),
+            &Unpack::Failed {
+                reason: "index-pack abnormal exit".into()
+            }
+        );
+        assert!(!res.is_success());
+    }
+
+    #[test]
+    fn invalid_reports() {
+        assert!(matches!(
+            Response::from_lines(["ok refs/heads/main".as_bytes().as_bstr()]),
+            Err(Error::MissingUnpackStatus)
+        ));
+        assert!(matches!(
+            Response::from_lines(
+                ["unpack ok", "option forced-update"]
+                    .iter()
+                    .map(|l| l.as_bytes().as_bstr())
+            ),
+            Err(Error::OptionWithoutRef { .. })
+        ));
+        assert!(matches!(
+            Response::from_lines(
+                ["unpack ok", "what refs/heads/main"]
+                    .iter()
+                    .map(|l| l.as_bytes().as_bstr())
+            ),
+            Err(Error::UnknownLineType { .. })
+        ));
+        assert!(matches!(
+            Response::from_lines(
+                ["unpack ok", "ok refs/heads/main", "option old-oid foo"]
+                    .iter()
+                    .map(|l| l.as_bytes().as_bstr())
+            ),
+            Err(Error::InvalidObjectId { .. })
+        ));
+    }
+}
+
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+mod arguments {
+    use bstr::ByteSlice;
+    use git_transport::{client::Capabilities, Protocol};
+
+    use crate::push::{Arguments, Update};
+
+    fn arguments(capabilities: &[u8]) -> Arguments {
+        Arguments::new(
+            &Capabilities::from_bytes(capabilities).expect("valid capabilities").0,
+            Some(("agent", Some("git/oxide".into()))),
+        )
+    }
+
+    fn transport(out: &mut Vec<u8>) -> git_transport::client::git::Connection<&'static [u8], &mut Vec<u8>> {
+        git_transport::client::git::Connection::new(
+            &[],
+            out,
+            Protocol::V1,
+            b"does/not/matter".as_bstr().to_owned(),
+            None::<(&str, _)>,
+            git_transport::client::git::ConnectMode::Process, // avoid header to be sent
+        )
+    }
+
+    fn update(ref_name: &str, old: &str, new: &str) -> Update {
+        Update {
+            ref_name: ref_name.into(),
+            old_id: git_hash::ObjectId::from_hex(old.as_bytes()).expect("valid hex"),
+            new_id: git_hash::ObjectId::from_hex(new.as_bytes()).expect("valid hex"),
+        }
+    }
+
+    #[test]
+    fn best_report_status_is_chosen() {
+        let args = arguments(b"\0report-status report-status-v2 side-band-64k delete-refs");
+        assert!(args.has_report_status());
+        assert!(args.has_sideband());
+        assert!(args.can_delete_refs());
+        assert!(!args.can_use_atomic());
+        assert!(!args.can_use_push_options());
+
+        let args = arguments(b"\0atomic push-options");
+        assert!(!args.has_report_status());
+        assert!(!args.has_sideband());
+        assert!(!args.can_delete_refs());
+        assert!(args.can_use_atomic());
+        assert!(args.can_use_push_options());
+    }
+
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn updates_with_features_and_push_options() {
+        let mut out = Vec::new();
+        let mut t = transport(&mut out);
+        let mut args = arguments(b"\0report-status-v2 atomic push-options delete-refs ofs-delta");
+        args.update(update(
+            "refs/heads/main",
+            "7b333369de1221f9bfbbe03a3a13e9a09bc1c907",
+            "ff333369de1221f9bfbbe03a3a13e9a09bc1ffff",
+        ));
+        args.update(update(
+            "refs/heads/gone",
+            "ff333369de1221f9bfbbe03a3a13e9a09bc1ffff",
+            "0000000000000000000000000000000000000000",
+        ));
+        args.use_atomic();
+        args.push_option("ci.skip");
+        args.push_option("reviewer=me");
+        assert!(args.needs_pack());
+
+        args.send(&mut t).await.expect("sending to buffer to work");
+        drop(t);
+        assert_eq!(
+            out.as_bstr(),
+            b"00a47b333369de1221f9bfbbe03a3a13e9a09bc1c907 ff333369de1221f9bfbbe03a3a13e9a09bc1ffff refs/heads/main\0report-status-v2 ofs-delta agent=git/oxide atomic push-options0065ff333369de1221f9bfbbe03a3a13e9a09bc1ffff 0000000000000000000000000000000000000000 refs/heads/gone0000000bci.skip000freviewer=me0000"
+                .as_bstr()
+        );
+    }
+
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn deletions_only_need_no_pack() {
+        let mut out = Vec::new();
+        let mut t = transport(&mut out);
+        let mut args = arguments(b"\0report-status delete-refs");
+        args.update(update(
+            "refs/heads/gone",
+            "ff333369de1221f9bfbbe03a3a13e9a09bc1ffff",
+            "0000000000000000000000000000000000000000",
+        ));
+        assert!(!args.needs_pack());
+
+        args.send(&mut t).await.expect("sending to buffer to work");
+        drop(t);
+        assert_eq!(
+            out.as_bstr(),
+            b"0083ff333369de1221f9bfbbe03a3a13e9a09bc1ffff 0000000000000000000000000000000000000000 refs/heads/gone\0report-status agent=git/oxide0000"
+                .as_bstr()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use git_features::progress::Progress;
+use git_transport::client;
+use maybe_async::maybe_async;
+
+use crate::{
+    credentials,
+    push::{Action, Arguments, Delegate, Error, Response},
+};
+
+/// Perform a 'push' operation with the server using `transport`, with `delegate` deciding which refs to update and providing the pack.
+/// **Note** that `delegate` has blocking operations and thus this entire call should be on an executor which can handle
+/// that. This could be the current thread blocking, or another thread.
+///
+/// * `authenticate(operation_to_perform)` is used to receive credentials for the connection and potentially store it
+///   if the server indicates 'permission denied'. Note that not all transport support authentication or authorization.
+/// * `progress` is used to emit progress messages.
+/// * `agent` is the name of the git client to present as `agent`, like `"my-app (v2.0)"`.
+///
+/// Returns `None` if the `delegate` cancelled the operation, if there was no update to perform or if the server doesn't
+/// support reporting the status of the updates. Otherwise the report of the server is returned, which indicates
+/// for each ref whether or not it was updated.
+///
+/// _Note_ that the transport should be configured to use protocol V1, as `git-receive-pack` doesn't support V2.
+#[allow(clippy::result_large_err)]
+#[maybe_async]
+pub async fn push<F, D, T, P>(
+    mut transport: T,
+    mut delegate: D,
+    authenticate: F,
+    mut progress: P,
+    agent: impl Into<String>,
+) -> Result<Option<Response>, Error>
+where
+    F: FnMut(credentials::helper::Action) -> credentials::protocol::Result,
+    D: Delegate,
+    T: client::Transport,
+    P: Progress,
+    P::SubProgress: 'static,
+{
+    let crate::handshake::Outcome {
+        server_protocol_version,
+        refs,
+        capabilities,
+    } = crate::push::handshake(
+        &mut transport,
+        authenticate,
+        delegate.handshake_extra_parameters(),
+        &mut progress,
+    )
+    .await?;
+    let refs = match refs {
+        Some(refs) => refs,
+        None => {
+            return Err(Error::UnsupportedProtocolVersion {
+                actual: server_protocol_version,
+            })
+        }
+    };
+
+    let mut arguments = Arguments::new(&capabilities, Some(("agent", Some(crate::agent(agent).into()))));
+    match delegate.prepare_push(&capabilities, &refs, &mut arguments) {
+        Ok(Action::Continue) if !arguments.is_empty() => {}
+        Ok(_) => {
+            crate::indicate_end_of_interaction(transport).await?;
+            return Ok(None);
+        }
+        Err(err) => {
+            crate::indicate_end_of_interaction(transport).await?;
+            return Err(err.into());
+        }
+    }
+
+    progress.step();
+    progress.set_name("send updates");
+    let (mut writer, mut reader) = arguments.send(&mut transport).await?;
+    if arguments.needs_pack() {
+        progress.step();
+        progress.set_name("send pack");
+        delegate
+            .send_pack(&mut writer, progress.add_child("pack"), arguments.updates())
+            .await?;
+    }
+    drop(writer);
+
+    if !arguments.has_report_status() {
+        return Ok(None);
+    }
+    progress.step();
+    progress.set_name("receive report");
+    if arguments.has_sideband() {
+        crate::fetch_fn::setup_remote_progress(&mut progress, &mut reader);
+    }
+    Ok(Some(
+        Response::from_line_reader(&mut reader, arguments.has_sideband()).await?,
+    ))
+}
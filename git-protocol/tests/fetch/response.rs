@@ -72,7 +72,12 @@ mod v1 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands()).await?;
+            let r = fetch::Response::from_line_reader_in_round(
+                Protocol::V1,
+                &mut provider.as_read_without_sidebands(),
+                false,
+            )
+            .await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -198,7 +203,12 @@ mod v2 {
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v2/fetch-no-pack.response");
-            let r = fetch::Response::from_line_reader(Protocol::V2, &mut provider.as_read_without_sidebands()).await?;
+            let r = fetch::Response::from_line_reader_in_round(
+                Protocol::V2,
+                &mut provider.as_read_without_sidebands(),
+                false,
+            )
+            .await?;
             assert_eq!(r.acknowledgements(), &[Acknowledgement::Nak,]);
             Ok(())
         }
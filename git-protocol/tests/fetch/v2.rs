@@ -35,6 +35,7 @@ async fn clone_abort_prep() -> crate::Result {
         transport.into_inner().1.as_bstr(),
         format!(
             "002fgit-upload-pack does/not/matter\0\0version=2\00014command=ls-refs
+0017object-format=sha1
 0014agent={}
 0001000csymrefs
 0009peel
@@ -93,6 +94,7 @@ async fn ls_remote() -> crate::Result {
         transport.into_inner().1.as_bstr(),
         format!(
             "0044git-upload-pack does/not/matter\0\0version=2\0value-only\0key=value\00014command=ls-refs
+0017object-format=sha1
 0014agent={}
 0001000csymrefs
 0009peel
@@ -183,6 +185,7 @@ async fn ref_in_want() -> crate::Result {
         transport.into_inner().1.as_bstr(),
         format!(
             "002fgit-upload-pack does/not/matter\0\0version=2\00012command=fetch
+0017object-format=sha1
 0014agent={}
 0001000ethin-pack
 000eofs-delta
@@ -0,0 +1,162 @@
+use std::io;
+
+use bstr::ByteSlice;
+use git_features::progress;
+use git_protocol::{
+    handshake,
+    push::{self, response, Action, Arguments, Update},
+};
+use git_transport::{client::Capabilities, Protocol};
+
+use crate::fetch::{helper_unused, oid, transport};
+
+/// Updates `refs/heads/main` and deletes `refs/heads/gone`, writing a fake pack.
+#[derive(Default)]
+pub struct PushDelegate {
+    refs: Vec<handshake::Ref>,
+    updates_seen_when_sending_pack: Vec<Update>,
+    cancel: bool,
+}
+
+const PACK: &[u8] = b"PACK and more";
+
+impl push::DelegateBlocking for PushDelegate {
+    fn prepare_push(
+        &mut self,
+        _server: &Capabilities,
+        refs: &[handshake::Ref],
+        arguments: &mut Arguments,
+    ) -> io::Result<Action> {
+        self.refs = refs.to_owned();
+        if self.cancel {
+            return Ok(Action::Cancel);
+        }
+        arguments.update(Update {
+            ref_name: "refs/heads/main".into(),
+            old_id: oid("808e50d724f604f69ab93c6da2919c014667bedb"),
+            new_id: oid("7fe1b98b39423b71e14217aa299a03b7c937d656"),
+        });
+        arguments.update(Update {
+            ref_name: "refs/heads/gone".into(),
+            old_id: oid("7fe1b98b39423b71e14217aa299a03b7c937d656"),
+            new_id: git_hash::Kind::Sha1.null(),
+        });
+        arguments.use_atomic();
+        arguments.push_option("ci.skip");
+        Ok(Action::Continue)
+    }
+}
+
+#[cfg(feature = "blocking-client")]
+mod blocking_io {
+    use std::io;
+
+    use git_features::progress::Progress;
+    use git_protocol::push::{self, Update};
+
+    use crate::push::{PushDelegate, PACK};
+
+    impl push::Delegate for PushDelegate {
+        fn send_pack(
+            &mut self,
+            mut output: impl io::Write,
+            _progress: impl Progress,
+            updates: &[Update],
+        ) -> io::Result<()> {
+            self.updates_seen_when_sending_pack = updates.to_owned();
+            output.write_all(PACK)
+        }
+    }
+}
+
+#[cfg(feature = "async-client")]
+mod async_io {
+    use std::io;
+
+    use async_trait::async_trait;
+    use futures_io::AsyncWrite;
+    use futures_lite::AsyncWriteExt;
+    use git_features::progress::Progress;
+    use git_protocol::push::{self, Update};
+
+    use crate::push::{PushDelegate, PACK};
+
+    #[async_trait(?Send)]
+    impl push::Delegate for PushDelegate {
+        async fn send_pack(
+            &mut self,
+            mut output: impl AsyncWrite + Unpin + 'async_trait,
+            _progress: impl Progress,
+            updates: &[Update],
+        ) -> io::Result<()> {
+            self.updates_seen_when_sending_pack = updates.to_owned();
+            output.write_all(PACK).await
+        }
+    }
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn update_and_delete_with_report_in_sideband() -> crate::Result {
+    let mut delegate = PushDelegate::default();
+    let mut transport = transport(
+        Vec::new(),
+        "v1/push.response",
+        Protocol::V1,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let res = git_protocol::push(&mut transport, &mut delegate, helper_unused, progress::Discard, "agent")
+        .await?
+        .expect("a report was received");
+
+    assert_eq!(delegate.refs.len(), 2, "both refs were advertised");
+    assert_eq!(delegate.updates_seen_when_sending_pack.len(), 2);
+    assert_eq!(res.unpack(), &response::Unpack::Ok);
+    assert!(!res.is_success());
+    assert_eq!(
+        res.refs(),
+        &[
+            response::RefStatus {
+                ref_name: "refs/heads/main".into(),
+                status: response::Status::Ok,
+                options: vec![]
+            },
+            response::RefStatus {
+                ref_name: "refs/heads/gone".into(),
+                status: response::Status::Rejected {
+                    reason: "deletion prohibited".into()
+                },
+                options: vec![]
+            }
+        ]
+    );
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        b"0025git-receive-pack does/not/matter\x0000c5808e50d724f604f69ab93c6da2919c014667bedb 7fe1b98b39423b71e14217aa299a03b7c937d656 refs/heads/main\x00report-status-v2 side-band-64k ofs-delta object-format=sha1 agent=git/agent atomic push-options00657fe1b98b39423b71e14217aa299a03b7c937d656 0000000000000000000000000000000000000000 refs/heads/gone0000000bci.skip0000PACK and more"
+            .as_bstr()
+    );
+    Ok(())
+}
+
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn cancel_after_handshake() -> crate::Result {
+    let mut delegate = PushDelegate {
+        cancel: true,
+        ..Default::default()
+    };
+    let mut transport = transport(
+        Vec::new(),
+        "v1/push.response",
+        Protocol::V1,
+        git_transport::client::git::ConnectMode::Daemon,
+    );
+    let res = git_protocol::push(&mut transport, &mut delegate, helper_unused, progress::Discard, "agent").await?;
+    assert!(res.is_none(), "there is no report if nothing was pushed");
+    assert_eq!(delegate.refs.len(), 2);
+    assert!(delegate.updates_seen_when_sending_pack.is_empty());
+    assert_eq!(
+        transport.into_inner().1.as_bstr(),
+        b"0025git-receive-pack does/not/matter\x000000".as_bstr(),
+        "the connection is closed with a flush"
+    );
+    Ok(())
+}
@@ -125,7 +125,7 @@ pub struct PartialNameCow<'a>(Cow<'a, BStr>);
 pub struct PartialNameRef(BStr);
 
 /// A validated complete and fully qualified owned reference name, safe to use for all operations.
-#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd)]
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct PartialName(BString);
 
 /// A _validated_ prefix for references to act as a namespace.
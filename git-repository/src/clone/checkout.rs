@@ -4,7 +4,7 @@ use crate::{clone::PrepareCheckout, Repository};
 pub mod main_worktree {
     use std::{path::PathBuf, sync::atomic::AtomicBool};
 
-    use git_odb::FindExt;
+    use git_odb::{FindExt, Write};
 
     use crate::{clone::PrepareCheckout, Progress, Repository};
 
@@ -25,10 +25,11 @@ pub mod main_worktree {
         WriteIndex(#[from] git_index::file::write::Error),
         #[error(transparent)]
         CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error("Could not collapse directories outside of the sparse checkout cone")]
+        CollapseSparseDirectories(#[from] git_index::extension::tree::update::Error),
         #[error(transparent)]
         IndexCheckout(
-            #[from]
-            git_worktree::index::checkout::Error<git_odb::find::existing_object::Error<git_odb::store::find::Error>>,
+            #[from] git_worktree::index::checkout::Error<git_odb::find::existing::Error<git_odb::store::find::Error>>,
         ),
         #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
         OpenArcOdb(#[from] std::io::Error),
@@ -36,6 +37,9 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[cfg(feature = "blocking-network-client")]
+        #[error(transparent)]
+        Submodules(#[from] super::submodules::Error),
     }
 
     /// Modification
@@ -45,6 +49,11 @@ pub mod main_worktree {
         ///
         /// Note that this is a no-op if the remote was empty, leaving this repository empty as well. This can be validated by checking
         /// if the `head()` of the returned repository is not unborn.
+        ///
+        /// If `core.sparseCheckout` is enabled, only the entries included by the [sparse checkout patterns][Repository::sparse_checkout_patterns()]
+        /// are checked out, and with `index.sparse` all directories outside of the cone are stored as sparse directory entries.
+        ///
+        /// Submodules are checked out as empty directories, unless [submodules are enabled][PrepareCheckout::with_submodules()].
         pub fn main_worktree(
             &mut self,
             mut progress: impl crate::Progress,
@@ -73,8 +82,10 @@ pub mod main_worktree {
                 })?;
             let mut index = git_index::File::from_state(index, repo.index_path());
 
-            let mut opts = repo.config.checkout_options(repo.git_dir())?;
+            let mut opts = repo.config.checkout_options()?;
             opts.destination_is_initially_empty = true;
+            let sparse_patterns = repo.sparse_checkout_patterns()?;
+            opts.sparse_patterns = sparse_patterns.clone();
 
             let mut files = progress.add_child_with_id("checkout", *b"CLCF"); /* CLone Checkout Files */
             let mut bytes = progress.add_child_with_id("writing", *b"CLCB") /* CLone Checkout Bytes */;
@@ -88,7 +99,7 @@ pub mod main_worktree {
                 workdir,
                 {
                     let objects = repo.objects.clone().into_arc()?;
-                    move |oid, buf| objects.find_blob(oid, buf)
+                    move |oid, buf| objects.find(oid, buf)
                 },
                 &mut files,
                 &mut bytes,
@@ -98,12 +109,227 @@ pub mod main_worktree {
             files.show_throughput(start);
             bytes.show_throughput(start);
 
+            if let Some(patterns) = sparse_patterns {
+                if repo.config.sparse_index()? {
+                    patterns.collapse(&mut index, |tree| repo.objects.write(tree))?;
+                }
+            }
             index.write(Default::default())?;
+
+            #[cfg(feature = "blocking-network-client")]
+            if self.submodules {
+                let repo = self.repo.as_mut().expect("still present");
+                let mut progress = progress.add_child_with_id("submodules", *b"CLCS"); /* CLone Checkout Submodules */
+                super::submodules::checkout(repo, &index, &mut progress, should_interrupt)?;
+            }
             Ok((self.repo.take().expect("still present"), outcome))
         }
     }
 }
 
+#[cfg(feature = "blocking-network-client")]
+#[allow(clippy::result_large_err)]
+pub(crate) mod submodules {
+    use std::{
+        path::{Component, Path, PathBuf},
+        sync::atomic::AtomicBool,
+    };
+
+    use git_ref::transaction::PreviousValue;
+
+    use crate::{
+        bstr::{BString, ByteSlice},
+        clone::PrepareCheckout,
+        submodule, Progress, Repository,
+    };
+
+    /// The error returned when checking out submodules as part of [`PrepareCheckout::main_worktree()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Modules(#[from] submodule::modules::Error),
+        #[error(transparent)]
+        Path(#[from] submodule::config::path::Error),
+        #[error(transparent)]
+        Url(#[from] submodule::url::Error),
+        #[error(transparent)]
+        Update(#[from] submodule::config::update::Error),
+        #[error("Failed to load repo-local git configuration before writing")]
+        LoadConfig(#[from] git_config::file::init::from_paths::Error),
+        #[error("Failed to write {}", path.display())]
+        WriteFile { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        PrepareClone(#[from] crate::clone::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::clone::fetch::Error),
+        #[error("Could not detach HEAD of the submodule at the commit recorded in the superproject")]
+        DetachHead(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        Reopen(#[from] crate::open::Error),
+        #[error("Failed to check out submodule '{name}'")]
+        Checkout {
+            name: BString,
+            source: Box<super::main_worktree::Error>,
+        },
+    }
+
+    struct Submodule {
+        name: BString,
+        url: git_url::Url,
+        git_dir: PathBuf,
+        work_dir: PathBuf,
+        id: git_hash::ObjectId,
+    }
+
+    /// Initialize all submodules of `repo` which are recorded in `index` just like `git clone --recurse-submodules` would,
+    /// then clone them into the `modules` directory of `repo` and check out the recorded commit, recursively.
+    pub fn checkout(
+        repo: &mut Repository,
+        index: &git_index::State,
+        progress: &mut impl Progress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let mut to_clone = Vec::new();
+        let mut config = local_config(repo)?;
+        config
+            .set_raw_value("submodule", None, "active", ".")
+            .expect("valid static key");
+        for submodule in repo.submodules()?.into_iter().flatten() {
+            let path = submodule.path()?;
+            let id = match index
+                .entry_by_path_and_stage(path.as_ref(), 0)
+                .filter(|entry| entry.mode == git_index::entry::Mode::COMMIT)
+            {
+                Some(entry) => entry.id,
+                None => continue,
+            };
+            let url = submodule.url()?;
+            config
+                .set_raw_value("submodule", Some(submodule.name()), "url", url.to_bstring().as_bstr())
+                .expect("valid static key");
+            if submodule.update()? == Some(submodule::config::Update::None) {
+                continue;
+            }
+            to_clone.push(Submodule {
+                name: submodule.name().to_owned(),
+                url,
+                git_dir: submodule.git_dir(),
+                work_dir: submodule.work_dir()?.expect("superproject has a worktree"),
+                id,
+            });
+        }
+        write_file(&repo.git_dir().join("config"), &config.to_bstring())?;
+        crate::clone::fetch::util::replace_changed_local_config_file(repo, config);
+
+        progress.init(Some(to_clone.len()), crate::progress::count("submodules"));
+        for submodule in to_clone {
+            progress.set_name(submodule.name.to_str_lossy());
+            clone_and_checkout(repo, &submodule, should_interrupt).map_err(|err| Error::Checkout {
+                name: submodule.name.clone(),
+                source: Box::new(err),
+            })?;
+            progress.inc();
+        }
+        Ok(())
+    }
+
+    fn clone_and_checkout(
+        repo: &Repository,
+        submodule: &Submodule,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), super::main_worktree::Error> {
+        let parent_dir = submodule.git_dir.parent().expect("at least in modules directory");
+        std::fs::create_dir_all(parent_dir).map_err(|err| Error::WriteFile {
+            path: parent_dir.to_owned(),
+            source: err,
+        })?;
+        let mut fetch = crate::clone::PrepareFetch::new(
+            submodule.url.clone(),
+            &submodule.git_dir,
+            crate::create::Kind::Bare,
+            Default::default(),
+            repo.options.clone(),
+        )
+        .map_err(Error::from)?;
+        let (mut sub_repo, _outcome) = fetch
+            .fetch_only(crate::progress::Discard, should_interrupt)
+            .map_err(Error::from)?;
+
+        let mut config = local_config(&sub_repo)?;
+        config
+            .set_raw_value("core", None, "bare", "false")
+            .expect("valid static key");
+        let work_dir = relative_path(&submodule.git_dir, &submodule.work_dir);
+        config
+            .set_raw_value("core", None, "worktree", git_path::into_bstr(work_dir).as_ref())
+            .expect("valid static key");
+        write_file(&submodule.git_dir.join("config"), &config.to_bstring())?;
+        let mut dot_git = BString::from("gitdir: ");
+        dot_git.extend_from_slice(&git_path::into_bstr(relative_path(
+            &submodule.work_dir,
+            &submodule.git_dir,
+        )));
+        dot_git.push(b'\n');
+        write_file(&submodule.work_dir.join(".git"), &dot_git)?;
+
+        sub_repo
+            .reference(
+                "HEAD",
+                submodule.id,
+                PreviousValue::Any,
+                format!("checkout: moving to {}", submodule.id),
+            )
+            .map_err(Error::from)?;
+        sub_repo = crate::open_opts(&submodule.git_dir, repo.options.clone()).map_err(Error::from)?;
+
+        PrepareCheckout {
+            repo: Some(sub_repo),
+            submodules: true,
+        }
+        .main_worktree(crate::progress::Discard, should_interrupt)?;
+        Ok(())
+    }
+
+    fn local_config(repo: &Repository) -> Result<git_config::File<'static>, Error> {
+        let mut metadata = git_config::file::Metadata::from(git_config::Source::Local);
+        metadata.path = Some(repo.git_dir().join("config"));
+        Ok(git_config::File::from_paths_metadata(Some(metadata), Default::default())?.expect("one file to load"))
+    }
+
+    fn write_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+        std::fs::write(path, data).map_err(|err| Error::WriteFile {
+            path: path.to_owned(),
+            source: err,
+        })
+    }
+
+    /// Return `path` relative to the directory `base`, assuming both are either absolute or relative to the same directory.
+    fn relative_path(base: &Path, path: &Path) -> PathBuf {
+        let base: Vec<_> = base.components().filter(|c| *c != Component::CurDir).collect();
+        let path: Vec<_> = path.components().filter(|c| *c != Component::CurDir).collect();
+        let common = base.iter().zip(path.iter()).take_while(|(a, b)| a == b).count();
+        base[common..]
+            .iter()
+            .map(|_| Component::ParentDir)
+            .chain(path[common..].iter().copied())
+            .collect()
+    }
+}
+
+/// Builder
+impl PrepareCheckout {
+    /// If `recurse` is true, all submodules recorded in the checked out tree are initialized, cloned into the `modules`
+    /// directory of the repository and checked out at the recorded commit, recursively, similar to `git clone --recurse-submodules`.
+    ///
+    /// Submodules whose `update` mode is `none` are initialized, but not cloned.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn with_submodules(mut self, recurse: bool) -> Self {
+        self.submodules = recurse;
+        self
+    }
+}
+
 /// Access
 impl PrepareCheckout {
     /// Get access to the repository while the checkout isn't yet completed.
@@ -28,6 +28,13 @@ pub enum Error {
         source: git_validate::refname::Error,
         head_ref_name: BString,
     },
+    #[error("Failed to reopen the repository after changing its object hash to the one of the remote")]
+    Reopen(#[from] crate::open::Error),
+    #[error("The repository still uses {actual} after changing its object hash to {expected}, the one of the remote")]
+    ObjectHashMismatch {
+        expected: git_hash::Kind,
+        actual: git_hash::Kind,
+    },
     #[error("Failed to update HEAD with values from remote")]
     HeadUpdate(#[from] crate::reference::edit::Error),
 }
@@ -84,8 +91,12 @@ impl PrepareFetch {
         } else {
             clone_fetch_tags = remote::fetch::Tags::All.into();
         }
+        if let Some(filter) = self.filter {
+            remote = remote.with_filter(Some(filter));
+        }
 
         let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
+        let is_partial_clone = remote.is_promisor();
 
         // Now we are free to apply remote configuration we don't want to be written to disk.
         if let Some(fetch_tags) = clone_fetch_tags {
@@ -100,16 +111,31 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _, _> =
-            remote.connect(remote::Direction::Fetch, progress)?.prepare_fetch({
+        let mut progress = progress;
+        let pending_pack: remote::fetch::Prepare<'_, '_, _, _> = remote
+            .connect(remote::Direction::Fetch, &mut progress)?
+            .prepare_fetch({
                 let mut opts = self.fetch_options.clone();
                 if !opts.extra_refspecs.contains(&head_refspec) {
                     opts.extra_refspecs.push(head_refspec)
                 }
                 opts
             })?;
-        if pending_pack.ref_map().object_hash != repo.object_hash() {
-            unimplemented!("configure repository to expect a different object hash as advertised by the server")
+        let remote_object_hash = pending_pack.ref_map().object_hash;
+        if remote_object_hash != repo.object_hash() {
+            // The repository is still empty, so we can change its object hash to the one of the remote and start over
+            // with a repository that will accept its objects.
+            drop(pending_pack);
+            drop(remote);
+            util::configure_object_hash(repo, remote_object_hash)?;
+            // Starting over is only safe if the change took effect, or we would keep trying forever.
+            if repo.object_hash() != remote_object_hash {
+                return Err(Error::ObjectHashMismatch {
+                    expected: remote_object_hash,
+                    actual: repo.object_hash(),
+                });
+            }
+            return self.fetch_only(progress, should_interrupt);
         }
         let reflog_message = {
             let mut b = self.url.to_bstring();
@@ -121,6 +147,7 @@ impl PrepareFetch {
             .with_reflog_message(RefLogMessage::Override {
                 message: reflog_message.clone(),
             })
+            .with_shallow(self.shallow.clone())
             .receive(should_interrupt)?;
 
         util::replace_changed_local_config_file(repo, config);
@@ -130,6 +157,11 @@ impl PrepareFetch {
             reflog_message.as_ref(),
             remote_name.as_ref(),
         )?;
+        if is_partial_clone && repo.options.lazy_fetch {
+            // Let the object database obtain missing objects from the promisor remote that is now configured,
+            // as a checkout will need them.
+            util::reopen(repo)?;
+        }
 
         Ok((self.repo.take().expect("still present"), outcome))
     }
@@ -146,7 +178,13 @@ impl PrepareFetch {
         P::SubProgress: 'static,
     {
         let (repo, fetch_outcome) = self.fetch_only(progress, should_interrupt)?;
-        Ok((crate::clone::PrepareCheckout { repo: repo.into() }, fetch_outcome))
+        Ok((
+            crate::clone::PrepareCheckout {
+                repo: repo.into(),
+                submodules: false,
+            },
+            fetch_outcome,
+        ))
     }
 }
 
@@ -158,6 +196,22 @@ impl PrepareFetch {
         self.fetch_options = opts;
         self
     }
+    /// Make this clone a shallow one with the respective choice of shallow-ness, like `git clone --depth 1`.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub fn with_shallow(mut self, shallow: crate::remote::fetch::Shallow) -> Self {
+        self.shallow = shallow;
+        self
+    }
+    /// Make this a partial clone which omits objects according to `filter`, like `git clone --filter=blob:none`.
+    ///
+    /// The remote is configured as promisor remote with `remote.<name>.promisor` and `remote.<name>.partialCloneFilter`.
+    /// Note that objects omitted by the filter, like blobs needed for a checkout, can only be obtained on demand if the repository
+    /// was opened with [lazy fetching][crate::open::Options::lazy_fetch()] enabled.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub fn with_filter(mut self, filter: crate::remote::fetch::Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
     /// Use `f` to apply arbitrary changes to the remote that is about to be used to fetch a pack.
     ///
     /// The passed in `remote` will be un-named and pre-configured to be a default remote as we know it from git-clone.
@@ -209,4 +263,4 @@ impl From<PrepareFetch> for Repository {
 }
 
 #[cfg(feature = "blocking-network-client")]
-mod util;
+pub(super) mod util;
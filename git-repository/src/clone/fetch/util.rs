@@ -26,6 +26,39 @@ pub fn write_remote_to_local_config_file(
     Ok(config)
 }
 
+/// Write `object_hash` into the local configuration of the empty `repo` and reopen it, to assure all of its parts use it.
+pub fn configure_object_hash(repo: &mut Repository, object_hash: git_hash::Kind) -> Result<(), Error> {
+    let mut metadata = git_config::file::Metadata::from(git_config::Source::Local);
+    let config_path = repo.git_dir().join("config");
+    metadata.path = Some(config_path.clone());
+    let mut config =
+        git_config::File::from_paths_metadata(Some(metadata), Default::default())?.expect("one file to load");
+    config
+        .set_raw_value("core", None, "repositoryFormatVersion", "1")
+        .expect("valid static key");
+    config
+        .set_raw_value(
+            "extensions",
+            None,
+            "objectFormat",
+            object_hash.to_string().to_ascii_lowercase().as_str(),
+        )
+        .expect("valid static key");
+    std::fs::write(config_path, config.to_bstring())?;
+    reopen(repo)
+}
+
+/// Open `repo` again to pick up all changes to its configuration.
+pub fn reopen(repo: &mut Repository) -> Result<(), Error> {
+    *repo = crate::ThreadSafeRepository::open_from_paths(
+        repo.git_dir().to_owned(),
+        repo.work_dir().map(ToOwned::to_owned),
+        repo.options.clone(),
+    )?
+    .to_thread_local();
+    Ok(())
+}
+
 pub fn replace_changed_local_config_file(repo: &mut Repository, mut config: git_config::File<'static>) {
     let repo_config = git_features::threading::OwnShared::make_mut(&mut repo.config.resolved);
     let ids_to_remove: Vec<_> = repo_config
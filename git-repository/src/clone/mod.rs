@@ -18,6 +18,12 @@ pub struct PrepareFetch {
     /// Options for preparing a fetch operation.
     #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
     fetch_options: crate::remote::ref_map::Options,
+    /// How to handle shallow clones
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    shallow: crate::remote::fetch::Shallow,
+    /// The filter to use for partial clones
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    filter: Option<crate::remote::fetch::Filter>,
     /// The url to clone from
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     url: git_url::Url,
@@ -68,6 +74,10 @@ impl PrepareFetch {
             url,
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             fetch_options: Default::default(),
+            #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+            shallow: Default::default(),
+            #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+            filter: None,
             repo: Some(repo),
             remote_name: None,
             configure_remote: None,
@@ -81,6 +91,9 @@ impl PrepareFetch {
 pub struct PrepareCheckout {
     /// A freshly initialized repository which is owned by us, or `None` if it was handed to the user
     pub(self) repo: Option<crate::Repository>,
+    /// If true, active submodules are cloned and checked out recursively after the main worktree.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    pub(self) submodules: bool,
 }
 
 ///
@@ -116,6 +116,17 @@ impl Cache {
             .into()
     }
 
+    /// The path to the user-level attributes file, as configured by `core.attributesFile`, or the default one in the
+    /// `XDG` configuration directory.
+    pub(crate) fn attributes_file(&self) -> Result<Option<PathBuf>, checkout_options::Error> {
+        Ok(
+            match self.trusted_file_path("core", None, "attributesFile").transpose()? {
+                Some(attributes) => Some(attributes.into_owned()),
+                None => self.xdg_config_path("attributes").ok().flatten(),
+            },
+        )
+    }
+
     /// A helper to obtain a file from trusted configuration at `section_name`, `subsection_name`, and `key`, which is interpolated
     /// if present.
     pub(crate) fn trusted_file_path(
@@ -149,10 +160,7 @@ impl Cache {
     /// Collect everything needed to checkout files into a worktree.
     /// Note that some of the options being returned will be defaulted so safe settings, the caller might have to override them
     /// depending on the use-case.
-    pub(crate) fn checkout_options(
-        &self,
-        git_dir: &std::path::Path,
-    ) -> Result<git_worktree::index::checkout::Options, checkout_options::Error> {
+    pub(crate) fn checkout_options(&self) -> Result<git_worktree::index::checkout::Options, checkout_options::Error> {
         fn checkout_thread_limit_from_config(
             config: &git_config::File<'static>,
         ) -> Option<Result<usize, checkout_options::Error>> {
@@ -166,52 +174,163 @@ impl Cache {
             })
         }
 
-        fn boolean(me: &Cache, full_key: &'static str, default: bool) -> Result<bool, checkout_options::Error> {
-            let mut tokens = full_key.split('.');
-            let section = tokens.next().expect("section");
-            let key = tokens.next().expect("key");
-            assert!(tokens.next().is_none(), "core.<key>");
-            Ok(me
-                .apply_leniency(me.resolved.boolean(section, None, key))
-                .map_err(|err| checkout_options::Error::Configuration {
-                    key: full_key,
-                    source: err,
-                })?
-                .unwrap_or(default))
-        }
-
-        fn assemble_attribute_globals(
-            me: &Cache,
-            _git_dir: &std::path::Path,
-        ) -> Result<git_attributes::MatchGroup, checkout_options::Error> {
-            let _attributes_file = match me.trusted_file_path("core", None, "attributesFile").transpose()? {
-                Some(attributes) => Some(attributes.into_owned()),
-                None => me.xdg_config_path("attributes").ok().flatten(),
-            };
-            // TODO: implement git_attributes::MatchGroup::<git_attributes::Attributes>::from_git_dir(), similar to what's done for `Ignore`.
-            Ok(Default::default())
+        fn assemble_attribute_globals(me: &Cache) -> Result<git_attributes::MatchGroup, checkout_options::Error> {
+            let mut group = git_attributes::MatchGroup::default();
+            if let Some(attributes_file) = me.attributes_file()? {
+                group.add_patterns_file(attributes_file, true, None, &mut Vec::new())?;
+            }
+            Ok(group)
         }
 
         let thread_limit = self.apply_leniency(checkout_thread_limit_from_config(&self.resolved))?;
         Ok(git_worktree::index::checkout::Options {
             fs: git_worktree::fs::Capabilities {
-                precompose_unicode: boolean(self, "core.precomposeUnicode", false)?,
-                ignore_case: boolean(self, "core.ignoreCase", false)?,
-                executable_bit: boolean(self, "core.fileMode", true)?,
-                symlink: boolean(self, "core.symlinks", true)?,
+                precompose_unicode: self.checkout_boolean("core.precomposeUnicode", false)?,
+                ignore_case: self.checkout_boolean("core.ignoreCase", false)?,
+                executable_bit: self.checkout_boolean("core.fileMode", true)?,
+                symlink: self.checkout_boolean("core.symlinks", true)?,
             },
             thread_limit,
             destination_is_initially_empty: false,
             overwrite_existing: false,
             keep_going: false,
-            trust_ctime: boolean(self, "core.trustCTime", true)?,
+            trust_ctime: self.checkout_boolean("core.trustCTime", true)?,
             check_stat: self
                 .resolved
                 .string("core", None, "checkStat")
                 .map_or(true, |v| v.as_ref() != "minimal"),
-            attribute_globals: assemble_attribute_globals(self, git_dir)?,
+            attribute_globals: assemble_attribute_globals(self)?,
+            filters: self.filter_pipeline()?,
+            sparse_patterns: None,
         })
     }
+
+    /// Read the sparse checkout patterns from `info/sparse-checkout` in `git_dir` if `core.sparseCheckout` is enabled,
+    /// in cone mode unless `core.sparseCheckoutCone` is false.
+    pub(crate) fn sparse_checkout_patterns(
+        &self,
+        git_dir: &std::path::Path,
+    ) -> Result<Option<git_worktree::sparse::Patterns>, checkout_options::Error> {
+        if !self.checkout_boolean("core.sparseCheckout", false)? {
+            return Ok(None);
+        }
+        let cone = self.checkout_boolean("core.sparseCheckoutCone", true)?;
+        git_worktree::sparse::Patterns::from_file(git_dir.join("info").join("sparse-checkout"), cone)
+            .map_err(checkout_options::Error::SparseCheckoutFile)
+    }
+
+    /// Return true if `index.sparse` is set to collapse directories outside of the sparse checkout cone.
+    pub(crate) fn sparse_index(&self) -> Result<bool, checkout_options::Error> {
+        self.checkout_boolean("index.sparse", false)
+    }
+
+    fn checkout_boolean(&self, full_key: &'static str, default: bool) -> Result<bool, checkout_options::Error> {
+        let mut tokens = full_key.split('.');
+        let section = tokens.next().expect("section");
+        let key = tokens.next().expect("key");
+        assert!(tokens.next().is_none(), "<section>.<key>");
+        Ok(self
+            .apply_leniency(self.resolved.boolean(section, None, key))
+            .map_err(|err| checkout_options::Error::Configuration {
+                key: full_key,
+                source: err,
+            })?
+            .unwrap_or(default))
+    }
+
+    /// Collect the end-of-line configuration and all filter drivers from trusted configuration to convert files
+    /// between the worktree and the object database.
+    pub(crate) fn filter_pipeline(&self) -> Result<git_filter::Pipeline, checkout_options::Error> {
+        use git_filter::eol;
+        let auto_crlf = match self.resolved.string("core", None, "autocrlf") {
+            Some(value) if value.eq_ignore_ascii_case(b"input") => eol::AutoCrlf::Input,
+            _ => match self
+                .apply_leniency(self.resolved.boolean("core", None, "autocrlf"))
+                .map_err(|source| checkout_options::Error::Configuration {
+                    key: "core.autocrlf",
+                    source,
+                })? {
+                Some(true) => eol::AutoCrlf::Enabled,
+                Some(false) | None => eol::AutoCrlf::Disabled,
+            },
+        };
+        let eol = self
+            .apply_leniency(self.resolved.string("core", None, "eol").map(|value| {
+                if value.eq_ignore_ascii_case(b"lf") {
+                    Ok(Some(eol::Mode::Lf))
+                } else if value.eq_ignore_ascii_case(b"crlf") {
+                    Ok(Some(eol::Mode::CrLf))
+                } else if value.eq_ignore_ascii_case(b"native") {
+                    Ok(None)
+                } else {
+                    Err(git_config::value::Error::new(
+                        "Expected one of 'lf', 'crlf' or 'native'",
+                        value.into_owned(),
+                    ))
+                }
+            }))
+            .map_err(|source| checkout_options::Error::Configuration {
+                key: "core.eol",
+                source,
+            })?
+            .flatten();
+
+        let mut drivers = Vec::<git_filter::Driver>::new();
+        let mut filter = self.filter_config_section;
+        for section in self
+            .resolved
+            .sections_by_name_and_filter("filter", &mut filter)
+            .into_iter()
+            .flatten()
+        {
+            let name = match section.header().subsection_name() {
+                Some(name) => name,
+                None => continue,
+            };
+            let idx = match drivers.iter().position(|d| d.name == name) {
+                Some(idx) => idx,
+                None => {
+                    drivers.push(git_filter::Driver {
+                        name: name.to_owned(),
+                        ..Default::default()
+                    });
+                    drivers.len() - 1
+                }
+            };
+            let driver = &mut drivers[idx];
+            // Later sections override values of earlier ones.
+            for (key, field) in [
+                ("clean", &mut driver.clean),
+                ("smudge", &mut driver.smudge),
+                ("process", &mut driver.process),
+            ] {
+                if let Some(value) = section.value(key) {
+                    *field = Some(value.into_owned());
+                }
+            }
+            if let Some(value) = section.value_implicit("required") {
+                let required = match value {
+                    None => Some(true),
+                    Some(value) => self
+                        .apply_leniency(Some(git_config::Boolean::try_from(value.as_ref()).map(|b| b.0)))
+                        .map_err(|source| checkout_options::Error::Configuration {
+                            key: "filter.<driver>.required",
+                            source,
+                        })?,
+                };
+                if let Some(required) = required {
+                    driver.required = required;
+                }
+            }
+        }
+
+        Ok(git_filter::Pipeline::new(
+            git_filter::pipeline::Options {
+                eol_config: eol::Configuration { auto_crlf, eol },
+            },
+            drivers,
+        ))
+    }
     pub(crate) fn xdg_config_path(
         &self,
         resource_file_name: &str,
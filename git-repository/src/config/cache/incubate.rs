@@ -42,6 +42,8 @@ impl StageOne {
                 config.string("extensions", None, "objectFormat").map(|format| {
                     if format.as_ref().eq_ignore_ascii_case(b"sha1") {
                         Ok(git_hash::Kind::Sha1)
+                    } else if format.as_ref().eq_ignore_ascii_case(b"sha256") {
+                        Ok(git_hash::Kind::Sha256)
                     } else {
                         Err(Error::UnsupportedObjectFormat {
                             name: format.to_vec().into(),
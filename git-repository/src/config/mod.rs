@@ -113,6 +113,10 @@ pub mod checkout_options {
         },
         #[error("Failed to interpolate the attribute file configured at `core.attributesFile`")]
         AttributesFileInterpolation(#[from] git_config::path::interpolate::Error),
+        #[error("Could not read the attributes file")]
+        AttributesFile(#[from] std::io::Error),
+        #[error("Could not read the sparse checkout patterns")]
+        SparseCheckoutFile(#[source] std::io::Error),
     }
 }
 
@@ -0,0 +1,158 @@
+//! Convert files between their representation in the worktree and in the object database, as driven by attributes
+//! and the repository configuration.
+use git_odb::FindExt;
+
+pub use git_filter as plumbing;
+
+use crate::{bstr::BStr, Id, Repository};
+
+/// A pipeline to apply all filters to files as they are written to the object database or checked out into the worktree,
+/// as configured with `core.autocrlf`, `core.eol` and `filter.<driver>` along with the attributes of each file.
+///
+/// Obtain it with [`Worktree::filter_pipeline()`][crate::Worktree::filter_pipeline()]. Long-running filter processes
+/// are kept alive for as long as the pipeline exists.
+pub struct Pipeline<'a> {
+    repo: &'a Repository,
+    index: &'a git_index::State,
+    cache: git_worktree::fs::Cache<'a>,
+    inner: git_filter::Pipeline,
+    attributes: git_attributes::Outcome,
+}
+
+///
+pub mod pipeline {
+    /// The error returned by [`Worktree::filter_pipeline()`][crate::Worktree::filter_pipeline()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Attributes(#[from] crate::worktree::attributes::Error),
+        #[error(transparent)]
+        Configuration(#[from] crate::config::checkout_options::Error),
+    }
+}
+
+///
+pub mod convert {
+    /// The error returned by [`Pipeline::convert_to_git()`][crate::filter::Pipeline::convert_to_git()] and
+    /// [`Pipeline::convert_to_worktree()`][crate::filter::Pipeline::convert_to_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not obtain the attributes of the file")]
+        Attributes(#[from] std::io::Error),
+        #[error(transparent)]
+        Convert(#[from] git_filter::pipeline::convert::Error),
+    }
+}
+
+///
+pub mod write_blob {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Pipeline::write_blob_from_worktree()`][crate::filter::Pipeline::write_blob_from_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read '{}' from the worktree", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        Convert(#[from] super::convert::Error),
+        #[error(transparent)]
+        Write(#[from] crate::object::write::Error),
+    }
+}
+
+impl<'repo> crate::Worktree<'repo> {
+    /// Create a pipeline to convert files between the worktree and the object database, with attributes being read from
+    /// the worktree or the `index`, along with the repository configuration.
+    ///
+    /// Note that filter drivers are only used if they are configured in trusted configuration files.
+    pub fn filter_pipeline<'a>(&self, index: &'a git_index::State) -> Result<Pipeline<'a>, pipeline::Error>
+    where
+        'repo: 'a,
+    {
+        Ok(Pipeline {
+            repo: self.parent,
+            index,
+            cache: self.attributes(index, None)?,
+            inner: self.parent.config.filter_pipeline()?,
+            attributes: Default::default(),
+        })
+    }
+}
+
+impl<'a> Pipeline<'a> {
+    /// Convert `src`, the content of the file at the repository-relative `rela_path`, so it can be stored in the object database,
+    /// and write the result into `out`. Return `true` if `out` was written, or `false` if `src` is unchanged.
+    ///
+    /// Note that the object of `rela_path` in the index is consulted for automatic end-of-line conversions.
+    pub fn convert_to_git(&mut self, src: &[u8], rela_path: &BStr, out: &mut Vec<u8>) -> Result<bool, convert::Error> {
+        let repo = self.repo;
+        let index = self.index;
+        self.cache
+            .at_entry(rela_path, Some(false), |id, buf| repo.objects.find_blob(id, buf))?
+            .matching_attributes(&mut self.attributes);
+        Ok(self.inner.convert_to_git(
+            src,
+            rela_path,
+            &self.attributes,
+            |buf| {
+                let entry = match index.entry_by_path_and_stage(rela_path, 0) {
+                    Some(entry) => entry,
+                    None => return Ok(None),
+                };
+                repo.objects.find_blob(entry.id, buf)?;
+                Ok(Some(()))
+            },
+            out,
+        )?)
+    }
+
+    /// Convert `src`, the content of the blob with `id`, so it can be written to the worktree at the repository-relative
+    /// `rela_path`, and write the result into `out`. Return `true` if `out` was written, or `false` if `src` is unchanged.
+    pub fn convert_to_worktree(
+        &mut self,
+        src: &[u8],
+        rela_path: &BStr,
+        id: &git_hash::oid,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, convert::Error> {
+        let repo = self.repo;
+        self.cache
+            .at_entry(rela_path, Some(false), |id, buf| repo.objects.find_blob(id, buf))?
+            .matching_attributes(&mut self.attributes);
+        Ok(self
+            .inner
+            .convert_to_worktree(src, rela_path, id, &self.attributes, out)?)
+    }
+
+    /// Read the file at the repository-relative `rela_path` from the worktree, convert it with all filters that apply to it
+    /// and write the result into the object database as blob, similar to `git hash-object -w <path>`.
+    ///
+    /// Symbolic links are written as is, without applying any filter.
+    pub fn write_blob_from_worktree(&mut self, rela_path: &BStr) -> Result<Id<'a>, write_blob::Error> {
+        let path = self.cache.base().join(git_path::from_bstr(rela_path));
+        let io_err = |source| write_blob::Error::Io {
+            source,
+            path: path.clone(),
+        };
+        let meta = std::fs::symlink_metadata(&path).map_err(io_err)?;
+        if meta.file_type().is_symlink() {
+            let destination = std::fs::read_link(&path).map_err(io_err)?;
+            let destination = git_path::into_bstr(destination);
+            return Ok(self
+                .repo
+                .write_blob(git_path::to_unix_separators_on_windows(destination).as_ref())?);
+        }
+
+        let src = std::fs::read(&path).map_err(io_err)?;
+        let mut out = Vec::new();
+        let data = if self.convert_to_git(&src, rela_path, &mut out)? {
+            &out
+        } else {
+            &src
+        };
+        Ok(self.repo.write_blob(data)?)
+    }
+}
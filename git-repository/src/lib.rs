@@ -178,8 +178,8 @@ pub(crate) type Config = OwnShared<git_config::File<'static>>;
 ///
 mod types;
 pub use types::{
-    Commit, Head, Id, Kind, Object, ObjectDetached, Reference, Remote, Repository, Tag, ThreadSafeRepository, Tree,
-    Worktree,
+    Commit, Head, Id, Kind, Object, ObjectDetached, Reference, Remote, Repository, Submodule, Tag,
+    ThreadSafeRepository, Tree, Worktree,
 };
 
 ///
@@ -303,11 +303,23 @@ pub mod mailmap;
 ///
 pub mod worktree;
 
+///
+pub mod filter;
+
 pub mod revision;
 
 ///
 pub mod remote;
 
+pub mod shallow;
+
+pub mod promisor;
+
+///
+pub mod submodule;
+
+pub mod status;
+
 ///
 pub mod init;
 
@@ -41,12 +41,13 @@ impl<'a, 'old> Platform<'a, 'old> {
             visit: for_each,
             err: None,
         };
-        match git_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data)).needed_to_obtain(
-            TreeRefIter::from_bytes(&other.data),
-            &mut self.state,
-            |oid, buf| repo.objects.find_tree_iter(oid, buf),
-            &mut delegate,
-        ) {
+        match git_diff::tree::Changes::from(TreeRefIter::from_bytes(&self.lhs.data, repo.object_hash()))
+            .needed_to_obtain(
+                TreeRefIter::from_bytes(&other.data, repo.object_hash()),
+                &mut self.state,
+                |oid, buf| repo.objects.find_tree_iter(oid, buf),
+                &mut delegate,
+            ) {
             Ok(()) => match delegate.err {
                 Some(err) => Err(Error::ForEach(Box::new(err))),
                 None => Ok(()),
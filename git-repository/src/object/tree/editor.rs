@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    tree::EntryMode,
+};
+
+use crate::{ext::ObjectIdExt, object, Id, Tree};
+
+/// The error returned by [`Editor`] methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The path '{path}' is empty or contains empty components")]
+    InvalidPath { path: BString },
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    WriteObject(#[from] object::write::Error),
+}
+
+/// Editing
+impl<'repo> Tree<'repo> {
+    /// Start editing this tree to create a new one by changing entries at arbitrary paths.
+    ///
+    /// Note that this tree remains unchanged.
+    pub fn edit(&self) -> Editor<'repo> {
+        Editor {
+            repo: self.repo,
+            root: self.id,
+            trees: Default::default(),
+        }
+    }
+}
+
+/// A tree along the path of an edit.
+struct Node {
+    tree: git_object::Tree,
+    /// The id of the tree before it was edited, or `None` if it was newly created.
+    id: Option<ObjectId>,
+    /// If true, the tree differs from the one identified by `id`.
+    changed: bool,
+}
+
+/// An editor to change entries of a tree at arbitrary paths and [write][Editor::write()] only the trees which changed.
+///
+/// Intermediate trees are created as needed, and trees that become empty are removed from their parent.
+pub struct Editor<'repo> {
+    repo: &'repo crate::Repository,
+    root: ObjectId,
+    /// All trees along the paths of edits, keyed by their location relative to the root, which is the empty path.
+    trees: BTreeMap<BString, Node>,
+}
+
+/// Editing
+impl<'repo> Editor<'repo> {
+    /// Insert a new entry at the slash-separated `path` with the given `mode` and `id`, or replace the existing entry at this path.
+    ///
+    /// Missing intermediate trees are created, and intermediate entries that are not trees are replaced by one.
+    pub fn upsert(
+        &mut self,
+        path: impl AsRef<BStr>,
+        mode: EntryMode,
+        id: impl Into<ObjectId>,
+    ) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let (parent, name) = split_path(path)?;
+        self.load_trees_to(parent, true)?;
+
+        let id = id.into();
+        let node = self.trees.get_mut(parent).expect("loaded or created");
+        let entries = &mut node.tree.entries;
+        let existing = entries.iter().position(|e| e.filename == name);
+        if let Some(pos) = existing {
+            if entries[pos].mode == mode && entries[pos].oid == id {
+                return Ok(self);
+            }
+            entries.remove(pos);
+        }
+        insert_sorted(entries, mode, name, id);
+        node.changed = true;
+        if existing.is_some() {
+            self.forget_trees_at(path);
+        }
+        Ok(self)
+    }
+
+    /// Remove the entry at the slash-separated `path`, along with all of its entries if it is a tree.
+    ///
+    /// Nothing happens if there is no such entry. Trees that become empty by this are removed as well.
+    pub fn remove(&mut self, path: impl AsRef<BStr>) -> Result<&mut Self, Error> {
+        let path = path.as_ref();
+        let (parent, name) = split_path(path)?;
+        if !self.load_trees_to(parent, false)? {
+            return Ok(self);
+        }
+
+        let node = self.trees.get_mut(parent).expect("loaded");
+        if let Some(pos) = node.tree.entries.iter().position(|e| e.filename == name) {
+            node.tree.entries.remove(pos);
+            node.changed = true;
+            self.forget_trees_at(path);
+        }
+        Ok(self)
+    }
+
+    /// Write all changed trees into the object database and return the id of the new root tree.
+    ///
+    /// Trees which didn't change are not written, and the editor can be used to make further changes to the newly written tree.
+    pub fn write(&mut self) -> Result<Id<'repo>, Error> {
+        let mut trees: Vec<_> = std::mem::take(&mut self.trees).into_iter().collect();
+        // Children are written before their parents, whose entries they update.
+        trees.sort_by_key(|(path, _)| std::cmp::Reverse(depth(path.as_ref())));
+        let mut new_ids = BTreeMap::<BString, Option<ObjectId>>::new();
+        let mut root = self.root;
+        for (path, mut node) in trees {
+            for mut entry in std::mem::take(&mut node.tree.entries) {
+                if entry.mode.is_tree() {
+                    let mut child_path = path.clone();
+                    push_component(&mut child_path, entry.filename.as_ref());
+                    match new_ids.get(&child_path) {
+                        Some(None) => {
+                            node.changed = true;
+                            continue;
+                        }
+                        Some(Some(id)) => {
+                            node.changed |= entry.oid != *id;
+                            entry.oid = *id;
+                        }
+                        None => {}
+                    }
+                }
+                node.tree.entries.push(entry);
+            }
+
+            let new_id = if node.tree.entries.is_empty() && !path.is_empty() {
+                None
+            } else if node.changed {
+                Some(self.repo.write_object(&node.tree)?.detach())
+            } else {
+                node.id
+            };
+            if path.is_empty() {
+                root = new_id.expect("the root tree is always written");
+            }
+            new_ids.insert(path, new_id);
+        }
+        self.root = root;
+        Ok(root.attach(self.repo))
+    }
+}
+
+/// Utilities
+impl<'repo> Editor<'repo> {
+    /// Assure all trees leading to and including `path` are loaded, creating those that don't exist if `create` is true.
+    /// Return `false` if a tree doesn't exist and `create` is false.
+    fn load_trees_to(&mut self, path: &BStr, create: bool) -> Result<bool, Error> {
+        if !self.trees.contains_key(BStr::new("")) {
+            let root = self.load(self.root)?;
+            self.trees.insert(BString::default(), root);
+        }
+        let mut parent = BString::default();
+        for component in path.split_str("/").filter(|c| !c.is_empty()) {
+            let mut current = parent.clone();
+            push_component(&mut current, component.as_bstr());
+            if !self.trees.contains_key(&current) {
+                let existing = self.trees[&parent]
+                    .tree
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .find(|(_, e)| e.filename == component)
+                    .map(|(pos, e)| (pos, e.mode.is_tree(), e.oid));
+                let node = match existing {
+                    Some((_, true, id)) => self.load(id)?,
+                    Some((_, false, _)) | None if create => {
+                        let null_id = self.root.kind().null();
+                        let parent_node = self.trees.get_mut(&parent).expect("parents are loaded first");
+                        if let Some((pos, _, _)) = existing {
+                            parent_node.tree.entries.remove(pos);
+                        }
+                        insert_sorted(
+                            &mut parent_node.tree.entries,
+                            EntryMode::Tree,
+                            component.as_bstr(),
+                            null_id,
+                        );
+                        parent_node.changed = true;
+                        new_node()
+                    }
+                    _ => return Ok(false),
+                };
+                self.trees.insert(current.clone(), node);
+            }
+            parent = current;
+        }
+        Ok(true)
+    }
+
+    fn load(&self, id: ObjectId) -> Result<Node, Error> {
+        let object = self.repo.find_object(id)?;
+        Ok(Node {
+            tree: git_object::TreeRef::from_bytes(&object.data, self.repo.object_hash())?.into(),
+            id: Some(id),
+            changed: false,
+        })
+    }
+
+    /// Forget all trees at and below `path` as the entry there was changed directly.
+    fn forget_trees_at(&mut self, path: &BStr) {
+        self.trees.retain(|tree_path, _| {
+            !(tree_path == path || (tree_path.starts_with(path) && tree_path.get(path.len()) == Some(&b'/')))
+        });
+    }
+}
+
+fn new_node() -> Node {
+    Node {
+        tree: git_object::Tree::empty(),
+        id: None,
+        changed: true,
+    }
+}
+
+fn insert_sorted(entries: &mut Vec<git_object::tree::Entry>, mode: EntryMode, name: &BStr, id: ObjectId) {
+    let entry = git_object::tree::Entry {
+        mode,
+        filename: name.to_owned(),
+        oid: id,
+    };
+    let pos = entries.binary_search(&entry).unwrap_or_else(|pos| pos);
+    entries.insert(pos, entry);
+}
+
+/// Split `path` into the path of its parent tree and the name of the entry, or fail if any component is empty.
+fn split_path(path: &BStr) -> Result<(&BStr, &BStr), Error> {
+    if path.is_empty() || path.split_str("/").any(|c| c.is_empty()) {
+        return Err(Error::InvalidPath { path: path.to_owned() });
+    }
+    Ok(match path.rfind_byte(b'/') {
+        Some(pos) => (path[..pos].as_bstr(), path[pos + 1..].as_bstr()),
+        None => (BStr::new(""), path),
+    })
+}
+
+fn push_component(path: &mut BString, component: &BStr) {
+    if !path.is_empty() {
+        path.push_byte(b'/');
+    }
+    path.push_str(component);
+}
+
+fn depth(path: &BStr) -> usize {
+    if path.is_empty() {
+        0
+    } else {
+        path.find_iter("/").count() + 1
+    }
+}
@@ -25,6 +25,8 @@ pub struct Options {
     pub(crate) cli_config_overrides: Vec<BString>,
     /// Internal to pass an already obtained CWD on to where it may also be used. This avoids the CWD being queried more than once per repo.
     pub(crate) current_dir: Option<PathBuf>,
+    #[cfg(feature = "blocking-network-client")]
+    pub(crate) lazy_fetch: bool,
 }
 
 /// The error returned by [`crate::open()`].
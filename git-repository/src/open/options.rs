@@ -16,6 +16,8 @@ impl Default for Options {
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
+            #[cfg(feature = "blocking-network-client")]
+            lazy_fetch: false,
         }
     }
 }
@@ -129,6 +131,17 @@ impl Options {
         self
     }
 
+    /// If set, default is false, objects that are missing in a partial clone will be fetched from the promisor remote as soon as
+    /// they are looked up, instead of causing an error.
+    /// The promisor remote is configured in `extensions.partialClone`, or it's the first remote with `remote.<name>.promisor` set.
+    ///
+    /// Note that each missing object is fetched with its own connection to the remote, which is slow if many objects are missing.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn lazy_fetch(mut self, toggle: bool) -> Self {
+        self.lazy_fetch = toggle;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
         ThreadSafeRepository::open_opts(path, self)
@@ -149,6 +162,8 @@ impl git_sec::trust::DefaultForLevel for Options {
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                #[cfg(feature = "blocking-network-client")]
+                lazy_fetch: false,
             },
             git_sec::Trust::Reduced => Options {
                 object_store_slots: git_odb::store::init::Slots::Given(32), // limit resource usage
@@ -161,6 +176,8 @@ impl git_sec::trust::DefaultForLevel for Options {
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
+                #[cfg(feature = "blocking-network-client")]
+                lazy_fetch: false,
             },
         }
     }
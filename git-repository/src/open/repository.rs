@@ -132,6 +132,8 @@ impl ThreadSafeRepository {
             ref api_config_overrides,
             ref cli_config_overrides,
             ref current_dir,
+            #[cfg(feature = "blocking-network-client")]
+            lazy_fetch,
         } = options;
         let current_dir = current_dir.as_deref().expect("BUG: current_dir must be set by caller");
         let git_dir_trust = git_dir_trust.expect("trust must be been determined by now");
@@ -231,17 +233,38 @@ impl ThreadSafeRepository {
             })
             .unwrap_or_default();
 
+        let objects = git_odb::Store::at_opts(
+            common_dir_ref.join("objects"),
+            replacements,
+            git_odb::store::init::Options {
+                slots: object_store_slots,
+                object_hash: config.object_hash,
+                use_multi_pack_index: config.use_multi_pack_index,
+                current_dir: current_dir.to_owned().into(),
+            },
+        )?;
+        #[cfg(feature = "blocking-network-client")]
+        let objects = match crate::promisor::remote_name(&config.resolved, &mut filter_config_section) {
+            Some(remote_name) if lazy_fetch => objects.with_lazy_fetch({
+                let git_dir = git_dir.clone();
+                let work_dir = worktree_dir.clone();
+                let options = options.clone();
+                move |id| {
+                    crate::promisor::fetch::fetch_missing(
+                        git_dir.clone(),
+                        work_dir.clone(),
+                        remote_name.as_ref(),
+                        options.clone(),
+                        id,
+                    )
+                    .map_err(Into::into)
+                }
+            }),
+            _ => objects,
+        };
+
         Ok(ThreadSafeRepository {
-            objects: OwnShared::new(git_odb::Store::at_opts(
-                common_dir_ref.join("objects"),
-                replacements,
-                git_odb::store::init::Options {
-                    slots: object_store_slots,
-                    object_hash: config.object_hash,
-                    use_multi_pack_index: config.use_multi_pack_index,
-                    current_dir: current_dir.to_owned().into(),
-                },
-            )?),
+            objects: OwnShared::new(objects),
             common_dir,
             refs,
             work_tree: worktree_dir,
@@ -0,0 +1,104 @@
+//! Support for partial clones, i.e. repositories with objects omitted by a filter which can be obtained from a promisor remote on demand.
+
+/// Return the name of the promisor remote of a partial clone as configured in `extensions.partialClone`, or the first remote
+/// with `remote.<name>.promisor` set like `git` does, if there is one.
+#[cfg(feature = "blocking-network-client")]
+pub(crate) fn remote_name(
+    config: &git_config::File<'static>,
+    filter: &mut fn(&git_config::file::Metadata) -> bool,
+) -> Option<crate::bstr::BString> {
+    if let Some(name) = config
+        .string_filter("extensions", None, "partialClone", filter)
+        .filter(|name| !name.is_empty())
+    {
+        return Some(name.into_owned());
+    }
+    let remote_names: Vec<_> = config
+        .sections_by_name_and_filter("remote", &mut *filter)?
+        .filter_map(|section| section.header().subsection_name().map(ToOwned::to_owned))
+        .collect();
+    remote_names.into_iter().find(|name| {
+        matches!(
+            config.boolean_filter("remote", Some(name.as_ref()), "promisor", filter),
+            Some(Ok(true))
+        )
+    })
+}
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch {
+    pub(crate) use function::fetch_missing;
+
+    /// The error returned when obtaining a missing object from the promisor remote.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the repository to fetch into")]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        RefSpec(#[from] git_refspec::parse::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+        #[error("Failed to remove .keep file at \"{}\"", path.display())]
+        RemovePackKeepFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+    }
+
+    mod function {
+        use std::path::PathBuf;
+
+        use super::Error;
+        use crate::{
+            bstr::BStr,
+            remote,
+            remote::fetch::{Filter, Status, Tags},
+        };
+
+        /// Fetch the object with `id` from the promisor remote named `remote_name` into the repository at `git_dir` and `work_dir`,
+        /// which is opened with `options` for that purpose.
+        ///
+        /// Like `git`, only the object itself is requested without negotiating, and without fetching tags or updating any reference.
+        /// Blobs aren't fetched unless explicitly requested, which is why requesting a missing tree won't fetch the blobs it refers to.
+        pub fn fetch_missing(
+            git_dir: PathBuf,
+            work_dir: Option<PathBuf>,
+            remote_name: &BStr,
+            mut options: crate::open::Options,
+            id: &git_hash::oid,
+        ) -> Result<(), Error> {
+            // Don't fetch recursively if the fetch itself can't find an object.
+            options.lazy_fetch = false;
+            options
+                .api_config_overrides
+                .push("fetch.negotiationAlgorithm=noop".into());
+            let repo = crate::ThreadSafeRepository::open_from_paths(git_dir, work_dir, options)?.to_thread_local();
+
+            let mut remote = repo
+                .find_remote(remote_name)?
+                .with_fetch_tags(Tags::None)
+                .with_filter(Some(Filter::BlobNone));
+            remote.replace_refspecs(Some(id.to_string().as_str()), remote::Direction::Fetch)?;
+            let outcome = remote
+                .connect(remote::Direction::Fetch, git_features::progress::Discard)?
+                .prepare_fetch(Default::default())?
+                .receive(&std::sync::atomic::AtomicBool::default())?;
+
+            // Without ref updates, the pack isn't automatically unlocked.
+            if let Status::Change { write_pack_bundle, .. } = outcome.status {
+                if let Some(path) = write_pack_bundle.keep_path {
+                    std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -83,3 +83,35 @@ pub mod find {
         PackedRefsOpen(#[from] git_ref::packed::buffer::open::Error),
     }
 }
+
+///
+pub mod remote_tracking_ref {
+    use crate::bstr::BString;
+
+    /// The error returned by [Reference::remote_tracking_ref(…)][crate::Reference::remote_tracking_ref()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The name of the remote branch in 'branch.{branch}.merge' is invalid")]
+        InvalidMergeRef {
+            branch: BString,
+            source: git_validate::reference::name::Error,
+        },
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error("The remote branch {remote_ref:?} is not stored as a remote tracking branch as per the fetch ref-specs of remote {remote:?}")]
+        NoTrackingRef { remote_ref: BString, remote: BString },
+        #[error("The tracking branch {name:?} obtained from the ref-specs of remote {remote:?} is invalid")]
+        InvalidTrackingRef {
+            name: BString,
+            remote: BString,
+            source: git_validate::reference::name::Error,
+        },
+        #[error("The push ref-specs of remote {remote:?} do not include branch {branch:?}")]
+        UnmatchedPushSpecs { remote: BString, branch: BString },
+        #[error("Pushing to {push:?} is ambiguous with 'push.default=simple' as the upstream branch is {upstream:?}")]
+        AmbiguousSimplePush { upstream: BString, push: BString },
+        #[error("The value {value:?} of 'push.default' is invalid")]
+        InvalidPushDefault { value: BString },
+    }
+}
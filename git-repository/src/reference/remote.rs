@@ -1,6 +1,12 @@
 use std::convert::TryInto;
 
-use crate::{remote, Reference};
+use git_refspec::instruction::Push;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    reference::remote_tracking_ref::Error,
+    remote, Reference,
+};
 
 /// Remotes
 impl<'repo> Reference<'repo> {
@@ -48,4 +54,165 @@ impl<'repo> Reference<'repo> {
             }),
         })
     }
+
+    /// Return the full name of the remote tracking branch that corresponds to this local branch for `direction`, like
+    /// `refs/remotes/origin/main` for `refs/heads/main`, which is what `@{upstream}` and `@{push}` resolve to respectively.
+    ///
+    /// For _fetching_, it's the branch in `branch.<name>.merge` on the remote in `branch.<name>.remote`, mapped through the
+    /// fetch ref-specs of that remote, or the local branch in `branch.<name>.merge` itself if the remote is `.`.
+    ///
+    /// For _pushing_, it's the branch we would push to on the [remote for pushing][Self::remote_name()], or on the
+    /// [default remote][crate::Repository::remote_default_name()] if there is none, mapped through the fetch ref-specs
+    /// of that remote. The branch on the remote is determined by its push ref-specs, or by `push.default` if there are none.
+    ///
+    /// Return `None` if this isn't a local branch or if no remote tracking branch is configured for `direction`, which
+    /// includes `push.default=nothing`.
+    pub fn remote_tracking_ref(&self, direction: remote::Direction) -> Option<Result<git_ref::FullName, Error>> {
+        match direction {
+            remote::Direction::Fetch => self.upstream_tracking_ref(),
+            remote::Direction::Push => self.push_tracking_ref(),
+        }
+        .transpose()
+    }
+
+    fn local_branch_name(&self) -> Option<&BStr> {
+        match self.name().category_and_short_name()? {
+            (git_ref::Category::LocalBranch, name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn upstream_tracking_ref(&self) -> Result<Option<git_ref::FullName>, Error> {
+        let branch = match self.local_branch_name() {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+        let config = &self.repo.config.resolved;
+        let (remote_name, merge) = match (
+            config.string("branch", Some(branch), "remote"),
+            config.string("branch", Some(branch), "merge"),
+        ) {
+            (Some(remote_name), Some(merge)) => (remote_name, merge),
+            _ => return Ok(None),
+        };
+        if remote_name.as_ref() == "." {
+            return merge
+                .into_owned()
+                .try_into()
+                .map(Some)
+                .map_err(|err| Error::InvalidMergeRef {
+                    branch: branch.to_owned(),
+                    source: err,
+                });
+        }
+        let remote = self.repo.find_remote(remote_name.as_ref())?;
+        tracking_ref(&remote, merge.as_ref()).map(Some)
+    }
+
+    fn push_tracking_ref(&self) -> Result<Option<git_ref::FullName>, Error> {
+        if self.local_branch_name().is_none() {
+            return Ok(None);
+        }
+        let remote = match self.remote(remote::Direction::Push) {
+            Some(remote) => remote?,
+            None => match self.repo.remote_default_name(remote::Direction::Push) {
+                Some(name) => self.repo.find_remote(name.as_ref())?,
+                None => return Ok(None),
+            },
+        };
+        let branch_ref = self.name().as_bstr();
+        let push_specs = remote.refspecs(remote::Direction::Push);
+        if !push_specs.is_empty() {
+            let destination = push_specs
+                .iter()
+                .find_map(|spec| push_destination(spec.to_ref(), branch_ref))
+                .ok_or_else(|| Error::UnmatchedPushSpecs {
+                    remote: remote_name(&remote),
+                    branch: branch_ref.to_owned(),
+                })?;
+            return tracking_ref(&remote, destination.as_ref()).map(Some);
+        }
+
+        let config = &self.repo.config.resolved;
+        let is_mirror = remote
+            .name()
+            .and_then(|name| config.boolean("remote", Some(name.as_bstr()), "mirror"))
+            .map_or(false, |res| res.unwrap_or(false));
+        if is_mirror {
+            return tracking_ref(&remote, branch_ref).map(Some);
+        }
+        let push_default = config.string("push", None, "default");
+        match push_default.as_ref().map(|value| value.as_bytes()) {
+            Some(b"nothing") => Ok(None),
+            Some(b"matching" | b"current") => tracking_ref(&remote, branch_ref).map(Some),
+            Some(b"upstream" | b"tracking") => self.upstream_tracking_ref(),
+            None | Some(b"simple") => {
+                let upstream = match self.upstream_tracking_ref()? {
+                    Some(upstream) => upstream,
+                    None => return Ok(None),
+                };
+                let push = tracking_ref(&remote, branch_ref)?;
+                if push != upstream {
+                    return Err(Error::AmbiguousSimplePush {
+                        upstream: upstream.into_inner(),
+                        push: push.into_inner(),
+                    });
+                }
+                Ok(Some(push))
+            }
+            Some(_) => Err(Error::InvalidPushDefault {
+                value: push_default.expect("set").into_owned(),
+            }),
+        }
+    }
+}
+
+/// Map the reference named `remote_ref` on `remote` to the local tracking branch as per the fetch ref-specs of `remote`.
+fn tracking_ref(remote: &crate::Remote<'_>, remote_ref: &BStr) -> Result<git_ref::FullName, Error> {
+    let null_id = git_hash::ObjectId::null(remote.repo().object_hash());
+    let outcome =
+        git_refspec::MatchGroup::from_fetch_specs(remote.refspecs(remote::Direction::Fetch).iter().map(|s| s.to_ref()))
+            .match_remotes(std::iter::once(git_refspec::match_group::Item {
+                full_ref_name: remote_ref,
+                target: &null_id,
+                object: None,
+            }));
+    let name = outcome
+        .mappings
+        .into_iter()
+        .find_map(|mapping| mapping.rhs)
+        .ok_or_else(|| Error::NoTrackingRef {
+            remote_ref: remote_ref.to_owned(),
+            remote: remote_name(remote),
+        })?;
+    name.clone()
+        .into_owned()
+        .try_into()
+        .map_err(|err| Error::InvalidTrackingRef {
+            name: name.into_owned(),
+            remote: remote_name(remote),
+            source: err,
+        })
+}
+
+/// Return the name of the reference on the remote that the push `spec` would update with `local_ref`, if any.
+fn push_destination(spec: git_refspec::RefSpecRef<'_>, local_ref: &BStr) -> Option<BString> {
+    match spec.instruction() {
+        git_refspec::Instruction::Push(Push::Matching { src, dst, .. }) => match src.find_byte(b'*') {
+            Some(_) => remote::matches_pattern(src, local_ref).map(|matched| {
+                let star = dst.find_byte(b'*').expect("patterns are validated to be on both sides");
+                let mut name: BString = dst[..star].into();
+                name.push_str(matched);
+                name.push_str(&dst[star + 1..]);
+                name
+            }),
+            None => (src == local_ref).then(|| dst.to_owned()),
+        },
+        git_refspec::Instruction::Push(Push::AllMatchingBranches { .. }) => Some(local_ref.to_owned()),
+        git_refspec::Instruction::Push(Push::Delete { .. }) | git_refspec::Instruction::Fetch(_) => None,
+    }
+}
+
+fn remote_name(remote: &crate::Remote<'_>) -> BString {
+    remote.name().map(|name| name.as_bstr().to_owned()).unwrap_or_default()
 }
@@ -27,6 +27,17 @@ impl<'repo> Remote<'repo> {
         self.fetch_tags
     }
 
+    /// Return the filter applied to objects when fetching from this remote, if this is a partial clone.
+    pub fn filter(&self) -> Option<remote::fetch::Filter> {
+        self.filter
+    }
+
+    /// Return true if this is a _promisor_ remote, i.e. a remote from which objects that are missing locally can be obtained
+    /// on demand, as configured with `remote.<name>.promisor` or implied by a [filter][Self::filter()].
+    pub fn is_promisor(&self) -> bool {
+        self.promisor
+    }
+
     /// Return the url used for the given `direction` with rewrites from `url.<base>.insteadOf|pushInsteadOf`, unless the instance
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
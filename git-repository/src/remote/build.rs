@@ -29,6 +29,15 @@ impl Remote<'_> {
         self
     }
 
+    /// Set the `filter` to apply to objects when fetching to obtain a partial clone, or `None` to fetch all objects.
+    ///
+    /// Setting a filter also makes this remote a _promisor_ remote that missing objects can be fetched from on demand.
+    pub fn with_filter(mut self, filter: Option<remote::fetch::Filter>) -> Self {
+        self.promisor |= filter.is_some();
+        self.filter = filter;
+        self
+    }
+
     fn push_url_inner<Url, E>(mut self, push_url: Url, should_rewrite_urls: bool) -> Result<Self, remote::init::Error>
     where
         Url: TryInto<git_url::Url, Error = E>,
@@ -65,7 +65,7 @@ impl<'repo> Remote<'repo> {
     /// Connect to the url suitable for `direction` and return a handle through which operations can be performed.
     ///
     /// Note that the `protocol.version` configuration key affects the transport protocol used to connect,
-    /// with `2` being the default.
+    /// with `2` being the default. When pushing, protocol `1` is always used as `git-receive-pack` doesn't support any other.
     ///
     /// The transport used for connection can be configured via `transport_mut().configure()` assuming the actually
     /// used transport is well known. If that's not the case, the transport can be created by hand and passed to
@@ -119,24 +119,27 @@ impl<'repo> Remote<'repo> {
         }
 
         use git_protocol::transport::Protocol;
-        let version = self
-            .repo
-            .config
-            .resolved
-            .integer("protocol", None, "version")
-            .unwrap_or(Ok(2))
-            .map_err(|err| Error::UnknownProtocol { given: err.input })
-            .and_then(|num| {
-                Ok(match num {
-                    1 => Protocol::V1,
-                    2 => Protocol::V2,
-                    num => {
-                        return Err(Error::UnknownProtocol {
-                            given: num.to_string().into(),
-                        })
-                    }
-                })
-            })?;
+        let version = if direction == crate::remote::Direction::Push {
+            Protocol::V1
+        } else {
+            self.repo
+                .config
+                .resolved
+                .integer("protocol", None, "version")
+                .unwrap_or(Ok(2))
+                .map_err(|err| Error::UnknownProtocol { given: err.input })
+                .and_then(|num| {
+                    Ok(match num {
+                        1 => Protocol::V1,
+                        2 => Protocol::V2,
+                        num => {
+                            return Err(Error::UnknownProtocol {
+                                given: num.to_string().into(),
+                            })
+                        }
+                    })
+                })?
+        };
 
         let url = self.url(direction).ok_or(Error::MissingUrl { direction })?.to_owned();
         if !self.repo.config.url_scheme()?.allow(&url.scheme) {
@@ -1,7 +1,7 @@
 use std::convert::TryInto;
 
 use super::Error;
-use crate::Repository;
+use crate::{remote::fetch::negotiate, Repository};
 
 pub fn index_threads(repo: &Repository) -> Result<Option<usize>, Error> {
     let lenient_config = repo.options.lenient_config;
@@ -65,3 +65,32 @@ pub fn pack_index_version(repo: &Repository) -> Result<git_pack::index::Version,
         },
     )
 }
+
+pub fn negotiation_algorithm(repo: &Repository) -> Result<negotiate::Algorithm, Error> {
+    use crate::bstr::ByteSlice;
+    use negotiate::Algorithm;
+    let config = &repo.config.resolved;
+    let filter = &mut repo.filter_config_section();
+    let default = match config.boolean_filter("feature", None, "experimental", filter) {
+        Some(Ok(true)) => Algorithm::Skipping,
+        _ => Algorithm::default(),
+    };
+    Ok(
+        match config
+            .string_filter("fetch", None, "negotiationAlgorithm", filter)
+            .as_deref()
+            .map(|v| v.as_bytes())
+        {
+            None | Some(b"default") => default,
+            Some(b"noop") => Algorithm::Noop,
+            Some(b"consecutive") => Algorithm::Consecutive,
+            Some(b"skipping") => Algorithm::Skipping,
+            Some(_) if repo.options.lenient_config => default,
+            Some(value) => {
+                return Err(Error::UnknownNegotiationAlgorithm {
+                    value: value.as_bstr().into(),
+                })
+            }
+        },
+    )
+}
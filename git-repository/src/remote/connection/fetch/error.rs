@@ -8,6 +8,8 @@ pub enum Error {
         desired: Option<i64>,
         source: Option<git_config::value::Error>,
     },
+    #[error("The value of fetch.negotiationAlgorithm must be one of 'noop', 'consecutive', 'skipping' or 'default', got {value:?}")]
+    UnknownNegotiationAlgorithm { value: crate::bstr::BString },
     #[error("Could not decode server reply")]
     FetchResponse(#[from] git_protocol::fetch::response::Error),
     #[error("Cannot fetch from a remote that uses {remote} while local repository uses {local} for object hashes")]
@@ -23,6 +25,22 @@ pub enum Error {
     WritePack(#[from] git_pack::bundle::write::Error),
     #[error(transparent)]
     UpdateRefs(#[from] super::refs::update::Error),
+    #[error("The remote doesn't support {feature:?}, which is required for {description}")]
+    MissingServerFeature {
+        feature: &'static str,
+        description: &'static str,
+    },
+    #[error("Could not acquire the lock on the shallow file to update it")]
+    LockShallowFile(#[from] git_lock::acquire::Error),
+    #[error("Could not read the shallow file to learn about the current shallow boundary")]
+    ReadShallowFile(#[from] crate::shallow::open::Error),
+    #[error("Could not write the updated shallow boundary to the shallow file")]
+    WriteShallowFile(#[from] crate::shallow::write::Error),
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error("Failed to remove .keep file at \"{}\"", path.display())]
     RemovePackKeepFile {
         path: std::path::PathBuf,
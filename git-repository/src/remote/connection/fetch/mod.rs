@@ -130,6 +130,7 @@ where
             dry_run: DryRun::No,
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
+            shallow: Default::default(),
         })
     }
 }
@@ -160,6 +161,7 @@ where
     dry_run: DryRun,
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
+    shallow: remote::fetch::Shallow,
 }
 
 /// Builder
@@ -189,6 +191,15 @@ where
         self.reflog_message = reflog_message.into();
         self
     }
+
+    /// Define how to handle the shallow boundary of the repository, which allows to make complete repositories shallow,
+    /// change the boundary of shallow ones or make them complete again.
+    ///
+    /// By default, shallow repositories remain shallow and complete repositories remain complete.
+    pub fn with_shallow(mut self, shallow: remote::fetch::Shallow) -> Self {
+        self.shallow = shallow;
+        self
+    }
 }
 
 impl<'remote, 'repo, T, P> Drop for Prepare<'remote, 'repo, T, P>
@@ -0,0 +1,106 @@
+use git_hash::ObjectId;
+
+use super::{Flags, Graph, Negotiator, Queue, COMMON, COMMON_REF, POPPED, SEEN};
+
+/// A port of `git`'s `consecutive` negotiation algorithm, which sends all local commits as `have`, newest first,
+/// and stops walking the history of commits the remote has acknowledged.
+#[derive(Default)]
+pub(crate) struct Algorithm {
+    revs: Queue,
+    non_common_revs: usize,
+}
+
+impl Algorithm {
+    /// Put `id` into the queue if it doesn't have any of the flags in `mark` yet, and add `mark` to its flags.
+    fn add_to_queue(&mut self, id: ObjectId, mark: Flags, graph: &mut Graph<'_>) {
+        if let Some(commit) = graph.commit(id) {
+            if commit.flags & mark == 0 {
+                commit.flags |= mark;
+                self.revs.insert(commit.time, id);
+                if commit.flags & COMMON == 0 {
+                    self.non_common_revs += 1;
+                }
+            }
+        }
+    }
+
+    /// Mark `id` as common, or only its ancestors if `ancestors_only` is set, and queue it if it wasn't seen yet.
+    fn mark_common(&mut self, id: ObjectId, ancestors_only: bool, graph: &mut Graph<'_>) {
+        let mut stack = vec![(id, ancestors_only)];
+        while let Some((id, ancestors_only)) = stack.pop() {
+            let (flags, parents) = match graph.commit(id) {
+                Some(commit) if commit.flags & COMMON == 0 => {
+                    if !ancestors_only {
+                        commit.flags |= COMMON;
+                    }
+                    (commit.flags, commit.parents.clone())
+                }
+                _ => continue,
+            };
+            if flags & SEEN == 0 {
+                self.add_to_queue(id, SEEN, graph);
+            } else {
+                if !ancestors_only && flags & POPPED == 0 {
+                    self.non_common_revs = self.non_common_revs.saturating_sub(1);
+                }
+                stack.extend(parents.into_iter().map(|id| (id, false)));
+            }
+        }
+    }
+}
+
+impl Negotiator for Algorithm {
+    fn known_common(&mut self, id: ObjectId, graph: &mut Graph<'_>) {
+        if graph.flags(id) & SEEN == 0 {
+            self.add_to_queue(id, COMMON_REF | SEEN, graph);
+            self.mark_common(id, true, graph);
+        }
+    }
+
+    fn add_tip(&mut self, id: ObjectId, graph: &mut Graph<'_>) {
+        self.add_to_queue(id, SEEN, graph);
+    }
+
+    fn next_have(&mut self, graph: &mut Graph<'_>) -> Option<ObjectId> {
+        loop {
+            if self.non_common_revs == 0 {
+                return None;
+            }
+            let id = self.revs.pop()?;
+            let commit = graph.commit(id).expect("queued commits are present");
+            commit.flags |= POPPED;
+            let flags = commit.flags;
+            let parents = commit.parents.clone();
+            if flags & COMMON == 0 {
+                self.non_common_revs -= 1;
+            }
+
+            let (to_send, mark) = if flags & COMMON != 0 {
+                // Don't send it, and ignore all ancestors.
+                (None, COMMON | SEEN)
+            } else if flags & COMMON_REF != 0 {
+                // Send it, but ignore all ancestors.
+                (Some(id), COMMON | SEEN)
+            } else {
+                (Some(id), SEEN)
+            };
+            for parent_id in parents {
+                if graph.flags(parent_id) & SEEN == 0 {
+                    self.add_to_queue(parent_id, mark, graph);
+                }
+                if mark & COMMON != 0 {
+                    self.mark_common(parent_id, true, graph);
+                }
+            }
+            if to_send.is_some() {
+                return to_send;
+            }
+        }
+    }
+
+    fn in_common_with_remote(&mut self, id: ObjectId, graph: &mut Graph<'_>) -> bool {
+        let known_to_be_common = graph.flags(id) & COMMON != 0;
+        self.mark_common(id, false, graph);
+        known_to_be_common
+    }
+}
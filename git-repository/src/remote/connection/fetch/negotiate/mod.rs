@@ -0,0 +1,418 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use git_date::SecondsSinceUnixEpoch;
+use git_hash::ObjectId;
+use git_odb::Find;
+use smallvec::SmallVec;
+
+use crate::remote::fetch::{Shallow, Tags};
+
+mod consecutive;
+mod skipping;
+
+/// The way the negotiation is performed, as configured by `fetch.negotiationAlgorithm`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+    /// Do not send any `have`s at all, and let the server send everything reachable from our `want`s.
+    Noop,
+    /// Walk our local history commit by commit, newest first, and send each as `have`. This is `git`'s default.
+    Consecutive,
+    /// Like [`Consecutive`][Algorithm::Consecutive], but skip commits in exponentially growing steps
+    /// until the server acknowledges a common commit, which converges faster on long histories.
+    Skipping,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Consecutive
+    }
+}
+
+/// The error returned during negotiation.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("We were unable to figure out what objects the server should send after {rounds} round(s)")]
+    NegotiationFailed { rounds: usize },
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+}
+
+/// The amount of `have`s to send in the first round.
+const INITIAL_FLUSH: usize = 16;
+/// The amount of `have`s that can be sent without a response on a stateful connection without risking a deadlock.
+const PIPESAFE_FLUSH: usize = 32;
+/// The amount of `have`s after which we stop doubling their amount per round on stateless connections.
+const LARGE_FLUSH: usize = 16384;
+/// The amount of `have`s we are willing to send after the last acknowledgement before giving up.
+const MAX_IN_VAIN: usize = 256;
+
+type Flags = u8;
+/// The commit is reachable from one of our local references.
+const COMPLETE: Flags = 1 << 0;
+/// The commit is known to be present on the remote.
+const COMMON: Flags = 1 << 1;
+/// The commit is advertised by the remote and present locally, but the remote doesn't know it yet.
+const COMMON_REF: Flags = 1 << 2;
+/// The commit was put into the queue of commits to consider for sending.
+const SEEN: Flags = 1 << 3;
+/// The commit was taken from the queue of commits to consider for sending.
+const POPPED: Flags = 1 << 4;
+
+/// A commit as needed for negotiation.
+struct Commit {
+    time: SecondsSinceUnixEpoch,
+    parents: SmallVec<[ObjectId; 2]>,
+    flags: Flags,
+}
+
+/// A lazily populated commit graph, to know the commit time and parents of local commits and keep flags for each of them.
+pub(crate) struct Graph<'repo> {
+    repo: &'repo crate::Repository,
+    /// The object database of `repo`, which doesn't fetch missing objects in partial clones as these are expected to be missing.
+    objects: crate::OdbHandle,
+    buf: Vec<u8>,
+    /// All commits we tried to look up so far, or `None` if they aren't present locally or aren't commits.
+    commits: git_hashtable::HashMap<ObjectId, Option<Commit>>,
+    /// Annotated tags which are reachable from our local references.
+    complete_tags: git_hashtable::HashSet<ObjectId>,
+}
+
+impl<'repo> Graph<'repo> {
+    fn new(repo: &'repo crate::Repository) -> Self {
+        let mut objects = repo.objects.clone();
+        objects.skip_lazy_fetch = true;
+        Graph {
+            repo,
+            objects,
+            buf: Vec::new(),
+            commits: Default::default(),
+            complete_tags: Default::default(),
+        }
+    }
+
+    /// Return the commit at `id`, or `None` if it doesn't exist locally or can't be decoded.
+    fn commit(&mut self, id: ObjectId) -> Option<&mut Commit> {
+        if !self.commits.contains_key(&id) {
+            let commit = self.lookup(id);
+            self.commits.insert(id, commit);
+        }
+        self.commits.get_mut(&id).and_then(Option::as_mut)
+    }
+
+    /// Return the flags of the commit at `id`, or `0` if it doesn't exist.
+    fn flags(&mut self, id: ObjectId) -> Flags {
+        self.commit(id).map_or(0, |c| c.flags)
+    }
+
+    fn lookup(&mut self, id: ObjectId) -> Option<Commit> {
+        use git_object::commit::ref_iter::Token;
+        let data = self.objects.try_find(id, &mut self.buf).ok()??;
+        let iter = data.try_into_commit_iter()?;
+        let mut parents = SmallVec::new();
+        for token in iter {
+            match token.ok()? {
+                Token::Tree { .. } => continue,
+                Token::Parent { id } => parents.push(id),
+                Token::Author { .. } => continue,
+                Token::Committer { signature } => {
+                    return Some(Commit {
+                        time: signature.time.seconds_since_unix_epoch,
+                        parents,
+                        flags: 0,
+                    })
+                }
+                _ => break,
+            }
+        }
+        None
+    }
+
+    /// Follow annotated tags starting at `id` and return the first object that isn't a tag,
+    /// marking all tags on the way as complete if `mark_complete` is set.
+    fn peel_tags(&mut self, mut id: ObjectId, mark_complete: bool) -> ObjectId {
+        while let Ok(Some(data)) = self.objects.try_find(id, &mut self.buf) {
+            match data.try_into_tag_iter().and_then(|tag| tag.target_id().ok()) {
+                Some(target) => {
+                    if mark_complete {
+                        self.complete_tags.insert(id);
+                    }
+                    id = target;
+                }
+                None => break,
+            }
+        }
+        id
+    }
+
+    fn is_complete(&mut self, id: ObjectId) -> bool {
+        self.complete_tags.contains(&id) || self.flags(id) & COMPLETE != 0
+    }
+}
+
+/// A queue of commits sorted by commit time, newest first, and commits of the same time in insertion order.
+#[derive(Default)]
+struct Queue {
+    inner: BinaryHeap<(SecondsSinceUnixEpoch, Reverse<usize>, ObjectId)>,
+    count: usize,
+}
+
+impl Queue {
+    fn insert(&mut self, time: SecondsSinceUnixEpoch, id: ObjectId) {
+        self.inner.push((time, Reverse(self.count), id));
+        self.count += 1;
+    }
+
+    fn pop(&mut self) -> Option<ObjectId> {
+        self.inner.pop().map(|(_, _, id)| id)
+    }
+
+    fn peek_time(&self) -> Option<SecondsSinceUnixEpoch> {
+        self.inner.peek().map(|(time, _, _)| *time)
+    }
+}
+
+/// The interface implemented by all negotiation algorithms, which decides which commits to send as `have` in which order.
+trait Negotiator {
+    /// Mark `id` as common with the remote, as the remote advertised it and we have it locally.
+    fn known_common(&mut self, id: ObjectId, graph: &mut Graph<'_>);
+    /// Add `id` as starting point of the walk through our local history.
+    fn add_tip(&mut self, id: ObjectId, graph: &mut Graph<'_>);
+    /// Produce the next commit to send as `have`, or `None` if there is nothing more to send.
+    fn next_have(&mut self, graph: &mut Graph<'_>) -> Option<ObjectId>;
+    /// Handle an acknowledgement of the remote for `id` and return `true` if it was already known to be common.
+    fn in_common_with_remote(&mut self, id: ObjectId, graph: &mut Graph<'_>) -> bool;
+}
+
+/// The negotiator for [`Algorithm::Noop`].
+struct Noop;
+
+impl Negotiator for Noop {
+    fn known_common(&mut self, _id: ObjectId, _graph: &mut Graph<'_>) {}
+
+    fn add_tip(&mut self, _id: ObjectId, _graph: &mut Graph<'_>) {}
+
+    fn next_have(&mut self, _graph: &mut Graph<'_>) -> Option<ObjectId> {
+        None
+    }
+
+    fn in_common_with_remote(&mut self, _id: ObjectId, _graph: &mut Graph<'_>) -> bool {
+        false
+    }
+}
+
+/// The state of a negotiation that is kept across rounds.
+pub(crate) struct State<'repo> {
+    graph: Graph<'repo>,
+    negotiator: Box<dyn Negotiator>,
+    /// If `true`, the server doesn't remember what we told it in previous rounds.
+    stateless: bool,
+    /// The amount of `have`s to send in the next round.
+    haves_to_send: usize,
+    /// The amount of `have`s sent since the last time the server acknowledged a common commit.
+    in_vain: usize,
+    seen_ack: bool,
+    /// All commits the server acknowledged as common, which have to be sent again on stateless connections.
+    common: Vec<ObjectId>,
+}
+
+impl<'repo> State<'repo> {
+    /// Prepare a negotiation with `algo` for `repo`, with `stateless` being `true` if the server doesn't remember
+    /// the `have`s of previous rounds, which is the case in protocol V2 or if the connection doesn't persist across requests.
+    pub(crate) fn new(algo: Algorithm, repo: &'repo crate::Repository, stateless: bool) -> Self {
+        State {
+            graph: Graph::new(repo),
+            negotiator: match algo {
+                Algorithm::Noop => Box::new(Noop),
+                Algorithm::Consecutive => Box::new(consecutive::Algorithm::default()),
+                Algorithm::Skipping => Box::new(skipping::Algorithm::default()),
+            },
+            stateless,
+            haves_to_send: INITIAL_FLUSH,
+            in_vain: 0,
+            seen_ack: false,
+            common: Vec::new(),
+        }
+    }
+
+    /// Return the amount of `have`s to send in the round after one with `count` `have`s.
+    fn next_flush(&self, count: usize) -> usize {
+        if self.stateless {
+            if count < LARGE_FLUSH {
+                count << 1
+            } else {
+                count * 11 / 10
+            }
+        } else if count < PIPESAFE_FLUSH {
+            count << 1
+        } else {
+            count + PIPESAFE_FLUSH
+        }
+    }
+}
+
+/// Negotiate one round with the negotiation `state` by looking at `ref_map` and adjust `arguments` to contain the haves and wants.
+/// If this is not the first round, the `previous_response` is set with the last recorded server response.
+/// Returns `true` if the negotiation is done from our side so the server won't keep asking.
+///
+/// In the first round, we determine which of the remote refs we already have by walking back from our local refs, and `want`
+/// all others. If we don't want anything, `arguments` will be empty after this call.
+/// If `shallow` changes the shallow boundary, all remote refs are wanted as the server has to compute the new boundary for them.
+pub(crate) fn one_round(
+    state: &mut State<'_>,
+    round: usize,
+    ref_map: &crate::remote::fetch::RefMap,
+    fetch_tags: Tags,
+    shallow: &Shallow,
+    arguments: &mut git_protocol::fetch::Arguments,
+    previous_response: Option<&git_protocol::fetch::Response>,
+) -> Result<bool, Error> {
+    if round == 1 {
+        let has_wants = mark_complete_and_common_ref(state, ref_map, fetch_tags, shallow, arguments)?;
+        if !has_wants {
+            return Ok(true);
+        }
+    }
+
+    if let Some(response) = previous_response {
+        let mut received_ready = false;
+        for ack in response.acknowledgements() {
+            match ack {
+                git_protocol::fetch::response::Acknowledgement::Common(id) => {
+                    if !state.common.contains(id) {
+                        state.common.push(*id);
+                    }
+                    let was_known_to_be_common = state.negotiator.in_common_with_remote(*id, &mut state.graph);
+                    // Only progress, i.e. learning about a new common commit, allows to send more haves.
+                    if !was_known_to_be_common {
+                        state.in_vain = 0;
+                    }
+                    state.seen_ack = true;
+                }
+                git_protocol::fetch::response::Acknowledgement::Ready => received_ready = true,
+                git_protocol::fetch::response::Acknowledgement::Nak => {}
+            }
+        }
+        if received_ready {
+            // The server knows enough to produce a pack, which it will send once we say we are done.
+            if state.stateless {
+                for id in &state.common {
+                    arguments.have(id);
+                }
+            }
+            return Ok(true);
+        }
+    }
+
+    if state.stateless {
+        for id in &state.common {
+            arguments.have(id);
+        }
+    }
+    let mut haves_added = 0;
+    while haves_added < state.haves_to_send {
+        match state.negotiator.next_have(&mut state.graph) {
+            Some(id) => {
+                arguments.have(id);
+                haves_added += 1;
+            }
+            None => break,
+        }
+    }
+    state.haves_to_send = state.next_flush(state.haves_to_send);
+    state.in_vain += haves_added;
+
+    Ok(haves_added == 0 || (state.seen_ack && state.in_vain >= MAX_IN_VAIN))
+}
+
+/// Mark all commits reachable from local refs as complete if they are at least as recent as the most recent remote ref
+/// we have locally, and tell the negotiator about all remote refs that are complete.
+/// Then `want` all remote refs that aren't complete and add all local refs as tips to start negotiating from.
+/// Like `git`, nothing is marked complete if `shallow` changes the shallow boundary.
+///
+/// Returns `true` if there was at least one `want`.
+fn mark_complete_and_common_ref(
+    state: &mut State<'_>,
+    ref_map: &crate::remote::fetch::RefMap,
+    fetch_tags: Tags,
+    shallow: &Shallow,
+    arguments: &mut git_protocol::fetch::Arguments,
+) -> Result<bool, Error> {
+    let deepen = !matches!(shallow, Shallow::NoChange);
+    let tag_refspec_to_ignore = fetch_tags.to_refspec().filter(|_| matches!(fetch_tags, Tags::Included));
+    let remote_ids: Vec<_> = ref_map
+        .mappings
+        .iter()
+        .filter(|mapping| {
+            !tag_refspec_to_ignore.map_or(false, |tag_spec| {
+                mapping
+                    .spec_index
+                    .implicit_index()
+                    .and_then(|idx| ref_map.extra_refspecs.get(idx))
+                    .map_or(false, |spec| spec.to_ref() == tag_spec)
+            })
+        })
+        .filter_map(|mapping| mapping.remote.as_id().map(ToOwned::to_owned))
+        .collect();
+
+    let graph = &mut state.graph;
+    let cutoff = remote_ids
+        .iter()
+        .filter_map(|id| graph.commit(*id).map(|c| c.time))
+        .max();
+
+    let mut local_tips = Vec::new();
+    let mut queue = Queue::default();
+    let repo = graph.repo;
+    for reference in repo.references()?.all()?.filter_map(Result::ok) {
+        let id = match reference.target().try_id() {
+            Some(id) => id.to_owned(),
+            None => continue,
+        };
+        let id = graph.peel_tags(id, !deepen);
+        if let Some(commit) = graph.commit(id) {
+            if !deepen && commit.flags & COMPLETE == 0 {
+                commit.flags |= COMPLETE;
+                queue.insert(commit.time, id);
+            }
+            local_tips.push(id);
+        }
+    }
+
+    if let Some(cutoff) = cutoff {
+        while queue.peek_time().map_or(false, |time| time >= cutoff) {
+            let id = queue.pop().expect("just peeked");
+            let parents = graph.commit(id).map(|c| c.parents.clone()).unwrap_or_default();
+            for parent_id in parents {
+                if let Some(parent) = graph.commit(parent_id) {
+                    if parent.flags & COMPLETE == 0 {
+                        parent.flags |= COMPLETE;
+                        queue.insert(parent.time, parent_id);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut has_wants = false;
+    let mut wanted = git_hashtable::HashSet::default();
+    for id in remote_ids {
+        if graph.is_complete(id) {
+            if graph.flags(id) & COMPLETE != 0 {
+                state.negotiator.known_common(id, graph);
+            }
+        } else if wanted.insert(id) {
+            arguments.want(id);
+            has_wants = true;
+        }
+    }
+
+    if has_wants {
+        for id in local_tips {
+            state.negotiator.add_tip(id, graph);
+        }
+    }
+    Ok(has_wants)
+}
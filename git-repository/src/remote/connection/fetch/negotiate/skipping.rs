@@ -0,0 +1,151 @@
+use git_hash::ObjectId;
+
+use super::{Flags, Graph, Negotiator, Queue, COMMON, COMMON_REF, POPPED, SEEN};
+
+/// The skip state of a commit in the queue.
+#[derive(Default, Clone, Copy)]
+struct Entry {
+    original_ttl: u16,
+    /// The amount of commits to skip before sending one as `have`.
+    ttl: u16,
+}
+
+/// A port of `git`'s `skipping` negotiation algorithm, which sends local commits as `have` while skipping an exponentially
+/// growing amount of ancestors in between, until the remote acknowledges one of them.
+#[derive(Default)]
+pub(crate) struct Algorithm {
+    revs: Queue,
+    /// The skip state of all commits in `revs`.
+    entries: git_hashtable::HashMap<ObjectId, Entry>,
+    non_common_revs: usize,
+}
+
+impl Algorithm {
+    /// Put `id` into the queue and add `mark` to its flags.
+    fn add_to_queue(&mut self, id: ObjectId, mark: Flags, graph: &mut Graph<'_>) -> bool {
+        match graph.commit(id) {
+            Some(commit) => {
+                commit.flags |= mark | SEEN;
+                self.revs.insert(commit.time, id);
+                self.entries.insert(id, Entry::default());
+                if mark & COMMON == 0 {
+                    self.non_common_revs += 1;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Mark `id` and all of its ancestors that were seen already as common.
+    fn mark_common(&mut self, id: ObjectId, graph: &mut Graph<'_>) {
+        match graph.commit(id) {
+            Some(commit) if commit.flags & COMMON == 0 => commit.flags |= COMMON,
+            _ => return,
+        }
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let commit = graph.commit(id).expect("only present commits are on the stack");
+            if commit.flags & POPPED == 0 {
+                self.non_common_revs = self.non_common_revs.saturating_sub(1);
+            }
+            for parent_id in commit.parents.clone() {
+                if let Some(parent) = graph.commit(parent_id) {
+                    if parent.flags & SEEN == 0 || parent.flags & COMMON != 0 {
+                        continue;
+                    }
+                    parent.flags |= COMMON;
+                    stack.push(parent_id);
+                }
+            }
+        }
+    }
+
+    /// Queue `parent_id` of the commit at `id` with the skip state `entry`, or update its skip state if it is queued already.
+    /// Return `true` if the parent is in the queue.
+    fn push_parent(&mut self, id: ObjectId, entry: Entry, parent_id: ObjectId, graph: &mut Graph<'_>) -> bool {
+        let parent_flags = match graph.commit(parent_id) {
+            Some(parent) => parent.flags,
+            None => return false,
+        };
+        if parent_flags & SEEN != 0 {
+            if parent_flags & POPPED != 0 {
+                // The parent was popped already due to clock skew, so pretend it doesn't exist.
+                return false;
+            }
+        } else {
+            self.add_to_queue(parent_id, 0, graph);
+        }
+
+        if graph.flags(id) & (COMMON | COMMON_REF) != 0 {
+            self.mark_common(parent_id, graph);
+        } else {
+            let new_original_ttl = if entry.ttl > 0 {
+                entry.original_ttl
+            } else {
+                (entry.original_ttl.saturating_mul(3) / 2).saturating_add(1)
+            };
+            let new_ttl = if entry.ttl > 0 { entry.ttl - 1 } else { new_original_ttl };
+            let parent_entry = self.entries.get_mut(&parent_id).expect("queued commits have an entry");
+            if parent_entry.original_ttl < new_original_ttl {
+                parent_entry.original_ttl = new_original_ttl;
+                parent_entry.ttl = new_ttl;
+            }
+        }
+        true
+    }
+}
+
+impl Negotiator for Algorithm {
+    fn known_common(&mut self, id: ObjectId, graph: &mut Graph<'_>) {
+        if graph.flags(id) & SEEN == 0 {
+            self.add_to_queue(id, COMMON_REF, graph);
+        }
+    }
+
+    fn add_tip(&mut self, id: ObjectId, graph: &mut Graph<'_>) {
+        if graph.flags(id) & SEEN == 0 {
+            self.add_to_queue(id, 0, graph);
+        }
+    }
+
+    fn next_have(&mut self, graph: &mut Graph<'_>) -> Option<ObjectId> {
+        loop {
+            if self.non_common_revs == 0 {
+                return None;
+            }
+            let id = self.revs.pop()?;
+            let entry = self.entries.remove(&id).expect("queued commits have an entry");
+            let commit = graph.commit(id).expect("queued commits are present");
+            commit.flags |= POPPED;
+            let parents = commit.parents.clone();
+            let is_common = commit.flags & COMMON != 0;
+            if !is_common {
+                self.non_common_revs -= 1;
+            }
+
+            let mut to_send = (!is_common && entry.ttl == 0).then(|| id);
+            let mut parent_pushed = false;
+            for parent_id in parents {
+                parent_pushed |= self.push_parent(id, entry, parent_id, graph);
+            }
+            if !is_common && !parent_pushed {
+                // This commit has no parents, or all of them were popped already due to clock skew, so send it anyway.
+                to_send = Some(id);
+            }
+            if to_send.is_some() {
+                return to_send;
+            }
+        }
+    }
+
+    fn in_common_with_remote(&mut self, id: ObjectId, graph: &mut Graph<'_>) -> bool {
+        let flags = graph.flags(id);
+        if flags & SEEN == 0 {
+            // We never sent this commit as `have`, so it can't be acknowledged either.
+            return false;
+        }
+        self.mark_common(id, graph);
+        flags & COMMON != 0
+    }
+}
@@ -6,9 +6,9 @@ use git_protocol::transport::client::Transport;
 use crate::{
     remote,
     remote::{
-        connection::fetch::config,
+        connection::{fetch::config, setup_remote_progress},
         fetch,
-        fetch::{negotiate, refs, Error, Outcome, Prepare, RefLogMessage, Status},
+        fetch::{negotiate, refs, Error, Outcome, Prepare, RefLogMessage, Shallow, Status},
     },
     Progress,
 };
@@ -26,7 +26,24 @@ where
     /// ### Negotiation
     ///
     /// "fetch.negotiationAlgorithm" describes algorithms `git` uses currently, with the default being `consecutive` and `skipping` being
-    /// experimented with. We currently implement something we could call 'naive' which works for now.
+    /// experimented with, and both are implemented here. `noop` is supported as well, and `feature.experimental` makes
+    /// `skipping` the default just like in `git`.
+    ///
+    /// Negotiation takes multiple rounds if needed, with the amount of `have`s sent increasing in each round until the remote
+    /// signals that it is ready to send a pack, or we run out of commits to offer.
+    ///
+    /// ### Shallow clones
+    ///
+    /// The [shallow boundary][Prepare::with_shallow()] is sent to the remote along with the commits that are already shallow,
+    /// and the shallow commits it sends back are recorded in the `shallow` file, which is locked for the duration of the fetch.
+    /// Deepening the history or making a shallow repository complete again works the same way.
+    ///
+    /// ### Partial clones
+    ///
+    /// If the remote has a [filter][crate::Remote::filter()], it's sent to the remote to omit objects, which fails if the remote doesn't
+    /// support filters.
+    /// Packs received from _promisor_ remotes are marked with a `.promisor` file listing the remote refs they were fetched for,
+    /// which tells `git` that objects referenced from within the pack may be missing and can be obtained from the remote.
     ///
     /// ### Pack `.keep` files
     ///
@@ -88,6 +105,12 @@ where
         }
         let mut previous_response = None::<git_protocol::fetch::Response>;
         let mut round = 1;
+        let mut negotiate = negotiate::State::new(
+            config::negotiation_algorithm(repo)?,
+            repo,
+            matches!(protocol_version, git_protocol::transport::Protocol::V2)
+                || !con.transport.connection_persists_across_multiple_requests(),
+        );
 
         if self.ref_map.object_hash != repo.object_hash() {
             return Err(Error::IncompatibleObjectHash {
@@ -96,20 +119,44 @@ where
             });
         }
 
+        let shallow_commits = repo.shallow_commits()?;
+        let shallow_lock = if (shallow_commits.is_some() || !matches!(self.shallow, Shallow::NoChange))
+            && matches!(self.dry_run, fetch::DryRun::No)
+        {
+            Some(git_lock::File::acquire_to_update_resource(
+                repo.shallow_file(),
+                git_lock::acquire::Fail::Immediately,
+                None,
+            )?)
+        } else {
+            None
+        };
+        add_shallow_args(&mut arguments, &self.shallow, shallow_commits.as_deref())?;
+        if let Some(filter) = con.remote.filter {
+            if !arguments.can_use_filter() {
+                return Err(Error::MissingServerFeature {
+                    feature: "filter",
+                    description: "partial clones which omit objects until they are needed",
+                });
+            }
+            arguments.filter(&filter.to_string());
+        }
+        let mut shallow_updates = Vec::new();
+
         let reader = 'negotiation: loop {
             progress.step();
             progress.set_name(format!("negotiate (round {})", round));
 
             let is_done = match negotiate::one_round(
-                negotiate::Algorithm::Naive,
+                &mut negotiate,
                 round,
-                repo,
                 &self.ref_map,
                 con.remote.fetch_tags,
+                &self.shallow,
                 &mut arguments,
                 previous_response.as_ref(),
             ) {
-                Ok(_) if arguments.is_empty() => {
+                Ok(_) if round == 1 && arguments.is_empty() => {
                     git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
                     let update_refs = refs::update(
                         repo,
@@ -137,16 +184,23 @@ where
             round += 1;
             let mut reader = arguments.send(&mut con.transport, is_done).await?;
             if sideband_all {
-                setup_remote_progress(progress, &mut reader);
+                setup_remote_progress(progress, &mut reader, *b"FERP"); /* FEtch Remote Progress*/
             }
-            let response = git_protocol::fetch::Response::from_line_reader(protocol_version, &mut reader).await?;
+            let response =
+                git_protocol::fetch::Response::from_line_reader_in_round(protocol_version, &mut reader, is_done)
+                    .await?;
+            shallow_updates.extend_from_slice(response.shallow_updates());
             if response.has_pack() {
                 progress.step();
                 progress.set_name("receiving pack");
                 if !sideband_all {
-                    setup_remote_progress(progress, &mut reader);
+                    setup_remote_progress(progress, &mut reader, *b"FERP");
                 }
                 break 'negotiation reader;
+            } else if is_done {
+                drop(reader);
+                git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
+                return Err(negotiate::Error::NegotiationFailed { rounds: round - 1 }.into());
             } else {
                 previous_response = Some(response);
             }
@@ -173,8 +227,10 @@ where
                 con.progress,
                 should_interrupt,
                 Some(Box::new({
-                    let repo = repo.clone();
-                    move |oid, buf| repo.objects.find(oid, buf).ok()
+                    let mut objects = repo.objects.clone();
+                    // Thin-pack bases are local objects, and missing ones must not be fetched while receiving a pack.
+                    objects.skip_lazy_fetch = true;
+                    move |oid, buf| objects.find(oid, buf).ok()
                 })),
                 options,
             )?)
@@ -187,6 +243,12 @@ where
             git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
         }
 
+        if let Some(shallow_lock) = shallow_lock {
+            if !shallow_updates.is_empty() {
+                crate::shallow::write(shallow_lock, shallow_commits, &shallow_updates)?;
+            }
+        }
+
         let update_refs = refs::update(
             repo,
             self.reflog_message
@@ -201,6 +263,13 @@ where
         )?;
 
         if let Some(bundle) = write_pack_bundle.as_mut() {
+            if con.remote.promisor {
+                if let Some(data_path) = bundle.data_path.as_ref() {
+                    let path = data_path.with_extension("promisor");
+                    std::fs::write(&path, promisor_file_content(&self.ref_map.mappings))
+                        .map_err(|err| Error::WritePromisorFile { path, source: err })?;
+                }
+            }
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
                     std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
@@ -221,18 +290,77 @@ where
     }
 }
 
-fn setup_remote_progress<P>(
-    progress: &mut P,
-    reader: &mut Box<dyn git_protocol::transport::client::ExtendedBufRead + Unpin + '_>,
-) where
-    P: Progress,
-    P::SubProgress: 'static,
-{
-    use git_protocol::transport::client::ExtendedBufRead;
-    reader.set_progress_handler(Some(Box::new({
-        let mut remote_progress = progress.add_child_with_id("remote", *b"FERP"); /* FEtch Remote Progress*/
-        move |is_err: bool, data: &[u8]| {
-            git_protocol::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress)
+/// Produce the content of a `.promisor` file like `git` does, with one `<object-id> <remote ref name>` line per ref we fetched.
+fn promisor_file_content(mappings: &[fetch::Mapping]) -> Vec<u8> {
+    use std::io::Write;
+    let mut out = Vec::new();
+    for (id, name) in mappings.iter().filter_map(|m| m.remote.as_id().zip(m.remote.as_name())) {
+        writeln!(out, "{} {}", id, name).expect("in-memory write");
+    }
+    out
+}
+
+fn add_shallow_args(
+    args: &mut git_protocol::fetch::Arguments,
+    shallow: &Shallow,
+    shallow_commits: Option<&[git_hash::ObjectId]>,
+) -> Result<(), Error> {
+    if (shallow_commits.is_some() || !matches!(shallow, Shallow::NoChange)) && !args.can_use_shallow() {
+        return Err(Error::MissingServerFeature {
+            feature: "shallow",
+            description: "shallow clones need server support to remain shallow, otherwise bigger than expected packs are sent effectively unshallowing the repository",
+        });
+    }
+    if let Some(shallow_commits) = shallow_commits {
+        for commit in shallow_commits {
+            args.shallow(commit);
+        }
+    }
+    match shallow {
+        Shallow::NoChange => {}
+        Shallow::DepthAtRemote(commits) => args.deepen(commits.get() as usize),
+        Shallow::Deepen(commits) => {
+            if !args.can_use_deepen_relative() {
+                return Err(Error::MissingServerFeature {
+                    feature: "deepen-relative",
+                    description: "deepening the history relative to the current shallow boundary",
+                });
+            }
+            args.deepen(*commits as usize);
+            args.deepen_relative();
         }
-    }) as git_protocol::transport::client::HandleProgress));
+        Shallow::Since { cutoff } => {
+            if !args.can_use_deepen_since() {
+                return Err(Error::MissingServerFeature {
+                    feature: "deepen-since",
+                    description: "shallow clones with a cutoff date",
+                });
+            }
+            args.deepen_since(cutoff.seconds_since_unix_epoch.max(0) as usize);
+        }
+        Shallow::Exclude {
+            remote_refs,
+            since_cutoff,
+        } => {
+            if !args.can_use_deepen_not() {
+                return Err(Error::MissingServerFeature {
+                    feature: "deepen-not",
+                    description: "shallow clones excluding the history of given references",
+                });
+            }
+            if let Some(cutoff) = since_cutoff {
+                if !args.can_use_deepen_since() {
+                    return Err(Error::MissingServerFeature {
+                        feature: "deepen-since",
+                        description: "shallow clones with a cutoff date",
+                    });
+                }
+                args.deepen_since(cutoff.seconds_since_unix_epoch.max(0) as usize);
+            }
+            for ref_ in remote_refs {
+                args.deepen_not(ref_.as_ref().as_bstr());
+            }
+        }
+    }
+    Ok(())
 }
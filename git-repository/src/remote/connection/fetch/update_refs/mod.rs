@@ -54,6 +54,12 @@ pub(crate) fn update(
 ) -> Result<update::Outcome, update::Error> {
     let mut edits = Vec::new();
     let mut updates = Vec::new();
+    // Objects that are missing in a partial clone must not be fetched just to see if an update is a fast-forward.
+    let repo = &{
+        let mut repo = repo.clone();
+        repo.objects.skip_lazy_fetch = true;
+        repo
+    };
 
     let implicit_tag_refspec = fetch_tags
         .to_refspec()
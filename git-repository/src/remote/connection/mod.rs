@@ -20,6 +20,23 @@ pub struct Connection<'a, 'repo, T, P> {
     pub(crate) progress: P,
 }
 
+pub(crate) fn setup_remote_progress<P>(
+    progress: &mut P,
+    reader: &mut Box<dyn git_protocol::transport::client::ExtendedBufRead + Unpin + '_>,
+    id: [u8; 4],
+) where
+    P: crate::Progress,
+    P::SubProgress: 'static,
+{
+    use git_protocol::transport::client::ExtendedBufRead;
+    reader.set_progress_handler(Some(Box::new({
+        let mut remote_progress = progress.add_child_with_id("remote", id);
+        move |is_err: bool, data: &[u8]| {
+            git_protocol::RemoteProgress::translate_to_progress(is_err, data, &mut remote_progress)
+        }
+    }) as git_protocol::transport::client::HandleProgress));
+}
+
 mod access;
 
 ///
@@ -27,3 +44,6 @@ pub mod ref_map;
 
 ///
 pub mod fetch;
+
+///
+pub mod push;
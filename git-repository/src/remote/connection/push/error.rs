@@ -0,0 +1,34 @@
+/// The error returned by [`send()`](super::Prepare::send()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The remote doesn't support atomic pushes")]
+    AtomicUnsupported,
+    #[error("The remote doesn't support push options")]
+    PushOptionsUnsupported,
+    #[error(transparent)]
+    Client(#[from] git_protocol::transport::client::Error),
+    #[error("Could not decode the report of the remote")]
+    PushResponse(#[from] git_protocol::push::response::Error),
+    #[error("Failed to traverse the commits to send")]
+    TraverseCommits(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] crate::object::find::existing::Error),
+    #[error("Failed to create the pack to send")]
+    CreatePack(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("Failed to write the pack to the remote")]
+    WritePack(#[from] std::io::Error),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+impl git_protocol::transport::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::PushResponse(err) => err.is_spurious(),
+            Error::Client(err) => err.is_spurious(),
+            Error::WritePack(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use git_protocol::transport::client::Transport;
+
+use crate::{
+    bstr::BString,
+    remote,
+    remote::{fetch::DryRun, push::Update, Connection},
+    Progress,
+};
+
+mod error;
+pub use error::Error;
+
+/// The status of the remote repository after the push operation.
+#[derive(Debug, Clone)]
+pub enum Status {
+    /// Nothing was sent as there was no ref update to perform, either because all remote refs are up-to-date or
+    /// because all updates were rejected locally.
+    NoChangeNeeded,
+    /// A dry run was performed which leaves the remote repository without any change, as no update nor pack was sent.
+    DryRun,
+    /// All updates to perform were sent along with a pack containing all objects the remote needs to apply them.
+    Sent {
+        /// The report of the remote about each ref update, or `None` if the remote doesn't support reporting its status.
+        ///
+        /// Note that only the updates that were actually sent are reported.
+        report: Option<git_protocol::push::Response>,
+    },
+}
+
+/// The outcome of sending ref updates via [`Prepare::send()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// All updates derived from the push ref-specs, including the ones that weren't sent as they are up-to-date or
+    /// were rejected locally.
+    pub updates: Vec<Update>,
+    /// The status of the operation to indicate what happened.
+    pub status: Status,
+}
+
+///
+pub mod prepare {
+    use crate::bstr::BString;
+
+    /// The error returned by [`prepare_push()`][super::Connection::prepare_push()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Failed to configure the transport before connecting to {url:?}")]
+        GatherTransportConfig {
+            url: BString,
+            source: crate::config::transport::Error,
+        },
+        #[error("Failed to configure the transport layer")]
+        ConfigureTransport(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        ConfigureCredentials(#[from] crate::config::credential_helpers::Error),
+        #[error(transparent)]
+        Handshake(#[from] git_protocol::handshake::Error),
+        #[error("The remote must advertise its refs when pushing, which it doesn't do in protocol {actual:?}")]
+        UnsupportedProtocolVersion { actual: git_protocol::transport::Protocol },
+        #[error("The source {name:?} of a push ref-spec didn't match any reference or object")]
+        UnmatchedSource { name: BString },
+        #[error("Cannot push HEAD to a branch of the same name as it is detached")]
+        DetachedHead,
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        InitReferenceIteration(#[from] crate::reference::iter::init::Error),
+        #[error("Failed to iterate local references")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::Error),
+        #[error(transparent)]
+        FindExistingObject(#[from] crate::object::find::existing::Error),
+    }
+
+    impl git_protocol::transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Handshake(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+
+impl<'remote, 'repo, T, P> Connection<'remote, 'repo, T, P>
+where
+    T: Transport,
+    P: Progress,
+{
+    /// Perform a handshake with the remote and derive all ref updates from the push ref-specs of the remote and the refs
+    /// it advertised, to be sent with [`Prepare::send()`].
+    /// Note that at this point, the `transport` should already be configured using the [`transport_mut()`][Self::transport_mut()]
+    /// method, as it will be consumed here.
+    ///
+    /// If the remote has no push ref-specs, the current branch will be pushed to the branch of the same name on the remote,
+    /// similar to what `git` does with `push.default=current`.
+    ///
+    /// # Deviation
+    ///
+    /// Remote tracking branches aren't updated after pushing, and `push.default` isn't read.
+    ///
+    /// # Async Experimental
+    ///
+    /// Note that this implementation is currently limited correctly in blocking mode only as it relies on Drop semantics to close the connection
+    /// should the push not be performed. Furthermore, the code creating the pack is inherently blocking and it's not offloaded to a thread,
+    /// making this call block the executor.
+    #[allow(clippy::result_large_err)]
+    #[git_protocol::maybe_async::maybe_async]
+    pub async fn prepare_push(mut self) -> Result<Prepare<'remote, 'repo, T, P>, prepare::Error> {
+        let mut credentials_storage;
+        let url = self.transport.to_url();
+        let authenticate = match self.authenticate.as_mut() {
+            Some(f) => f,
+            None => {
+                let url = self
+                    .remote
+                    .url(remote::Direction::Push)
+                    .map(ToOwned::to_owned)
+                    .unwrap_or_else(|| git_url::parse(url.as_ref()).expect("valid URL to be provided by transport"));
+                credentials_storage = self.configured_credentials(url)?;
+                &mut credentials_storage
+            }
+        };
+
+        if self.transport_options.is_none() {
+            self.transport_options = self
+                .remote
+                .repo
+                .transport_options(url.as_ref(), self.remote.name().map(|n| n.as_bstr()))
+                .map_err(|err| prepare::Error::GatherTransportConfig {
+                    source: err,
+                    url: url.into_owned(),
+                })?;
+        }
+        if let Some(config) = self.transport_options.as_ref() {
+            self.transport.configure(&**config)?;
+        }
+        let mut handshake =
+            git_protocol::push::handshake(&mut self.transport, authenticate, Vec::new(), &mut self.progress).await?;
+        let remote_refs = match handshake.refs.take() {
+            Some(refs) => refs,
+            None => {
+                git_protocol::indicate_end_of_interaction(&mut self.transport)
+                    .await
+                    .ok();
+                return Err(prepare::Error::UnsupportedProtocolVersion {
+                    actual: handshake.server_protocol_version,
+                });
+            }
+        };
+
+        let default_specs;
+        let specs = match self.remote.refspecs(remote::Direction::Push) {
+            [] => {
+                default_specs = [git_refspec::parse("HEAD".into(), git_refspec::parse::Operation::Push)
+                    .expect("valid")
+                    .to_owned()];
+                &default_specs[..]
+            }
+            specs => specs,
+        };
+        let updates = match update::compute(
+            self.remote.repo,
+            specs,
+            &remote_refs,
+            handshake.capabilities.contains("delete-refs"),
+        ) {
+            Ok(updates) => updates,
+            Err(err) => {
+                git_protocol::indicate_end_of_interaction(&mut self.transport)
+                    .await
+                    .ok();
+                return Err(err);
+            }
+        };
+        Ok(Prepare {
+            con: Some(self),
+            handshake,
+            remote_refs,
+            updates,
+            dry_run: DryRun::No,
+            atomic: false,
+            push_options: Vec::new(),
+        })
+    }
+}
+
+/// Access
+impl<'remote, 'repo, T, P> Prepare<'remote, 'repo, T, P>
+where
+    T: Transport,
+{
+    /// Return all updates derived from the push ref-specs, including those that won't be sent.
+    pub fn updates(&self) -> &[Update] {
+        &self.updates
+    }
+
+    /// Return all refs advertised by the remote.
+    pub fn remote_refs(&self) -> &[git_protocol::handshake::Ref] {
+        &self.remote_refs
+    }
+
+    /// Return additional information provided by the server as part of the handshake, like its capabilities.
+    ///
+    /// Note that the `refs` field is always `None` as the refs are available via [`remote_refs()`][Self::remote_refs()].
+    pub fn handshake(&self) -> &git_protocol::handshake::Outcome {
+        &self.handshake
+    }
+}
+
+mod send;
+mod update;
+
+/// A structure to hold the result of the handshake with the remote and configure the upcoming push operation.
+pub struct Prepare<'remote, 'repo, T, P>
+where
+    T: Transport,
+{
+    con: Option<Connection<'remote, 'repo, T, P>>,
+    handshake: git_protocol::handshake::Outcome,
+    remote_refs: Vec<git_protocol::handshake::Ref>,
+    updates: Vec<Update>,
+    dry_run: DryRun,
+    atomic: bool,
+    push_options: Vec<BString>,
+}
+
+/// Builder
+impl<'remote, 'repo, T, P> Prepare<'remote, 'repo, T, P>
+where
+    T: Transport,
+{
+    /// If dry run is enabled, the remote won't be changed as neither ref updates nor a pack will be sent.
+    pub fn with_dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled.then(|| DryRun::Yes).unwrap_or(DryRun::No);
+        self
+    }
+
+    /// If enabled, the remote applies either all updates or none of them.
+    ///
+    /// Sending fails if the remote doesn't support atomic pushes.
+    pub fn with_atomic(mut self, enabled: bool) -> Self {
+        self.atomic = enabled;
+        self
+    }
+
+    /// Pass `options` to the hooks of the remote, similar to `git push --push-option`.
+    ///
+    /// Sending fails if there are options but the remote doesn't support them.
+    pub fn with_push_options(mut self, options: impl IntoIterator<Item = impl Into<BString>>) -> Self {
+        self.push_options = options.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl<'remote, 'repo, T, P> Drop for Prepare<'remote, 'repo, T, P>
+where
+    T: Transport,
+{
+    fn drop(&mut self) {
+        if let Some(mut con) = self.con.take() {
+            #[cfg(feature = "async-network-client")]
+            {
+                // TODO: this should be an async drop once the feature is available.
+                //       Right now we block the executor by forcing this communication, but that only
+                //       happens if the user didn't actually try to send the updates, which consumes the
+                //       connection in an async context.
+                git_protocol::futures_lite::future::block_on(git_protocol::indicate_end_of_interaction(
+                    &mut con.transport,
+                ))
+                .ok();
+            }
+            #[cfg(not(feature = "async-network-client"))]
+            {
+                git_protocol::indicate_end_of_interaction(&mut con.transport).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::HashSet,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_hash::ObjectId;
+use git_odb::FindExt;
+use git_protocol::transport::client::Transport;
+
+use crate::{
+    remote::{
+        connection::setup_remote_progress,
+        fetch::DryRun,
+        push::{Error, Outcome, Prepare, Status, Update},
+    },
+    Progress, Repository,
+};
+
+impl<'remote, 'repo, T, P> Prepare<'remote, 'repo, T, P>
+where
+    T: Transport,
+    P: Progress,
+    P::SubProgress: 'static,
+{
+    /// Send all ref updates that aren't rejected or up-to-date to the remote along with a pack of all objects it doesn't have yet,
+    /// and receive its report about which of the updates were applied.
+    ///
+    /// The pack is created by traversing all commits reachable from the objects to push, stopping at commits reachable from
+    /// any ref advertised by the remote that we have locally. It may contain a few objects that the remote already has.
+    ///
+    /// ### Async Mode Shortcoming
+    ///
+    /// Currently the entire process of creating the pack is blocking the executor. This can be fixed using the `blocking` crate, but it
+    /// didn't seem worth the tradeoff of having more complex code.
+    ///
+    /// ### Configuration
+    ///
+    /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    #[git_protocol::maybe_async::maybe_async]
+    pub async fn send(mut self, should_interrupt: &AtomicBool) -> Result<Outcome, Error> {
+        let mut con = self.con.take().expect("send() can only be called once");
+        let updates = std::mem::take(&mut self.updates);
+        let repo = con.remote.repo;
+
+        let mut arguments =
+            git_protocol::push::Arguments::new(&self.handshake.capabilities, Some(repo.config.user_agent_tuple()));
+        for update in updates.iter().filter(|u| u.mode.is_update()) {
+            arguments.update(git_protocol::push::Update {
+                ref_name: update.remote.clone(),
+                old_id: update.old_id.unwrap_or_else(|| repo.object_hash().null()),
+                new_id: update.new_id.unwrap_or_else(|| repo.object_hash().null()),
+            });
+        }
+        if arguments.is_empty() || matches!(self.dry_run, DryRun::Yes) {
+            git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
+            return Ok(Outcome {
+                status: match self.dry_run {
+                    DryRun::Yes => Status::DryRun,
+                    DryRun::No => Status::NoChangeNeeded,
+                },
+                updates,
+            });
+        }
+        if self.atomic {
+            if !arguments.can_use_atomic() {
+                git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
+                return Err(Error::AtomicUnsupported);
+            }
+            arguments.use_atomic();
+        }
+        if !self.push_options.is_empty() {
+            if !arguments.can_use_push_options() {
+                git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
+                return Err(Error::PushOptionsUnsupported);
+            }
+            for option in self.push_options.drain(..) {
+                arguments.push_option(option);
+            }
+        }
+
+        let progress = &mut con.progress;
+        progress.step();
+        progress.set_name("send updates");
+        let (writer, mut reader) = arguments.send(&mut con.transport).await?;
+        if arguments.needs_pack() {
+            progress.step();
+            progress.set_name("send pack");
+            #[cfg(feature = "async-network-client")]
+            let mut writer = git_protocol::futures_lite::io::BlockOn::new(writer);
+            #[cfg(not(feature = "async-network-client"))]
+            let mut writer = writer;
+            write_pack(
+                repo,
+                &updates,
+                &self.remote_refs,
+                &mut writer,
+                progress.add_child("pack"),
+                should_interrupt,
+            )?;
+            writer.flush()?;
+        } else {
+            drop(writer);
+        }
+
+        let report = if arguments.has_report_status() {
+            progress.step();
+            progress.set_name("receive report");
+            if arguments.has_sideband() {
+                setup_remote_progress(progress, &mut reader, *b"PURP"); /* PUsh Remote Progress */
+            }
+            Some(git_protocol::push::Response::from_line_reader(&mut reader, arguments.has_sideband()).await?)
+        } else {
+            None
+        };
+
+        Ok(Outcome {
+            updates,
+            status: Status::Sent { report },
+        })
+    }
+}
+
+/// Write a pack with all objects needed to apply `updates` to `out`, assuming the remote has all objects reachable from `remote_refs`.
+fn write_pack(
+    repo: &Repository,
+    updates: &[Update],
+    remote_refs: &[git_protocol::handshake::Ref],
+    out: impl std::io::Write,
+    mut progress: impl Progress + 'static,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    use git_pack::data::output;
+
+    let peeled_commit = |id: &git_hash::oid| -> Result<Option<ObjectId>, Error> {
+        Ok(match repo.try_find_object(id.to_owned()).ok().flatten() {
+            Some(obj) => {
+                let obj = obj.peel_tags_to_end()?;
+                (obj.kind == git_object::Kind::Commit).then(|| obj.id)
+            }
+            None => None,
+        })
+    };
+    let mut remote_commits = Vec::new();
+    for remote_ref in remote_refs {
+        if let Some(commit) = remote_ref.unpack().1.map(peeled_commit).transpose()?.flatten() {
+            remote_commits.push(commit);
+        }
+    }
+    // Commits beyond the boundary of shallow clones are missing, and we skip them while hiding all others we can reach.
+    let mut hidden = HashSet::new();
+    for commit in git_traverse::commit::Ancestors::new(
+        remote_commits,
+        git_traverse::commit::ancestors::State::default(),
+        |id, buf| repo.objects.find_commit_iter(id, buf),
+    ) {
+        match commit {
+            Ok(id) => {
+                hidden.insert(id);
+            }
+            Err(git_traverse::commit::ancestors::Error::FindExisting { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    let mut input = Vec::new();
+    let mut tips = Vec::new();
+    for new_id in updates
+        .iter()
+        .filter(|u| u.mode.is_update())
+        .filter_map(|u| u.new_id.as_ref())
+    {
+        let commit = peeled_commit(new_id)?;
+        if commit.as_ref() != Some(new_id) {
+            input.push(*new_id);
+        }
+        tips.extend(commit);
+    }
+    for commit in git_traverse::commit::Ancestors::filtered(
+        tips,
+        git_traverse::commit::ancestors::State::default(),
+        |id, buf| repo.objects.find_commit_iter(id, buf),
+        |id| !hidden.contains(id),
+    ) {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        input.push(commit?);
+    }
+
+    let mut db = repo.objects.clone().into_arc()?;
+    db.prevent_pack_unload();
+    db.ignore_replacements = true;
+    let (counts, _outcome) = output::count::objects_unthreaded(
+        db.clone(),
+        input.into_iter().map(Ok::<_, std::convert::Infallible>),
+        progress.add_child("counting"),
+        should_interrupt,
+        output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+    )
+    .map_err(|err| Error::CreatePack(err.into()))?;
+
+    let num_objects = counts.len();
+    let mut entries = git_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        db,
+        progress.add_child("creating entries"),
+        output::entry::iter_from_counts::Options {
+            mode: output::entry::iter_from_counts::Mode::PackCopyAndDeltaCompression,
+            allow_thin_pack: false,
+            ..Default::default()
+        },
+    ));
+    let mut write_progress = progress.add_child("writing");
+    write_progress.init(None, git_features::progress::bytes());
+    let mut pack = output::bytes::FromEntriesIter::new(
+        entries.by_ref(),
+        out,
+        num_objects as u32,
+        git_pack::data::Version::V2,
+        repo.object_hash(),
+    );
+    for written in pack.by_ref() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        write_progress.inc_by(written.map_err(|err| Error::CreatePack(err.into()))? as usize);
+    }
+    Ok(())
+}
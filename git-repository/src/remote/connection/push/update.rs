@@ -0,0 +1,229 @@
+use std::collections::{BTreeMap, HashSet};
+
+use git_hash::ObjectId;
+use git_odb::{Find, FindExt};
+use git_refspec::instruction::Push;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    ext::ObjectIdExt,
+    remote::{
+        connection::push::prepare::Error,
+        matches_pattern,
+        push::{Mode, Update},
+    },
+    Repository,
+};
+
+/// A local object to push along with the full name of the reference that pointed to it, if there was one.
+struct Source {
+    name: Option<BString>,
+    id: ObjectId,
+}
+
+/// Produce one update for each remote ref that the push `specs` apply to, given all `remote_refs` advertised by the server,
+/// in the order of the `specs` which produced them.
+///
+/// `can_delete_refs` is true if the server supports the `delete-refs` capability.
+#[allow(clippy::result_large_err)]
+pub(crate) fn compute(
+    repo: &Repository,
+    specs: &[git_refspec::RefSpec],
+    remote_refs: &[git_protocol::handshake::Ref],
+    can_delete_refs: bool,
+) -> Result<Vec<Update>, Error> {
+    let remote_refs: BTreeMap<&BStr, ObjectId> = remote_refs
+        .iter()
+        .filter_map(|r| {
+            let (name, id, _peeled) = r.unpack();
+            id.map(|id| (name, id.to_owned()))
+        })
+        .collect();
+    let mut seen_destinations = HashSet::new();
+    let mut out = Vec::new();
+    for (spec_index, spec) in specs.iter().enumerate() {
+        let spec = spec.to_ref();
+        #[allow(clippy::result_large_err)]
+        let mut push = |source: Option<Source>, remote: BString, force: bool| -> Result<(), Error> {
+            if !seen_destinations.insert(remote.clone()) {
+                return Ok(());
+            }
+            let old_id = remote_refs.get(remote.as_bstr()).copied();
+            let (local, new_id) = source.map_or((None, None), |s| (s.name, Some(s.id)));
+            let mode = mode(repo, remote.as_ref(), old_id, new_id, force, can_delete_refs)?;
+            out.push(Update {
+                local,
+                remote,
+                old_id,
+                new_id,
+                mode,
+                spec_index,
+            });
+            Ok(())
+        };
+        match spec.instruction() {
+            git_refspec::Instruction::Fetch(_) => unreachable!("push specs are parsed as such"),
+            git_refspec::Instruction::Push(Push::AllMatchingBranches { allow_non_fast_forward }) => {
+                for reference in repo.references()?.local_branches()? {
+                    let reference = reference.map_err(Error::IterReferences)?;
+                    let name = reference.name().as_bstr();
+                    if !remote_refs.contains_key(name) {
+                        continue;
+                    }
+                    let remote = name.to_owned();
+                    push(resolve_source(repo, name)?, remote, allow_non_fast_forward)?;
+                }
+            }
+            git_refspec::Instruction::Push(Push::Delete { ref_or_pattern }) => match ref_or_pattern.find_byte(b'*') {
+                Some(_) => {
+                    for name in remote_refs
+                        .keys()
+                        .filter(|name| matches_pattern(ref_or_pattern, name).is_some())
+                    {
+                        push(None, (*name).to_owned(), false)?;
+                    }
+                }
+                None => {
+                    let remote = expand_destination(ref_or_pattern, None, &remote_refs);
+                    push(None, remote, false)?;
+                }
+            },
+            git_refspec::Instruction::Push(Push::Matching {
+                src,
+                dst,
+                allow_non_fast_forward,
+            }) => match src.find_byte(b'*') {
+                Some(_) => {
+                    let mut names = Vec::new();
+                    for reference in repo.references()?.all()? {
+                        let reference = reference.map_err(Error::IterReferences)?;
+                        if let Some(matched) = matches_pattern(src, reference.name().as_bstr()) {
+                            let star = dst.find_byte(b'*').expect("patterns are validated to be on both sides");
+                            let mut remote: BString = dst[..star].into();
+                            remote.push_str(matched);
+                            remote.push_str(&dst[star + 1..]);
+                            names.push((reference.name().as_bstr().to_owned(), remote));
+                        }
+                    }
+                    for (local, remote) in names {
+                        push(resolve_source(repo, local.as_ref())?, remote, allow_non_fast_forward)?;
+                    }
+                }
+                None => {
+                    let source =
+                        resolve_source(repo, src)?.ok_or_else(|| Error::UnmatchedSource { name: src.into() })?;
+                    let remote = if dst == "HEAD" && src == "HEAD" {
+                        source
+                            .name
+                            .clone()
+                            .filter(|name| name.starts_with(b"refs/"))
+                            .ok_or(Error::DetachedHead)?
+                    } else {
+                        expand_destination(dst, source.name.as_ref().map(|n| n.as_bstr()), &remote_refs)
+                    };
+                    push(Some(source), remote, allow_non_fast_forward)?;
+                }
+            },
+        }
+    }
+    Ok(out)
+}
+
+/// Resolve `name` into the object to push, following symbolic refs like `HEAD` to the reference they point to.
+/// If it's not a reference, it's parsed as revision instead.
+#[allow(clippy::result_large_err)]
+fn resolve_source(repo: &Repository, name: &BStr) -> Result<Option<Source>, Error> {
+    let mut reference = match repo.try_find_reference(name)? {
+        Some(r) => r,
+        None => {
+            return Ok(repo.rev_parse_single(name).ok().map(|id| Source {
+                name: None,
+                id: id.detach(),
+            }))
+        }
+    };
+    loop {
+        match reference.target() {
+            git_ref::TargetRef::Peeled(id) => {
+                return Ok(Some(Source {
+                    name: Some(reference.name().as_bstr().to_owned()),
+                    id: id.to_owned(),
+                }))
+            }
+            git_ref::TargetRef::Symbolic(target) => {
+                reference = match repo.try_find_reference(target)? {
+                    Some(r) => r,
+                    None => return Ok(None),
+                };
+            }
+        }
+    }
+}
+
+/// Turn a possibly partial `name` into a full ref name on the remote, like `git` does.
+///
+/// It's used as is if it starts with `refs/`, and otherwise it's matched against existing branches and tags on the remote, falling back
+/// to the category of `local`, the full name of the source reference, if it doesn't exist yet.
+fn expand_destination(name: &BStr, local: Option<&BStr>, remote_refs: &BTreeMap<&BStr, ObjectId>) -> BString {
+    if name.starts_with(b"refs/") {
+        return name.to_owned();
+    }
+    let candidates = ["refs/heads/", "refs/tags/"].map(|prefix| {
+        let mut full: BString = prefix.into();
+        full.push_str(name);
+        full
+    });
+    if let Some(existing) = candidates.iter().find(|c| remote_refs.contains_key(c.as_bstr())) {
+        return existing.to_owned();
+    }
+    let [branch, tag] = candidates;
+    match local {
+        Some(local) if local.starts_with(b"refs/tags/") => tag,
+        _ => branch,
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn mode(
+    repo: &Repository,
+    remote: &BStr,
+    old_id: Option<ObjectId>,
+    new_id: Option<ObjectId>,
+    force: bool,
+    can_delete_refs: bool,
+) -> Result<Mode, Error> {
+    Ok(match (old_id, new_id) {
+        (None, None) => Mode::NoChangeNeeded,
+        (Some(_), None) if !can_delete_refs => Mode::RejectedDeletion,
+        (Some(_), None) => Mode::Delete,
+        (None, Some(_)) => Mode::New,
+        (Some(old), Some(new)) if old == new => Mode::NoChangeNeeded,
+        (Some(_), Some(_)) if force => Mode::Forced,
+        (Some(_), Some(_)) if remote.starts_with(b"refs/tags/") => Mode::RejectedTagUpdate,
+        (Some(old), Some(new)) => {
+            if is_ancestor(repo, old, new)? {
+                Mode::FastForward
+            } else {
+                Mode::RejectedNonFastForward
+            }
+        }
+    })
+}
+
+/// Return true if `old` is a commit reachable from `new`, which also requires `old` to be present locally.
+#[allow(clippy::result_large_err)]
+fn is_ancestor(repo: &Repository, old: ObjectId, new: ObjectId) -> Result<bool, Error> {
+    let new = match repo.try_find_object(new)? {
+        Some(obj) => match obj.peel_tags_to_end()?.try_into_commit() {
+            Ok(commit) => commit.id,
+            Err(_) => return Ok(false),
+        },
+        None => return Ok(false),
+    };
+    if !repo.objects.contains(old) {
+        return Ok(false);
+    }
+    Ok(new
+        .ancestors(|id, buf| repo.objects.find_commit_iter(id, buf))
+        .any(|id| id.map_or(false, |id| id == old)))
+}
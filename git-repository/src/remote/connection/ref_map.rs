@@ -260,6 +260,7 @@ fn extract_object_format(
             })?;
             match object_format {
                 "sha1" => git_hash::Kind::Sha1,
+                "sha256" => git_hash::Kind::Sha256,
                 unknown => return Err(Error::UnknownObjectFormat { format: unknown.into() }),
             }
         } else {
@@ -10,6 +10,8 @@ pub mod find {
             "The value for 'remote.<name>.tagOpt` is invalid and must either be '--tags' or '--no-tags': \"{value}\""
         )]
         TagOpt { value: BString },
+        #[error("The value for 'remote.<name>.partialCloneFilter' is not a supported filter like 'blob:none', 'blob:limit=<n>' or 'tree:<depth>': \"{value}\"")]
+        PartialCloneFilter { value: BString },
         #[error("{spec:?} {kind} ref-spec failed to parse")]
         RefSpec {
             spec: BString,
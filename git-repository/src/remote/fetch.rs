@@ -18,6 +18,57 @@ pub(crate) enum WritePackedRefs {
     Only,
 }
 
+/// Describe how shallow clones are handled when fetching, with variants defining how the *shallow boundary* is handled.
+///
+/// The *shallow boundary* is a set of commits whose parents are not present in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub enum Shallow {
+    /// Fetch all changes from the remote without affecting the shallow boundary at all.
+    ///
+    /// This also means that repositories that aren't shallow will remain like that.
+    NoChange,
+    /// Receive updates to `depth` commits in the history of the refs to fetch (from the viewpoint of the remote),
+    /// with the value of `1` meaning to receive only the commit a ref is pointing to.
+    ///
+    /// This may update the shallow boundary to increase or decrease the amount of available history.
+    DepthAtRemote(std::num::NonZeroU32),
+    /// Increase the number of commits and thus expand the shallow boundary by `depth` commits as seen from our local
+    /// shallow boundary, with a value of `0` having no effect.
+    Deepen(u32),
+    /// Set the shallow boundary at the `cutoff` time, meaning that there will be no commits beyond that time.
+    Since {
+        /// The date beyond which there will be no history.
+        cutoff: git_date::Time,
+    },
+    /// Receive all history excluding all commits reachable from `remote_refs`. These can be long or short
+    /// ref names or tag names.
+    Exclude {
+        /// The ref names to exclude, short or long. Note that ambiguous short names will cause the remote to abort
+        /// without an error message being transferred (because the protocol does not support it)
+        remote_refs: Vec<git_ref::PartialName>,
+        /// If some, this field has the same meaning as [`Shallow::Since`] which can be used in combination
+        /// with excluded references.
+        since_cutoff: Option<git_date::Time>,
+    },
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+impl Default for Shallow {
+    fn default() -> Self {
+        Shallow::NoChange
+    }
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+impl Shallow {
+    /// Produce a variant that causes the repository to lose its shallow boundary, effectively by extending it
+    /// beyond all limits, like `git fetch --unshallow`.
+    pub fn undo() -> Self {
+        Shallow::DepthAtRemote(std::num::NonZeroU32::new(i32::MAX as u32).expect("valid at compile time"))
+    }
+}
+
 /// Describe how to handle tags when fetching
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Tags {
@@ -52,6 +103,64 @@ impl Tags {
     }
 }
 
+/// An object filter to make a fetch or clone partial, with the omitted objects being obtained later on demand
+/// from the promisor remote.
+///
+/// It's configured with `remote.<name>.partialCloneFilter`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// Omit all blobs, like `blob:none`, for a _blobless_ clone.
+    BlobNone,
+    /// Omit all blobs that are at least `bytes` in size, like `blob:limit=<bytes>`.
+    BlobLimit {
+        /// The size in bytes at which blobs are omitted.
+        bytes: u64,
+    },
+    /// Omit all trees and blobs that are `depth` or more levels below a root tree, like `tree:<depth>`, with `0` making for a
+    /// _treeless_ clone.
+    TreeDepth {
+        /// The depth at which trees and blobs are omitted.
+        depth: u32,
+    },
+}
+
+impl Filter {
+    /// Parse `spec` as filter in the format understood by `git`, like `blob:none`, `blob:limit=1m` or `tree:0`, or return `None`
+    /// if it's unknown or invalid.
+    pub fn from_spec(spec: &crate::bstr::BStr) -> Option<Self> {
+        use std::convert::TryFrom;
+
+        use crate::bstr::ByteSlice;
+        Some(if spec == "blob:none" {
+            Filter::BlobNone
+        } else if let Some(limit) = spec.strip_prefix(b"blob:limit=") {
+            Filter::BlobLimit {
+                bytes: git_config::Integer::try_from(limit.as_bstr())
+                    .ok()?
+                    .to_decimal()?
+                    .try_into()
+                    .ok()?,
+            }
+        } else if let Some(depth) = spec.strip_prefix(b"tree:") {
+            Filter::TreeDepth {
+                depth: depth.to_str().ok()?.parse().ok()?,
+            }
+        } else {
+            return None;
+        })
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Filter::BlobNone => f.write_str("blob:none"),
+            Filter::BlobLimit { bytes } => write!(f, "blob:limit={}", bytes),
+            Filter::TreeDepth { depth } => write!(f, "tree:{}", depth),
+        }
+    }
+}
+
 /// Information about the relationship between our refspecs, and remote references with their local counterparts.
 #[derive(Default, Debug, Clone)]
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
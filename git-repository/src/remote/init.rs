@@ -36,6 +36,8 @@ impl<'repo> Remote<'repo> {
         push_specs: Vec<RefSpec>,
         should_rewrite_urls: bool,
         fetch_tags: remote::fetch::Tags,
+        promisor: bool,
+        filter: Option<remote::fetch::Filter>,
         repo: &'repo Repository,
     ) -> Result<Self, Error> {
         debug_assert!(
@@ -54,6 +56,8 @@ impl<'repo> Remote<'repo> {
             fetch_specs,
             push_specs,
             fetch_tags,
+            promisor,
+            filter,
             repo,
         })
     }
@@ -80,6 +84,8 @@ impl<'repo> Remote<'repo> {
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             fetch_tags: Default::default(),
+            promisor: false,
+            filter: None,
             repo,
         })
     }
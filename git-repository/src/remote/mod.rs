@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::bstr::BStr;
+use crate::bstr::{BStr, ByteSlice};
 
 /// The direction of an operation carried out (or to be carried out) through a remote.
 #[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
@@ -45,6 +45,9 @@ pub mod init;
 ///
 pub mod fetch;
 
+///
+pub mod push;
+
 ///
 #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
 pub mod connect;
@@ -59,3 +62,10 @@ pub mod save;
 
 mod access;
 pub(crate) mod url;
+
+/// If `name` matches the ref-spec `pattern` with a single `*`, return the portion of `name` matched by it.
+pub(crate) fn matches_pattern<'a>(pattern: &BStr, name: &'a BStr) -> Option<&'a BStr> {
+    let (prefix, suffix) = pattern.split_once_str(b"*")?;
+    (name.len() >= prefix.len() + suffix.len() && name.starts_with(prefix) && name.ends_with(suffix))
+        .then(|| name[prefix.len()..name.len() - suffix.len()].as_bstr())
+}
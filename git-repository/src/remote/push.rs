@@ -0,0 +1,77 @@
+use crate::bstr::BString;
+
+/// Describe the way a ref on the remote is going to be updated, or why it won't be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// No change is needed as the remote ref already points to the object we would push, or because the ref to delete
+    /// doesn't exist on the remote.
+    NoChangeNeeded,
+    /// The remote ref points to an ancestor of the object we push, allowing for a fast-forward.
+    FastForward,
+    /// The remote ref will be set to the object we push without taking into consideration its ancestry, as the ref-spec forces it.
+    Forced,
+    /// The ref doesn't exist on the remote and will be created.
+    New,
+    /// The ref will be deleted on the remote.
+    Delete,
+    /// Tags on the remote can never be overwritten unless the ref-spec specifies force.
+    RejectedTagUpdate,
+    /// The update would not be a fast-forward, and force is not specified in the ref-spec.
+    ///
+    /// This is also the case if the object the remote ref points to isn't present locally, so it must be fetched first.
+    RejectedNonFastForward,
+    /// The ref can't be deleted as the remote doesn't support the `delete-refs` capability.
+    RejectedDeletion,
+}
+
+impl Mode {
+    /// Return true if this update is going to be sent to the remote.
+    pub fn is_update(&self) -> bool {
+        matches!(self, Mode::FastForward | Mode::Forced | Mode::New | Mode::Delete)
+    }
+
+    /// Return true if this update was rejected locally, and won't be sent to the remote.
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self,
+            Mode::RejectedTagUpdate | Mode::RejectedNonFastForward | Mode::RejectedDeletion
+        )
+    }
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::NoChangeNeeded => "up-to-date",
+            Mode::FastForward => "fast-forward",
+            Mode::Forced => "forced-update",
+            Mode::New => "new",
+            Mode::Delete => "deleted",
+            Mode::RejectedTagUpdate => "rejected (would overwrite existing tag)",
+            Mode::RejectedNonFastForward => "rejected (non-fast-forward)",
+            Mode::RejectedDeletion => "rejected (remote does not support deleting refs)",
+        }
+        .fmt(f)
+    }
+}
+
+/// A single update of a reference on the remote, as derived from the push ref-specs of a remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    /// The full name of the local reference that is pushed, like `refs/heads/main`, or `None` if the ref-spec
+    /// deletes the remote ref or if its source is an object.
+    pub local: Option<BString>,
+    /// The full name of the reference on the remote side to create, update or delete, like `refs/heads/main`.
+    pub remote: BString,
+    /// The id the remote ref has as advertised by the remote, or `None` if it doesn't exist yet.
+    pub old_id: Option<git_hash::ObjectId>,
+    /// The id to set the remote ref to, or `None` if it should be deleted.
+    pub new_id: Option<git_hash::ObjectId>,
+    /// The way the remote ref is going to be updated, or why it won't be.
+    pub mode: Mode,
+    /// The index into the push ref-specs of the remote which produced this update.
+    pub spec_index: usize,
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub use super::connection::push::{prepare, Error, Outcome, Prepare, Status};
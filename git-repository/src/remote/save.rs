@@ -47,7 +47,15 @@ impl Remote<'_> {
                 .collect::<Vec<_>>()
         }) {
             let mut sections_to_remove = Vec::new();
-            const KEYS_TO_REMOVE: &[&str] = &["url", "pushurl", "fetch", "push", "tagOpt"];
+            const KEYS_TO_REMOVE: &[&str] = &[
+                "url",
+                "pushurl",
+                "fetch",
+                "push",
+                "tagOpt",
+                "promisor",
+                "partialclonefilter",
+            ];
             for id in section_ids {
                 let mut section = config.section_mut_by_id(id).expect("just queried");
                 let was_empty = section.num_values() == 0;
@@ -85,6 +93,12 @@ impl Remote<'_> {
                 .into(),
             )
         }
+        if self.promisor {
+            section.push(as_key("promisor"), Some("true".into()));
+        }
+        if let Some(filter) = self.filter {
+            section.push(as_key("partialclonefilter"), Some(filter.to_string().as_str().into()));
+        }
         for (key, spec) in self
             .fetch_specs
             .iter()
@@ -1,5 +1,3 @@
-use std::time::SystemTime;
-
 use crate::bstr::{BString, ByteSlice};
 
 /// Identity handling.
@@ -126,13 +124,10 @@ impl Personas {
                     .map(|v| v.into_owned()),
             )
         }
-        let now = SystemTime::now();
         let parse_date = |key: &str| -> Option<git_date::Time> {
-            config.string_by_key(key).and_then(|date| {
-                date.to_str()
-                    .ok()
-                    .and_then(|date| git_date::parse(date, Some(now)).ok())
-            })
+            config
+                .string_by_key(key)
+                .and_then(|date| date.to_str().ok().and_then(|date| git_date::parse::strict(date).ok()))
         };
 
         let (committer_name, committer_email) = entity_in_section("committer", config, true);
@@ -30,7 +30,10 @@ pub(crate) mod permissions;
 mod reference;
 mod remote;
 mod revision;
+mod shallow;
 mod snapshots;
 mod state;
+mod status;
+mod submodule;
 mod thread_safe;
 mod worktree;
@@ -98,6 +98,20 @@ impl crate::Repository {
             .map(|oid| oid.attach(self))
     }
 
+    /// Write all trees needed to represent the entries of `index` and return the id of the root tree, like `git write-tree`.
+    ///
+    /// Valid trees of the [`tree` extension][git_index::State::tree()] are reused and won't be written again, while the
+    /// extension itself remains unchanged.
+    /// Note that entries with conflicts cause an error, as trees can only be written from merged entries.
+    pub fn write_tree_from_index(
+        &self,
+        index: &git_index::State,
+    ) -> Result<Id<'_>, git_index::extension::tree::update::Error> {
+        index
+            .write_tree(|tree| self.objects.write(tree))
+            .map(|id| id.attach(self))
+    }
+
     /// Create a tag reference named `name` (without `refs/tags/` prefix) pointing to a newly created tag object
     /// which in turn points to `target` and return the newly created reference.
     ///
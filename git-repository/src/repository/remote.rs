@@ -140,6 +140,22 @@ impl crate::Repository {
             Some(Err(err)) => return Some(Err(err)),
             None => Default::default(),
         };
+        let object_filter = config
+            .string_filter("remote", Some(name_or_url), "partialCloneFilter", &mut filter)
+            .map(|spec| {
+                remote::fetch::Filter::from_spec(spec.as_ref()).ok_or_else(|| find::Error::PartialCloneFilter {
+                    value: spec.into_owned(),
+                })
+            });
+        let object_filter = match object_filter {
+            Some(Ok(v)) => Some(v),
+            Some(Err(err)) => return Some(Err(err)),
+            None => None,
+        };
+        let promisor = matches!(
+            config.boolean_filter("remote", Some(name_or_url), "promisor", &mut filter),
+            Some(Ok(true))
+        ) || object_filter.is_some();
 
         match (url, fetch_specs, push_url, push_specs) {
             (None, None, None, None) => None,
@@ -175,6 +191,8 @@ impl crate::Repository {
                         push_specs,
                         rewrite_urls,
                         fetch_tags,
+                        promisor,
+                        object_filter,
                         self,
                     )
                     .map_err(Into::into),
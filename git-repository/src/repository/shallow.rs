@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use crate::{bstr::ByteSlice, shallow};
+
+impl crate::Repository {
+    /// Return `true` if the repository is a shallow clone, i.e. contains history only up to a certain depth.
+    pub fn is_shallow(&self) -> bool {
+        self.shallow_file()
+            .metadata()
+            .map_or(false, |m| m.is_file() && m.len() > 0)
+    }
+
+    /// Return a sorted list of shallow commits, i.e. commits whose parents are not available in this repository
+    /// as its history was cut at them, or `None` if this repository isn't [shallow][Self::is_shallow()].
+    pub fn shallow_commits(&self) -> Result<Option<Vec<git_hash::ObjectId>>, shallow::open::Error> {
+        let buf = match std::fs::read(self.shallow_file()) {
+            Ok(buf) => buf,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut commits = buf
+            .lines()
+            .map(git_hash::ObjectId::from_hex)
+            .collect::<Result<Vec<_>, _>>()?;
+        if commits.is_empty() {
+            return Ok(None);
+        }
+        commits.sort();
+        Ok(Some(commits))
+    }
+
+    /// Return the path to the `shallow` file which contains hashes, one per line, that describe commits that don't have their
+    /// parents within this repository.
+    ///
+    /// Note that it may not exist if the repository isn't actually shallow.
+    pub fn shallow_file(&self) -> PathBuf {
+        self.common_dir().join("shallow")
+    }
+}
@@ -0,0 +1,10 @@
+use crate::status;
+
+impl crate::Repository {
+    /// Obtain a platform to learn about changes between the `HEAD` tree, the index and the worktree, similar to `git status`.
+    ///
+    /// This requires a worktree and fails if there is none.
+    pub fn status(&self) -> Result<status::Platform<'_>, status::Error> {
+        status::Platform::new(self)
+    }
+}
@@ -0,0 +1,34 @@
+use std::rc::Rc;
+
+use crate::{submodule, Submodule};
+
+impl crate::Repository {
+    /// Read and parse the `.gitmodules` file in the worktree of this repository, or return `None` if there is no worktree
+    /// or no such file.
+    pub fn modules(&self) -> Result<Option<git_submodule::File>, submodule::modules::Error> {
+        let path = match self.work_dir() {
+            Some(work_dir) => work_dir.join(".gitmodules"),
+            None => return Ok(None),
+        };
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(git_submodule::File::from_bytes(&bytes, path)?))
+    }
+
+    /// Return an iterator over all submodules configured in the [`.gitmodules` file][Self::modules()], or `None`
+    /// if there is no such file.
+    pub fn submodules(&self) -> Result<Option<impl Iterator<Item = Submodule<'_>>>, submodule::modules::Error> {
+        Ok(self.modules()?.map(|modules| {
+            let modules = Rc::new(modules);
+            let names: Vec<_> = modules.names().map(ToOwned::to_owned).collect();
+            names.into_iter().map(move |name| Submodule {
+                modules: Rc::clone(&modules),
+                name,
+                repo: self,
+            })
+        }))
+    }
+}
@@ -85,6 +85,16 @@ impl crate::Repository {
         .map_err(Into::into)
     }
 
+    /// Return the patterns of the sparse checkout as read from `info/sparse-checkout` if `core.sparseCheckout` is enabled,
+    /// in cone mode unless `core.sparseCheckoutCone` is false.
+    ///
+    /// `None` is returned if sparse checkouts are disabled or if there are no patterns.
+    pub fn sparse_checkout_patterns(
+        &self,
+    ) -> Result<Option<git_worktree::sparse::Patterns>, crate::config::checkout_options::Error> {
+        self.config.sparse_checkout_patterns(self.git_dir())
+    }
+
     /// Return a shared worktree index which is updated automatically if the in-memory snapshot has become stale as the underlying file
     /// on disk has changed.
     ///
@@ -1,8 +1,25 @@
-use crate::{ext::ReferenceExt, revision::Spec, Id, Reference};
+use git_odb::FindExt;
+
+use crate::{ext::ReferenceExt, revision, revision::Spec, Id, Reference};
 
 ///
 pub mod parse;
 
+///
+pub mod walk {
+    /// The error returned by [`Spec::rev_walk()`][crate::revision::Spec::rev_walk()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error("Could not compute the merge bases")]
+        MergeBase(#[from] git_traverse::commit::ancestors::Error),
+    }
+}
+
 mod impls {
     use std::ops::{Deref, DerefMut};
 
@@ -88,3 +105,35 @@ impl<'repo> Spec<'repo> {
         }
     }
 }
+
+/// Traversal
+impl<'repo> Spec<'repo> {
+    /// Return a platform to walk all commits included by this specification while hiding all commits it excludes,
+    /// similar to `git rev-list <spec>`.
+    ///
+    /// For example, `a..b` walks `b` with `a` hidden, and `a...b` walks `a` and `b` with all of their merge bases hidden.
+    pub fn rev_walk(&self) -> Result<revision::walk::Platform<'repo>, walk::Error> {
+        use git_revision::Spec::*;
+        let repo = self.repo;
+        let parents = |id: git_hash::ObjectId| -> Result<Vec<git_hash::ObjectId>, walk::Error> {
+            Ok(Id::from_id(id, repo)
+                .object()?
+                .try_into_commit()?
+                .parent_ids()
+                .map(|id| id.detach())
+                .collect())
+        };
+        let (tips, hidden) = match self.inner {
+            Include(id) => (vec![id], Vec::new()),
+            Exclude(id) => (Vec::new(), vec![id]),
+            Range { from, to } => (vec![to], vec![from]),
+            Merge { theirs, ours } => (
+                vec![theirs, ours],
+                git_traverse::commit::merge_bases(theirs, &[ours], |oid, buf| repo.objects.find_commit_iter(oid, buf))?,
+            ),
+            IncludeOnlyParents(id) => (parents(id)?, Vec::new()),
+            ExcludeParents(id) => (vec![id], parents(id)?),
+        };
+        Ok(repo.rev_walk(tips).with_hidden(hidden))
+    }
+}
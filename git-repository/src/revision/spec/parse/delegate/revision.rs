@@ -9,6 +9,7 @@ use git_revision::spec::parse::{
 use crate::{
     bstr::{BStr, BString, ByteSlice},
     ext::ReferenceExt,
+    remote,
     revision::spec::parse::{Delegate, Error, RefsHint},
 };
 
@@ -104,59 +105,84 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
 
     fn reflog(&mut self, query: ReflogLookup) -> Option<()> {
         self.unset_disambiguate_call();
+        let r = match &mut self.refs[self.idx] {
+            Some(r) => r.clone().attach(self.repo),
+            val @ None => match self.repo.head().map(|head| head.try_into_referent()) {
+                Ok(Some(r)) => {
+                    *val = Some(r.clone().detach());
+                    r
+                }
+                Ok(None) => {
+                    self.err.push(Error::UnbornHeadsHaveNoRefLog);
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        let mut platform = r.log_iter();
         match query {
-            ReflogLookup::Date(_date) => {
-                self.err.push(Error::Planned {
-                    dependency: "remote handling and ref-specs are fleshed out more",
-                });
-                None
-            }
-            ReflogLookup::Entry(no) => {
-                let r = match &mut self.refs[self.idx] {
-                    Some(r) => r.clone().attach(self.repo),
-                    val @ None => match self.repo.head().map(|head| head.try_into_referent()) {
-                        Ok(Some(r)) => {
-                            *val = Some(r.clone().detach());
-                            r
-                        }
-                        Ok(None) => {
-                            self.err.push(Error::UnbornHeadsHaveNoRefLog);
-                            return None;
-                        }
-                        Err(err) => {
-                            self.err.push(err.into());
-                            return None;
-                        }
-                    },
-                };
-                let mut platform = r.log_iter();
-                match platform.rev().ok().flatten() {
-                    Some(mut it) => match it.nth(no).and_then(Result::ok) {
-                        Some(line) => {
-                            self.objs[self.idx]
-                                .get_or_insert_with(HashSet::default)
-                                .insert(line.new_oid);
-                            Some(())
+            ReflogLookup::Date(date) => {
+                let id = platform.rev().ok().flatten().and_then(|it| {
+                    // Like git, pick the newest entry that isn't younger than `date`, or fall back to the state
+                    // before the oldest entry if the log doesn't reach back far enough.
+                    let mut oldest = None;
+                    for line in it.filter_map(Result::ok) {
+                        if line.signature.time.seconds_since_unix_epoch <= date.seconds_since_unix_epoch {
+                            return Some(line.new_oid);
                         }
-                        None => {
-                            let available = platform.rev().ok().flatten().map_or(0, |it| it.count());
-                            self.err.push(Error::RefLogEntryOutOfRange {
-                                reference: r.detach(),
-                                desired: no,
-                                available,
-                            });
-                            None
+                        oldest = Some(line);
+                    }
+                    oldest.map(|line| {
+                        if line.previous_oid.is_null() {
+                            line.new_oid
+                        } else {
+                            line.previous_oid
                         }
-                    },
+                    })
+                });
+                match id {
+                    Some(id) => {
+                        self.objs[self.idx].get_or_insert_with(HashSet::default).insert(id);
+                        Some(())
+                    }
                     None => {
                         self.err.push(Error::MissingRefLog {
                             reference: r.name().as_bstr().into(),
-                            action: "lookup entry",
+                            action: "lookup entry by date",
                         });
                         None
                     }
                 }
             }
+            ReflogLookup::Entry(no) => match platform.rev().ok().flatten() {
+                Some(mut it) => match it.nth(no).and_then(Result::ok) {
+                    Some(line) => {
+                        self.objs[self.idx]
+                            .get_or_insert_with(HashSet::default)
+                            .insert(line.new_oid);
+                        Some(())
+                    }
+                    None => {
+                        let available = platform.rev().ok().flatten().map_or(0, |it| it.count());
+                        self.err.push(Error::RefLogEntryOutOfRange {
+                            reference: r.detach(),
+                            desired: no,
+                            available,
+                        });
+                        None
+                    }
+                },
+                None => {
+                    self.err.push(Error::MissingRefLog {
+                        reference: r.name().as_bstr().into(),
+                        action: "lookup entry",
+                    });
+                    None
+                }
+            },
         }
     }
 
@@ -215,11 +241,50 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         }
     }
 
-    fn sibling_branch(&mut self, _kind: SiblingBranch) -> Option<()> {
+    fn sibling_branch(&mut self, kind: SiblingBranch) -> Option<()> {
         self.unset_disambiguate_call();
-        self.err.push(Error::Planned {
-            dependency: "remote handling and ref-specs are fleshed out more",
-        });
-        None
+        // Like `git`, `HEAD` refers to the branch it points to.
+        let r = match self.refs[self.idx].as_ref().filter(|r| r.name.as_bstr() != "HEAD") {
+            Some(r) => r.clone().attach(self.repo),
+            None => match self.repo.head().map(|head| head.try_into_referent()) {
+                Ok(Some(r)) => r,
+                Ok(None) => {
+                    self.err.push(Error::HeadIsNotABranch);
+                    return None;
+                }
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        let direction = match kind {
+            SiblingBranch::Upstream => remote::Direction::Fetch,
+            SiblingBranch::Push => remote::Direction::Push,
+        };
+        let name = match r.remote_tracking_ref(direction) {
+            Some(Ok(name)) => name,
+            Some(Err(err)) => {
+                self.err.push(err.into());
+                return None;
+            }
+            None => {
+                self.err.push(Error::NoRemoteTrackingBranch {
+                    reference: r.name().as_bstr().into(),
+                    direction,
+                });
+                return None;
+            }
+        };
+        match self.repo.find_reference(name.as_ref()) {
+            Ok(tracking_ref) => {
+                self.refs[self.idx] = Some(tracking_ref.detach());
+                Some(())
+            }
+            Err(err) => {
+                self.err.push(err.into());
+                None
+            }
+        }
     }
 }
@@ -62,8 +62,15 @@ pub struct Options {
 pub enum Error {
     #[error("Unborn heads do not have a reflog yet")]
     UnbornHeadsHaveNoRefLog,
-    #[error("This feature will be implemented once {dependency}")]
-    Planned { dependency: &'static str },
+    #[error("HEAD does not point to a branch")]
+    HeadIsNotABranch,
+    #[error("Reference {reference:?} has no remote tracking branch configured to {}", direction.as_str())]
+    NoRemoteTrackingBranch {
+        reference: BString,
+        direction: crate::remote::Direction,
+    },
+    #[error(transparent)]
+    RemoteTrackingRef(#[from] crate::reference::remote_tracking_ref::Error),
     #[error("Reference {reference:?} does not have a reference log, cannot {action}")]
     MissingRefLog { reference: BString, action: &'static str },
     #[error("HEAD has {available} prior checkouts and checkout number {desired} is out of range")]
@@ -175,6 +182,8 @@ pub enum Error {
     },
     #[error(transparent)]
     Traverse(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
     #[error("Spec does not contain a single object id")]
     SingleNotFound,
 }
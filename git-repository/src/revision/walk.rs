@@ -1,13 +1,24 @@
 use git_hash::ObjectId;
 use git_odb::FindExt;
 
-use crate::{revision, Repository};
+use crate::{bstr::ByteSlice, revision, Repository};
+
+/// The error returned by [`Platform::all()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    AncestorIter(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    ShallowCommits(#[from] crate::shallow::open::Error),
+}
 
 /// A platform to traverse the revision graph by adding starting points as well as points which shouldn't be crossed,
 /// returned by [`Repository::rev_walk()`].
 pub struct Platform<'repo> {
     pub(crate) repo: &'repo Repository,
     pub(crate) tips: Vec<ObjectId>,
+    pub(crate) hidden: Vec<ObjectId>,
     pub(crate) sorting: git_traverse::commit::Sorting,
     pub(crate) parents: git_traverse::commit::Parents,
 }
@@ -17,6 +28,7 @@ impl<'repo> Platform<'repo> {
         revision::walk::Platform {
             repo,
             tips: tips.into_iter().map(Into::into).collect(),
+            hidden: Vec::new(),
             sorting: Default::default(),
             parents: Default::default(),
         }
@@ -26,6 +38,10 @@ impl<'repo> Platform<'repo> {
 /// Create-time builder methods
 impl<'repo> Platform<'repo> {
     /// Set the sort mode for commits to the given value. The default is to order by topology.
+    ///
+    /// Sorting by [`TopoOrder`][git_traverse::commit::Sorting::TopoOrder] or
+    /// [`AuthorDateOrder`][git_traverse::commit::Sorting::AuthorDateOrder] uses the commit-graph of the repository
+    /// unless it's disabled with `core.commitGraph`.
     pub fn sorting(mut self, sorting: git_traverse::commit::Sorting) -> Self {
         self.sorting = sorting;
         self
@@ -36,6 +52,15 @@ impl<'repo> Platform<'repo> {
         self.parents = git_traverse::commit::Parents::First;
         self
     }
+
+    /// Don't return commits reachable from any of the given `tips` and don't traverse past them, similar to `^tip` in
+    /// `git rev-list`, even if clock skew makes them appear younger than the commits they are reachable from.
+    ///
+    /// Hidden commits that are parents of returned commits are available as [boundary][revision::Walk::boundary()].
+    pub fn with_hidden(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.hidden.extend(tips.into_iter().map(Into::into));
+        self
+    }
 }
 
 /// Produce the iterator
@@ -46,23 +71,46 @@ impl<'repo> Platform<'repo> {
     ///
     /// It's highly recommended to set an [`object cache`][Repository::object_cache_size()] on the parent repo
     /// to greatly speed up performance if the returned id is supposed to be looked up right after.
-    pub fn all(self) -> Result<revision::Walk<'repo>, git_traverse::commit::ancestors::Error> {
+    ///
+    /// # Shallow Repositories
+    ///
+    /// In [shallow repositories][Repository::is_shallow()], the traversal stops at the shallow boundary as
+    /// the parents of shallow commits are ignored, and the commit-graph isn't used.
+    pub fn all(self) -> Result<revision::Walk<'repo>, Error> {
         let Platform {
             repo,
             tips,
+            hidden,
             sorting,
             parents,
         } = self;
+        let shallow_commits = repo.shallow_commits()?;
+        let commit_graph = (shallow_commits.is_none()
+            && matches!(
+                sorting,
+                git_traverse::commit::Sorting::TopoOrder | git_traverse::commit::Sorting::AuthorDateOrder
+            ))
+        .then(|| commit_graph(repo))
+        .flatten();
         Ok(revision::Walk {
             repo,
             inner: Box::new(
                 git_traverse::commit::Ancestors::new(
                     tips,
                     git_traverse::commit::ancestors::State::default(),
-                    move |oid, buf| repo.objects.find_commit_iter(oid, buf),
+                    move |oid, buf| match shallow_commits.as_ref() {
+                        Some(commits) if commits.binary_search_by(|id| id.as_ref().cmp(oid)).is_ok() => {
+                            repo.objects.find_commit_iter(oid, buf)?;
+                            remove_parents(buf);
+                            Ok(git_object::CommitRefIter::from_bytes(buf))
+                        }
+                        _ => repo.objects.find_commit_iter(oid, buf),
+                    },
                 )
                 .sorting(sorting)?
-                .parents(parents),
+                .hide(hidden)?
+                .parents(parents)
+                .commit_graph(commit_graph),
             ),
             is_shallow: None,
             error_on_missing_commit: false,
@@ -70,14 +118,60 @@ impl<'repo> Platform<'repo> {
     }
 }
 
+/// Remove all `parent` lines from the headers of the commit in `buf`, which is what makes a commit shallow.
+fn remove_parents(buf: &mut Vec<u8>) {
+    let mut pos = 0;
+    while let Some(line_len) = buf[pos..].find_byte(b'\n').map(|end| end + 1) {
+        if line_len == 1 {
+            // The empty line separating the headers from the message.
+            break;
+        }
+        if buf[pos..].starts_with(b"parent ") {
+            buf.drain(pos..pos + line_len);
+        } else {
+            pos += line_len;
+        }
+    }
+}
+
+/// Return the commit-graph of `repo` if there is one and if it's not disabled with `core.commitGraph`.
+fn commit_graph(repo: &Repository) -> Option<git_commitgraph::Graph> {
+    let enabled = repo
+        .config
+        .resolved
+        .boolean("core", None, "commitGraph")
+        .map_or(true, |enabled| enabled.unwrap_or(true));
+    enabled
+        .then(|| git_commitgraph::Graph::from_info_dir(repo.objects.store_ref().path().join("info")).ok())
+        .flatten()
+}
+
 pub(crate) mod iter {
+    use std::borrow::Borrow;
+
     use crate::{ext::ObjectIdExt, Id};
 
+    /// A traversal which knows its boundary.
+    pub(crate) trait Traversal:
+        Iterator<Item = Result<git_hash::ObjectId, git_traverse::commit::ancestors::Error>>
+    {
+        fn boundary(&self) -> &[git_hash::ObjectId];
+    }
+
+    impl<Find, Predicate, StateMut> Traversal for git_traverse::commit::Ancestors<Find, Predicate, StateMut>
+    where
+        Self: Iterator<Item = Result<git_hash::ObjectId, git_traverse::commit::ancestors::Error>>,
+        StateMut: Borrow<git_traverse::commit::ancestors::State>,
+    {
+        fn boundary(&self) -> &[git_hash::ObjectId] {
+            git_traverse::commit::Ancestors::boundary(self)
+        }
+    }
+
     /// The iterator returned by [`crate::revision::walk::Platform::all()`].
     pub struct Walk<'repo> {
         pub(crate) repo: &'repo crate::Repository,
-        pub(crate) inner:
-            Box<dyn Iterator<Item = Result<git_hash::ObjectId, git_traverse::commit::ancestors::Error>> + 'repo>,
+        pub(crate) inner: Box<dyn Traversal + 'repo>,
         pub(crate) error_on_missing_commit: bool,
         // TODO: tests
         /// After iteration this flag is true if the iteration was stopped prematurely due to missing parent commits.
@@ -100,6 +194,14 @@ pub(crate) mod iter {
             self.error_on_missing_commit = true;
             self
         }
+
+        /// Return all [hidden][crate::revision::walk::Platform::with_hidden()] commits that are parents of commits
+        /// returned so far, which is what `git rev-list --boundary` marks with `-`.
+        ///
+        /// The boundary is complete once the iteration ended.
+        pub fn boundary(&self) -> impl Iterator<Item = Id<'repo>> + '_ {
+            self.inner.boundary().iter().map(|id| id.attach(self.repo))
+        }
     }
 
     impl<'repo> Iterator for Walk<'repo> {
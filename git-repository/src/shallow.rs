@@ -0,0 +1,76 @@
+//! Handling of shallow repositories, i.e. repositories with a history that ends at the commits listed in their `shallow` file.
+
+///
+pub mod open {
+    /// The error returned by [`Repository::shallow_commits()`][crate::Repository::shallow_commits()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open the shallow file for reading")]
+        Io(#[from] std::io::Error),
+        #[error("Could not decode a line in the shallow file as hex-encoded object hash")]
+        DecodeHash(#[from] git_hash::decode::Error),
+    }
+}
+
+///
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub mod write {
+    pub(crate) use function::write;
+
+    /// The error returned when writing the shallow file after fetching.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Commit(#[from] git_lock::commit::Error<git_lock::File>),
+        #[error("Could not remove an empty shallow file")]
+        RemoveEmpty(#[source] std::io::Error),
+        #[error("Failed to write object id to shallow file")]
+        Io(#[source] std::io::Error),
+    }
+
+    mod function {
+        use std::io::Write;
+
+        use git_hash::ObjectId;
+        use git_protocol::fetch::response::ShallowUpdate;
+
+        use super::Error;
+
+        /// Write the previously obtained (possibly non-existing) `shallow_commits` to the shallow `file`
+        /// after applying all `updates`.
+        ///
+        /// If this leaves the list of shallow commits empty, the file is removed as the repository isn't shallow anymore.
+        pub fn write(
+            mut file: git_lock::File,
+            shallow_commits: Option<Vec<ObjectId>>,
+            updates: &[ShallowUpdate],
+        ) -> Result<(), Error> {
+            let mut shallow_commits = shallow_commits.unwrap_or_default();
+            for update in updates {
+                match update {
+                    ShallowUpdate::Shallow(id) => shallow_commits.push(*id),
+                    ShallowUpdate::Unshallow(id) => shallow_commits.retain(|oid| oid != id),
+                }
+            }
+            if shallow_commits.is_empty() {
+                if let Err(err) = std::fs::remove_file(file.resource_path()) {
+                    if err.kind() != std::io::ErrorKind::NotFound {
+                        return Err(Error::RemoveEmpty(err));
+                    }
+                }
+            } else {
+                shallow_commits.sort();
+                shallow_commits.dedup();
+                for commit in shallow_commits {
+                    writeln!(file, "{}", commit).map_err(Error::Io)?;
+                }
+                file.commit()?;
+            }
+            Ok(())
+        }
+    }
+}
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub(crate) use write::write;
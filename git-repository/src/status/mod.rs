@@ -0,0 +1,359 @@
+//! Obtain information about changes between the `HEAD` tree, the index and the worktree, similar to `git status`.
+use std::{cmp::Ordering, sync::atomic::AtomicBool};
+
+use git_hash::ObjectId;
+use git_odb::FindExt;
+
+pub use git_worktree::status::{index_to_worktree::Change as IndexWorktreeChange, untracked::Files as UntrackedFiles};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// The error returned by [`Repository::status()`] and [`Platform::for_each()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to obtain its status")]
+    MissingWorktree,
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WorktreeOptions(#[from] crate::config::checkout_options::Error),
+    #[error("The value '{value}' of status.showUntrackedFiles is invalid, it must be one of 'no', 'normal' or 'all'")]
+    UntrackedFilesMode { value: BString },
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    FindHeadObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    ExpandSparseDirectories(#[from] git_index::sparse::expand::Error),
+    #[error(transparent)]
+    Attributes(#[from] crate::worktree::attributes::Error),
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[from] std::io::Error),
+    #[error(transparent)]
+    Excludes(#[from] crate::worktree::excludes::Error),
+    #[error(transparent)]
+    IndexWorktree(#[from] git_worktree::status::index_to_worktree::Error),
+    #[error(transparent)]
+    Untracked(#[from] git_worktree::status::untracked::Error),
+}
+
+/// The way an entry in the `HEAD` tree differs from the one in the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TreeIndexChange {
+    /// The entry was added to the index.
+    Addition {
+        /// The id of the entry in the index.
+        id: ObjectId,
+    },
+    /// The entry was removed from the index.
+    Deletion {
+        /// The id of the entry in the tree.
+        previous_id: ObjectId,
+    },
+    /// The entry changed its content or its mode, or both.
+    Modification {
+        /// The id of the entry in the tree.
+        previous_id: ObjectId,
+        /// The id of the entry in the index.
+        id: ObjectId,
+    },
+}
+
+/// An item produced by [`Platform::for_each()`].
+#[derive(Debug, Clone, Copy)]
+pub enum Item<'a> {
+    /// An entry that is different in the `HEAD` tree and in the index, which is a staged change.
+    TreeIndex {
+        /// The repository-relative path of the entry.
+        path: &'a BStr,
+        /// The way the entry differs.
+        change: TreeIndexChange,
+    },
+    /// An entry which has conflicts as it's the result of an incomplete merge. Its stages are not compared to the tree or worktree.
+    Conflict {
+        /// The repository-relative path of the entry.
+        path: &'a BStr,
+        /// If true, the common ancestor is present.
+        ancestor: bool,
+        /// If true, our version is present.
+        ours: bool,
+        /// If true, their version is present.
+        theirs: bool,
+    },
+    /// An entry that is different in the index and in the worktree, which is an unstaged change.
+    IndexWorktree {
+        /// The repository-relative path of the entry.
+        path: &'a BStr,
+        /// The index entry itself.
+        entry: &'a git_index::Entry,
+        /// The way the entry differs.
+        change: IndexWorktreeChange,
+    },
+    /// A file or directory in the worktree that isn't tracked in the index.
+    Untracked {
+        /// The repository-relative path of the item, without trailing slash if it is a directory.
+        path: &'a BStr,
+        /// If true, the item is a directory all of whose content is untracked.
+        is_dir: bool,
+    },
+    /// A file or directory in the worktree that isn't tracked and matches an exclude pattern.
+    Ignored {
+        /// The repository-relative path of the item, without trailing slash if it is a directory.
+        path: &'a BStr,
+        /// If true, the item is a directory all of whose content is ignored.
+        is_dir: bool,
+    },
+}
+
+/// A platform to configure which changes are obtained, returned by [`Repository::status()`].
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) index: crate::worktree::Index,
+    pub(crate) index_worktree_options: git_worktree::status::index_to_worktree::Options,
+    pub(crate) untracked_files: Option<UntrackedFiles>,
+    pub(crate) ignored: bool,
+}
+
+impl<'repo> Platform<'repo> {
+    pub(crate) fn new(repo: &'repo Repository) -> Result<Self, Error> {
+        let worktree = repo.worktree().ok_or(Error::MissingWorktree)?;
+        let index = worktree.index()?;
+        let checkout_options = repo.config.checkout_options()?;
+        let untracked_files = match repo.config.resolved.string("status", None, "showUntrackedFiles") {
+            None => Some(UntrackedFiles::Collapsed),
+            Some(value) => match value.to_lowercase().as_slice() {
+                b"no" | b"false" | b"off" | b"0" => None,
+                b"normal" | b"true" | b"on" | b"yes" | b"1" => Some(UntrackedFiles::Collapsed),
+                b"all" => Some(UntrackedFiles::All),
+                _ if repo.config.lenient_config => Some(UntrackedFiles::Collapsed),
+                _ => {
+                    return Err(Error::UntrackedFilesMode {
+                        value: value.into_owned(),
+                    })
+                }
+            },
+        };
+        Ok(Platform {
+            repo,
+            index,
+            index_worktree_options: git_worktree::status::index_to_worktree::Options {
+                fs: checkout_options.fs,
+                stat: git_index::entry::stat::Options {
+                    trust_ctime: checkout_options.trust_ctime,
+                    check_stat: checkout_options.check_stat,
+                    ..Default::default()
+                },
+                thread_limit: checkout_options.thread_limit,
+                filters: checkout_options.filters,
+            },
+            untracked_files,
+            ignored: false,
+        })
+    }
+}
+
+/// Create-time builder methods
+impl<'repo> Platform<'repo> {
+    /// Configure how untracked files are reported, or don't report them at all if `None`.
+    ///
+    /// The default is configured by `status.showUntrackedFiles`.
+    pub fn untracked_files(mut self, mode: Option<UntrackedFiles>) -> Self {
+        self.untracked_files = mode;
+        self
+    }
+
+    /// If `toggle` is true, report ignored files as well. This has no effect if untracked files are not reported.
+    pub fn ignored(mut self, toggle: bool) -> Self {
+        self.ignored = toggle;
+        self
+    }
+
+    /// Use no more than `thread_limit` threads to compare the index with the worktree, or as many as there are logical cores
+    /// if `None` or 0. The default is configured by `checkout.workers`.
+    pub fn thread_limit(mut self, thread_limit: Option<usize>) -> Self {
+        self.index_worktree_options.thread_limit = thread_limit;
+        self
+    }
+}
+
+/// Obtain the changes
+impl<'repo> Platform<'repo> {
+    /// Call `cb` with each change between the `HEAD` tree and the index, followed by each change between the index and
+    /// the worktree and finally each untracked and ignored file as configured.
+    ///
+    /// Items of each kind are produced in the order of their paths, and the operation can be interrupted with `should_interrupt`.
+    pub fn for_each(self, should_interrupt: &AtomicBool, mut cb: impl FnMut(Item<'_>)) -> Result<(), Error> {
+        let Platform {
+            repo,
+            index,
+            index_worktree_options,
+            untracked_files,
+            ignored,
+        } = self;
+        tree_to_index(repo, &index, &mut cb)?;
+
+        let worktree = repo.worktree().expect("present as checked when created");
+        git_worktree::status::index_to_worktree(
+            &index,
+            &worktree.attributes(&index, None)?,
+            {
+                let objects = repo.objects.clone().into_arc()?;
+                move |oid, buf| objects.find_blob(oid, buf)
+            },
+            index_worktree_options,
+            should_interrupt,
+            |path, entry, change| cb(Item::IndexWorktree { path, entry, change }),
+        )?;
+
+        if let Some(files) = untracked_files {
+            let mut cache = worktree.excludes(&index, None)?;
+            git_worktree::status::untracked(
+                &index,
+                &mut cache,
+                |id, buf| repo.objects.find_blob(id, buf),
+                git_worktree::status::untracked::Options { files, ignored },
+                should_interrupt,
+                |path, kind, is_dir| {
+                    cb(match kind {
+                        git_worktree::status::untracked::Kind::Untracked => Item::Untracked { path, is_dir },
+                        git_worktree::status::untracked::Kind::Ignored => Item::Ignored { path, is_dir },
+                    })
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the tree `HEAD` points to with `index` and call `cb` with all changes and conflicts in the order of their paths.
+///
+/// Sparse directories of `index` are expanded if their tree differs from the one in `HEAD`, and skipped otherwise.
+fn tree_to_index(repo: &Repository, index: &git_index::State, cb: &mut impl FnMut(Item<'_>)) -> Result<(), Error> {
+    let (mut tree_entries, tree_dirs) = {
+        let mut head = repo.head()?;
+        let tree = match head.peel_to_id_in_place().transpose()? {
+            Some(id) => id.object()?.peel_to_tree()?,
+            None => repo.empty_tree(),
+        };
+        let mut recorder = git_traverse::tree::Recorder::default();
+        tree.traverse().breadthfirst(&mut recorder)?;
+        let (mut dirs, mut entries): (Vec<_>, Vec<_>) = recorder.records.into_iter().partition(|e| e.mode.is_tree());
+        entries.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+        dirs.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+        (entries.into_iter().peekable(), dirs)
+    };
+    let tree_dir_id = |dir: &BStr| {
+        let dir = dir.strip_suffix(b"/").unwrap_or(dir).as_bstr();
+        tree_dirs
+            .binary_search_by(|e| e.filepath.as_bstr().cmp(dir))
+            .ok()
+            .map(|idx| tree_dirs[idx].oid)
+    };
+
+    let expanded;
+    let index = if index
+        .entries()
+        .iter()
+        .any(|e| e.mode.is_sparse() && tree_dir_id(e.path(index)) != Some(e.id))
+    {
+        let mut index = index.clone();
+        let changed_dirs: Vec<_> = index
+            .entries()
+            .iter()
+            .filter(|e| e.mode.is_sparse() && tree_dir_id(e.path(&index)) != Some(e.id))
+            .map(|e| e.path(&index).to_owned())
+            .collect();
+        index.expand_sparse_directories(
+            |dir| changed_dirs.iter().any(|changed| changed == dir),
+            |id, buf| repo.objects.find_tree_iter(id, buf).ok(),
+        )?;
+        expanded = index;
+        &expanded
+    } else {
+        index
+    };
+
+    let mut index_entries = index
+        .entries()
+        .iter()
+        .filter(|e| !e.flags.contains(git_index::entry::Flags::INTENT_TO_ADD))
+        .peekable();
+    loop {
+        let ordering = match (tree_entries.peek(), index_entries.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(tree_entry), Some(index_entry)) => tree_entry.filepath.as_bstr().cmp(index_entry.path(index)),
+        };
+        match ordering {
+            Ordering::Less => {
+                let tree_entry = tree_entries.next().expect("peeked");
+                cb(Item::TreeIndex {
+                    path: tree_entry.filepath.as_bstr(),
+                    change: TreeIndexChange::Deletion {
+                        previous_id: tree_entry.oid,
+                    },
+                });
+            }
+            Ordering::Greater | Ordering::Equal => {
+                let index_entry = index_entries.next().expect("peeked");
+                let path = index_entry.path(index);
+                if index_entry.mode.is_sparse() {
+                    // The directory is unchanged as its tree is the same as in `HEAD`.
+                    while tree_entries.next_if(|e| e.filepath.starts_with(path)).is_some() {}
+                    continue;
+                }
+                let tree_entry = (ordering == Ordering::Equal).then(|| tree_entries.next().expect("peeked"));
+                if index_entry.stage() != 0 {
+                    let (mut ancestor, mut ours, mut theirs) = (false, false, false);
+                    for entry in std::iter::once(index_entry)
+                        .chain(std::iter::from_fn(|| index_entries.next_if(|e| e.path(index) == path)))
+                    {
+                        match entry.stage() {
+                            1 => ancestor = true,
+                            2 => ours = true,
+                            _ => theirs = true,
+                        }
+                    }
+                    cb(Item::Conflict {
+                        path,
+                        ancestor,
+                        ours,
+                        theirs,
+                    });
+                    continue;
+                }
+                match tree_entry {
+                    None => cb(Item::TreeIndex {
+                        path,
+                        change: TreeIndexChange::Addition { id: index_entry.id },
+                    }),
+                    Some(tree_entry) => {
+                        if tree_entry.oid != index_entry.id
+                            || u32::from(tree_entry.mode as u16) != index_entry.mode.bits()
+                        {
+                            cb(Item::TreeIndex {
+                                path,
+                                change: TreeIndexChange::Modification {
+                                    previous_id: tree_entry.oid,
+                                    id: index_entry.id,
+                                },
+                            })
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Submodules are repositories whose commits are recorded in the trees and the index of their _superproject_,
+//! and which are configured in its `.gitmodules` file.
+use std::{borrow::Cow, path::PathBuf};
+
+pub use git_submodule::{config, State};
+
+use crate::{bstr::BStr, remote, Repository, Submodule};
+
+///
+pub mod modules {
+    /// The error returned by [Repository::modules()][crate::Repository::modules()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the '.gitmodules' file")]
+        OpenFile(#[from] std::io::Error),
+        #[error("The '.gitmodules' file could not be parsed")]
+        Parse(#[from] git_config::file::init::Error),
+    }
+}
+
+///
+pub mod url {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::url()][crate::Submodule::url()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Modules(#[from] git_submodule::config::url::Error),
+        #[error("The url of submodule '{submodule}' in the configuration of the superproject could not be parsed")]
+        Parse {
+            submodule: BString,
+            source: git_url::parse::Error,
+        },
+    }
+}
+
+///
+pub mod index_id {
+    /// The error returned by [Submodule::index_id()][crate::Submodule::index_id()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Path(#[from] git_submodule::config::path::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+    }
+}
+
+///
+pub mod open {
+    /// The error returned by [Submodule::open()][crate::Submodule::open()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Path(#[from] git_submodule::config::path::Error),
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+    }
+}
+
+/// Access
+impl<'repo> Submodule<'repo> {
+    /// Return the name of the submodule, which identifies it in the `.gitmodules` file and in the configuration
+    /// of the superproject.
+    pub fn name(&self) -> &BStr {
+        self.name.as_ref()
+    }
+
+    /// Return the path of the submodule relative to the worktree of the superproject.
+    pub fn path(&self) -> Result<Cow<'_, BStr>, config::path::Error> {
+        self.modules.path(self.name())
+    }
+
+    /// Return the url of the submodule, preferring the one in the configuration of the superproject, which is set
+    /// once the submodule is initialized.
+    ///
+    /// Otherwise the url in the `.gitmodules` file is used, with relative urls resolved against the url of the
+    /// default remote of the superproject, or against its worktree if there is no such remote.
+    pub fn url(&self) -> Result<git_url::Url, url::Error> {
+        if let Some(url) = self.repo.config.resolved.string("submodule", Some(self.name()), "url") {
+            return git_url::parse(url.as_ref()).map_err(|err| url::Error::Parse {
+                submodule: self.name.clone(),
+                source: err,
+            });
+        }
+        let base = self
+            .repo
+            .remote_default_name(remote::Direction::Fetch)
+            .and_then(|name| self.repo.find_remote(name.as_ref()).ok())
+            .and_then(|remote| remote.url(remote::Direction::Fetch).map(git_url::Url::to_bstring))
+            .unwrap_or_else(|| {
+                git_path::into_bstr(self.repo.work_dir().unwrap_or_else(|| self.repo.git_dir())).into_owned()
+            });
+        Ok(self.modules.resolved_url(self.name(), base.as_ref())?)
+    }
+
+    /// Return the way the submodule should be updated, preferring the configuration of the superproject
+    /// over the `.gitmodules` file, or `None` if it isn't configured which implies [`Update::Checkout`][config::Update::Checkout].
+    ///
+    /// Note that [commands][config::Update::Command] are only allowed in the configuration of the superproject.
+    pub fn update(&self) -> Result<Option<config::Update>, config::update::Error> {
+        match self
+            .repo
+            .config
+            .resolved
+            .string("submodule", Some(self.name()), "update")
+        {
+            Some(value) => {
+                config::Update::try_from(value.as_ref())
+                    .map(Some)
+                    .map_err(|()| config::update::Error::Invalid {
+                        submodule: self.name.clone(),
+                        actual: value.into_owned(),
+                    })
+            }
+            None => self.modules.update(self.name()),
+        }
+    }
+
+    /// Return true if the submodule is active and should thus be checked out along with the superproject.
+    pub fn is_active(&self) -> Result<bool, git_submodule::is_active::Error> {
+        self.modules.is_active(self.name(), &self.repo.config.resolved)
+    }
+
+    /// Return the state of the submodule within the superproject.
+    pub fn state(&self) -> Result<State, git_submodule::state::Error> {
+        self.modules.state(
+            self.name(),
+            &self.repo.config.resolved,
+            self.repo.common_dir(),
+            self.repo.work_dir(),
+        )
+    }
+
+    /// Return the path to the repository of the submodule in the `modules` directory of the superproject,
+    /// which may or may not exist.
+    pub fn git_dir(&self) -> PathBuf {
+        self.modules.git_dir(self.name(), self.repo.common_dir())
+    }
+
+    /// Return the path to the worktree of the submodule, or `None` if the superproject is bare.
+    pub fn work_dir(&self) -> Result<Option<PathBuf>, config::path::Error> {
+        let path = self.path()?;
+        Ok(self
+            .repo
+            .work_dir()
+            .map(|work_dir| work_dir.join(git_path::from_bstr(path))))
+    }
+
+    /// Return the id of the commit recorded for the submodule in the index of the superproject, or `None` if there
+    /// is no such entry.
+    pub fn index_id(&self) -> Result<Option<git_hash::ObjectId>, index_id::Error> {
+        let path = self.path()?;
+        let index = self.repo.index()?;
+        Ok(index
+            .entry_by_path_and_stage(path.as_ref(), 0)
+            .filter(|entry| entry.mode == git_index::entry::Mode::COMMIT)
+            .map(|entry| entry.id))
+    }
+
+    /// Open the repository of the submodule, or return `None` if it doesn't exist yet.
+    ///
+    /// Its repository is expected in the [`git_dir()`][Self::git_dir()], but a `.git` directory in its worktree is
+    /// supported as well.
+    pub fn open(&self) -> Result<Option<Repository>, open::Error> {
+        let git_dir = self.git_dir();
+        let git_dir = if git_dir.is_dir() {
+            git_dir
+        } else {
+            match self.work_dir()?.map(|work_dir| work_dir.join(".git")) {
+                Some(git_dir) if git_dir.is_dir() => git_dir,
+                _ => return Ok(None),
+            }
+        };
+        Ok(Some(crate::open_opts(git_dir, self.repo.options.clone())?))
+    }
+}
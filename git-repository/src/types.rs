@@ -177,6 +177,16 @@ pub struct ThreadSafeRepository {
     pub(crate) index: crate::worktree::IndexStorage,
 }
 
+/// A submodule of a superproject, providing access to its configuration and state.
+#[derive(Debug, Clone)]
+pub struct Submodule<'repo> {
+    /// The parsed `.gitmodules` file of the superproject, shared among all of its submodules.
+    pub(crate) modules: std::rc::Rc<git_submodule::File>,
+    /// The name of the submodule.
+    pub(crate) name: crate::bstr::BString,
+    pub(crate) repo: &'repo Repository,
+}
+
 /// A remote which represents a way to interact with hosts for remote clones of the parent repository.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Remote<'repo> {
@@ -197,6 +207,10 @@ pub struct Remote<'repo> {
     pub(crate) push_specs: Vec<git_refspec::RefSpec>,
     /// Tell us what to do with tags when fetched.
     pub(crate) fetch_tags: remote::fetch::Tags,
+    /// If true, objects that are missing locally can be obtained from this remote, which makes it a promisor remote.
+    pub(crate) promisor: bool,
+    /// The filter to apply to objects when fetching to make a partial clone.
+    pub(crate) filter: Option<remote::fetch::Filter>,
     // /// Delete local tracking branches that don't exist on the remote anymore.
     // pub(crate) prune: bool,
     // /// Delete tags that don't exist on the remote anymore, equivalent to pruning the refspec `refs/tags/*:refs/tags/*`.
@@ -127,11 +127,11 @@ pub mod excludes {
             overrides: Option<git_attributes::MatchGroup<git_attributes::Ignore>>,
         ) -> Result<git_worktree::fs::Cache<'a>, Error> {
             let repo = self.parent;
-            let case = repo
-                .config
-                .ignore_case
-                .then(|| git_glob::pattern::Case::Fold)
-                .unwrap_or_default();
+            let case = if repo.config.ignore_case {
+                git_glob::pattern::Case::Fold
+            } else {
+                git_glob::pattern::Case::Sensitive
+            };
             let mut buf = Vec::with_capacity(512);
             let excludes_file = match repo.config.excludes_file().transpose()? {
                 Some(user_path) => Some(user_path),
@@ -158,3 +158,77 @@ pub mod excludes {
         }
     }
 }
+
+///
+pub mod attributes {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Worktree::attributes()`][crate::Worktree::attributes()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read repository attributes or exclude files.")]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        EnvironmentPermission(#[from] git_sec::permission::Error<PathBuf>),
+        #[error("The value for `core.excludesFile` could not be read from configuration")]
+        ExcludesFilePathInterpolation(#[from] git_config::path::interpolate::Error),
+        #[error(transparent)]
+        AttributesFile(#[from] crate::config::checkout_options::Error),
+    }
+
+    impl<'repo> crate::Worktree<'repo> {
+        /// Configure a file-system cache for accessing the attributes of files below the repository, along with checking
+        /// whether they are excluded, with exclude `overrides` taking precedence.
+        ///
+        /// Attributes are read from `core.attributesFile`, `info/attributes` and all `.gitattributes` files in the worktree
+        /// or the `index`, and obtained with [`Platform::matching_attributes()`][git_worktree::fs::cache::Platform::matching_attributes()].
+        pub fn attributes<'a>(
+            &self,
+            index: &'a git_index::State,
+            overrides: Option<git_attributes::MatchGroup<git_attributes::Ignore>>,
+        ) -> Result<git_worktree::fs::Cache<'a>, Error> {
+            let repo = self.parent;
+            let case = if repo.config.ignore_case {
+                git_glob::pattern::Case::Fold
+            } else {
+                git_glob::pattern::Case::Sensitive
+            };
+            let mut buf = Vec::with_capacity(512);
+
+            let mut globals = git_attributes::MatchGroup::default();
+            if let Some(attributes_file) = repo.config.attributes_file()? {
+                // Like `git`, which changes into the worktree root first, assume relative paths to be relative to it.
+                globals.add_patterns_file(self.path.join(attributes_file), true, None, &mut buf)?;
+            }
+            let mut info = git_attributes::MatchGroup::default();
+            info.add_patterns_file(repo.git_dir().join("info").join("attributes"), true, None, &mut buf)?;
+
+            let excludes_file = match repo.config.excludes_file().transpose()? {
+                Some(user_path) => Some(user_path),
+                None => repo.config.xdg_config_path("ignore")?,
+            };
+            let state = git_worktree::fs::cache::State::for_add(
+                git_worktree::fs::cache::state::Attributes::new(globals, info),
+                git_worktree::fs::cache::state::Ignore::new(
+                    overrides.unwrap_or_default(),
+                    git_attributes::MatchGroup::<git_attributes::Ignore>::from_git_dir(
+                        repo.git_dir(),
+                        excludes_file,
+                        &mut buf,
+                    )?,
+                    None,
+                    case,
+                ),
+            );
+            let attribute_list = state.build_attribute_list(index, index.path_backing(), case);
+            Ok(git_worktree::fs::Cache::new(
+                self.path,
+                state,
+                case,
+                buf,
+                attribute_list,
+            ))
+        }
+    }
+}
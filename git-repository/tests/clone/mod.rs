@@ -252,6 +252,116 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_and_checkout_with_submodules_as_empty_directories() -> crate::Result {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let remote = git_testtools::scripted_fixture_read_only("make_recursive_submodules.sh")?.join("super");
+        let mut prepare = git::prepare_clone(remote, tmp.path())?;
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let work_dir = repo.work_dir().expect("non-bare");
+        for path in ["m1", "dir/nested", "not-updated"] {
+            let path = work_dir.join(path);
+            assert!(path.is_dir(), "{:?} must be a directory", path);
+            assert_eq!(std::fs::read_dir(&path)?.count(), 0, "it's empty");
+        }
+        let states = repo
+            .submodules()?
+            .expect("present")
+            .map(|sm| sm.state())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(states.len(), 3);
+        assert!(states
+            .iter()
+            .all(|s| !s.is_initialized && !s.is_active && !s.repository_exists && !s.worktree_checkout));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_and_checkout_with_submodules_recursively() -> crate::Result {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let fixture = git_testtools::scripted_fixture_read_only("make_recursive_submodules.sh")?;
+        let mut prepare = git::prepare_clone(fixture.join("super"), tmp.path())?;
+        let (checkout, _out) =
+            prepare.fetch_then_checkout(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout
+            .with_submodules(true)
+            .main_worktree(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let work_dir = repo.work_dir().expect("non-bare");
+        assert_eq!(
+            std::fs::read(work_dir.join("m1/this"))?,
+            b"",
+            "the commit recorded in the superproject is checked out, not the one of the remote HEAD"
+        );
+        assert_eq!(std::fs::read(work_dir.join("m1/subdir/that"))?, b"content\n");
+        assert_eq!(
+            std::fs::read(work_dir.join("dir/nested/m1/this"))?,
+            b"hello\n",
+            "nested submodules are checked out as well"
+        );
+        assert_eq!(
+            std::fs::read_dir(work_dir.join("not-updated"))?.count(),
+            0,
+            "submodules which are not to be updated stay empty"
+        );
+
+        let submodules: Vec<_> = repo.submodules()?.expect("present").collect();
+        assert_eq!(
+            submodules.iter().map(|sm| sm.name().to_string()).collect::<Vec<_>>(),
+            ["m1", "dir/nested", "not-updated"]
+        );
+        for sm in &submodules {
+            let state = sm.state()?;
+            assert!(
+                state.is_initialized && state.is_active,
+                "all submodules are initialized"
+            );
+            assert_eq!(
+                state.repository_exists && state.worktree_checkout,
+                sm.name() != "not-updated",
+                "{}",
+                sm.name()
+            );
+            assert_eq!(
+                sm.url()?.path,
+                git_path::into_bstr(git_path::realpath(fixture.join(if sm.name() == "dir/nested" {
+                    "nested"
+                } else {
+                    "module1"
+                }))?)
+                .as_ref(),
+                "relative urls are resolved against the url of the superproject's remote"
+            );
+        }
+
+        let m1 = submodules[0].open()?.expect("cloned");
+        assert_eq!(
+            m1.git_dir(),
+            submodules[0].git_dir(),
+            "the repository lives in the modules directory"
+        );
+        assert_eq!(
+            m1.work_dir().map(git_path::realpath).transpose()?,
+            Some(git_path::realpath(work_dir.join("m1"))?)
+        );
+        assert_eq!(
+            m1.head_id()?,
+            submodules[0].index_id()?.expect("recorded"),
+            "HEAD is detached at the recorded commit"
+        );
+        assert!(m1.head()?.is_detached());
+
+        let nested = git::open(work_dir.join("dir/nested"))?;
+        assert!(nested
+            .git_dir()
+            .ends_with(std::path::Path::new("modules").join("dir/nested")));
+        assert!(nested.index()?.entries().len() > 1, "the index was written");
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_empty_remote_repo() -> crate::Result {
         let tmp = git_testtools::tempfile::TempDir::new()?;
@@ -298,6 +408,177 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_only_from_sha256_remote() -> crate::Result {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let remote_repo = git_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?;
+        let (repo, _out) = git::prepare_clone_bare(remote_repo, tmp.path())?
+            .fetch_only(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        assert_eq!(
+            repo.object_hash(),
+            git::hash::Kind::Sha256,
+            "the object hash of the remote is adopted"
+        );
+        assert_eq!(
+            git::open_opts(repo.git_dir(), git::open::Options::isolated())?.object_hash(),
+            git::hash::Kind::Sha256,
+            "it's persisted in the configuration as well"
+        );
+        assert_eq!(repo.head_commit()?.ancestors().all()?.count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_shallow_with_depth_1() -> crate::Result {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = git::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(git::remote::fetch::Shallow::DepthAtRemote(1.try_into()?))
+            .fetch_only(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert!(repo.is_shallow(), "the shallow file was written");
+        let shallow_commits = repo.shallow_commits()?.expect("present");
+        assert!(
+            shallow_commits.contains(&repo.head_id()?.detach()),
+            "the commit at HEAD has no parents anymore"
+        );
+        assert_eq!(
+            repo.head_commit()?
+                .ancestors()
+                .all()?
+                .error_on_missing_commit()
+                .collect::<Result<Vec<_>, _>>()?
+                .len(),
+            1,
+            "the traversal stops at the shallow boundary"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_partial_clone_without_blobs_and_fetch_them_on_demand() -> crate::Result {
+        use git::odb::Find;
+        let remote_dir = git_testtools::scripted_fixture_read_only("make_partial_clone_repo.sh")?.join("remote");
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = git::clone::PrepareFetch::new(
+            remote_dir,
+            tmp.path(),
+            git::create::Kind::Bare,
+            Default::default(),
+            git::open::Options::isolated().lazy_fetch(true),
+        )?
+        .with_filter(git::remote::fetch::Filter::BlobNone)
+        .fetch_only(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let config = repo.config_snapshot();
+        assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+        assert_eq!(
+            config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+            "blob:none"
+        );
+        let remote = repo.find_remote("origin")?;
+        assert!(remote.is_promisor());
+        assert_eq!(remote.filter(), Some(git::remote::fetch::Filter::BlobNone));
+
+        match out.status {
+            git::remote::fetch::Status::Change { write_pack_bundle, .. } => {
+                let promisor_file = write_pack_bundle
+                    .data_path
+                    .expect("pack written")
+                    .with_extension("promisor");
+                let content = std::fs::read(promisor_file)?;
+                assert!(
+                    content.contains_str(" refs/heads/main\n"),
+                    "the refs we fetched are recorded like git does"
+                );
+            }
+            _ => unreachable!("a clone always carries a change"),
+        }
+
+        let blob_id = repo
+            .head_commit()?
+            .tree()?
+            .lookup_entry_by_path("a")?
+            .expect("present")
+            .object_id();
+        assert!(
+            !repo.objects.contains(blob_id),
+            "blobs were filtered, and checking for existence doesn't fetch them"
+        );
+        assert!(
+            git::open_opts(repo.git_dir(), git::open::Options::isolated())?
+                .try_find_object(blob_id)?
+                .is_none(),
+            "without lazy fetching, missing objects remain missing"
+        );
+
+        assert_eq!(repo.find_object(blob_id)?.data.as_slice(), b"a\n", "fetched on demand");
+        assert!(repo.objects.contains(blob_id), "the object is now present for good");
+        let pack_files_with_extension = |extension: &str| -> std::io::Result<usize> {
+            Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().map_or(false, |ext| ext == extension))
+                .count())
+        };
+        assert_eq!(
+            pack_files_with_extension("promisor")?,
+            2,
+            "packs fetched on demand are marked as well"
+        );
+        assert_eq!(pack_files_with_extension("keep")?, 0, "no pack remains locked");
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_into_partial_clone_with_lazy_fetching_only_fetches_what_it_negotiated() -> crate::Result {
+        use git::odb::Find;
+        let tmp = git_testtools::scripted_fixture_writable("make_partial_clone_repo.sh")?;
+        let repo = git::open_opts(
+            tmp.path().join("partial"),
+            git::open::Options::isolated().lazy_fetch(true),
+        )?;
+        let num_packs = || -> std::io::Result<usize> {
+            Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "pack"))
+                .count())
+        };
+        assert_eq!(num_packs()?, 1, "only the pack of the clone");
+
+        let remote = repo.find_remote("origin")?;
+        assert_eq!(
+            remote.filter(),
+            Some(git::remote::fetch::Filter::BlobNone),
+            "the filter of the clone is used for subsequent fetches"
+        );
+        let out = remote
+            .connect(git::remote::Direction::Fetch, git::progress::Discard)?
+            .prepare_fetch(Default::default())?
+            .receive(&std::sync::atomic::AtomicBool::default())?;
+        assert!(
+            matches!(out.status, git::remote::fetch::Status::Change { .. }),
+            "the new commit of the remote was fetched"
+        );
+        assert_eq!(
+            num_packs()?,
+            2,
+            "the new commit isn't fetched on demand while negotiating or updating refs, only the pack of the fetch is received"
+        );
+
+        let head = repo.find_reference("refs/remotes/origin/main")?.id();
+        let new_blob_id = head
+            .object()?
+            .try_into_commit()?
+            .tree()?
+            .lookup_entry_by_path("d")?
+            .expect("present")
+            .object_id();
+        assert!(
+            !repo.objects.contains(new_blob_id),
+            "the blob of the new commit was filtered as well"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = git_testtools::tempfile::TempDir::new()?;
@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo "*.md info" > .git/info/attributes
+cat <<EOF_ >.gitattributes
+[attr]my-macro macro-a -macro-b
+*.bin binary
+*.txt text my-macro
+EOF_
+mkdir dir
+echo "*.txt !my-macro eol=lf" > dir/.gitattributes
+echo "ignored" > .gitignore
+
+git add . && git commit -q -m "init"
+
+git check-attr -a -- a.txt b.bin c.md dir/d.txt dir/e.bin > git-check-attr.baseline
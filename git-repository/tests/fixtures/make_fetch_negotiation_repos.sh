@@ -0,0 +1,35 @@
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function commit () {
+  tick
+  echo "$1" > file
+  git add file
+  git commit -q -m "$1"
+}
+
+git init -q remote
+(cd remote
+  for i in $(seq 1 5); do commit "shared-$i"; done
+)
+
+git clone -q remote local
+(cd local
+  # more local commits than fit into the first rounds of negotiation
+  for i in $(seq 1 50); do commit "local-$i"; done
+)
+
+(cd remote
+  for i in $(seq 1 3); do commit "remote-$i"; done
+)
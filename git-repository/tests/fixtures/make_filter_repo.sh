@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.autocrlf false
+git config filter.lower.clean "tr A-Z a-z"
+git config filter.lower.smudge "tr a-z A-Z"
+git config filter.lower.required true
+
+cat <<EOF_ >.gitattributes
+*.crlf text eol=crlf
+ident.txt ident
+*.lower filter=lower
+EOF_
+git add . && git commit -q -m "init"
+
+printf 'a\r\nb\r\n' > text.crlf
+printf '$Id: 1234 $\n' > ident.txt
+printf 'UPPER\n' > file.lower
+printf 'a\r\n' > untouched
+
+git hash-object text.crlf ident.txt file.lower untouched > git-hash-object.baseline
@@ -0,0 +1,21 @@
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git config uploadpack.allowFilter true
+  git config uploadpack.allowAnySHA1InWant true
+
+  mkdir dir
+  echo a > a
+  echo b > dir/b
+  git add . && git commit -q -m c1
+  echo c > c
+  git add c && git commit -q -m c2
+)
+
+git clone -q --no-checkout --filter=blob:none "file://$PWD/remote" partial
+
+(cd remote
+  echo d > d
+  git add d && git commit -q -m c3
+)
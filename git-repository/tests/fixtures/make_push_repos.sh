@@ -0,0 +1,27 @@
+set -eu -o pipefail
+
+# IMPORTANT: keep this repo small as it's used for writes, hence will be executed for each writer!
+git init -q --bare base
+git -C base config receive.advertisePushOptions true
+
+git init -q clone
+git -C clone remote add origin "$PWD/base"
+(cd clone
+  touch a
+  git add a
+  git commit -q -m "a"
+  git branch to-delete
+  git branch diverged
+  git tag v0.1
+  git push -q origin main to-delete diverged v0.1
+
+  touch b
+  git add b
+  git commit -q -m "b"
+  git branch new-branch
+  git tag -m "annotated" v1.0
+  git tag -f v0.1
+  git checkout -q diverged
+  git commit -q --amend -m "diverged"
+  git checkout -q main
+)
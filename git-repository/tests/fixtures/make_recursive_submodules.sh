@@ -0,0 +1,38 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q module1
+(cd module1
+  touch this
+  mkdir subdir
+  echo content > subdir/that
+  git add .
+  git commit -q -m c1
+  echo hello >> this
+  git commit -q -am c2
+)
+
+git init -q nested
+(cd nested
+  touch file
+  git add file
+  git commit -q -m init
+
+  git -c protocol.file.allow=always submodule add ../module1 m1
+  git commit -q -m "add module 1"
+)
+
+git init -q super
+(cd super
+  touch file
+  git add file
+  git commit -q -m init
+
+  git -c protocol.file.allow=always submodule add ../module1 m1
+  (cd m1 && git checkout -q HEAD~1)
+  git -c protocol.file.allow=always submodule add ../nested dir/nested
+  git -c protocol.file.allow=always submodule add ../module1 not-updated
+  git config -f .gitmodules submodule.not-updated.update none
+  git add .
+  git commit -q -m "add submodules"
+)
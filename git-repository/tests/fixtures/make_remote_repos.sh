@@ -305,3 +305,38 @@ git clone --shared base detached-head
 (cd detached-head
   git checkout @~1
 )
+
+git clone --shared base tracking-branches
+(cd tracking-branches
+  git branch --track a origin/a
+  git branch --track different-name origin/b
+  git branch no-upstream
+  git branch --track local-upstream main
+)
+
+git clone --shared base tracking-branches-push-current
+(cd tracking-branches-push-current
+  git config push.default current
+  git branch --track different-name origin/b
+  git branch --track local-upstream main
+)
+
+git clone --shared base tracking-branches-push-nothing
+(cd tracking-branches-push-nothing
+  git config push.default nothing
+)
+
+git clone --shared base tracking-branches-push-specs
+(cd tracking-branches-push-specs
+  git config remote.origin.push 'refs/heads/*:refs/heads/pushed/*'
+  git config --add remote.origin.fetch 'refs/heads/pushed/*:refs/remotes/origin/pushed/*'
+  git update-ref refs/remotes/origin/pushed/main main
+)
+
+git clone --shared base tracking-branches-push-remote
+(cd tracking-branches-push-remote
+  git remote add fork ../base
+  git fetch -q fork
+  git config push.default current
+  git config branch.main.pushRemote fork
+)
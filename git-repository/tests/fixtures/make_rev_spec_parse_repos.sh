@@ -383,6 +383,15 @@ git init complex_graph
   baseline "HEAD@{5}"
   baseline "main@{12345}"
 
+  baseline "main@{1112912200 -0700}"
+  baseline "main@{1112912413 -0700}"
+  baseline "@{1112912500 -0700}"
+  baseline "HEAD@{1112912480 -0700}"
+  baseline "main@{2030-01-01}"
+  baseline "main@{2 weeks ago}"
+  baseline "main@{yesterday}"
+  baseline "main@{1000000000 -0700}"
+
   baseline "@^{}"
   baseline "main^{}"
   baseline "b-tag^{}"
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256
+
+mkdir dir
+echo a > a
+echo b > dir/b
+git add . && git commit -q -m c1
+echo a2 > a
+git commit -q -am c2
+
+git repack -adq --write-midx
+git commit-graph write --no-progress --reachable
+echo c > dir/c
+git add . && git commit -q -m c3
@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir d s keep
+for name in a d/a s/a keep/a; do
+  echo $name > $name
+done
+git add .
+git commit -q -m c1
+
+echo changed > s/a
+git commit -q -am c2
+
+git sparse-checkout set --cone --sparse-index keep
+git reset -q --soft HEAD~1
+echo staged > keep/a && git add keep/a
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo "*.log" > .gitignore
+for name in a b c conflict; do
+  echo $name > $name
+done
+git add .
+git commit -q -m c1
+
+git checkout -q -b other
+echo theirs > conflict
+git commit -q -am "their change"
+
+git checkout -q main
+echo ours > conflict
+git commit -q -am "our change"
+git merge -q other || :
+
+echo staged > a && git add a
+echo "and modified" >> a
+git rm -q --cached b
+rm c
+echo new > n && git add n
+echo ignored > x.log
+mkdir u && echo untracked > u/file
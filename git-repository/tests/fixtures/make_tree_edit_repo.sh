@@ -0,0 +1,21 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -b main
+mkdir -p a/b/c a.d
+echo content > a/b/c/file
+echo content > a/b/other
+echo content > a.txt
+echo content > a.d/file
+echo content > unchanged
+git add .
+git commit -q -m base
+
+echo content > a/b/new
+mkdir -p x/y
+echo content > x/y/z
+git rm -q -r a/b/c a.d/file
+git add .
+git commit -q -m edited
@@ -98,3 +98,87 @@ mod diff {
             .into_tree()
     }
 }
+
+mod editor {
+    use git_object::tree::EntryMode;
+    use git_repository as git;
+
+    fn tree_named<'repo>(repo: &'repo git::Repository, rev_spec: &str) -> git::Tree<'repo> {
+        repo.rev_parse_single(rev_spec)
+            .unwrap()
+            .object()
+            .unwrap()
+            .peel_to_kind(git::object::Kind::Tree)
+            .unwrap()
+            .into_tree()
+    }
+
+    #[test]
+    fn upsert_and_remove_produce_the_same_tree_as_git() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_tree_edit_repo.sh")?;
+        let base = tree_named(&repo, "HEAD~1");
+        let expected = tree_named(&repo, "HEAD");
+        let blob = repo.write_blob("content\n")?;
+
+        let mut editor = base.edit();
+        editor
+            .upsert("a/b/new", EntryMode::Blob, blob)?
+            .upsert("x/y/z", EntryMode::Blob, blob)?
+            .remove("a/b/c")?
+            .remove("a.d/file")?
+            .remove("does/not/exist")?;
+        let actual = editor.write()?;
+        assert_eq!(
+            actual, expected.id,
+            "intermediate trees are created, and empty ones are removed"
+        );
+        assert_eq!(editor.write()?, actual, "writing again without edits changes nothing");
+        assert_eq!(base.edit().write()?, base.id, "no edits, no change");
+
+        editor.upsert("a/b/new", EntryMode::Blob, blob)?;
+        assert_eq!(editor.write()?, actual, "upserting the same entry is no change");
+        Ok(())
+    }
+
+    #[test]
+    fn upsert_replaces_non_trees_with_trees_and_vice_versa() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_tree_edit_repo.sh")?;
+        let blob = repo.write_blob("content\n")?;
+        let mut editor = tree_named(&repo, "HEAD").edit();
+
+        let entry = |id: git::Id<'_>, path: &str| {
+            id.object()
+                .unwrap()
+                .into_tree()
+                .lookup_entry_by_path(path)
+                .unwrap()
+                .map(|e| (e.mode(), e.object_id()))
+        };
+
+        let id = editor.upsert("unchanged/file", EntryMode::Blob, blob)?.write()?;
+        assert_eq!(entry(id, "unchanged/file"), Some((EntryMode::Blob, blob.detach())));
+        assert_eq!(entry(id, "unchanged").expect("present").0, EntryMode::Tree);
+
+        let id = editor.upsert("a", EntryMode::Blob, blob)?.write()?;
+        assert_eq!(entry(id, "a"), Some((EntryMode::Blob, blob.detach())));
+        assert_eq!(entry(id, "a/b/other"), None, "the previous tree is gone");
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_paths_are_rejected() -> crate::Result {
+        let repo = crate::named_repo("make_tree_edit_repo.sh")?;
+        let mut editor = repo.empty_tree().edit();
+        for path in ["", "a//b", "/a", "a/"] {
+            assert!(
+                matches!(
+                    editor.remove(path),
+                    Err(git::object::tree::editor::Error::InvalidPath { .. })
+                ),
+                "{:?}",
+                path
+            );
+        }
+        Ok(())
+    }
+}
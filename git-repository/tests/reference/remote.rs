@@ -171,3 +171,130 @@ fn url_as_remote_name() -> crate::Result {
     }
     Ok(())
 }
+
+mod remote_tracking_ref {
+    use git_repository as git;
+    use git_repository::reference::remote_tracking_ref::Error;
+
+    use crate::remote;
+
+    fn tracking_ref(
+        repo: &git::Repository,
+        branch: &str,
+        direction: git::remote::Direction,
+    ) -> Option<Result<String, Error>> {
+        repo.find_reference(branch)
+            .expect("branch exists")
+            .remote_tracking_ref(direction)
+            .map(|res| res.map(|name| name.as_bstr().to_string()))
+    }
+
+    #[test]
+    fn upstream_is_mapped_through_fetch_specs() {
+        let repo = remote::repo("tracking-branches");
+        for (branch, expected) in [
+            ("main", "refs/remotes/origin/main"),
+            ("different-name", "refs/remotes/origin/b"),
+            ("local-upstream", "refs/heads/main"),
+        ] {
+            assert_eq!(
+                tracking_ref(&repo, branch, git::remote::Direction::Fetch)
+                    .expect("configured")
+                    .unwrap(),
+                expected
+            );
+        }
+        assert!(
+            tracking_ref(&repo, "no-upstream", git::remote::Direction::Fetch).is_none(),
+            "nothing is configured"
+        );
+    }
+
+    #[test]
+    fn only_local_branches_have_remote_tracking_refs() {
+        let repo = remote::repo("tracking-branches");
+        for direction in [git::remote::Direction::Fetch, git::remote::Direction::Push] {
+            assert!(tracking_ref(&repo, "refs/remotes/origin/main", direction).is_none());
+        }
+    }
+
+    #[test]
+    fn push_with_simple_push_default_must_match_upstream() {
+        let repo = remote::repo("tracking-branches");
+        assert_eq!(
+            tracking_ref(&repo, "main", git::remote::Direction::Push)
+                .expect("configured")
+                .unwrap(),
+            "refs/remotes/origin/main"
+        );
+        assert!(matches!(
+            tracking_ref(&repo, "different-name", git::remote::Direction::Push).expect("configured"),
+            Err(Error::AmbiguousSimplePush { .. })
+        ));
+        assert!(
+            tracking_ref(&repo, "no-upstream", git::remote::Direction::Push).is_none(),
+            "without upstream, 'simple' has no destination"
+        );
+        assert!(
+            matches!(
+                tracking_ref(&repo, "local-upstream", git::remote::Direction::Push).expect("configured"),
+                Err(Error::NoTrackingRef { .. })
+            ),
+            "the local repository doesn't have tracking branches for itself"
+        );
+    }
+
+    #[test]
+    fn push_with_current_push_default_uses_the_branch_name() {
+        let repo = remote::repo("tracking-branches-push-current");
+        for (branch, expected) in [
+            ("main", "refs/remotes/origin/main"),
+            ("different-name", "refs/remotes/origin/different-name"),
+        ] {
+            assert_eq!(
+                tracking_ref(&repo, branch, git::remote::Direction::Push)
+                    .expect("configured")
+                    .unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn push_with_nothing_push_default_has_no_destination() {
+        let repo = remote::repo("tracking-branches-push-nothing");
+        assert!(tracking_ref(&repo, "main", git::remote::Direction::Push).is_none());
+        assert!(
+            tracking_ref(&repo, "main", git::remote::Direction::Fetch).is_some(),
+            "fetching is unaffected"
+        );
+    }
+
+    #[test]
+    fn push_specs_take_precedence() {
+        let repo = remote::repo("tracking-branches-push-specs");
+        assert_eq!(
+            tracking_ref(&repo, "main", git::remote::Direction::Push)
+                .expect("configured")
+                .unwrap(),
+            "refs/remotes/origin/pushed/main"
+        );
+    }
+
+    #[test]
+    fn push_remote_of_branch_is_used() {
+        let repo = remote::repo("tracking-branches-push-remote");
+        assert_eq!(
+            tracking_ref(&repo, "main", git::remote::Direction::Push)
+                .expect("configured")
+                .unwrap(),
+            "refs/remotes/fork/main"
+        );
+        assert_eq!(
+            tracking_ref(&repo, "main", git::remote::Direction::Fetch)
+                .expect("configured")
+                .unwrap(),
+            "refs/remotes/origin/main"
+        );
+    }
+}
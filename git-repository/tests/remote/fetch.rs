@@ -69,15 +69,12 @@ mod blocking_and_async_io {
                 .await?;
 
             match res.status {
-                git::remote::fetch::Status::Change { write_pack_bundle, update_refs } => {
-                    assert_eq!(write_pack_bundle.index.data_hash, hex_to_id("029d08823bd8a8eab510ad6ac75c823cfd3ed31e"));
-                    assert_eq!(write_pack_bundle.index.num_objects, 0, "empty pack");
-                    assert!(write_pack_bundle.data_path.as_deref().map_or(false, |p| p.is_file()));
-                    assert!(write_pack_bundle.index_path.as_deref().map_or(false, |p| p.is_file()));
+                git::remote::fetch::Status::NoPackReceived { update_refs } => {
                     assert_eq!(update_refs.edits.len(), expected_ref_count);
-                    assert!(!write_pack_bundle.keep_path.as_deref().map_or(false, |p| p.is_file()), ".keep files are deleted if at least one ref-edit was made or the pack is empty");
-                },
-                _ => unreachable!("Naive negotiation sends the same have and wants, resulting in an empty pack (technically no change, but we don't detect it) - empty packs are fine")
+                }
+                _ => unreachable!(
+                    "the negotiation realizes that all remote objects are present locally and doesn't ask for a pack"
+                ),
             }
         }
         Ok(())
@@ -127,20 +124,140 @@ mod blocking_and_async_io {
                 .await?;
 
             match res.status {
-                git::remote::fetch::Status::Change { write_pack_bundle, update_refs } => {
-                    assert_eq!(write_pack_bundle.index.data_hash, hex_to_id(expected_data_hash), );
-                    assert_eq!(write_pack_bundle.index.num_objects, 3 + num_objects_offset, "{fetch_tags:?}");
+                git::remote::fetch::Status::Change {
+                    write_pack_bundle,
+                    update_refs,
+                } => {
+                    assert_eq!(write_pack_bundle.index.data_hash, hex_to_id(expected_data_hash),);
+                    assert_eq!(
+                        write_pack_bundle.index.num_objects,
+                        3 + num_objects_offset,
+                        "{fetch_tags:?}"
+                    );
                     assert!(write_pack_bundle.data_path.as_deref().map_or(false, |p| p.is_file()));
                     assert!(write_pack_bundle.index_path.as_deref().map_or(false, |p| p.is_file()));
                     assert_eq!(update_refs.edits.len(), expected_ref_edits, "{fetch_tags:?}");
                     assert_eq!(write_pack_bundle.keep_path.as_deref().map_or(false, |p| p.is_file()), update_refs.edits.is_empty(),".keep are kept if there was no edit to prevent `git gc` from clearing out the pack as it's not referred to necessarily");
-                },
-                _ => unreachable!("Naive negotiation sends the same have and wants, resulting in an empty pack (technically no change, but we don't detect it) - empty packs are fine")
+                }
+                _ => unreachable!("there is a new commit on the remote which we don't have yet"),
+            }
+        }
+        Ok(())
+    }
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn fetch_with_multi_round_negotiation() -> crate::Result {
+        for (algorithm, expected_objects) in [("noop", 24), ("consecutive", 9), ("skipping", 9)] {
+            for version in [
+                git::protocol::transport::Protocol::V2,
+                git::protocol::transport::Protocol::V1,
+            ] {
+                let tmp = git_testtools::scripted_fixture_writable("make_fetch_negotiation_repos.sh")?;
+                let daemon = spawn_git_daemon_if_async(tmp.path())?;
+                let mut repo = git::open_opts(tmp.path().join("local"), git::open::Options::isolated())?;
+                {
+                    let mut config = repo.config_snapshot_mut();
+                    config.set_raw_value("protocol", None, "version", (version as u8).to_string().as_str())?;
+                    config.set_raw_value("fetch", None, "negotiationAlgorithm", algorithm)?;
+                }
+                let remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), "remote");
+                let outcome = remote
+                    .connect(Fetch, progress::Discard)
+                    .await?
+                    .prepare_fetch(Default::default())
+                    .await?
+                    .receive(&AtomicBool::default())
+                    .await?;
+                match outcome.status {
+                    fetch::Status::Change {
+                        write_pack_bundle,
+                        update_refs,
+                    } => {
+                        assert_eq!(
+                            write_pack_bundle.index.num_objects, expected_objects,
+                            "{algorithm} {version:?}: only 'noop' sends the shared history as well"
+                        );
+                        assert_eq!(update_refs.edits.len(), 1);
+                    }
+                    _ => unreachable!("the remote has new commits"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn fetch_shallow_deepen_and_unshallow() -> crate::Result {
+        for version in [
+            git::protocol::transport::Protocol::V2,
+            git::protocol::transport::Protocol::V1,
+        ] {
+            let tmp = git_testtools::scripted_fixture_writable("make_shallow_repo.sh")?;
+            let daemon = spawn_git_daemon_if_async(tmp.path())?;
+            let mut repo = git::open_opts(tmp.path().join("shallow"), git::open::Options::isolated())?;
+            repo.config_snapshot_mut()
+                .set_raw_value("protocol", None, "version", (version as u8).to_string().as_str())?;
+            let history_len = |repo: &git::Repository| -> crate::Result<usize> {
+                Ok(repo
+                    .head_commit()?
+                    .ancestors()
+                    .all()?
+                    .error_on_missing_commit()
+                    .collect::<Result<Vec<_>, _>>()?
+                    .len())
+            };
+            assert_eq!(history_len(&repo)?, 1, "{version:?}: the fixture is cloned with depth 1");
+
+            for (shallow, expected_history_len) in [
+                (fetch::Shallow::Deepen(2), 3),
+                (fetch::Shallow::NoChange, 3),
+                (fetch::Shallow::undo(), 5),
+            ] {
+                let remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), "remote");
+                remote
+                    .connect(Fetch, progress::Discard)
+                    .await?
+                    .prepare_fetch(Default::default())
+                    .await?
+                    .with_shallow(shallow.clone())
+                    .receive(&AtomicBool::default())
+                    .await?;
+                assert_eq!(history_len(&repo)?, expected_history_len, "{version:?} {shallow:?}");
             }
+            assert!(
+                !repo.is_shallow(),
+                "{version:?}: the shallow file is removed once the entire history is present"
+            );
         }
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn unknown_negotiation_algorithms_are_rejected() -> crate::Result {
+        let tmp = git_testtools::scripted_fixture_writable("make_fetch_negotiation_repos.sh")?;
+        let mut repo = git::open_opts(
+            tmp.path().join("local"),
+            git::open::Options::isolated().strict_config(true),
+        )?;
+        repo.config_snapshot_mut()
+            .set_raw_value("fetch", None, "negotiationAlgorithm", "unknown")?;
+        let err = repo
+            .find_remote("origin")?
+            .connect(Fetch, progress::Discard)?
+            .prepare_fetch(Default::default())?
+            .receive(&AtomicBool::default())
+            .unwrap_err();
+        assert!(matches!(err, fetch::Error::UnknownNegotiationAlgorithm { .. }));
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -71,6 +71,7 @@ pub(crate) fn cow_str(s: &str) -> Cow<str> {
 
 mod connect;
 pub(crate) mod fetch;
+mod push;
 mod ref_map;
 mod save;
 mod name {
@@ -0,0 +1,202 @@
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
+mod blocking_and_async_io {
+    use std::sync::atomic::AtomicBool;
+
+    use git_protocol::maybe_async;
+    use git_repository as git;
+    use git_repository::remote::{
+        push::{Mode, Status},
+        Direction::Push,
+    };
+
+    use crate::remote::{into_daemon_remote_if_async, spawn_git_daemon_if_async};
+
+    fn repo_rw() -> (git::Repository, git_testtools::tempfile::TempDir) {
+        let dir = git_testtools::scripted_fixture_writable_with_args(
+            "make_push_repos.sh",
+            None::<String>,
+            git_testtools::Creation::ExecuteScript,
+        )
+        .unwrap();
+        let repo = git::open_opts(dir.path().join("clone"), git::open::Options::isolated()).unwrap();
+        (repo, dir)
+    }
+
+    fn remote_id(dir: &std::path::Path, name: &str) -> Option<git::ObjectId> {
+        let remote = git::open_opts(dir.join("base"), git::open::Options::isolated()).unwrap();
+        remote.try_find_reference(name).unwrap().map(|r| r.id().detach())
+    }
+
+    fn local_id(repo: &git::Repository, name: &str) -> git::ObjectId {
+        repo.find_reference(name).unwrap().id().detach()
+    }
+
+    fn modes(updates: &[git::remote::push::Update]) -> Vec<(&str, Mode)> {
+        updates
+            .iter()
+            .map(|u| (std::str::from_utf8(u.remote.as_ref()).expect("valid"), u.mode))
+            .collect()
+    }
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn push_updates_and_receive_report() -> crate::Result {
+        let (repo, tmp) = repo_rw();
+        let daemon = spawn_git_daemon_if_async(tmp.path())?;
+        let mut remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), "base");
+        remote.replace_refspecs(
+            [
+                "refs/heads/main",
+                "new-branch",
+                ":to-delete",
+                "diverged",
+                "refs/tags/*:refs/tags/*",
+            ],
+            Push,
+        )?;
+
+        let expected_modes = vec![
+            ("refs/heads/main", Mode::FastForward),
+            ("refs/heads/new-branch", Mode::New),
+            ("refs/heads/to-delete", Mode::Delete),
+            ("refs/heads/diverged", Mode::RejectedNonFastForward),
+            ("refs/tags/v0.1", Mode::RejectedTagUpdate),
+            ("refs/tags/v1.0", Mode::New),
+        ];
+        let previous_main = remote_id(tmp.path(), "refs/heads/main");
+        let outcome = remote
+            .connect(Push, git::progress::Discard)
+            .await?
+            .prepare_push()
+            .await?
+            .with_dry_run(true)
+            .send(&AtomicBool::default())
+            .await?;
+        assert!(matches!(outcome.status, Status::DryRun));
+        assert_eq!(modes(&outcome.updates), expected_modes);
+        assert_eq!(
+            remote_id(tmp.path(), "refs/heads/main"),
+            previous_main,
+            "the remote doesn't change in dry-run mode"
+        );
+
+        let prepare = remote
+            .connect(Push, git::progress::Discard)
+            .await?
+            .prepare_push()
+            .await?;
+        assert_eq!(modes(prepare.updates()), expected_modes);
+        assert_eq!(prepare.remote_refs().len(), 4, "3 branches and a tag");
+        let outcome = prepare.send(&AtomicBool::default()).await?;
+        let report = match outcome.status {
+            Status::Sent { report } => report.expect("file and daemon transports support reporting"),
+            _ => unreachable!("there are updates to send"),
+        };
+        assert!(report.is_success());
+        assert_eq!(
+            report.refs().iter().map(|r| r.ref_name.to_string()).collect::<Vec<_>>(),
+            [
+                "refs/heads/main",
+                "refs/heads/new-branch",
+                "refs/heads/to-delete",
+                "refs/tags/v1.0"
+            ],
+            "only updates that weren't rejected locally are sent"
+        );
+
+        assert_eq!(
+            remote_id(tmp.path(), "refs/heads/main"),
+            Some(local_id(&repo, "refs/heads/main"))
+        );
+        assert_eq!(
+            remote_id(tmp.path(), "refs/heads/new-branch"),
+            Some(local_id(&repo, "refs/heads/new-branch"))
+        );
+        assert_eq!(remote_id(tmp.path(), "refs/heads/to-delete"), None);
+        assert_ne!(
+            remote_id(tmp.path(), "refs/heads/diverged"),
+            Some(local_id(&repo, "refs/heads/diverged"))
+        );
+        assert_eq!(
+            remote_id(tmp.path(), "refs/tags/v1.0"),
+            Some(local_id(&repo, "refs/tags/v1.0")),
+            "the annotated tag object is sent as well"
+        );
+
+        let outcome = remote
+            .connect(Push, git::progress::Discard)
+            .await?
+            .prepare_push()
+            .await?
+            .send(&AtomicBool::default())
+            .await?;
+        assert!(
+            matches!(outcome.status, Status::NoChangeNeeded),
+            "the remaining updates are all rejected or up-to-date"
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn push_forced_atomically_with_push_options() -> crate::Result {
+        let (repo, tmp) = repo_rw();
+        let daemon = spawn_git_daemon_if_async(tmp.path())?;
+        let mut remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), "base");
+        remote.replace_refspecs(["+diverged", "+refs/tags/v0.1"], Push)?;
+
+        let outcome = remote
+            .connect(Push, git::progress::Discard)
+            .await?
+            .prepare_push()
+            .await?
+            .with_atomic(true)
+            .with_push_options(["ci.skip"])
+            .send(&AtomicBool::default())
+            .await?;
+        assert_eq!(
+            modes(&outcome.updates),
+            [("refs/heads/diverged", Mode::Forced), ("refs/tags/v0.1", Mode::Forced)]
+        );
+        assert!(matches!(outcome.status, Status::Sent { report: Some(report) } if report.is_success()));
+        assert_eq!(
+            remote_id(tmp.path(), "refs/heads/diverged"),
+            Some(local_id(&repo, "refs/heads/diverged"))
+        );
+        assert_eq!(
+            remote_id(tmp.path(), "refs/tags/v0.1"),
+            Some(local_id(&repo, "refs/tags/v0.1"))
+        );
+        Ok(())
+    }
+
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn push_current_branch_without_push_specs() -> crate::Result {
+        let (repo, tmp) = repo_rw();
+        let daemon = spawn_git_daemon_if_async(tmp.path())?;
+        let remote = into_daemon_remote_if_async(repo.find_remote("origin")?, daemon.as_ref(), "base");
+        assert!(remote.refspecs(Push).is_empty());
+
+        let outcome = remote
+            .connect(Push, git::progress::Discard)
+            .await?
+            .prepare_push()
+            .await?
+            .send(&AtomicBool::default())
+            .await?;
+        assert_eq!(modes(&outcome.updates), [("refs/heads/main", Mode::FastForward)]);
+        assert!(matches!(outcome.status, Status::Sent { report: Some(report) } if report.is_success()));
+        assert_eq!(
+            remote_id(tmp.path(), "refs/heads/main"),
+            Some(local_id(&repo, "refs/heads/main"))
+        );
+        Ok(())
+    }
+}
@@ -120,6 +120,23 @@ mod save_as_to {
         );
         Ok(())
     }
+
+    #[test]
+    fn filters_make_promisor_remotes() -> crate::Result {
+        let repo = basic_repo()?;
+        let filter = git::remote::fetch::Filter::TreeDepth { depth: 0 };
+        let mut remote = repo.remote_at("https://example.com/path")?.with_filter(Some(filter));
+        assert!(remote.is_promisor());
+        assert_eq!(remote.filter(), Some(filter));
+
+        let mut config = git::config::File::default();
+        remote.save_as_to("origin", &mut config)?;
+        assert_eq!(
+            uniformize(config.to_string()),
+            "[remote \"origin\"]\n\turl = https://example.com/path\n\tpromisor = true\n\tpartialclonefilter = tree:0\n"
+        );
+        Ok(())
+    }
 }
 
 fn uniformize(input: String) -> String {
@@ -20,7 +20,7 @@ fn author_and_committer_and_fallback() {
             )
             .set("GIT_AUTHOR_NAME", "author")
             .set("GIT_AUTHOR_EMAIL", "author@email")
-            .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00")
+            .set("GIT_AUTHOR_DATE", "1979-02-26 18:30:00 +0030")
             .set("GIT_COMMITTER_NAME", "commiter-overrider-unused")
             .set("GIT_COMMITTER_EMAIL", "committer-override-unused@email")
             .set("GIT_COMMITTER_DATE", "1980-02-26 18:30:00")
@@ -47,12 +47,12 @@ fn author_and_committer_and_fallback() {
                 name: "author".into(),
                 email: "author@email".into(),
                 time: git_date::Time {
-                    seconds_since_unix_epoch: 42,
+                    seconds_since_unix_epoch: 288900000,
                     offset_in_seconds: 1800,
                     sign: git_date::time::Sign::Plus
                 }
             }),
-            "the only parsesable marker time we know right now, indicating time parse success"
+            "dates are parsed strictly, like git does"
         );
         {
             let actual = repo.committer().expect("set");
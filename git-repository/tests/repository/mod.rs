@@ -6,6 +6,8 @@ mod open;
 mod reference;
 mod remote;
 mod state;
+mod status;
+mod submodule;
 mod worktree;
 
 #[test]
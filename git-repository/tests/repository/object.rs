@@ -301,6 +301,93 @@ mod commit {
         );
         Ok(())
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn from_trees_written_by_the_index() -> crate::Result {
+        let _env = freeze_time();
+        let (repo, _keep) = crate::repo_rw_opts("make_tree_edit_repo.sh", restricted_and_git())?;
+        let head_tree_id = repo.head_commit()?.tree_id()?;
+        let index = repo.index()?;
+        assert_eq!(
+            repo.write_tree_from_index(&index)?,
+            head_tree_id,
+            "an unchanged index produces the tree it was created from"
+        );
+
+        let mut index = repo.open_index()?;
+        let subtree = |tree: &git::index::extension::Tree, name: &str| -> git::index::extension::Tree {
+            tree.children
+                .iter()
+                .find(|child| child.name.as_slice() == name.as_bytes())
+                .cloned()
+                .expect("subtree present")
+        };
+        let x_before = subtree(index.tree().expect("written by git commit"), "x");
+        assert_eq!(
+            x_before.num_entries,
+            Some(1),
+            "valid trees count all entries below them"
+        );
+
+        let blob_id = repo.write_blob("new")?.detach();
+        index.upsert_entry(
+            "a/b/new-file".into(),
+            Default::default(),
+            blob_id,
+            git::index::entry::Flags::empty(),
+            git::index::entry::Mode::FILE,
+        );
+        let root = index.tree().expect("still present").clone();
+        assert_eq!(root.num_entries, None, "the root tree is invalidated");
+        let a = subtree(&root, "a");
+        assert_eq!(
+            a.num_entries, None,
+            "all trees leading to the new entry are invalidated"
+        );
+        assert_eq!(subtree(&a, "b").num_entries, None);
+        let x = subtree(&root, "x");
+        assert_eq!(x.num_entries, x_before.num_entries, "unrelated trees stay valid…");
+        assert_eq!(x.id, x_before.id, "…and will be reused");
+
+        let tree_id = repo.write_tree_from_index(&index)?;
+        let root_after_write = index.tree().expect("still present");
+        assert_eq!(
+            root_after_write.num_entries, None,
+            "the tree extension isn't changed when writing trees"
+        );
+        assert_eq!(subtree(root_after_write, "a").num_entries, None);
+
+        index.write(Default::default())?;
+        let git_write_tree = std::process::Command::new("git")
+            .arg("write-tree")
+            .current_dir(repo.work_dir().expect("non-bare"))
+            .output()?;
+        assert!(git_write_tree.status.success());
+        assert_eq!(
+            tree_id,
+            hex_to_id(std::str::from_utf8(&git_write_tree.stdout)?.trim_end()),
+            "the tree is the same as the one written by git"
+        );
+
+        let parent = repo.head_id()?;
+        let commit_id = repo.commit("HEAD", "add a/b/new-file", tree_id, Some(parent))?;
+        let tree = commit_id.object()?.into_commit().tree()?;
+        let entry = tree.lookup_entry_by_path("a/b/new-file")?.expect("present");
+        assert_eq!(entry.object_id(), blob_id, "the staged entry is part of the commit");
+        assert_eq!(
+            commit_id
+                .object()?
+                .into_commit()
+                .tree()?
+                .lookup_entry_by_path("x")?
+                .expect("present")
+                .object_id(),
+            x_before.id,
+            "the reused tree is part of the commit"
+        );
+        Ok(())
+    }
 }
 
 fn empty_bare_repo() -> crate::Result<(tempfile::TempDir, git::Repository)> {
@@ -31,6 +31,40 @@ mod missing_config_file {
     }
 }
 
+mod object_format {
+    use git_repository as git;
+
+    use crate::util::named_repo;
+
+    #[test]
+    fn sha256() -> crate::Result {
+        let repo = named_repo("make_sha256_repo.sh")?;
+        assert_eq!(repo.object_hash(), git::hash::Kind::Sha256);
+
+        let head = repo.head_commit()?;
+        assert_eq!(head.id.kind(), git::hash::Kind::Sha256);
+        assert_eq!(head.message()?.summary().as_ref(), "c3");
+        assert_eq!(
+            head.ancestors().all()?.count(),
+            3,
+            "loose and packed commits are found, with the help of the multi-pack index and the commit-graph"
+        );
+
+        let tree = head.tree()?;
+        let entry = tree
+            .lookup_entry_by_path("dir/b")?
+            .expect("nested entries of trees with 32 byte ids can be decoded");
+        assert_eq!(entry.object()?.data, b"b\n");
+        assert_eq!(
+            repo.find_object(git::hash::ObjectId::empty_tree(repo.object_hash()))?
+                .id,
+            git::hash::ObjectId::empty_tree(git::hash::Kind::Sha256),
+            "the empty tree is always present"
+        );
+        Ok(())
+    }
+}
+
 mod not_a_repository {
     use git_repository as git;
 
@@ -123,6 +123,18 @@ mod find_remote {
         Ok(())
     }
 
+    #[test]
+    fn promisor_and_partial_clone_filter() -> crate::Result {
+        let repo = git::open_opts(
+            git_testtools::scripted_fixture_read_only("make_partial_clone_repo.sh")?.join("partial"),
+            git::open::Options::isolated(),
+        )?;
+        let remote = repo.find_remote("origin")?;
+        assert!(remote.is_promisor(), "set by git when cloning with a filter");
+        assert_eq!(remote.filter(), Some(git::remote::fetch::Filter::BlobNone));
+        Ok(())
+    }
+
     #[test]
     fn typical() -> crate::Result {
         let repo = remote::repo("clone");
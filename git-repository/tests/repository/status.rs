@@ -0,0 +1,101 @@
+use std::sync::atomic::AtomicBool;
+
+use git_repository as git;
+use git_repository::status::{IndexWorktreeChange, Item, TreeIndexChange};
+
+use crate::{named_repo, Result};
+
+fn items(platform: git::status::Platform<'_>) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    platform.for_each(&AtomicBool::default(), |item| {
+        out.push(match item {
+            Item::TreeIndex { path, change } => format!(
+                "tree-index {} {}",
+                match change {
+                    TreeIndexChange::Addition { .. } => "A",
+                    TreeIndexChange::Deletion { .. } => "D",
+                    TreeIndexChange::Modification { .. } => "M",
+                },
+                path
+            ),
+            Item::Conflict {
+                path,
+                ancestor,
+                ours,
+                theirs,
+            } => format!("conflict {}{}{} {}", ancestor as u8, ours as u8, theirs as u8, path),
+            Item::IndexWorktree { path, change, .. } => format!(
+                "index-worktree {} {}",
+                match change {
+                    IndexWorktreeChange::Removed => "D",
+                    IndexWorktreeChange::Type => "T",
+                    IndexWorktreeChange::Modification { .. } => "M",
+                },
+                path
+            ),
+            Item::Untracked { path, is_dir } => format!("untracked {}{}", path, if is_dir { "/" } else { "" }),
+            Item::Ignored { path, is_dir } => format!("ignored {}{}", path, if is_dir { "/" } else { "" }),
+        })
+    })?;
+    Ok(out)
+}
+
+#[test]
+fn staged_unstaged_conflicting_and_untracked_changes() -> Result {
+    let repo = named_repo("make_status_repo.sh")?;
+    assert_eq!(
+        items(repo.status()?)?,
+        [
+            "tree-index M a",
+            "tree-index D b",
+            "conflict 111 conflict",
+            "tree-index A n",
+            "index-worktree M a",
+            "index-worktree D c",
+            "untracked b",
+            "untracked u/",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_files_can_be_listed_individually_with_ignored_files() -> Result {
+    let repo = named_repo("make_status_repo.sh")?;
+    assert_eq!(
+        items(
+            repo.status()?
+                .untracked_files(Some(git::status::UntrackedFiles::All))
+                .ignored(true)
+        )?,
+        [
+            "tree-index M a",
+            "tree-index D b",
+            "conflict 111 conflict",
+            "tree-index A n",
+            "index-worktree M a",
+            "index-worktree D c",
+            "untracked b",
+            "untracked u/file",
+            "ignored x.log",
+        ]
+    );
+    assert_eq!(
+        items(repo.status()?.untracked_files(None))?.len(),
+        6,
+        "untracked files can be turned off"
+    );
+    Ok(())
+}
+
+#[test]
+fn sparse_directories_are_compared_by_tree_and_expanded_if_changed() -> Result {
+    let repo = named_repo("make_sparse_status_repo.sh")?;
+    assert!(repo.index()?.is_sparse(), "the index has sparse directories");
+    assert_eq!(
+        items(repo.status()?)?,
+        ["tree-index M keep/a", "tree-index M s/a"],
+        "like git, the unchanged sparse directory d/ doesn't show up, and only the changed file within s/ is listed"
+    );
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use git_repository as git;
+
+#[test]
+fn submodules_of_a_superproject_with_initialized_submodules() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_read_only("make_submodules.sh")?;
+    let repo = git::open(dir.join("with-submodules"))?;
+    let submodules: Vec<_> = repo.submodules()?.expect("modules file present").collect();
+    assert_eq!(
+        submodules.iter().map(|sm| sm.name().to_string()).collect::<Vec<_>>(),
+        ["m1", "dir/m1"]
+    );
+
+    for sm in &submodules {
+        assert_eq!(sm.path()?.as_ref(), sm.name(), "name and path are the same here");
+        assert_eq!(
+            sm.state()?,
+            git::submodule::State {
+                is_initialized: true,
+                is_active: true,
+                repository_exists: true,
+                worktree_checkout: true,
+            }
+        );
+        assert_eq!(sm.update()?, None);
+        assert!(sm.url()?.path.ends_with(b"/module1"));
+
+        let sm_repo = sm.open()?.expect("repository exists");
+        assert_eq!(sm_repo.git_dir(), sm.git_dir());
+        assert_eq!(
+            Some(sm_repo.head_id()?.detach()),
+            sm.index_id()?,
+            "the submodule was added at its current HEAD"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn no_submodules_without_modules_file() -> crate::Result {
+    let repo = crate::named_repo("make_basic_repo.sh")?;
+    assert!(repo.modules()?.is_none());
+    assert!(repo.submodules()?.is_none());
+    Ok(())
+}
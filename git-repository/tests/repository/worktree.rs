@@ -304,3 +304,98 @@ fn run_assertions(main_repo: git::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod attributes {
+    use git_odb::FindExt;
+    use git_repository as git;
+
+    use crate::named_repo;
+
+    #[test]
+    fn all_attributes_match_git_check_attr() -> crate::Result {
+        let repo = named_repo("make_attributes_repo.sh")?;
+        let worktree = repo.worktree().expect("non-bare");
+        let index = worktree.index()?;
+        let mut cache = worktree.attributes(&index, None)?;
+
+        let baseline = std::fs::read_to_string(worktree.base().join("git-check-attr.baseline"))?;
+        let mut expected = std::collections::BTreeMap::<&str, Vec<String>>::new();
+        for line in baseline.lines() {
+            let (path, assignment) = line.split_once(": ").expect("path: name: value");
+            expected.entry(path).or_default().push(assignment.to_owned());
+        }
+        assert_eq!(expected.len(), 5, "every path has attributes");
+
+        let mut outcome = git::attrs::Outcome::default();
+        for (path, mut expected) in expected {
+            let platform = cache.at_entry(path, Some(false), |id, buf| repo.objects.find_blob(id, buf))?;
+            platform.matching_attributes(&mut outcome);
+            let mut actual: Vec<_> = outcome
+                .iter()
+                .map(|a| {
+                    format!(
+                        "{}: {}",
+                        a.name.as_str(),
+                        match a.state {
+                            git::attrs::StateRef::Set => "set".into(),
+                            git::attrs::StateRef::Unset => "unset".into(),
+                            git::attrs::StateRef::Value(v) => v.to_string(),
+                            git::attrs::StateRef::Unspecified => unreachable!("never returned"),
+                        }
+                    )
+                })
+                .collect();
+            actual.sort();
+            expected.sort();
+            assert_eq!(actual, expected, "{}", path);
+        }
+
+        assert!(
+            cache
+                .at_entry("ignored", Some(false), |id, buf| repo.objects.find_blob(id, buf))?
+                .is_excluded(),
+            "excludes are available too"
+        );
+        Ok(())
+    }
+}
+
+mod filter_pipeline {
+    use git_repository as git;
+
+    use crate::repo_rw;
+
+    #[test]
+    fn write_blob_from_worktree_matches_git_hash_object() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_filter_repo.sh")?;
+        let worktree = repo.worktree().expect("non-bare");
+        let index = worktree.index()?;
+        let mut pipeline = worktree.filter_pipeline(&index)?;
+
+        let baseline = std::fs::read_to_string(worktree.base().join("git-hash-object.baseline"))?;
+        let paths = ["text.crlf", "ident.txt", "file.lower", "untouched"];
+        assert_eq!(baseline.lines().count(), paths.len());
+        for (path, expected) in paths.into_iter().zip(baseline.lines()) {
+            let id = pipeline.write_blob_from_worktree(path.into())?;
+            assert_eq!(id, git::hash::ObjectId::from_hex(expected.as_bytes())?, "{}", path);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn convert_to_worktree_applies_smudge_filters() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_filter_repo.sh")?;
+        let worktree = repo.worktree().expect("non-bare");
+        let index = worktree.index()?;
+        let mut pipeline = worktree.filter_pipeline(&index)?;
+
+        let id = git::hash::ObjectId::null(repo.object_hash());
+        let mut out = Vec::new();
+        assert!(pipeline.convert_to_worktree(b"a\nb\n", "text.crlf".into(), &id, &mut out)?);
+        assert_eq!(out, b"a\r\nb\r\n");
+        assert!(pipeline.convert_to_worktree(b"lower\n", "file.lower".into(), &id, &mut out)?);
+        assert_eq!(out, b"LOWER\n");
+        assert!(!pipeline.convert_to_worktree(b"a\n", "untouched".into(), &id, &mut out)?);
+        Ok(())
+    }
+}
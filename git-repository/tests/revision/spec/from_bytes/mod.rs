@@ -14,20 +14,54 @@ mod peel;
 mod sibling_branch {
     use git_repository::revision::spec::parse::Error;
 
-    use crate::revision::spec::from_bytes::{parse_spec_no_baseline, repo};
+    use crate::{remote, revision::spec::from_bytes::parse_spec_no_baseline};
 
     #[test]
-    fn is_planned_and_delayed_until_remotes_are_sorted() {
-        let repo = repo("complex_graph").unwrap();
-        assert!(matches!(
-            parse_spec_no_baseline("main@{push}", &repo).unwrap_err(),
-            Error::Planned { .. }
-        ));
+    fn upstream_and_push() {
+        let repo = remote::repo("tracking-branches");
+        for (spec, expected) in [
+            ("main@{upstream}", "refs/remotes/origin/main"),
+            ("main@{u}", "refs/remotes/origin/main"),
+            ("@{u}", "refs/remotes/origin/main"),
+            ("@{push}", "refs/remotes/origin/main"),
+            ("HEAD@{u}", "refs/remotes/origin/main"),
+            ("HEAD@{push}", "refs/remotes/origin/main"),
+            ("different-name@{U}", "refs/remotes/origin/b"),
+            ("local-upstream@{upstream}", "refs/heads/main"),
+        ] {
+            let spec =
+                parse_spec_no_baseline(spec, &repo).unwrap_or_else(|_| panic!("{} to be parsed successfully", spec));
+            assert_eq!(spec.first_reference().expect("set").name.as_bstr(), expected);
+            assert_eq!(spec.second_reference(), None);
+        }
+
+        let repo = remote::repo("tracking-branches-push-remote");
+        let spec = parse_spec_no_baseline("main@{push}", &repo).unwrap();
+        assert_eq!(
+            spec.first_reference().expect("set").name.as_bstr(),
+            "refs/remotes/fork/main"
+        );
+    }
 
+    #[test]
+    fn missing_or_ambiguous_configuration() {
+        let repo = remote::repo("tracking-branches");
+        assert_eq!(
+            parse_spec_no_baseline("no-upstream@{u}", &repo)
+                .unwrap_err()
+                .to_string(),
+            "Reference \"refs/heads/no-upstream\" has no remote tracking branch configured to fetch"
+        );
         assert!(matches!(
-            parse_spec_no_baseline("main@{upstream}", &repo).unwrap_err(),
-            Error::Planned { .. }
+            parse_spec_no_baseline("different-name@{push}", &repo).unwrap_err(),
+            Error::RemoteTrackingRef(git_repository::reference::remote_tracking_ref::Error::AmbiguousSimplePush { .. })
         ));
+        for spec in ["@{u}", "HEAD@{u}"] {
+            assert!(matches!(
+                parse_spec_no_baseline(spec, &remote::repo("detached-head")).unwrap_err(),
+                Error::HeadIsNotABranch
+            ));
+        }
     }
 }
 
@@ -1,7 +1,4 @@
-use git_repository::{
-    prelude::ObjectIdExt,
-    revision::{spec::parse::Error, Spec},
-};
+use git_repository::{prelude::ObjectIdExt, revision::Spec};
 use git_testtools::hex_to_id;
 
 use crate::revision::spec::from_bytes::{parse_spec, parse_spec_no_baseline, repo};
@@ -76,10 +73,40 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
+fn by_date() {
     let repo = repo("complex_graph").unwrap();
-    assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
-    ));
+
+    for (spec, expected) in [
+        ("main@{1112912200 -0700}", "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc"),
+        ("main@{1112912413 -0700}", "5b3f9e24965d0b28780b7ce5daf2b5b7f7e0459f"),
+        ("@{1112912500 -0700}", "5b3f9e24965d0b28780b7ce5daf2b5b7f7e0459f"),
+        ("HEAD@{1112912480 -0700}", "44ee37c98bfdd8de5936f0dbc525a679c8441e0b"),
+        ("main@{2030-01-01}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("main@{2 weeks ago}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+        ("main@{yesterday}", "55e825ebe8fd2ff78cad3826afb696b96b576a7e"),
+    ] {
+        let spec = parse_spec(spec, &repo).unwrap_or_else(|_| panic!("{} to be parsed successfully", spec));
+        assert_eq!(spec, Spec::from_id(hex_to_id(expected).attach(&repo)));
+        assert!(spec.first_reference().is_some(), "the reference is set even if implied");
+    }
+}
+
+#[test]
+fn by_date_before_the_oldest_entry_yields_the_oldest_known_state() {
+    let repo = repo("complex_graph").unwrap();
+    assert_eq!(
+        parse_spec("main@{1000000000 -0700}", &repo).unwrap(),
+        Spec::from_id(hex_to_id("9f9eac6bd1cd4b4cc6a494f044b28c985a22972b").attach(&repo)),
+        "git warns about the log not going back far enough, but uses the first known value nonetheless"
+    );
+}
+
+#[test]
+fn by_date_unborn_head() {
+    let repo = &repo("new").unwrap();
+
+    assert_eq!(
+        parse_spec_no_baseline("@{2 weeks ago}", repo).unwrap_err().to_string(),
+        "Unborn heads do not have a reflog yet"
+    );
 }
@@ -0,0 +1,113 @@
+use git_repository as git;
+
+use git_testtools::hex_to_id;
+
+use crate::named_repo;
+
+const MERGE: &str = "288e509293165cb5630d08f4185bdf2445bf6170";
+const C2: &str = "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7";
+const B1C1: &str = "bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac";
+const C1: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+
+fn ids(hex: &[&str]) -> Vec<git::ObjectId> {
+    hex.iter().copied().map(hex_to_id).collect()
+}
+
+/// Return the commits and boundary commits of walking `spec`, similar to `git rev-list --boundary <spec>`.
+fn rev_list(repo: &git::Repository, spec: &str) -> crate::Result<(Vec<git::ObjectId>, Vec<git::ObjectId>)> {
+    let mut walk = repo.rev_parse(spec)?.rev_walk()?.all()?;
+    let commits = walk
+        .by_ref()
+        .map(|id| id.map(|id| id.detach()))
+        .collect::<Result<_, _>>()?;
+    Ok((commits, walk.boundary().map(|id| id.detach()).collect()))
+}
+
+#[test]
+fn with_hidden() -> crate::Result {
+    let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+    let mut walk = repo.rev_walk([hex_to_id(MERGE)]).with_hidden([hex_to_id(B1C1)]).all()?;
+    assert_eq!(
+        walk.by_ref()
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ids(&[MERGE, C2])
+    );
+    assert_eq!(
+        walk.boundary().map(|id| id.detach()).collect::<Vec<_>>(),
+        ids(&[B1C1, C1])
+    );
+    Ok(())
+}
+
+#[test]
+fn specs() -> crate::Result {
+    let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+    for (spec, expected, expected_boundary) in [
+        ("main", &[MERGE, C2, B1C1, C1][..], &[][..]),
+        ("^main", &[], &[]),
+        ("branch1..main", &[MERGE, C2], &[B1C1, C1]),
+        ("main..branch1", &[], &[]),
+        ("main~1...branch1", &[C2, B1C1], &[C1]),
+        ("main...branch1", &[MERGE, C2], &[B1C1, C1]),
+        ("main^@", &[C2, B1C1, C1], &[]),
+        ("main^!", &[MERGE], &[C2, B1C1]),
+    ] {
+        let (commits, boundary) = rev_list(&repo, spec)?;
+        assert_eq!(commits, ids(expected), "{}", spec);
+        assert_eq!(boundary, ids(expected_boundary), "{}", spec);
+    }
+    Ok(())
+}
+
+#[test]
+fn sorting_in_topo_and_author_date_order() -> crate::Result {
+    let repo = named_repo("make_repo_with_fork_and_dates.sh")?;
+    for (sorting, expected) in [
+        (git::traverse::commit::Sorting::TopoOrder, [MERGE, B1C1, C2, C1]),
+        (git::traverse::commit::Sorting::AuthorDateOrder, [MERGE, C2, B1C1, C1]),
+    ] {
+        let commits = repo
+            .rev_walk([hex_to_id(MERGE)])
+            .sorting(sorting)
+            .all()?
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            commits,
+            ids(&expected),
+            "git rev-list --topo-order/--author-date-order main"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn shallow_commits_end_the_traversal() -> crate::Result {
+    let tmp = git_testtools::scripted_fixture_writable("make_shallow_repo.sh")?;
+    let repo = git::open_opts(tmp.path().join("remote"), git::open::Options::isolated())?;
+    let boundary = repo.rev_parse_single("HEAD~2")?.detach();
+    std::fs::write(repo.shallow_file(), format!("{}\n", boundary))?;
+
+    for (sorting, name) in [
+        (git::traverse::commit::Sorting::Topological, "topological"),
+        (git::traverse::commit::Sorting::TopoOrder, "topo-order"),
+    ] {
+        let commits = repo
+            .head_commit()?
+            .ancestors()
+            .sorting(sorting)
+            .all()?
+            .error_on_missing_commit()
+            .map(|id| id.map(|id| id.detach()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            commits.len(),
+            3,
+            "{}: parents of shallow commits are ignored even if they are present",
+            name
+        );
+        assert_eq!(commits.last(), Some(&boundary));
+    }
+    Ok(())
+}
@@ -4,7 +4,7 @@ use git_testtools::tempfile;
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 pub fn freeze_time() -> git_testtools::Env<'static> {
-    let frozen_time = "1979-02-26 18:30:00";
+    let frozen_time = "@42 +0030";
     git_testtools::Env::new()
         .unset("GIT_AUTHOR_NAME")
         .unset("GIT_AUTHOR_EMAIL")
@@ -147,6 +147,7 @@ pub(crate) mod function {
     use std::{borrow::Cow, cmp::Ordering, collections::VecDeque, iter::FromIterator};
 
     use bstr::BStr;
+    use git_date::SecondsSinceUnixEpoch;
     use git_hash::oid;
     use git_hashtable::{hash_map, HashMap};
     use git_object::CommitRefIter;
@@ -201,7 +202,7 @@ pub(crate) mod function {
         let mut buf = Vec::new();
         let mut parent_buf = Vec::new();
 
-        let mut queue = VecDeque::from_iter(Some((commit.to_owned(), u32::MAX)));
+        let mut queue = VecDeque::from_iter(Some((commit.to_owned(), SecondsSinceUnixEpoch::MAX)));
         let mut candidates = Vec::new();
         let mut commits_seen = 0;
         let mut gave_up_on_commit = None;
@@ -290,7 +291,7 @@ pub(crate) mod function {
         });
 
         if let Some(commit_id) = gave_up_on_commit {
-            queue.push_front((commit_id, u32::MAX));
+            queue.push_front((commit_id, SecondsSinceUnixEpoch::MAX));
             commits_seen -= 1;
         }
 
@@ -318,7 +319,7 @@ pub(crate) mod function {
         find: &mut Find,
         buf: &mut Vec<u8>,
         parent_buf: &mut Vec<u8>,
-        queue: &mut VecDeque<(git_hash::ObjectId, u32)>,
+        queue: &mut VecDeque<(git_hash::ObjectId, SecondsSinceUnixEpoch)>,
         seen: &mut HashMap<git_hash::ObjectId, Flags>,
         commit: &git_hash::oid,
         commit_flags: Flags,
@@ -378,7 +379,7 @@ pub(crate) mod function {
 
     #[allow(clippy::too_many_arguments)]
     fn finish_depth_computation<'name, Find, E>(
-        mut queue: VecDeque<(git_hash::ObjectId, u32)>,
+        mut queue: VecDeque<(git_hash::ObjectId, SecondsSinceUnixEpoch)>,
         mut find: Find,
         best_candidate: &mut Candidate<'name>,
         mut seen: HashMap<git_hash::ObjectId, Flags>,
@@ -0,0 +1,121 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use bstr::BStr;
+
+use crate::{config, File};
+
+/// Access
+impl File {
+    /// Return an iterator over the names of all submodules in the order of their first appearance.
+    ///
+    /// Names which could be used to escape the `modules` directory of the superproject are skipped, just like `git` does it.
+    pub fn names(&self) -> impl Iterator<Item = &BStr> + '_ {
+        let mut seen = BTreeSet::<&BStr>::new();
+        self.config
+            .sections_by_name("submodule")
+            .into_iter()
+            .flatten()
+            .filter_map(|section| section.header().subsection_name())
+            .filter(move |name| is_valid_name(name) && seen.insert(name))
+    }
+
+    /// Return the name of the submodule whose path is `path`, if there is one.
+    pub fn name_by_path(&self, path: &BStr) -> Option<&BStr> {
+        self.names()
+            .find(|name| self.config.string("submodule", Some(name), "path").as_deref() == Some(path))
+    }
+
+    /// Return the path of the submodule `name` relative to the worktree of the superproject, which is validated to not
+    /// leave the worktree.
+    pub fn path(&self, name: &BStr) -> Result<Cow<'_, BStr>, config::path::Error> {
+        let path = self
+            .config
+            .string("submodule", Some(name), "path")
+            .ok_or_else(|| config::path::Error::Missing {
+                submodule: name.to_owned(),
+            })?;
+        if path.starts_with(b"/") || git_path::from_bstr(path.as_ref()).is_absolute() {
+            return Err(config::path::Error::Absolute {
+                submodule: name.to_owned(),
+                actual: path.into_owned(),
+            });
+        }
+        if path.split(|b| *b == b'/').any(|c| c == b"..") {
+            return Err(config::path::Error::OutsideOfWorktree {
+                submodule: name.to_owned(),
+                actual: path.into_owned(),
+            });
+        }
+        Ok(path)
+    }
+
+    /// Return the url of the submodule `name` as configured, which may be [relative][crate::url::is_relative()] to the url
+    /// of the superproject.
+    ///
+    /// Use [`resolved_url()`][Self::resolved_url()] to resolve relative urls.
+    pub fn url(&self, name: &BStr) -> Result<git_url::Url, config::url::Error> {
+        let url = self.raw_url(name)?;
+        git_url::parse(url.as_ref()).map_err(|err| config::url::Error::Parse {
+            submodule: name.to_owned(),
+            source: err,
+        })
+    }
+
+    /// Return the url of the submodule `name` after resolving it against `base` if it is relative.
+    ///
+    /// `base` is typically the url of the default remote of the superproject, or the path to its worktree if there is none.
+    pub fn resolved_url(&self, name: &BStr, base: &BStr) -> Result<git_url::Url, config::url::Error> {
+        let url = self.raw_url(name)?;
+        let url = crate::url::resolve(base, url.as_ref()).map_err(|err| config::url::Error::Resolve {
+            submodule: name.to_owned(),
+            source: err,
+        })?;
+        git_url::parse(url.as_ref()).map_err(|err| config::url::Error::Parse {
+            submodule: name.to_owned(),
+            source: err,
+        })
+    }
+
+    /// Return the way the submodule `name` should be updated, or `None` if it isn't configured which implies
+    /// [`Update::Checkout`][config::Update::Checkout].
+    ///
+    /// Note that [commands][config::Update::Command] are rejected as these are only allowed in the configuration of the superproject.
+    pub fn update(&self, name: &BStr) -> Result<Option<config::Update>, config::update::Error> {
+        let value = match self.config.string("submodule", Some(name), "update") {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let update = config::Update::try_from(value.as_ref()).map_err(|()| config::update::Error::Invalid {
+            submodule: name.to_owned(),
+            actual: value.clone().into_owned(),
+        })?;
+        if let config::Update::Command(_) = update {
+            return Err(config::update::Error::CommandForbiddenInModulesFile {
+                submodule: name.to_owned(),
+                actual: value.into_owned(),
+            });
+        }
+        Ok(Some(update))
+    }
+
+    /// Return the path to the repository of submodule `name` within the `common_dir` of the superproject.
+    pub fn git_dir(&self, name: &BStr, common_dir: &Path) -> PathBuf {
+        common_dir.join("modules").join(git_path::from_bstr(name))
+    }
+
+    fn raw_url(&self, name: &BStr) -> Result<Cow<'_, BStr>, config::url::Error> {
+        self.config
+            .string("submodule", Some(name), "url")
+            .ok_or_else(|| config::url::Error::Missing {
+                submodule: name.to_owned(),
+            })
+    }
+}
+
+fn is_valid_name(name: &BStr) -> bool {
+    !name.is_empty() && !name.split(|b| *b == b'/' || *b == b'\\').any(|c| c == b"..")
+}
@@ -0,0 +1,95 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// Determine how a submodule is updated when its superproject is checked out or updated, as configured in
+/// `submodule.<name>.update`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Update {
+    /// The commit recorded in the superproject is checked out on a detached `HEAD` in the submodule.
+    Checkout,
+    /// The current branch of the submodule is rebased onto the commit recorded in the superproject.
+    Rebase,
+    /// The commit recorded in the superproject is merged into the current branch of the submodule.
+    Merge,
+    /// The given shell command is executed with the commit recorded in the superproject as its only argument.
+    ///
+    /// Note that this is only allowed if it's configured in the configuration of the superproject, but not in `.gitmodules`.
+    Command(BString),
+    /// The submodule is not updated at all.
+    None,
+}
+
+impl Default for Update {
+    fn default() -> Self {
+        Update::Checkout
+    }
+}
+
+impl<'a> TryFrom<&'a BStr> for Update {
+    type Error = ();
+
+    fn try_from(value: &'a BStr) -> Result<Self, Self::Error> {
+        Ok(match value.as_bytes() {
+            b"checkout" => Update::Checkout,
+            b"rebase" => Update::Rebase,
+            b"merge" => Update::Merge,
+            b"none" => Update::None,
+            command if command.first() == Some(&b'!') => Update::Command(command[1..].to_owned().into()),
+            _ => return Err(()),
+        })
+    }
+}
+
+///
+pub mod path {
+    use bstr::BString;
+
+    /// The error returned by [File::path()][crate::File::path()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The path of submodule '{submodule}' is not configured")]
+        Missing { submodule: BString },
+        #[error("The path '{actual}' of submodule '{submodule}' must be relative to the worktree")]
+        Absolute { submodule: BString, actual: BString },
+        #[error("The path '{actual}' of submodule '{submodule}' points outside of the worktree")]
+        OutsideOfWorktree { submodule: BString, actual: BString },
+    }
+}
+
+///
+pub mod url {
+    use bstr::BString;
+
+    /// The error returned by [File::url()][crate::File::url()] and [File::resolved_url()][crate::File::resolved_url()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The url of submodule '{submodule}' is not configured")]
+        Missing { submodule: BString },
+        #[error("The relative url of submodule '{submodule}' could not be resolved")]
+        Resolve {
+            submodule: BString,
+            source: crate::url::resolve::Error,
+        },
+        #[error("The url of submodule '{submodule}' could not be parsed")]
+        Parse {
+            submodule: BString,
+            source: git_url::parse::Error,
+        },
+    }
+}
+
+///
+pub mod update {
+    use bstr::BString;
+
+    /// The error returned by [File::update()][crate::File::update()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The update value '{actual}' of submodule '{submodule}' is invalid")]
+        Invalid { submodule: BString, actual: BString },
+        #[error("The update command '{actual}' of submodule '{submodule}' is only allowed in the configuration of the superproject")]
+        CommandForbiddenInModulesFile { submodule: BString, actual: BString },
+    }
+}
@@ -0,0 +1,76 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::File;
+
+/// The error returned by [File::is_active()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The value of 'submodule.{submodule}.active' could not be interpreted as boolean")]
+    ActiveBoolean {
+        submodule: bstr::BString,
+        source: git_config::value::Error,
+    },
+    #[error("The pathspec in 'submodule.active' could not be parsed")]
+    ActivePathspec(#[from] git_pathspec::parse::Error),
+    #[error(transparent)]
+    Path(#[from] crate::config::path::Error),
+}
+
+impl File {
+    /// Return true if the submodule `name` is active according to `config`, the configuration of the superproject.
+    ///
+    /// Just like in `git`, `submodule.<name>.active` takes precedence. Otherwise, the pathspecs in `submodule.active`
+    /// are matched against the path of the submodule if present, and if none of these is configured the submodule is
+    /// active if it was initialized, i.e. `submodule.<name>.url` is set.
+    pub fn is_active(&self, name: &BStr, config: &git_config::File<'_>) -> Result<bool, Error> {
+        if let Some(active) = config.boolean("submodule", Some(name), "active") {
+            return active.map_err(|err| Error::ActiveBoolean {
+                submodule: name.to_owned(),
+                source: err,
+            });
+        }
+        if let Some(specs) = config.strings("submodule", None, "active") {
+            let path = self.path(name)?;
+            let mut is_active = false;
+            for spec in specs {
+                let spec = git_pathspec::parse(spec.as_ref())?;
+                if pathspec_matches(&spec, path.as_ref()) {
+                    if spec.signature.contains(git_pathspec::MagicSignature::EXCLUDE) {
+                        return Ok(false);
+                    }
+                    is_active = true;
+                }
+            }
+            return Ok(is_active);
+        }
+        Ok(config.string("submodule", Some(name), "url").is_some())
+    }
+}
+
+fn pathspec_matches(spec: &git_pathspec::Pattern, path: &BStr) -> bool {
+    let ignore_case = spec.signature.contains(git_pathspec::MagicSignature::ICASE);
+    let prefix = spec.path.as_bstr();
+    if prefix.is_empty() || prefix == "." {
+        return true;
+    }
+    let eq = |a: &[u8], b: &[u8]| if ignore_case { a.eq_ignore_ascii_case(b) } else { a == b };
+    let prefix = prefix.strip_suffix(b"/").unwrap_or(prefix);
+    if path.len() >= prefix.len()
+        && eq(&path[..prefix.len()], prefix)
+        && (path.len() == prefix.len() || path[prefix.len()] == b'/')
+    {
+        return true;
+    }
+    if spec.search_mode == git_pathspec::MatchMode::Literal {
+        return false;
+    }
+    let mut mode = git_glob::wildmatch::Mode::empty();
+    if ignore_case {
+        mode |= git_glob::wildmatch::Mode::IGNORE_CASE;
+    }
+    if spec.search_mode == git_pathspec::MatchMode::PathAwareGlob {
+        mode |= git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL;
+    }
+    git_glob::wildmatch(spec.path.as_bstr(), path, mode)
+}
@@ -1,2 +1,77 @@
-#![deny(rust_2018_idioms)]
+//! Primitives for describing git submodules as configured in `.gitmodules` files, and for determining their
+//! state within a superproject.
+//!
+//! The superproject is the repository which contains submodules, each of which is recorded as entry of type _commit_
+//! in its trees and in its index.
+#![deny(missing_docs, rust_2018_idioms)]
 #![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+/// All information about submodules as configured in a `.gitmodules` file.
+///
+/// Note that the configuration of the superproject may override some of these values once a submodule is initialized,
+/// which is why some methods take the superproject configuration into consideration.
+#[derive(Debug, Clone)]
+pub struct File {
+    config: git_config::File<'static>,
+}
+
+/// The state of a submodule within its superproject, as obtained by [`File::state()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct State {
+    /// If true, the submodule was initialized, meaning its url was recorded in the configuration of the superproject.
+    pub is_initialized: bool,
+    /// If true, the submodule is active and should be checked out when the superproject is checked out.
+    pub is_active: bool,
+    /// If true, the repository of the submodule exists in the `modules/<name>` directory of the superproject's common directory.
+    pub repository_exists: bool,
+    /// If true, the submodule's directory in the worktree of the superproject contains a `.git` file or directory.
+    pub worktree_checkout: bool,
+}
+
+mod access;
+
+///
+pub mod config;
+
+///
+pub mod is_active;
+
+///
+pub mod state;
+
+///
+pub mod url;
+
+/// Instantiation
+impl File {
+    /// Parse `bytes` as the contents of a `.gitmodules` file, which was optionally read from `path`.
+    ///
+    /// Note that includes are never followed, just like `git` does it.
+    pub fn from_bytes(bytes: &[u8], path: impl Into<Option<PathBuf>>) -> Result<Self, git_config::file::init::Error> {
+        let mut meta = git_config::file::Metadata::api();
+        meta.path = path.into();
+        let mut buf = bytes.to_owned();
+        let config = git_config::File::from_bytes_owned(&mut buf, meta, Default::default())?;
+        Ok(Self::from_config(config))
+    }
+
+    /// Use `config` as source of submodule information, typically parsed from a `.gitmodules` file.
+    pub fn from_config(config: git_config::File<'static>) -> Self {
+        File { config }
+    }
+}
+
+/// Conversion
+impl File {
+    /// Return the underlying configuration.
+    pub fn config(&self) -> &git_config::File<'static> {
+        &self.config
+    }
+
+    /// Turn ourselves back into the underlying configuration.
+    pub fn into_config(self) -> git_config::File<'static> {
+        self.config
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use bstr::BStr;
+
+use crate::{File, State};
+
+/// The error returned by [File::state()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    IsActive(#[from] crate::is_active::Error),
+    #[error(transparent)]
+    Path(#[from] crate::config::path::Error),
+}
+
+impl File {
+    /// Obtain the state of the submodule `name` within its superproject, whose configuration is `config`.
+    ///
+    /// `common_dir` is the common directory of the superproject which contains the `modules` directory, and `work_dir` is
+    /// its worktree, if there is one.
+    pub fn state(
+        &self,
+        name: &BStr,
+        config: &git_config::File<'_>,
+        common_dir: &Path,
+        work_dir: Option<&Path>,
+    ) -> Result<State, Error> {
+        let worktree_checkout = match work_dir {
+            Some(work_dir) => {
+                let path = self.path(name)?;
+                work_dir.join(git_path::from_bstr(path)).join(".git").exists()
+            }
+            None => false,
+        };
+        Ok(State {
+            is_initialized: config.string("submodule", Some(name), "url").is_some(),
+            is_active: self.is_active(name, config)?,
+            repository_exists: self.git_dir(name, common_dir).is_dir(),
+            worktree_checkout,
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+///
+pub mod resolve {
+    use bstr::BString;
+
+    /// The error returned by [resolve()][super::resolve()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot strip one more component off the url '{url}' to resolve a relative submodule url")]
+        CannotStripComponent { url: BString },
+    }
+}
+
+/// Return true if `url` is relative to the url of the superproject, which is the case if it starts with `./` or `../`.
+pub fn is_relative(url: &BStr) -> bool {
+    url.starts_with(b"./") || url.starts_with(b"../")
+}
+
+/// Resolve the submodule `url` against `base`, which is typically the url of the default remote of the superproject,
+/// or the path to its worktree if there is no such remote.
+///
+/// Urls which [aren't relative][is_relative()] are returned unchanged. Otherwise each leading `../` removes
+/// one component of `base`, just like `git` does it, which also works for scp-like urls like `host:path`.
+pub fn resolve(base: &BStr, url: &BStr) -> Result<BString, resolve::Error> {
+    if !is_relative(url) {
+        return Ok(url.to_owned());
+    }
+    let mut base: BString = base.trim_end_with(|c| c == '/').into();
+    let base_is_relative = !base.contains_str("://") && !base.starts_with(b"/") && !base.contains(&b':');
+    if base_is_relative && !(base.starts_with(b"./") || base.starts_with(b"../") || base == ".") {
+        base.insert_str(0, "./");
+    }
+
+    let mut url = url;
+    let mut colon_separated = false;
+    loop {
+        if let Some(rest) = url.strip_prefix(b"../") {
+            url = rest.as_bstr();
+            colon_separated |= strip_last_component(&mut base, base_is_relative)?;
+        } else if let Some(rest) = url.strip_prefix(b"./") {
+            url = rest.as_bstr();
+        } else {
+            break;
+        }
+    }
+
+    base.push_byte(if colon_separated { b':' } else { b'/' });
+    base.push_str(url);
+    while base.ends_with(b"/") {
+        base.pop();
+    }
+    Ok(base)
+}
+
+/// Remove the last component of `url` and return true if it was separated by a colon, as in scp-like urls.
+fn strip_last_component(url: &mut BString, is_relative: bool) -> Result<bool, resolve::Error> {
+    if let Some(pos) = url.rfind_byte(b'/') {
+        url.truncate(pos);
+        Ok(false)
+    } else if let Some(pos) = url.rfind_byte(b':') {
+        url.truncate(pos);
+        Ok(true)
+    } else if is_relative || *url == "." {
+        Err(resolve::Error::CannotStripComponent { url: url.clone() })
+    } else {
+        *url = ".".into();
+        Ok(false)
+    }
+}
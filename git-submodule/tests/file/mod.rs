@@ -0,0 +1,139 @@
+use git_submodule::{config, File};
+
+fn modules() -> File {
+    File::from_bytes(
+        b"[submodule \"a\"]
+    path = dir/a
+    url = https://example.com/a.git
+[submodule \"b\"]
+    path = b
+    url = ../b
+    update = none
+[submodule \"a\"]
+    branch = main
+[submodule \"../escape\"]
+    path = escape
+    url = ../escape
+[submodule \"absolute\"]
+    path = /absolute
+    url = ../absolute
+    update = !rm -rf /
+[submodule \"outside\"]
+    path = dir/../../outside
+",
+        None,
+    )
+    .expect("valid")
+}
+
+fn config(bytes: &str) -> git_config::File<'_> {
+    git_config::File::try_from(bytes).expect("valid")
+}
+
+#[test]
+fn names_are_unique_and_in_order_without_those_leaving_the_modules_directory() {
+    let modules = modules();
+    assert_eq!(
+        modules.names().map(|n| n.to_string()).collect::<Vec<_>>(),
+        ["a", "b", "absolute", "outside"]
+    );
+    assert_eq!(modules.name_by_path("b".into()).expect("present"), "b");
+    assert_eq!(modules.name_by_path("dir/a".into()).expect("present"), "a");
+    assert!(modules.name_by_path("dir".into()).is_none());
+}
+
+#[test]
+fn paths_must_stay_within_the_worktree() {
+    let modules = modules();
+    assert_eq!(modules.path("a".into()).expect("valid").as_ref(), "dir/a");
+    assert!(matches!(
+        modules.path("absolute".into()),
+        Err(config::path::Error::Absolute { .. })
+    ));
+    assert!(matches!(
+        modules.path("outside".into()),
+        Err(config::path::Error::OutsideOfWorktree { .. })
+    ));
+    assert!(matches!(
+        modules.path("missing".into()),
+        Err(config::path::Error::Missing { .. })
+    ));
+}
+
+#[test]
+fn urls_may_be_resolved_against_the_superproject_url() -> crate::Result {
+    let modules = modules();
+    assert_eq!(modules.url("a".into())?.to_bstring(), "https://example.com/a.git");
+    assert_eq!(
+        modules
+            .resolved_url("b".into(), "https://example.com/org/super.git".into())?
+            .to_bstring(),
+        "https://example.com/org/b"
+    );
+    assert_eq!(
+        modules.resolved_url("a".into(), "/some/path".into())?.to_bstring(),
+        "https://example.com/a.git",
+        "absolute urls stay unchanged"
+    );
+    assert!(matches!(
+        modules.url("outside".into()),
+        Err(config::url::Error::Missing { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn update_rejects_commands() -> crate::Result {
+    let modules = modules();
+    assert_eq!(modules.update("a".into())?, None);
+    assert_eq!(modules.update("b".into())?, Some(config::Update::None));
+    assert!(matches!(
+        modules.update("absolute".into()),
+        Err(config::update::Error::CommandForbiddenInModulesFile { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn is_active_prefers_the_submodule_configuration_then_pathspecs_then_the_url() -> crate::Result {
+    let modules = modules();
+    assert!(!modules.is_active("a".into(), &config(""))?, "uninitialized");
+    assert!(modules.is_active("a".into(), &config("[submodule \"a\"]\nurl = x"))?);
+    assert!(!modules.is_active("a".into(), &config("[submodule \"a\"]\nurl = x\nactive = false"))?);
+    assert!(modules.is_active("b".into(), &config("[submodule]\nactive = ."))?);
+    assert!(modules.is_active("a".into(), &config("[submodule]\nactive = dir"))?);
+    assert!(modules.is_active("a".into(), &config("[submodule]\nactive = d*/a"))?);
+    assert!(
+        !modules.is_active(
+            "b".into(),
+            &config("[submodule]\nactive = dir\n[submodule \"b\"]\nurl = x")
+        )?,
+        "pathspecs take precedence over the url"
+    );
+    assert!(!modules.is_active("a".into(), &config("[submodule]\nactive = .\nactive = :(exclude)dir"))?);
+    Ok(())
+}
+
+#[test]
+fn state_reflects_the_superproject() -> crate::Result {
+    let modules = modules();
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let common_dir = tmp.path().join(".git");
+    std::fs::create_dir_all(modules.git_dir("a".into(), &common_dir))?;
+    std::fs::create_dir_all(tmp.path().join("b"))?;
+
+    let config = config("[submodule \"a\"]\nurl = x");
+    let state = modules.state("a".into(), &config, &common_dir, Some(tmp.path()))?;
+    assert_eq!(
+        state,
+        git_submodule::State {
+            is_initialized: true,
+            is_active: true,
+            repository_exists: true,
+            worktree_checkout: false,
+        }
+    );
+    let state = modules.state("b".into(), &config, &common_dir, Some(tmp.path()))?;
+    assert!(!state.is_initialized && !state.is_active && !state.repository_exists && !state.worktree_checkout);
+    Ok(())
+}
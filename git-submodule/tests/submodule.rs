@@ -0,0 +1,4 @@
+mod file;
+mod url;
+
+pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -0,0 +1,59 @@
+use git_submodule::url;
+
+#[test]
+fn absolute_urls_are_returned_unchanged() -> Result<(), url::resolve::Error> {
+    for input in [
+        "https://example.com/a.git",
+        "/absolute/path",
+        "host:path",
+        "relative/path",
+    ] {
+        assert!(!url::is_relative(input.into()));
+        assert_eq!(url::resolve("https://example.com/base".into(), input.into())?, input);
+    }
+    Ok(())
+}
+
+#[test]
+fn relative_urls_strip_components_of_the_base() -> Result<(), url::resolve::Error> {
+    for (base, input, expected) in [
+        (
+            "https://example.com/org/base.git",
+            "../sub.git",
+            "https://example.com/org/sub.git",
+        ),
+        (
+            "https://example.com/org/base.git/",
+            "./sub",
+            "https://example.com/org/base.git/sub",
+        ),
+        (
+            "https://example.com/org/base",
+            "../../other/sub/",
+            "https://example.com/other/sub",
+        ),
+        (
+            "git@example.com:org/base.git",
+            "../sub.git",
+            "git@example.com:org/sub.git",
+        ),
+        ("git@example.com:base.git", "../sub.git", "git@example.com:sub.git"),
+        ("/path/to/base", "../sub", "/path/to/sub"),
+        ("../base", "../sub", "../sub"),
+        ("base", "../sub", "./sub"),
+        ("base", "./sub", "./base/sub"),
+    ] {
+        assert!(url::is_relative(input.into()));
+        assert_eq!(url::resolve(base.into(), input.into())?, expected, "{base} + {input}");
+    }
+    Ok(())
+}
+
+#[test]
+fn relative_urls_fail_if_the_base_has_not_enough_components() {
+    let err = url::resolve("base".into(), "../../sub".into()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Cannot strip one more component off the url '.' to resolve a relative submodule url"
+    );
+}
@@ -5,6 +5,7 @@ pub struct Ancestors<Find, Predicate, StateMut> {
     state: StateMut,
     parents: Parents,
     sorting: Sorting,
+    commit_graph: Option<git_commitgraph::Graph>,
 }
 
 /// Specify how to handle commit parents during traversal.
@@ -42,8 +43,24 @@ pub enum Sorting {
     /// As the query is usually repeated with different cutoff dates, this search mode benefits greatly from an object cache.
     ByCommitTimeNewestFirstCutoffOlderThan {
         /// The amount of seconds since unix epoch, the same value obtained by any `git_date::Time` structure and the way git counts time.
-        time_in_seconds_since_epoch: u32,
+        time_in_seconds_since_epoch: i64,
     },
+    /// Commits are sorted such that no parent is returned before all of its children were returned, without
+    /// intermixing multiple lines of history, similar to `git rev-list --topo-order`.
+    ///
+    /// # Performance
+    ///
+    /// Without a [commit-graph][Ancestors::commit_graph()], all commits reachable from the tips are looked up before
+    /// the first commit can be returned. With it, generation numbers allow to only look as far as needed.
+    TopoOrder,
+    /// Like [`TopoOrder`][Sorting::TopoOrder], but among the commits whose children were all returned, the one with the
+    /// youngest author time is returned first, similar to `git rev-list --author-date-order`.
+    ///
+    /// # Performance
+    ///
+    /// Commits are always looked up as the commit-graph doesn't contain author times, but otherwise the same as
+    /// for [`TopoOrder`][Sorting::TopoOrder] applies.
+    AuthorDateOrder,
 }
 
 impl Default for Sorting {
@@ -52,16 +69,19 @@ impl Default for Sorting {
     }
 }
 
+mod merge_base;
+pub use merge_base::merge_bases;
+
 ///
 pub mod ancestors {
     use std::{
         borrow::{Borrow, BorrowMut},
-        collections::VecDeque,
+        collections::{BinaryHeap, VecDeque},
         iter::FromIterator,
     };
 
     use git_hash::{oid, ObjectId};
-    use git_hashtable::HashSet;
+    use git_hashtable::{HashMap, HashSet};
     use git_object::CommitRefIter;
 
     use crate::commit::{Ancestors, Parents, Sorting};
@@ -79,7 +99,9 @@ pub mod ancestors {
         ObjectDecode(#[from] git_object::decode::Error),
     }
 
-    type TimeInSeconds = u32;
+    type TimeInSeconds = i64;
+
+    mod topo;
 
     /// The state used and potentially shared by multiple graph traversals.
     #[derive(Default, Clone)]
@@ -88,6 +110,9 @@ pub mod ancestors {
         buf: Vec<u8>,
         seen: HashSet<ObjectId>,
         parents_buf: Vec<u8>,
+        hidden: HashSet<ObjectId>,
+        boundary: Vec<ObjectId>,
+        topo: Option<topo::Walk>,
     }
 
     impl State {
@@ -95,6 +120,9 @@ pub mod ancestors {
             self.next.clear();
             self.buf.clear();
             self.seen.clear();
+            self.hidden.clear();
+            self.boundary.clear();
+            self.topo = None;
         }
     }
 
@@ -105,6 +133,15 @@ pub mod ancestors {
             self.parents = mode;
             self
         }
+
+        /// Use `graph` to speed up sorting by [topology][Sorting::TopoOrder], as its generation numbers allow to return
+        /// commits without looking up all of their ancestors first.
+        ///
+        /// The graph should be the one of the object database `find` looks up commits in.
+        pub fn commit_graph(mut self, graph: Option<git_commitgraph::Graph>) -> Self {
+            self.commit_graph = graph;
+            self
+        }
     }
 
     /// Builder
@@ -143,6 +180,29 @@ pub mod ancestors {
             }
             Ok(self)
         }
+
+        /// Hide all commits reachable from `tips`, that is, neither return them nor traverse past them, similar to
+        /// `^tip` or `tip..` in `git rev-list`.
+        ///
+        /// Commits reachable from hidden tips are never returned, even if clock skew makes them appear younger than the
+        /// commits they are reachable from. To achieve this, the commit graph is traversed by commit time right away
+        /// until all commits that are left to be traversed are known to be hidden, similar to how `git rev-list` does it.
+        /// Note that the predicate isn't consulted during this traversal.
+        ///
+        /// Hidden commits whose children are returned are [boundary commits][Ancestors::boundary()].
+        pub fn hide(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Result<Self, Error> {
+            let tips: Vec<ObjectId> = tips.into_iter().map(Into::into).collect();
+            if tips.is_empty() {
+                return Ok(self);
+            }
+            let hidden = self.hidden_commits(tips.into_iter())?;
+            let state = self.state.borrow_mut();
+            state.next.retain(|(id, _)| !hidden.contains(id));
+            // Hidden tips may still be encountered as parents to be part of the boundary.
+            state.seen.retain(|id| !hidden.contains(id));
+            state.hidden = hidden;
+            Ok(self)
+        }
     }
 
     /// Initialization
@@ -211,6 +271,7 @@ pub mod ancestors {
                 state,
                 parents: Default::default(),
                 sorting: Default::default(),
+                commit_graph: None,
             }
         }
     }
@@ -223,6 +284,14 @@ pub mod ancestors {
         pub fn commit_iter(&self) -> CommitRefIter<'_> {
             CommitRefIter::from_bytes(&self.state.borrow().buf)
         }
+
+        /// Return all [hidden][Ancestors::hide()] commits encountered as parents of returned commits so far, in the order
+        /// they were encountered, similar to the commits marked with `-` by `git rev-list --boundary`.
+        ///
+        /// The list is complete once the iteration is done.
+        pub fn boundary(&self) -> &[ObjectId] {
+            &self.state.borrow().boundary
+        }
     }
 
     impl<Find, Predicate, StateMut, E> Iterator for Ancestors<Find, Predicate, StateMut>
@@ -235,11 +304,14 @@ pub mod ancestors {
         type Item = Result<ObjectId, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if matches!(self.parents, Parents::First) {
+            if matches!(self.sorting, Sorting::TopoOrder | Sorting::AuthorDateOrder) {
+                self.next_in_topo_order()
+            } else if matches!(self.parents, Parents::First) {
                 self.next_by_topology()
             } else {
                 match self.sorting {
                     Sorting::Topological => self.next_by_topology(),
+                    Sorting::TopoOrder | Sorting::AuthorDateOrder => unreachable!("handled above"),
                     Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(None),
                     Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
                         time_in_seconds_since_epoch,
@@ -251,7 +323,7 @@ pub mod ancestors {
 
     impl Sorting {
         /// If not topo sort, provide the cutoff date if present.
-        fn cutoff_time(&self) -> Option<u32> {
+        fn cutoff_time(&self) -> Option<TimeInSeconds> {
             match self {
                 Sorting::ByCommitTimeNewestFirstCutoffOlderThan {
                     time_in_seconds_since_epoch,
@@ -282,8 +354,12 @@ pub mod ancestors {
                         match token {
                             Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
                             Ok(git_object::commit::ref_iter::Token::Parent { id }) => {
+                                let is_hidden = state.hidden.contains(&id);
                                 let was_inserted = state.seen.insert(id);
-                                if !(was_inserted && (self.predicate)(&id)) {
+                                if is_hidden && was_inserted {
+                                    state.boundary.push(id);
+                                }
+                                if !(was_inserted && !is_hidden && (self.predicate)(&id)) {
                                     if is_first && matches!(self.parents, Parents::First) {
                                         break;
                                     } else {
@@ -351,8 +427,12 @@ pub mod ancestors {
                         match token {
                             Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
                             Ok(git_object::commit::ref_iter::Token::Parent { id }) => {
+                                let is_hidden = state.hidden.contains(&id);
                                 let was_inserted = state.seen.insert(id);
-                                if was_inserted && (self.predicate)(&id) {
+                                if is_hidden && was_inserted {
+                                    state.boundary.push(id);
+                                }
+                                if was_inserted && !is_hidden && (self.predicate)(&id) {
                                     state.next.push_back((id, 0));
                                 }
                                 if matches!(self.parents, Parents::First) {
@@ -374,4 +454,156 @@ pub mod ancestors {
             Some(Ok(oid))
         }
     }
+
+    /// The amount of hidden commits to traverse after all commits left to traverse were found to be hidden, to catch up
+    /// with commits that appear younger than they are due to clock skew.
+    const SLOP: usize = 5;
+
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    enum Color {
+        Interesting,
+        Hidden,
+    }
+
+    struct Info {
+        color: Color,
+        parents: Vec<ObjectId>,
+        /// If true, the parents of this commit were queued already.
+        traversed: bool,
+    }
+
+    /// Utilities
+    impl<Find, Predicate, StateMut, E> Ancestors<Find, Predicate, StateMut>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        StateMut: BorrowMut<State>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        /// Traverse the commits queued so far along with the hidden `tips` by commit time and return all commits that
+        /// turned out to be reachable from `tips`, stopping once only hidden commits are left to be traversed.
+        fn hidden_commits(&mut self, tips: impl Iterator<Item = ObjectId>) -> Result<HashSet<ObjectId>, Error> {
+            let mut commits = HashMap::<ObjectId, Info>::default();
+            let mut queue = BinaryHeap::<(TimeInSeconds, ObjectId)>::new();
+            let mut queued_interesting = 0;
+
+            let interesting: Vec<_> = self.state.borrow().next.iter().map(|(id, _)| *id).collect();
+            for (id, color) in interesting
+                .into_iter()
+                .map(|id| (id, Color::Interesting))
+                .chain(tips.map(|id| (id, Color::Hidden)))
+            {
+                self.enqueue(id, color, &mut commits, &mut queue, &mut queued_interesting)?;
+            }
+
+            let mut youngest_interesting_time = TimeInSeconds::MAX;
+            let mut slop = SLOP;
+            while let Some((time, id)) = queue.pop() {
+                let info = commits.get_mut(&id).expect("queued commits are known");
+                info.traversed = true;
+                let color = info.color;
+                let parents = match (color, self.parents) {
+                    (Color::Interesting, Parents::First) => info.parents.iter().take(1).copied().collect::<Vec<_>>(),
+                    _ => info.parents.clone(),
+                };
+                if color == Color::Interesting {
+                    queued_interesting -= 1;
+                    youngest_interesting_time = time;
+                }
+                for parent in parents {
+                    self.enqueue(parent, color, &mut commits, &mut queue, &mut queued_interesting)?;
+                }
+                if color == Color::Hidden {
+                    let still_interesting = queued_interesting > 0
+                        || queue
+                            .peek()
+                            .map_or(false, |(time, _)| *time >= youngest_interesting_time);
+                    slop = if still_interesting { SLOP } else { slop - 1 };
+                    if slop == 0 {
+                        break;
+                    }
+                }
+            }
+            Ok(commits
+                .into_iter()
+                .filter(|(_, info)| info.color == Color::Hidden)
+                .map(|(id, _)| id)
+                .collect())
+        }
+
+        /// Queue `id` with `color` unless it is known already, in which case it's marked as hidden along with all of its
+        /// known ancestors if `color` is hidden.
+        fn enqueue(
+            &mut self,
+            id: ObjectId,
+            color: Color,
+            commits: &mut HashMap<ObjectId, Info>,
+            queue: &mut BinaryHeap<(TimeInSeconds, ObjectId)>,
+            queued_interesting: &mut usize,
+        ) -> Result<(), Error> {
+            if commits.contains_key(&id) {
+                if color == Color::Hidden {
+                    let mut to_hide = vec![id];
+                    while let Some(id) = to_hide.pop() {
+                        let info = match commits.get_mut(&id) {
+                            Some(info) if info.color == Color::Interesting => info,
+                            _ => continue,
+                        };
+                        info.color = Color::Hidden;
+                        if info.traversed {
+                            to_hide.extend(info.parents.iter().copied());
+                        } else {
+                            *queued_interesting -= 1;
+                        }
+                    }
+                }
+                return Ok(());
+            }
+
+            let state = self.state.borrow_mut();
+            match (self.find)(&id, &mut state.parents_buf) {
+                Ok(commit_iter) => {
+                    let (time, parents) = time_and_parents(commit_iter)?;
+                    commits.insert(
+                        id,
+                        Info {
+                            color,
+                            parents,
+                            traversed: false,
+                        },
+                    );
+                    queue.push((time, id));
+                    if color == Color::Interesting {
+                        *queued_interesting += 1;
+                    }
+                }
+                // Missing commits, like in shallow clones, can't be traversed, and the iteration will handle them.
+                Err(_) => {
+                    commits.insert(
+                        id,
+                        Info {
+                            color,
+                            parents: Vec::new(),
+                            traversed: true,
+                        },
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Return the commit time and the parents of the commit in `commit_iter`.
+    pub(crate) fn time_and_parents(commit_iter: CommitRefIter<'_>) -> Result<(TimeInSeconds, Vec<ObjectId>), Error> {
+        use git_object::commit::ref_iter::Token;
+        let mut parents = Vec::new();
+        for token in commit_iter {
+            match token? {
+                Token::Tree { .. } | Token::Author { .. } => continue,
+                Token::Parent { id } => parents.push(id),
+                Token::Committer { signature } => return Ok((signature.time.seconds_since_unix_epoch, parents)),
+                _ => break,
+            }
+        }
+        Ok((0, parents))
+    }
 }
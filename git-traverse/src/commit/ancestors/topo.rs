@@ -0,0 +1,282 @@
+use std::{borrow::BorrowMut, cmp::Reverse, collections::BinaryHeap};
+
+use git_hash::{oid, ObjectId};
+use git_hashtable::HashMap;
+use git_object::CommitRefIter;
+
+use super::{Error, State, TimeInSeconds};
+use crate::commit::{Ancestors, Parents, Sorting};
+
+/// The generation number of commits that aren't part of a commit-graph, which makes them be treated as younger than all
+/// commits that are.
+const GENERATION_NUMBER_INFINITY: u32 = u32::MAX;
+
+/// What we know about a commit that is yet to be returned.
+#[derive(Clone)]
+struct Info {
+    generation: u32,
+    commit_time: TimeInSeconds,
+    author_time: TimeInSeconds,
+    parents: Vec<ObjectId>,
+}
+
+/// Commits that are ready to be returned as all of their children were returned.
+#[derive(Clone)]
+enum Ready {
+    /// The commit pushed last is returned first.
+    Stack(Vec<ObjectId>),
+    /// The commit with the youngest author date is returned first, or the one pushed first if dates are equal.
+    ByAuthorTime {
+        queue: BinaryHeap<(TimeInSeconds, Reverse<usize>, ObjectId)>,
+        count: usize,
+    },
+}
+
+impl Ready {
+    fn push(&mut self, id: ObjectId, info: &Info) {
+        match self {
+            Ready::Stack(stack) => stack.push(id),
+            Ready::ByAuthorTime { queue, count } => {
+                queue.push((info.author_time, Reverse(*count), id));
+                *count += 1;
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<ObjectId> {
+        match self {
+            Ready::Stack(stack) => stack.pop(),
+            Ready::ByAuthorTime { queue, .. } => queue.pop().map(|(_, _, id)| id),
+        }
+    }
+}
+
+/// The state of a walk which returns children before their parents, similar to the incremental topological walk of git.
+///
+/// The in-degree of each commit, that is the amount of its children that were not yet returned plus one, is computed
+/// by walking commits from the youngest generation to the oldest generation as needed. Commits are returned once their
+/// in-degree is one. Without generation numbers, all commits need to be walked to learn about their in-degree.
+#[derive(Clone)]
+pub(super) struct Walk {
+    commits: HashMap<ObjectId, Info>,
+    in_degree: HashMap<ObjectId, usize>,
+    in_degree_queue: BinaryHeap<(u32, TimeInSeconds, Reverse<usize>, ObjectId)>,
+    in_degree_count: usize,
+    min_generation: u32,
+    ready: Ready,
+}
+
+/// Utilities
+impl<Find, Predicate, StateMut, E> Ancestors<Find, Predicate, StateMut>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    Predicate: FnMut(&oid) -> bool,
+    StateMut: BorrowMut<State>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    pub(super) fn next_in_topo_order(&mut self) -> Option<Result<ObjectId, Error>> {
+        if self.state.borrow().topo.is_none() {
+            if let Err(err) = self.init_topo_walk() {
+                return Some(Err(err));
+            }
+        }
+        let id = self.topo_mut().ready.pop()?;
+        let info = self.topo_mut().commits.remove(&id).expect("ready commits are known");
+        self.topo_mut().in_degree.remove(&id);
+        if let Err(err) = self.expand_topo_walk(&info) {
+            return Some(Err(err));
+        }
+
+        let state = self.state.borrow_mut();
+        if let Err(err) = (self.find)(&id, &mut state.buf) {
+            return Some(Err(Error::FindExisting {
+                oid: id,
+                source: err.into(),
+            }));
+        }
+        Some(Ok(id))
+    }
+
+    fn topo_mut(&mut self) -> &mut Walk {
+        self.state.borrow_mut().topo.as_mut().expect("initialized")
+    }
+
+    fn init_topo_walk(&mut self) -> Result<(), Error> {
+        let tips: Vec<_> = std::mem::take(&mut self.state.borrow_mut().next)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        self.state.borrow_mut().topo = Some(Walk {
+            commits: Default::default(),
+            in_degree: Default::default(),
+            in_degree_queue: Default::default(),
+            in_degree_count: 0,
+            min_generation: GENERATION_NUMBER_INFINITY,
+            ready: match self.sorting {
+                Sorting::AuthorDateOrder => Ready::ByAuthorTime {
+                    queue: Default::default(),
+                    count: 0,
+                },
+                _ => Ready::Stack(Vec::new()),
+            },
+        });
+
+        for tip in &tips {
+            let info = self.topo_info(tip)?;
+            let topo = self.topo_mut();
+            topo.min_generation = topo.min_generation.min(info.generation);
+            topo.in_degree.insert(*tip, 1);
+            topo.queue_for_in_degree(*tip, &info);
+            topo.commits.insert(*tip, info);
+        }
+        let min_generation = self.topo_mut().min_generation;
+        self.compute_in_degrees_to_depth(min_generation)?;
+
+        let topo = self.topo_mut();
+        let mut ready_tips: Vec<_> = tips.into_iter().filter(|tip| topo.in_degree[tip] == 1).collect();
+        if let Ready::Stack(_) = topo.ready {
+            // The first tip should be returned first.
+            ready_tips.reverse();
+        }
+        for tip in ready_tips {
+            topo.ready.push(tip, &topo.commits[&tip]);
+        }
+        Ok(())
+    }
+
+    /// Learn that all children of `info` were returned, and mark its parents as ready once all of their children were
+    /// returned as well.
+    fn expand_topo_walk(&mut self, info: &Info) -> Result<(), Error> {
+        for parent in parents(info, self.parents) {
+            let state = self.state.borrow_mut();
+            if state.hidden.contains(parent) {
+                if state.seen.insert(*parent) {
+                    state.boundary.push(*parent);
+                }
+                continue;
+            }
+            let topo = state.topo.as_mut().expect("initialized");
+            let generation = match topo.commits.get(parent) {
+                Some(info) => info.generation,
+                None => continue,
+            };
+            if generation < topo.min_generation {
+                topo.min_generation = generation;
+                self.compute_in_degrees_to_depth(generation)?;
+            }
+            let topo = self.topo_mut();
+            let in_degree = topo.in_degree.get_mut(parent).expect("known commits have an in-degree");
+            *in_degree -= 1;
+            if *in_degree == 1 {
+                topo.ready.push(*parent, &topo.commits[parent]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the in-degree of all commits with a generation of at least `min_generation`.
+    fn compute_in_degrees_to_depth(&mut self, min_generation: u32) -> Result<(), Error> {
+        let mode = self.parents;
+        loop {
+            let topo = self.topo_mut();
+            let id = match topo.in_degree_queue.peek() {
+                Some((generation, _, _, id)) if *generation >= min_generation => *id,
+                _ => break,
+            };
+            topo.in_degree_queue.pop();
+            let commit_parents: Vec<_> = parents(&topo.commits[&id], mode).copied().collect();
+            for parent in commit_parents {
+                let state = self.state.borrow_mut();
+                if state.hidden.contains(&parent) {
+                    continue;
+                }
+                let topo = state.topo.as_mut().expect("initialized");
+                if let Some(in_degree) = topo.in_degree.get_mut(&parent) {
+                    *in_degree += 1;
+                    continue;
+                }
+                if !(state.seen.insert(parent) && (self.predicate)(&parent)) {
+                    continue;
+                }
+                let info = self.topo_info(&parent)?;
+                let topo = self.topo_mut();
+                topo.in_degree.insert(parent, 2);
+                topo.queue_for_in_degree(parent, &info);
+                topo.commits.insert(parent, info);
+            }
+        }
+        Ok(())
+    }
+
+    /// Obtain information about the commit with `id`, preferably from the commit-graph.
+    fn topo_info(&mut self, id: &oid) -> Result<Info, Error> {
+        let needs_author_time = matches!(self.sorting, Sorting::AuthorDateOrder);
+        if let Some(commit) = self.commit_graph.as_ref().and_then(|graph| graph.commit_by_id(id)) {
+            let graph = self.commit_graph.as_ref().expect("present");
+            let parents: Result<Vec<_>, _> = commit
+                .iter_parents()
+                .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                .collect();
+            if let (Ok(parents), false) = (parents, needs_author_time) {
+                return Ok(Info {
+                    generation: match commit.generation() {
+                        0 => GENERATION_NUMBER_INFINITY,
+                        generation => generation,
+                    },
+                    commit_time: commit.committer_timestamp() as TimeInSeconds,
+                    author_time: 0,
+                    parents,
+                });
+            }
+        }
+
+        let generation = self
+            .commit_graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(id))
+            .map_or(GENERATION_NUMBER_INFINITY, |commit| match commit.generation() {
+                0 => GENERATION_NUMBER_INFINITY,
+                generation => generation,
+            });
+        let state = self.state.borrow_mut();
+        let commit_iter = (self.find)(id, &mut state.parents_buf).map_err(|err| Error::FindExisting {
+            oid: id.to_owned(),
+            source: err.into(),
+        })?;
+        let mut info = Info {
+            generation,
+            commit_time: 0,
+            author_time: 0,
+            parents: Vec::new(),
+        };
+        for token in commit_iter {
+            use git_object::commit::ref_iter::Token;
+            match token? {
+                Token::Tree { .. } => continue,
+                Token::Parent { id } => info.parents.push(id),
+                Token::Author { signature } => info.author_time = signature.time.seconds_since_unix_epoch,
+                Token::Committer { signature } => {
+                    info.commit_time = signature.time.seconds_since_unix_epoch;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        Ok(info)
+    }
+}
+
+impl Walk {
+    fn queue_for_in_degree(&mut self, id: ObjectId, info: &Info) {
+        self.in_degree_queue
+            .push((info.generation, info.commit_time, Reverse(self.in_degree_count), id));
+        self.in_degree_count += 1;
+    }
+}
+
+fn parents(info: &Info, mode: Parents) -> impl Iterator<Item = &ObjectId> {
+    info.parents.iter().take(match mode {
+        Parents::All => usize::MAX,
+        Parents::First => 1,
+    })
+}
@@ -0,0 +1,140 @@
+use std::collections::BinaryHeap;
+
+use git_hash::{oid, ObjectId};
+use git_hashtable::HashMap;
+use git_object::CommitRefIter;
+
+use crate::commit::ancestors::{time_and_parents, Error};
+
+type Flags = u8;
+const FIRST: Flags = 1 << 0;
+const OTHERS: Flags = 1 << 1;
+const STALE: Flags = 1 << 2;
+const RESULT: Flags = 1 << 3;
+
+/// Return all best common ancestors of `first` and any of `others`, similar to `git merge-base --all first others…`.
+///
+/// A common ancestor is best if it's not an ancestor of another best common ancestor. The returned merge bases are
+/// sorted by commit time, newest first, and the list is empty if there is no common ancestor.
+///
+/// * `find` - a way to lookup commits by their ObjectId, writing their data into buffer and returning
+///   an iterator over commit tokens if the object is present and is a commit.
+pub fn merge_bases<Find, E>(first: ObjectId, others: &[ObjectId], find: Find) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if others.contains(&first) {
+        return Ok(vec![first]);
+    }
+    let mut graph = Graph {
+        find,
+        buf: Vec::new(),
+        commits: Default::default(),
+    };
+    let (bases, _flags) = graph.paint_down_to_common(first, others)?;
+    let mut bases = graph.remove_redundant(bases)?;
+    bases.sort_by_key(|id| std::cmp::Reverse(graph.commits[id].0));
+    Ok(bases)
+}
+
+struct Graph<Find> {
+    find: Find,
+    buf: Vec<u8>,
+    /// The commit time and parents of all commits looked up so far.
+    commits: HashMap<ObjectId, (i64, Vec<ObjectId>)>,
+}
+
+impl<Find, E> Graph<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn time(&mut self, id: ObjectId) -> Result<i64, Error> {
+        if let Some((time, _)) = self.commits.get(&id) {
+            return Ok(*time);
+        }
+        let commit_iter = (self.find)(&id, &mut self.buf).map_err(|err| Error::FindExisting {
+            oid: id,
+            source: err.into(),
+        })?;
+        let (time, parents) = time_and_parents(commit_iter)?;
+        self.commits.insert(id, (time, parents));
+        Ok(time)
+    }
+
+    /// Walk down from `first` and `others` by commit time, marking all commits with the side they are reachable from,
+    /// and return the common ancestors found along the way which aren't ancestors of other common ancestors, along with
+    /// the flags of all commits seen.
+    fn paint_down_to_common(
+        &mut self,
+        first: ObjectId,
+        others: &[ObjectId],
+    ) -> Result<(Vec<ObjectId>, HashMap<ObjectId, Flags>), Error> {
+        let mut flags = HashMap::<ObjectId, Flags>::default();
+        let mut queue = BinaryHeap::new();
+        for (id, flag) in std::iter::once((first, FIRST)).chain(others.iter().map(|id| (*id, OTHERS))) {
+            *flags.entry(id).or_default() |= flag;
+            queue.push((self.time(id)?, id));
+        }
+
+        let mut bases = Vec::new();
+        while queue.iter().any(|(_, id)| flags[id] & STALE == 0) {
+            let (_time, id) = queue.pop().expect("non-empty queue");
+            let commit_flags = flags.get_mut(&id).expect("queued commits have flags");
+            let mut propagated = *commit_flags & (FIRST | OTHERS | STALE);
+            if propagated & (FIRST | OTHERS) == FIRST | OTHERS {
+                if *commit_flags & RESULT == 0 {
+                    *commit_flags |= RESULT;
+                    bases.push(id);
+                }
+                propagated |= STALE;
+            }
+            let parents = self.commits[&id].1.clone();
+            for parent in parents {
+                let parent_flags = flags.entry(parent).or_default();
+                if *parent_flags & propagated == propagated {
+                    continue;
+                }
+                *parent_flags |= propagated;
+                queue.push((self.time(parent)?, parent));
+            }
+        }
+        bases.retain(|id| flags[id] & STALE == 0);
+        Ok((bases, flags))
+    }
+
+    /// Remove all `bases` which are reachable from other `bases`.
+    fn remove_redundant(&mut self, bases: Vec<ObjectId>) -> Result<Vec<ObjectId>, Error> {
+        if bases.len() < 2 {
+            return Ok(bases);
+        }
+        let mut redundant = vec![false; bases.len()];
+        for (idx, base) in bases.iter().enumerate() {
+            if redundant[idx] {
+                continue;
+            }
+            let others: Vec<_> = bases
+                .iter()
+                .enumerate()
+                .filter(|(other_idx, _)| *other_idx != idx && !redundant[*other_idx])
+                .map(|(_, id)| *id)
+                .collect();
+            let (_, flags) = self.paint_down_to_common(*base, &others)?;
+            if flags[base] & OTHERS != 0 {
+                redundant[idx] = true;
+            }
+            for (other_idx, other) in bases.iter().enumerate() {
+                if other_idx != idx && flags.get(other).map_or(false, |flags| flags & FIRST != 0) {
+                    redundant[other_idx] = true;
+                }
+            }
+        }
+        Ok(bases
+            .into_iter()
+            .zip(redundant)
+            .filter(|(_, redundant)| !redundant)
+            .map(|(id, _)| id)
+            .collect())
+    }
+}
@@ -269,4 +269,266 @@ mod ancestor {
         .with_parents(commit::Parents::First)
         .check()
     }
+
+    mod hidden {
+        use git_hash::ObjectId;
+        use git_odb::pack::FindExt;
+        use git_traverse::commit;
+
+        use crate::hex_to_id;
+
+        const N1: &str = "e278e233569f04395595c6baa70ae7253da0301a";
+        const M1: &str = "e6b823ce8fbbad1c0614a16a5263bc5bd0958529";
+        const C4: &str = "87f3f20507454bf00426dac204f25d6653df43d2";
+        const C3: &str = "8a01ef6240055298073434aae3a29b06dc735e68";
+        const F2: &str = "db0df2172e4cff464b8f7a1bf292010f3b191bc5";
+        const F1: &str = "e7b63de96b1705560a58b547dfb00a690c4cda01";
+        const C2: &str = "a430cdf4464603416065f8a86c1d5ca666d929b1";
+        const O2: &str = "d13677bfd07849fac4ab82059b200428d65e7a15";
+        const O1: &str = "50bdcc460df819c20b6ba26b13f1f9873c256c3d";
+
+        /// Return the commits and the boundary commits of a traversal of `tips` with `hidden` tips.
+        fn traverse(
+            tips: &[&str],
+            hidden: &[&str],
+            sorting: commit::Sorting,
+        ) -> crate::Result<(Vec<ObjectId>, Vec<ObjectId>)> {
+            let dir = git_testtools::scripted_fixture_read_only("make_traversal_repo_for_commits_with_hidden.sh")?;
+            let store = git_odb::at(dir.join(".git").join("objects"))?;
+            let mut iter = commit::Ancestors::new(
+                tips.iter().copied().map(hex_to_id),
+                commit::ancestors::State::default(),
+                move |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0),
+            )
+            .sorting(sorting)?
+            .hide(hidden.iter().copied().map(hex_to_id))?;
+            let commits = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+            Ok((commits, iter.boundary().to_owned()))
+        }
+
+        fn ids(hex: &[&str]) -> Vec<ObjectId> {
+            hex.iter().copied().map(hex_to_id).collect()
+        }
+
+        #[test]
+        fn commits_reachable_from_hidden_tips_are_not_returned() -> crate::Result {
+            for sorting in [commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+                // git rev-list --boundary main..feature
+                let (commits, boundary) = traverse(&[F2], &[C4], sorting)?;
+                assert_eq!(commits, ids(&[F2, F1]));
+                assert_eq!(boundary, ids(&[C2]));
+
+                // git rev-list --boundary feature..main
+                let (commits, boundary) = traverse(&[C4], &[F2], sorting)?;
+                assert_eq!(commits, ids(&[C4, C3]));
+                assert_eq!(boundary, ids(&[C2]));
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn hidden_tips_hide_tips() -> crate::Result {
+            let (commits, boundary) = traverse(&[C4, F2], &[C4], commit::Sorting::Topological)?;
+            assert_eq!(commits, ids(&[F2, F1]));
+            assert_eq!(boundary, ids(&[C2]));
+
+            let (commits, boundary) = traverse(&[C4], &[M1], commit::Sorting::Topological)?;
+            assert!(commits.is_empty());
+            assert!(boundary.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn hidden_merged_branches() -> crate::Result {
+            // git rev-list --boundary feature..merged
+            let (commits, boundary) = traverse(&[M1], &[F2], commit::Sorting::Topological)?;
+            assert_eq!(commits, ids(&[M1, C4, C3]));
+            assert_eq!(boundary, ids(&[F2, C2]));
+
+            let (commits, boundary) = traverse(&[M1], &[F2], commit::Sorting::ByCommitTimeNewestFirst)?;
+            assert_eq!(commits, ids(&[M1, C4, C3]));
+            assert_eq!(boundary, ids(&[F2, C2]));
+            Ok(())
+        }
+
+        #[test]
+        fn symmetric_difference_with_merge_bases() -> crate::Result {
+            // git rev-list --boundary main...feature
+            let (commits, boundary) = traverse(&[C4, F2], &[C2], commit::Sorting::ByCommitTimeNewestFirst)?;
+            assert_eq!(commits, ids(&[C4, C3, F2, F1]));
+            assert_eq!(boundary, ids(&[C2]));
+            Ok(())
+        }
+
+        #[test]
+        fn clock_skew_does_not_leak_hidden_commits() -> crate::Result {
+            for sorting in [commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+                // git rev-list --boundary old..new, where `old` commits seem older than their parents.
+                let (commits, boundary) = traverse(&[N1], &[O2], sorting)?;
+                assert_eq!(commits, ids(&[N1]));
+                assert_eq!(boundary, ids(&[C4]));
+
+                // git rev-list --boundary new...old
+                let (commits, boundary) = traverse(&[N1, O2], &[C4], sorting)?;
+                assert_eq!(commits, ids(&[N1, O2, O1]));
+                assert_eq!(boundary, ids(&[C4]));
+            }
+            Ok(())
+        }
+    }
+}
+
+mod topo_order {
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    fn baseline(dir: &std::path::Path, name: &str) -> crate::Result<(Vec<ObjectId>, Vec<ObjectId>, Vec<ObjectId>)> {
+        let baseline = std::fs::read_to_string(dir.join(format!("{}.baseline", name)))?;
+        let (revs, expected) = baseline
+            .split_once("\n\n")
+            .expect("revs and commits are separated by an empty line");
+        let (mut tips, mut hidden) = (Vec::new(), Vec::new());
+        for rev in revs.lines() {
+            match rev.strip_prefix('^') {
+                Some(hex) => hidden.push(ObjectId::from_hex(hex.as_bytes())?),
+                None => tips.push(ObjectId::from_hex(rev.as_bytes())?),
+            }
+        }
+        let expected = expected
+            .lines()
+            .map(|hex| ObjectId::from_hex(hex.as_bytes()))
+            .collect::<Result<_, _>>()?;
+        Ok((tips, hidden, expected))
+    }
+
+    #[test]
+    fn matches_git_with_and_without_commit_graph() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_read_only("make_traversal_repo_for_commits_in_topo_order.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        for (order, sorting) in [
+            ("topo", commit::Sorting::TopoOrder),
+            ("author-date", commit::Sorting::AuthorDateOrder),
+        ] {
+            for (case, parents) in [
+                ("main", commit::Parents::All),
+                ("a", commit::Parents::All),
+                ("main-and-a3", commit::Parents::All),
+                ("a-without-b", commit::Parents::All),
+                ("a-first-parent", commit::Parents::First),
+            ] {
+                let name = format!("{}-{}", order, case);
+                let (tips, hidden, expected) = baseline(&dir, &name)?;
+                for use_commit_graph in [false, true] {
+                    let graph = use_commit_graph
+                        .then(|| git_commitgraph::Graph::from_info_dir(dir.join(".git").join("objects").join("info")))
+                        .transpose()?;
+                    let store = store.clone();
+                    let actual = commit::Ancestors::new(
+                        tips.iter().cloned(),
+                        commit::ancestors::State::default(),
+                        move |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0),
+                    )
+                    .sorting(sorting)?
+                    .parents(parents)
+                    .commit_graph(graph)
+                    .hide(hidden.iter().cloned())?
+                    .collect::<Result<Vec<_>, _>>()?;
+                    assert_eq!(actual, expected, "{} with commit-graph: {}", name, use_commit_graph);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn commit_graph_avoids_looking_up_all_commits_first() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_read_only("make_traversal_repo_for_commits_in_topo_order.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let (tips, _, _) = baseline(&dir, "topo-a")?;
+        // The tip is looked up for sorting, and the returned commit is looked up to make its data available.
+        for (graph, expected_lookups) in [
+            (None, 2 + 13),
+            (
+                Some(git_commitgraph::Graph::from_info_dir(
+                    dir.join(".git").join("objects").join("info"),
+                )?),
+                2,
+            ),
+        ] {
+            let lookups = std::cell::Cell::new(0);
+            let store = store.clone();
+            let first =
+                commit::Ancestors::new(tips.iter().cloned(), commit::ancestors::State::default(), |oid, buf| {
+                    lookups.set(lookups.get() + 1);
+                    store.find_commit_iter(oid, buf).map(|t| t.0)
+                })
+                .commit_graph(graph)
+                .sorting(commit::Sorting::TopoOrder)?
+                .next()
+                .expect("one commit")?;
+            assert_eq!(first, tips[0]);
+            assert_eq!(lookups.get(), expected_lookups);
+        }
+        Ok(())
+    }
+}
+
+mod merge_bases {
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    use crate::hex_to_id;
+
+    #[test]
+    fn best_common_ancestors() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_read_only("make_traversal_repo_for_commits_with_hidden.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        for (first, others, expected) in [
+            // main and feature
+            (
+                "87f3f20507454bf00426dac204f25d6653df43d2",
+                &["db0df2172e4cff464b8f7a1bf292010f3b191bc5"][..],
+                &["a430cdf4464603416065f8a86c1d5ca666d929b1"][..],
+            ),
+            // old and new, despite clock skew
+            (
+                "d13677bfd07849fac4ab82059b200428d65e7a15",
+                &["e278e233569f04395595c6baa70ae7253da0301a"],
+                &["87f3f20507454bf00426dac204f25d6653df43d2"],
+            ),
+            // merged and new, with new having a merge base with either parent of merged
+            (
+                "e6b823ce8fbbad1c0614a16a5263bc5bd0958529",
+                &[
+                    "e278e233569f04395595c6baa70ae7253da0301a",
+                    "db0df2172e4cff464b8f7a1bf292010f3b191bc5",
+                ],
+                &[
+                    "87f3f20507454bf00426dac204f25d6653df43d2",
+                    "db0df2172e4cff464b8f7a1bf292010f3b191bc5",
+                ],
+            ),
+            // an ancestor of the other
+            (
+                "e7b63de96b1705560a58b547dfb00a690c4cda01",
+                &["e6b823ce8fbbad1c0614a16a5263bc5bd0958529"],
+                &["e7b63de96b1705560a58b547dfb00a690c4cda01"],
+            ),
+            // the same commit
+            (
+                "e7b63de96b1705560a58b547dfb00a690c4cda01",
+                &["e7b63de96b1705560a58b547dfb00a690c4cda01"],
+                &["e7b63de96b1705560a58b547dfb00a690c4cda01"],
+            ),
+        ] {
+            let others: Vec<_> = others.iter().copied().map(hex_to_id).collect();
+            let expected: Vec<_> = expected.iter().copied().map(hex_to_id).collect();
+            let actual = commit::merge_bases(hex_to_id(first), &others, |oid, buf| {
+                store.find_commit_iter(oid, buf).map(|t| t.0)
+            })?;
+            assert_eq!(actual, expected, "{} {:?}", first, others);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  local committer_day=$1 author_day=$2 message=$3
+  GIT_COMMITTER_DATE="2000-01-$committer_day 00:00:00 +0000" GIT_AUTHOR_DATE="2000-01-$author_day 00:00:00 +0000" \
+    git commit -q --allow-empty -m "$message"
+}
+
+function merge_at() {
+  local committer_day=$1 author_day=$2 branch=$3
+  GIT_COMMITTER_DATE="2000-01-$committer_day 00:00:00 +0000" GIT_AUTHOR_DATE="2000-01-$author_day 00:00:00 +0000" \
+    git merge -q --no-ff "$branch" -m "merge $branch"
+}
+
+# Write the tips and hidden commits of `git rev-parse <revs>`, followed by the output of `git rev-list <flags> <revs>`.
+function baseline() {
+  local name=$1 flags=$2
+  shift 2
+  {
+    git rev-parse "$@"
+    echo
+    git -c core.commitGraph=false rev-list $flags "$@"
+  } > "$name.baseline"
+}
+
+git init -q
+
+git checkout -q -b main
+commit_at 01 01 c1
+
+git checkout -q -b a
+commit_at 03 09 a1
+commit_at 05 02 a2
+
+git checkout -q main
+commit_at 02 08 c2
+
+git checkout -q -b b
+# a commit that claims to be older than its parent due to clock skew
+commit_at 01 03 b1
+commit_at 06 11 b2
+
+git checkout -q main
+commit_at 04 07 c3
+merge_at 07 04 a
+merge_at 08 05 b
+commit_at 09 06 c4
+
+git checkout -q a
+commit_at 10 10 a3
+merge_at 11 12 main
+commit_at 12 01 a4
+
+for order in topo author-date; do
+  baseline "$order-main" "--$order-order" main
+  baseline "$order-a" "--$order-order" a
+  baseline "$order-main-and-a3" "--$order-order" main a~2
+  baseline "$order-a-without-b" "--$order-order" a ^b
+  baseline "$order-a-first-parent" "--$order-order --first-parent" a
+done
+
+git commit-graph write --no-progress --reachable
@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  GIT_COMMITTER_DATE="$1 00:00:00 +0000" git commit -q --allow-empty -m "$2"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+commit_at 2000-01-01 c1
+commit_at 2001-01-01 c2
+
+git checkout -q -b feature
+commit_at 2002-01-01 f1
+commit_at 2003-01-01 f2
+
+git checkout -q main
+commit_at 2004-01-01 c3
+commit_at 2005-01-01 c4
+
+# commits on `old` claim to be older than their parents due to clock skew
+git checkout -q -b old
+commit_at 1990-01-01 o1
+commit_at 1991-01-01 o2
+
+git checkout -q main
+git checkout -q -b new
+commit_at 2020-01-01 n1
+
+git checkout -q -b merged main
+GIT_COMMITTER_DATE="2006-01-01 00:00:00 +0000" git merge -q feature -m m1
@@ -40,6 +40,33 @@ impl<'a, 'paths> Platform<'a, 'paths> {
             git_path::to_unix_separators_on_windows(git_path::into_bstr(self.parent.stack.current_relative.as_path()));
         ignore.matching_exclude_pattern(relative_path.as_bstr(), self.is_dir, self.parent.case)
     }
+
+    /// Match all attributes at the current position of the stack and store their state in `out`, similar to `git check-attr --all`,
+    /// while expanding macros.
+    ///
+    /// Note that `out` is reset and its macros are re-initialized, so it can be reused across calls.
+    ///
+    /// # Panics
+    ///
+    /// If the cache was configured without attributes.
+    pub fn matching_attributes(&self, out: &mut git_attributes::Outcome) {
+        let attributes = self.parent.state.attributes_or_panic();
+        let groups = attributes.match_groups();
+        // Macros may only be defined in the top-level attribute files, which is the first pattern list on the stack.
+        out.initialize_macros(
+            attributes
+                .globals
+                .patterns
+                .iter()
+                .chain(attributes.stack.patterns.first())
+                .chain(attributes.info.patterns.iter()),
+        );
+        let relative_path =
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(self.parent.stack.current_relative.as_path()));
+        for group in groups {
+            group.attributes_matching_relative_path(relative_path.as_bstr(), self.is_dir, self.parent.case, out);
+        }
+    }
 }
 
 impl<'a, 'paths> std::fmt::Debug for Platform<'a, 'paths> {
@@ -63,11 +90,21 @@ where
 {
     fn push_directory(&mut self, stack: &fs::Stack) -> std::io::Result<()> {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
-            }
-            State::AttributesAndIgnoreStack { ignore, attributes: _ } => {
-                // TODO: attributes
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => attributes.push_directory(
+                &stack.root,
+                &stack.current,
+                self.buf,
+                self.attribute_files_in_index,
+                &mut self.find,
+            )?,
+            State::AttributesAndIgnoreStack { ignore, attributes } => {
+                attributes.push_directory(
+                    &stack.root,
+                    &stack.current,
+                    self.buf,
+                    self.attribute_files_in_index,
+                    &mut self.find,
+                )?;
                 ignore.push_directory(
                     &stack.root,
                     &stack.current,
@@ -117,11 +154,11 @@ where
 
     fn pop_directory(&mut self) {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => {
+                attributes.pop_directory();
             }
-            State::AttributesAndIgnoreStack { attributes: _, ignore } => {
-                // TODO: attributes
+            State::AttributesAndIgnoreStack { attributes, ignore } => {
+                attributes.pop_directory();
                 ignore.pop_directory();
             }
             State::IgnoreStack(ignore) => {
@@ -148,20 +185,37 @@ fn create_leading_directory(
     match std::fs::create_dir(stack.current()) {
         Ok(()) => Ok(()),
         Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
-            let meta = stack.current().symlink_metadata()?;
+            let meta = match stack.current().symlink_metadata() {
+                Ok(meta) => meta,
+                // Another thread removed what was in our way, so try again and see if it created the directory.
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    #[cfg(debug_assertions)]
+                    {
+                        *mkdir_calls += 1;
+                    }
+                    return create_dir_or_accept_existing(stack.current());
+                }
+                Err(err) => return Err(err),
+            };
             if meta.is_dir() {
                 Ok(())
             } else if unlink_on_collision {
-                if meta.file_type().is_symlink() {
-                    crate::os::remove_symlink(stack.current())?;
+                let res = if meta.file_type().is_symlink() {
+                    crate::os::remove_symlink(stack.current())
                 } else {
-                    std::fs::remove_file(stack.current())?;
+                    std::fs::remove_file(stack.current())
+                };
+                match res {
+                    Ok(()) => {}
+                    // Another thread was faster in unlinking the same entry.
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err),
                 }
                 #[cfg(debug_assertions)]
                 {
                     *mkdir_calls += 1;
                 }
-                std::fs::create_dir(stack.current())
+                create_dir_or_accept_existing(stack.current())
             } else {
                 Err(err)
             }
@@ -169,3 +223,11 @@ fn create_leading_directory(
         Err(err) => Err(err),
     }
 }
+
+/// Create the directory at `path`, but accept it already existing as other threads may create it concurrently.
+fn create_dir_or_accept_existing(path: &Path) -> std::io::Result<()> {
+    match std::fs::create_dir(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists && path.symlink_metadata()?.is_dir() => Ok(()),
+        res => res,
+    }
+}
@@ -11,12 +11,13 @@ type IgnoreMatchGroup = git_attributes::MatchGroup<git_attributes::Ignore>;
 
 /// State related to attributes associated with files in the repository.
 #[derive(Default, Clone)]
-#[allow(unused)]
 pub struct Attributes {
     /// Attribute patterns that match the currently set directory (in the stack).
     pub stack: AttributeMatchGroup,
     /// Attribute patterns which aren't tied to the repository root, hence are global. They are consulted last.
     pub globals: AttributeMatchGroup,
+    /// Attribute patterns from `$GIT_DIR/info/attributes`, which are consulted first.
+    pub info: AttributeMatchGroup,
 }
 
 /// State related to the exclusion of files.
@@ -186,20 +187,68 @@ impl Ignore {
 }
 
 impl Attributes {
-    /// Create a new instance from an attribute match group that represents `globals`.
+    /// Create a new instance from an attribute match group that represents `globals`, along with the patterns in
+    /// `info/attributes` as `info`.
     ///
-    /// A stack of attributes will be applied on top of it later.
-    pub fn new(globals: AttributeMatchGroup) -> Self {
+    /// A stack of attributes will be applied on top of `globals` later, while `info` takes precedence over all of them.
+    pub fn new(globals: AttributeMatchGroup, info: AttributeMatchGroup) -> Self {
         Attributes {
             globals,
+            info,
             stack: Default::default(),
         }
     }
+
+    /// The match groups from highest priority to lowest.
+    pub(crate) fn match_groups(&self) -> [&AttributeMatchGroup; 3] {
+        [&self.info, &self.stack, &self.globals]
+    }
+
+    pub(crate) fn pop_directory(&mut self) {
+        self.stack.patterns.pop().expect("something to pop");
+    }
+
+    pub(crate) fn push_directory<Find, E>(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        buf: &mut Vec<u8>,
+        attribute_files_in_index: &[PathOidMapping<'_>],
+        mut find: Find,
+    ) -> std::io::Result<()>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let rela_dir = dir.strip_prefix(root).expect("dir in root");
+        let attr_path_relative =
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(rela_dir.join(".gitattributes")));
+        let attr_file_in_index = attribute_files_in_index.binary_search_by(|t| t.0.cmp(attr_path_relative.as_ref()));
+        let follow_symlinks = attr_file_in_index.is_err();
+        if !self
+            .stack
+            .add_patterns_file(dir.join(".gitattributes"), follow_symlinks, Some(root), buf)?
+        {
+            match attr_file_in_index {
+                Ok(idx) => {
+                    let blob = find(&attribute_files_in_index[idx].1, buf)
+                        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                    let attr_path = git_path::from_bstring(attr_path_relative.into_owned());
+                    self.stack.add_patterns_buffer(blob.data, attr_path, Some(root));
+                }
+                Err(_) => {
+                    // Need one stack level per component so push and pop matches.
+                    self.stack.patterns.push(Default::default())
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl From<AttributeMatchGroup> for Attributes {
     fn from(group: AttributeMatchGroup) -> Self {
-        Attributes::new(group)
+        Attributes::new(group, Default::default())
     }
 }
 
@@ -252,7 +301,8 @@ impl State {
                 a2_backing.as_ref()
             }
             State::CreateDirectoryAndAttributesStack { .. } => {
-                a1_backing = [(".gitattributes".into(), true)];
+                // Attributes for checkout are read from the index as the worktree doesn't have them yet.
+                a1_backing = [(".gitattributes".into(), false)];
                 a1_backing.as_ref()
             }
         };
@@ -289,6 +339,16 @@ impl State {
             .collect()
     }
 
+    pub(crate) fn attributes_or_panic(&self) -> &Attributes {
+        match self {
+            State::AttributesAndIgnoreStack { attributes, .. }
+            | State::CreateDirectoryAndAttributesStack { attributes, .. } => attributes,
+            State::IgnoreStack(_) => {
+                unreachable!("BUG: must not try to check attributes without it being setup")
+            }
+        }
+    }
+
     pub(crate) fn ignore_or_panic(&self) -> &Ignore {
         match self {
             State::IgnoreStack(v) => v,
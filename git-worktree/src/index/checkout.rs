@@ -61,6 +61,15 @@ pub struct Options {
     pub check_stat: bool,
     /// A group of attribute patterns that are applied globally, i.e. aren't rooted within the repository itself.
     pub attribute_globals: git_attributes::MatchGroup<Attributes>,
+    /// The filters to apply to the content of files before writing them into the worktree, as driven by their attributes.
+    ///
+    /// By default, no conversion is performed unless attributes ask for it.
+    pub filters: git_filter::Pipeline,
+    /// If set, the patterns of a sparse checkout to [apply][crate::sparse::Patterns::apply()] to the index before checking it out,
+    /// so that only the entries included by them are written to the worktree.
+    ///
+    /// If unset, entries which are already marked to skip the worktree are not checked out either.
+    pub sparse_patterns: Option<crate::sparse::Patterns>,
 }
 
 impl Default for Options {
@@ -74,6 +83,8 @@ impl Default for Options {
             check_stat: true,
             overwrite_existing: false,
             attribute_globals: Default::default(),
+            filters: Default::default(),
+            sparse_patterns: None,
         }
     }
 }
@@ -92,4 +103,16 @@ pub enum Error<E: std::error::Error + Send + Sync + 'static> {
         oid: git_hash::ObjectId,
         path: std::path::PathBuf,
     },
+    #[error(transparent)]
+    Sparse(#[from] crate::sparse::apply::Error),
+    #[error(transparent)]
+    ExpandSparseDirectories(#[from] git_index::sparse::expand::Error),
+    #[error("Sparse directory entry at {} must be expanded or skip the worktree to be checked out", .path)]
+    UnexpandedDirectory { path: BString },
+    #[error("Could not convert the content of {} for checkout", .path)]
+    Filter {
+        #[source]
+        err: git_filter::pipeline::convert::Error,
+        path: BString,
+    },
 }
@@ -1,4 +1,4 @@
-use std::{convert::TryInto, fs::OpenOptions, io::Write, path::Path, time::Duration};
+use std::{fs::OpenOptions, io::Write, path::Path};
 
 use bstr::BStr;
 use git_hash::oid;
@@ -11,13 +11,23 @@ pub struct Context<'a, 'paths, Find> {
     pub find: &'a mut Find,
     pub path_cache: &'a mut fs::Cache<'paths>,
     pub buf: &'a mut Vec<u8>,
+    pub filters: &'a mut git_filter::Pipeline,
+    pub attributes: &'a mut git_attributes::Outcome,
+    pub filter_buf: &'a mut Vec<u8>,
 }
 
 #[cfg_attr(not(unix), allow(unused_variables))]
 pub fn checkout<Find, E>(
     entry: &mut Entry,
     entry_path: &BStr,
-    Context { find, path_cache, buf }: Context<'_, '_, Find>,
+    Context {
+        find,
+        path_cache,
+        buf,
+        filters,
+        attributes,
+        filter_buf,
+    }: Context<'_, '_, Find>,
     index::checkout::Options {
         fs: fs::Capabilities {
             symlink,
@@ -30,14 +40,23 @@ pub fn checkout<Find, E>(
     }: index::checkout::Options,
 ) -> Result<usize, index::checkout::Error<E>>
 where
-    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E>,
     E: std::error::Error + Send + Sync + 'static,
 {
     let dest_relative = git_path::try_from_bstr(entry_path).map_err(|_| index::checkout::Error::IllformedUtf8 {
         path: entry_path.to_owned(),
     })?;
-    let is_dir = Some(entry.mode == git_index::entry::Mode::COMMIT || entry.mode == git_index::entry::Mode::DIR);
-    let dest = path_cache.at_path(dest_relative, is_dir, &mut *find)?.path();
+    if entry.mode.is_sparse() {
+        // Sparse directory entries have no content of their own, they must skip the worktree like `sparse::Patterns::apply()` does.
+        return Err(index::checkout::Error::UnexpandedDirectory {
+            path: entry_path.to_owned(),
+        });
+    }
+    let is_dir = Some(entry.mode == git_index::entry::Mode::COMMIT);
+    let platform = path_cache.at_path(dest_relative, is_dir, |id, buf| {
+        find(id, buf).map(|obj| git_object::BlobRef { data: obj.data })
+    })?;
+    let dest = platform.path();
 
     let object_size = match entry.mode {
         git_index::entry::Mode::FILE | git_index::entry::Mode::FILE_EXECUTABLE => {
@@ -46,6 +65,17 @@ where
                 oid: entry.id,
                 path: dest.to_path_buf(),
             })?;
+            platform.matching_attributes(attributes);
+            let data = if filters
+                .convert_to_worktree(obj.data, entry_path, &entry.id, attributes, filter_buf)
+                .map_err(|err| index::checkout::Error::Filter {
+                    err,
+                    path: entry_path.to_owned(),
+                })? {
+                filter_buf.as_slice()
+            } else {
+                obj.data
+            };
 
             #[cfg_attr(not(unix), allow(unused_mut))]
             let mut options = open_options(dest, destination_is_initially_empty, overwrite_existing);
@@ -59,7 +89,7 @@ where
             }
 
             let mut file = try_write_or_unlink(dest, overwrite_existing, |p| options.open(p))?;
-            file.write_all(obj.data)?;
+            file.write_all(data)?;
 
             // For possibly existing, overwritten files, we must change the file mode explicitly.
             #[cfg(unix)]
@@ -73,7 +103,7 @@ where
             //       revisit this once there is a bug to fix.
             update_fstat(entry, file.metadata()?)?;
             file.close()?;
-            obj.data.len()
+            data.len()
         }
         git_index::entry::Mode::SYMLINK => {
             let obj = find(&entry.id, buf).map_err(|err| index::checkout::Error::Find {
@@ -97,8 +127,16 @@ where
             update_fstat(entry, std::fs::symlink_metadata(dest)?)?;
             obj.data.len()
         }
-        git_index::entry::Mode::DIR => todo!(),
-        git_index::entry::Mode::COMMIT => todo!(),
+        git_index::entry::Mode::COMMIT => {
+            // Submodules are checked out separately, all we do is to create their empty directory just like `git` does.
+            match std::fs::create_dir(dest) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists && dest.is_dir() => {}
+                Err(err) => return Err(err.into()),
+            }
+            update_fstat(entry, std::fs::symlink_metadata(dest)?)?;
+            0
+        }
         _ => unreachable!(),
     };
     Ok(object_size)
@@ -167,23 +205,6 @@ fn update_fstat<E>(entry: &mut Entry, meta: std::fs::Metadata) -> Result<(), ind
 where
     E: std::error::Error + Send + Sync + 'static,
 {
-    let ctime = meta
-        .created()
-        .map_or(Ok(Duration::default()), |x| x.duration_since(std::time::UNIX_EPOCH))?;
-    let mtime = meta
-        .modified()
-        .map_or(Ok(Duration::default()), |x| x.duration_since(std::time::UNIX_EPOCH))?;
-
-    let stat = &mut entry.stat;
-    stat.mtime.secs = mtime
-        .as_secs()
-        .try_into()
-        .expect("by 2038 we found a solution for this");
-    stat.mtime.nsecs = mtime.subsec_nanos();
-    stat.ctime.secs = ctime
-        .as_secs()
-        .try_into()
-        .expect("by 2038 we found a solution for this");
-    stat.ctime.nsecs = ctime.subsec_nanos();
+    entry.stat = git_index::entry::Stat::from_fs(&meta)?;
     Ok(())
 }
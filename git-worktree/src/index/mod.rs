@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
 
 use git_features::{interrupt, parallel::in_parallel, progress, progress::Progress};
 use git_hash::oid;
@@ -11,20 +14,35 @@ pub(crate) mod entry;
 /// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
 /// `dir` is the directory into which to checkout the `index`.
 /// `git_dir` is the `.git` directory for reading additional per-repository configuration files.
+///
+/// `find` is used to obtain blobs, and trees if sparse directories have to be expanded.
+///
+/// If [sparse patterns][checkout::Options::sparse_patterns] are set, they are applied to `index` first after
+/// [expanding][crate::sparse::Patterns::expand_as_needed()] all sparse directories they include, and entries
+/// that are marked to skip the worktree are never checked out.
 #[allow(clippy::too_many_arguments)]
 pub fn checkout<Find, E>(
     index: &mut git_index::State,
     dir: impl Into<std::path::PathBuf>,
-    find: Find,
+    mut find: Find,
     files: &mut impl Progress,
     bytes: &mut impl Progress,
     should_interrupt: &AtomicBool,
-    options: checkout::Options,
+    mut options: checkout::Options,
 ) -> Result<checkout::Outcome, checkout::Error<E>>
 where
-    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E> + Send + Clone,
     E: std::error::Error + Send + Sync + 'static,
 {
+    if let Some(patterns) = options.sparse_patterns.take() {
+        let case = if options.fs.ignore_case {
+            git_glob::pattern::Case::Fold
+        } else {
+            git_glob::pattern::Case::Sensitive
+        };
+        patterns.expand_as_needed(index, |oid, buf| find(oid, buf).ok()?.try_into_tree_iter())?;
+        patterns.apply(index, case)?;
+    }
     let paths = index.take_path_backing();
     let res = checkout_inner(index, &paths, dir, find, files, bytes, should_interrupt, options);
     index.return_path_backing(paths);
@@ -39,10 +57,10 @@ fn checkout_inner<Find, E>(
     files: &mut impl Progress,
     bytes: &mut impl Progress,
     should_interrupt: &AtomicBool,
-    options: checkout::Options,
+    mut options: checkout::Options,
 ) -> Result<checkout::Outcome, checkout::Error<E>>
 where
-    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E> + Send + Clone,
     E: std::error::Error + Send + Sync + 'static,
 {
     let num_files = AtomicUsize::default();
@@ -61,9 +79,14 @@ where
 
     let state = fs::cache::State::for_checkout(options.overwrite_existing, options.attribute_globals.clone().into());
     let attribute_files = state.build_attribute_list(index, paths, case);
+    // Each thread gets its own copy of the filters, which starts its own filter processes as needed.
+    let filters = std::mem::take(&mut options.filters);
     let mut ctx = chunk::Context {
         buf: Vec::new(),
         path_cache: fs::Cache::new(dir, state, case, Vec::with_capacity(512), attribute_files),
+        filters,
+        attributes: Default::default(),
+        filter_buf: Vec::new(),
         find,
         options,
         num_files: &num_files,
@@ -78,8 +101,29 @@ where
         let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
         chunk::process(entries_with_paths, files, bytes, &mut ctx)?
     } else {
-        let entries_with_paths = interrupt::Iter::new(index.entries_mut_with_paths_in(paths), should_interrupt);
-        in_parallel(
+        // Entries that may collide on a case-insensitive file system are checked out by this thread once all others are done,
+        // in index order, so that the outcome is the same as if only one thread was used.
+        let may_collide = if ctx.options.fs.ignore_case {
+            entries_colliding_when_folding_case(index, paths)
+        } else {
+            Vec::new()
+        };
+        let mut colliding = Vec::new();
+        let entries_with_paths = interrupt::Iter::new(
+            index
+                .entries_mut_with_paths_in(paths)
+                .enumerate()
+                .filter_map(|(idx, entry_and_path)| {
+                    if may_collide.get(idx).copied().unwrap_or(false) {
+                        colliding.push(entry_and_path);
+                        None
+                    } else {
+                        Some(entry_and_path)
+                    }
+                }),
+            should_interrupt,
+        );
+        let mut outcome = in_parallel(
             git_features::iter::Chunks {
                 inner: entries_with_paths,
                 size: chunk_size,
@@ -97,7 +141,29 @@ where
                 aggregate: Default::default(),
                 marker: Default::default(),
             },
-        )?
+        )?;
+
+        let chunk::Outcome {
+            collisions,
+            errors,
+            delayed,
+            bytes_written,
+        } = chunk::process(
+            interrupt::Iter::new(colliding.into_iter(), should_interrupt),
+            files,
+            bytes,
+            &mut ctx,
+        )?;
+        outcome.collisions.extend(collisions);
+        outcome.errors.extend(errors);
+        outcome.delayed.extend(delayed);
+        outcome.bytes_written += bytes_written;
+
+        // Chunks are reduced in the order in which threads finish them, so restore the index order of all results.
+        outcome.collisions.sort_by(|a, b| a.path.cmp(&b.path));
+        outcome.errors.sort_by(|a, b| a.path.cmp(&b.path));
+        outcome.delayed.sort_by(|a, b| a.1.cmp(b.1));
+        outcome
     };
 
     for (entry, entry_path) in delayed {
@@ -120,6 +186,41 @@ where
     })
 }
 
+/// Return a flag for each entry in `index` which is `true` if its path may collide with the one of another entry
+/// on a case-insensitive file system.
+///
+/// This is the case if both paths are equal when ignoring the case of ascii characters, or if one of them would be
+/// a leading directory of the other.
+fn entries_colliding_when_folding_case(index: &git_index::State, paths: &git_index::PathStorage) -> Vec<bool> {
+    let folded: Vec<Vec<u8>> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path_in(paths).to_ascii_lowercase())
+        .collect();
+    let mut may_collide = vec![false; folded.len()];
+    let mut first_by_path = HashMap::<&[u8], usize>::with_capacity(folded.len());
+    for (idx, path) in folded.iter().enumerate() {
+        match first_by_path.entry(path.as_slice()) {
+            Entry::Occupied(first) => {
+                may_collide[*first.get()] = true;
+                may_collide[idx] = true;
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(idx);
+            }
+        }
+    }
+    for (idx, path) in folded.iter().enumerate() {
+        for slash_pos in path.iter().enumerate().filter(|(_, b)| **b == b'/').map(|(pos, _)| pos) {
+            if let Some(&other) = first_by_path.get(&path[..slash_pos]) {
+                may_collide[other] = true;
+                may_collide[idx] = true;
+            }
+        }
+    }
+    may_collide
+}
+
 mod chunk {
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -201,6 +302,9 @@ mod chunk {
         pub find: Find,
         pub path_cache: fs::Cache<'paths>,
         pub buf: Vec<u8>,
+        pub filters: git_filter::Pipeline,
+        pub attributes: git_attributes::Outcome,
+        pub filter_buf: Vec<u8>,
         pub options: checkout::Options,
         /// We keep these shared so that there is the chance for printing numbers that aren't looking like
         /// multiple of chunk sizes. Purely cosmetic. Otherwise it's the same as `files`.
@@ -214,7 +318,7 @@ mod chunk {
         ctx: &mut Context<'_, '_, Find>,
     ) -> Result<Outcome<'entry>, checkout::Error<E>>
     where
-        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E> + Clone,
         E: std::error::Error + Send + Sync + 'static,
     {
         let mut delayed = Vec::new();
@@ -223,7 +327,6 @@ mod chunk {
         let mut bytes_written = 0;
 
         for (entry, entry_path) in entries_with_paths {
-            // TODO: write test for that
             if entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE) {
                 files.inc();
                 continue;
@@ -264,18 +367,28 @@ mod chunk {
             find,
             path_cache,
             buf,
+            filters,
+            attributes,
+            filter_buf,
             options,
             num_files,
         }: &mut Context<'_, '_, Find>,
     ) -> Result<usize, checkout::Error<E>>
     where
-        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E> + Clone,
         E: std::error::Error + Send + Sync + 'static,
     {
         let res = entry::checkout(
             entry,
             entry_path,
-            entry::Context { find, path_cache, buf },
+            entry::Context {
+                find,
+                path_cache,
+                buf,
+                filters,
+                attributes,
+                filter_buf,
+            },
             options.clone(),
         );
         files.inc();
@@ -12,4 +12,10 @@ pub mod fs;
 ///
 pub mod index;
 
+///
+pub mod status;
+
+///
+pub mod sparse;
+
 pub(crate) mod os;
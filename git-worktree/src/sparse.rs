@@ -0,0 +1,207 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use bstr::{BStr, BString, ByteSlice};
+use git_hash::ObjectId;
+use git_index::entry::Flags;
+
+/// The patterns of a sparse checkout, typically read from `.git/info/sparse-checkout`, to decide which entries
+/// of the index are checked out.
+#[derive(Debug, Clone)]
+pub enum Patterns {
+    /// _Cone mode_ includes entire directories along with the files in all of their parent directories.
+    ///
+    /// It's used if `core.sparseCheckoutCone` is true and all patterns can be used in cone mode.
+    Cone {
+        /// Directories whose content is included recursively, without trailing slash.
+        recursive: BTreeSet<BString>,
+        /// Directories whose files are included, but not their sub-directories unless these are included themselves,
+        /// without trailing slash. The root directory is always included as empty path.
+        parents: BTreeSet<BString>,
+    },
+    /// Patterns like the ones in `.gitignore` files, which include all matching paths unless they are negated.
+    NonCone(git_attributes::MatchGroup<git_attributes::Ignore>),
+}
+
+///
+pub mod apply {
+    use bstr::BString;
+
+    /// The error returned by [Patterns::apply()][super::Patterns::apply()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(
+            "The sparse directory '{path}' is included by the sparse checkout patterns and must be expanded first"
+        )]
+        UnexpandedDirectory { path: BString },
+    }
+}
+
+/// Initialization
+impl Patterns {
+    /// Parse the sparse checkout patterns in `bytes`, which were read from `source`, in _cone mode_ if `cone` is true.
+    ///
+    /// Just like `git`, we fall back to non-cone mode if not all patterns can be used in cone mode.
+    pub fn from_bytes(bytes: &[u8], source: impl Into<PathBuf>, cone: bool) -> Self {
+        cone.then(|| parse_cone(bytes)).flatten().unwrap_or_else(|| {
+            let mut group = git_attributes::MatchGroup::default();
+            group.add_patterns_buffer(bytes, source, None);
+            Patterns::NonCone(group)
+        })
+    }
+
+    /// Read the sparse checkout patterns from the file at `path`, typically `.git/info/sparse-checkout`, in _cone mode_
+    /// if `cone` is true, or return `None` if it doesn't exist.
+    pub fn from_file(path: impl Into<PathBuf>, cone: bool) -> std::io::Result<Option<Self>> {
+        let path = path.into();
+        match std::fs::read(&path) {
+            Ok(bytes) => Ok(Some(Self::from_bytes(&bytes, path, cone))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Matching
+impl Patterns {
+    /// Return true if the file at the repository-relative `path` is included in the checkout.
+    ///
+    /// `case` is only used by non-cone patterns.
+    pub fn is_included(&self, path: &BStr, case: git_glob::pattern::Case) -> bool {
+        match self {
+            Patterns::Cone { recursive, parents } => {
+                let dir = path.rfind_byte(b'/').map_or(BStr::new(""), |pos| path[..pos].as_bstr());
+                parents.contains(dir) || is_in_recursive_dir(recursive, dir)
+            }
+            Patterns::NonCone(group) => {
+                let mut path = path;
+                let mut is_dir = false;
+                loop {
+                    if let Some(m) = group.pattern_matching_relative_path(path, Some(is_dir), case) {
+                        return !m.pattern.is_negative();
+                    }
+                    match path.rfind_byte(b'/') {
+                        Some(pos) => {
+                            path = path[..pos].as_bstr();
+                            is_dir = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return true if the directory at `dir`, with a trailing slash, is excluded entirely and can thus be represented
+    /// by a [sparse directory entry][git_index::entry::Mode::DIR].
+    ///
+    /// This is only possible in cone mode.
+    pub fn is_excluded_dir(&self, dir: &BStr) -> bool {
+        match self {
+            Patterns::Cone { recursive, parents } => {
+                let dir = dir.strip_suffix(b"/").unwrap_or(dir).as_bstr();
+                !parents.contains(dir) && !is_in_recursive_dir(recursive, dir)
+            }
+            Patterns::NonCone(_) => false,
+        }
+    }
+}
+
+/// Index Modification
+impl Patterns {
+    /// Mark all merged entries of `index` which are excluded by these patterns to [skip the worktree][Flags::SKIP_WORKTREE],
+    /// and clear this flag on all included entries, and return the amount of entries that skip the worktree.
+    /// `case` is used to match non-cone patterns.
+    ///
+    /// Note that [sparse directory entries][git_index::entry::Mode::DIR] which aren't excluded must be
+    /// [expanded][Patterns::expand_as_needed()] beforehand.
+    pub fn apply(&self, index: &mut git_index::State, case: git_glob::pattern::Case) -> Result<usize, apply::Error> {
+        let mut num_skipped = 0;
+        for (entry, path) in index.entries_mut_with_paths() {
+            if entry.mode.is_sparse() {
+                if !self.is_excluded_dir(path) {
+                    return Err(apply::Error::UnexpandedDirectory { path: path.to_owned() });
+                }
+                entry.flags.insert(Flags::SKIP_WORKTREE);
+            } else if entry.stage() != 0 || self.is_included(path, case) {
+                entry.flags.remove(Flags::SKIP_WORKTREE);
+            } else {
+                entry.flags.insert(Flags::SKIP_WORKTREE);
+            }
+            if entry.flags.contains(Flags::SKIP_WORKTREE) {
+                num_skipped += 1;
+            }
+        }
+        Ok(num_skipped)
+    }
+
+    /// Expand all [sparse directory entries][git_index::entry::Mode::DIR] of `index` which aren't excluded by these patterns,
+    /// using `find` to obtain their trees, and return the amount of expanded directories.
+    pub fn expand_as_needed<Find>(
+        &self,
+        index: &mut git_index::State,
+        find: Find,
+    ) -> Result<usize, git_index::sparse::expand::Error>
+    where
+        Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<git_object::TreeRefIter<'a>>,
+    {
+        index.expand_sparse_directories(|dir| !self.is_excluded_dir(dir), find)
+    }
+
+    /// Collapse all directories of `index` which are excluded by these patterns into
+    /// [sparse directory entries][git_index::entry::Mode::DIR], using `write` to store trees as needed,
+    /// and return the amount of created directory entries.
+    ///
+    /// This is what `git` does if `index.sparse` is true, which only works in cone mode.
+    pub fn collapse<E>(
+        &self,
+        index: &mut git_index::State,
+        write: impl FnMut(&git_object::Tree) -> Result<ObjectId, E>,
+    ) -> Result<usize, git_index::extension::tree::update::Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        index.collapse_sparse_directories(|dir| self.is_excluded_dir(dir), write)
+    }
+}
+
+fn is_in_recursive_dir(recursive: &BTreeSet<BString>, dir: &BStr) -> bool {
+    !dir.is_empty()
+        && (recursive.contains(dir) || dir.rfind_iter("/").any(|pos| recursive.contains(dir[..pos].as_bstr())))
+}
+
+/// Parse `bytes` as cone patterns the way `git` does it, or return `None` if a pattern can't be used in cone mode.
+fn parse_cone(bytes: &[u8]) -> Option<Patterns> {
+    let mut recursive = BTreeSet::<BString>::new();
+    let mut parents = BTreeSet::<BString>::new();
+    parents.insert(BString::default());
+    for line in bytes.lines().map(|line| line.trim_end()) {
+        if line.is_empty() || line.starts_with(b"#") || line == b"/*" || line == b"!/*/" {
+            continue;
+        }
+        let (is_negative, pattern) = match line.strip_prefix(b"!") {
+            Some(pattern) => (true, pattern),
+            None => (false, line),
+        };
+        let dir = pattern.strip_prefix(b"/")?;
+        let dir = if is_negative {
+            dir.strip_suffix(b"/*/")?
+        } else {
+            dir.strip_suffix(b"/")?
+        };
+        if dir.is_empty() || dir.iter().any(|b| matches!(b, b'*' | b'?' | b'[' | b'\\')) {
+            return None;
+        }
+        let dir = dir.as_bstr();
+        if is_negative {
+            if !recursive.remove(dir) {
+                return None;
+            }
+            parents.insert(dir.to_owned());
+        } else {
+            recursive.insert(dir.to_owned());
+            parents.extend(dir.rfind_iter("/").map(|pos| dir[..pos].to_owned()));
+        }
+    }
+    Some(Patterns::Cone { recursive, parents })
+}
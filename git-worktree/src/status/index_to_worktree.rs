@@ -0,0 +1,291 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BStr, ByteSlice};
+use git_features::parallel;
+use git_hash::oid;
+use git_index::{entry, Entry};
+
+use crate::fs;
+
+/// The way an index entry differs from the item on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The item was removed from the worktree.
+    Removed,
+    /// The item was replaced by one of a different kind, like a symbolic link replacing a file or a directory replacing a file.
+    Type,
+    /// The file changed its content or its executable bit, or both.
+    Modification {
+        /// If true, the executable bit of the file was added or removed.
+        executable_bit_changed: bool,
+        /// If true, the content of the file differs from the one in the index.
+        content_changed: bool,
+    },
+}
+
+/// Options for use in [`index_to_worktree()`][super::index_to_worktree()].
+#[derive(Default, Clone)]
+pub struct Options {
+    /// Capabilities of the file system, which determine how symbolic links and executable bits are compared.
+    pub fs: fs::Capabilities,
+    /// Configure how stat information is compared to avoid reading the content of files that didn't change.
+    pub stat: entry::stat::Options,
+    /// If set, don't use more than this amount of threads. Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    /// The filters to apply to the content of files before hashing them, as driven by their attributes, so that it can be
+    /// compared to the content stored in the index.
+    ///
+    /// By default, no conversion is performed unless attributes ask for it.
+    pub filters: git_filter::Pipeline,
+}
+
+/// The error returned by [`index_to_worktree()`][super::index_to_worktree()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read '{}' to compare it with the index", .path.display())]
+    Io { source: io::Error, path: PathBuf },
+    #[error("The modification time of a file on disk could not be obtained")]
+    Time(#[from] std::time::SystemTimeError),
+    #[error("Could not convert path to UTF8: {}", .path)]
+    IllformedUtf8 { path: bstr::BString },
+    #[error("Could not convert the content of {} for comparison with the index", .path)]
+    Filter {
+        #[source]
+        err: git_filter::pipeline::convert::Error,
+        path: bstr::BString,
+    },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Compare each entry in `index` with the item at its location in the worktree at [`cache.base()`][fs::Cache::base()], and return
+/// the changes along with the index of the entry they belong to, ordered by index.
+pub(crate) fn changes<Find, E>(
+    index: &git_index::State,
+    cache: &fs::Cache<'_>,
+    find: Find,
+    options: Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<(usize, Change)>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let (chunk_size, thread_limit, _) =
+        parallel::optimize_chunk_size_and_thread_limit(500, index.entries().len().into(), options.thread_limit, None);
+    let mut changes = parallel::in_parallel_if(
+        || index.entries().len() > 1_000,
+        index.entries().chunks(chunk_size).enumerate(),
+        thread_limit,
+        {
+            let options = &options;
+            // Each thread gets its own copy of the filters, which starts its own filter processes as needed.
+            move |_n| Context {
+                cache: cache.clone(),
+                find: find.clone(),
+                filters: options.filters.clone(),
+                attributes: Default::default(),
+                buf: Vec::new(),
+                filter_buf: Vec::new(),
+            }
+        },
+        |(chunk_index, entries): (usize, &[Entry]), ctx| {
+            let mut out = Vec::new();
+            for (offset, entry) in entries.iter().enumerate() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                if let Some(change) = compare(index, entry, &options, ctx)? {
+                    out.push((chunk_index * chunk_size + offset, change));
+                }
+            }
+            Ok(out)
+        },
+        parallel::reduce::Flatten::default(),
+    )?;
+    changes.sort_by_key(|(idx, _)| *idx);
+    Ok(changes)
+}
+
+/// The state each thread uses to compare entries.
+struct Context<'paths, Find> {
+    cache: fs::Cache<'paths>,
+    find: Find,
+    filters: git_filter::Pipeline,
+    attributes: git_attributes::Outcome,
+    buf: Vec<u8>,
+    filter_buf: Vec<u8>,
+}
+
+fn compare<Find, E>(
+    index: &git_index::State,
+    entry: &Entry,
+    Options {
+        fs: capabilities,
+        stat: stat_options,
+        ..
+    }: &Options,
+    Context {
+        cache,
+        find,
+        filters,
+        attributes,
+        buf,
+        filter_buf,
+    }: &mut Context<'_, Find>,
+) -> Result<Option<Change>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if entry.stage() != 0
+        || entry.mode.is_sparse()
+        || entry
+            .flags
+            .intersects(entry::Flags::SKIP_WORKTREE | entry::Flags::INTENT_TO_ADD)
+    {
+        return Ok(None);
+    }
+    let relative_path = entry.path(index);
+    let path = cache.base().join(
+        git_path::try_from_bstr(relative_path).map_err(|_| Error::IllformedUtf8 {
+            path: relative_path.to_owned(),
+        })?,
+    );
+    let meta = match std::fs::symlink_metadata(&path) {
+        Ok(meta) => meta,
+        Err(err) if is_missing(&err) => return Ok(Some(Change::Removed)),
+        Err(source) => return Err(Error::Io { source, path }),
+    };
+
+    let file_type = meta.file_type();
+    let is_symlink_in_index = entry.mode == entry::Mode::SYMLINK;
+    match entry.mode {
+        entry::Mode::COMMIT => {
+            // Submodules are compared by looking at their own repository, which is beyond what we do here.
+            return Ok((!file_type.is_dir()).then(|| Change::Type));
+        }
+        entry::Mode::SYMLINK if capabilities.symlink => {
+            if !file_type.is_symlink() {
+                return Ok(Some(Change::Type));
+            }
+        }
+        _ => {
+            if !file_type.is_file() {
+                return Ok(Some(Change::Type));
+            }
+        }
+    }
+
+    let executable_bit_changed = capabilities.executable_bit
+        && !is_symlink_in_index
+        && is_executable(&meta) != (entry.mode == entry::Mode::FILE_EXECUTABLE);
+    let stat = entry::Stat::from_fs(&meta)?;
+    let content_changed =
+        if entry.stat.matches(&stat, *stat_options) && !entry.stat.is_racy(index.timestamp(), *stat_options) {
+            false
+        } else if entry.stat.size != 0 && entry.stat.size != stat.size {
+            true
+        } else {
+            let data: &[u8] = if file_type.is_symlink() {
+                let target = std::fs::read_link(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                buf.clear();
+                buf.extend_from_slice(git_path::to_unix_separators_on_windows(git_path::into_bstr(target)).as_bytes());
+                buf
+            } else {
+                buf.clear();
+                let mut file = std::fs::File::open(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                io::Read::read_to_end(&mut file, buf).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                if convert_to_git(buf, relative_path, entry, cache, find, filters, attributes, filter_buf)
+                    .map_err(|source| Error::Io {
+                        source,
+                        path: path.clone(),
+                    })?
+                    .map_err(|err| Error::Filter {
+                        err,
+                        path: relative_path.to_owned(),
+                    })?
+                {
+                    filter_buf
+                } else {
+                    buf
+                }
+            };
+            git_object::compute_hash(index.object_hash(), git_object::Kind::Blob, data) != entry.id
+        };
+
+    Ok(
+        (executable_bit_changed || content_changed).then(|| Change::Modification {
+            executable_bit_changed,
+            content_changed,
+        }),
+    )
+}
+
+/// Convert `src`, the content of the file of `entry` at `rela_path`, with all `filters` that apply to it according to its
+/// attributes, and write the result into `out`. Return `true` if `out` was written, or `false` if `src` is unchanged.
+#[allow(clippy::too_many_arguments)]
+fn convert_to_git<Find, E>(
+    src: &[u8],
+    rela_path: &BStr,
+    entry: &Entry,
+    cache: &mut fs::Cache<'_>,
+    find: &mut Find,
+    filters: &mut git_filter::Pipeline,
+    attributes: &mut git_attributes::Outcome,
+    out: &mut Vec<u8>,
+) -> io::Result<Result<bool, git_filter::pipeline::convert::Error>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    cache
+        .at_entry(rela_path, Some(false), &mut *find)?
+        .matching_attributes(attributes);
+    Ok(filters.convert_to_git(
+        src,
+        rela_path,
+        attributes,
+        |buf| {
+            find(&entry.id, buf)?;
+            Ok(Some(()))
+        },
+        out,
+    ))
+}
+
+/// Return true if `err` indicates that there is no item at a path, either because it doesn't exist or
+/// because one of its leading components isn't a directory.
+pub(crate) fn is_missing(err: &io::Error) -> bool {
+    #[cfg(unix)]
+    if err.raw_os_error() == Some(20 /* ENOTDIR */) {
+        return true;
+    }
+    err.kind() == io::ErrorKind::NotFound
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o100 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
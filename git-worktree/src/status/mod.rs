@@ -0,0 +1,68 @@
+use std::sync::atomic::AtomicBool;
+
+use bstr::BStr;
+use git_hash::oid;
+
+use crate::fs;
+
+///
+pub mod index_to_worktree;
+
+///
+pub mod untracked;
+
+/// Compare all entries in `index` with the files in the worktree at [`cache.base()`][fs::Cache::base()] they are checked out to,
+/// and call `record` with each entry that differs along with its repository-relative path and the kind of
+/// [change][index_to_worktree::Change].
+///
+/// Entries are compared in parallel as configured by `options`, but `record` is called in the order of entries in the index.
+/// Stat information is used to avoid reading files whenever possible, and files whose stat information can't be trusted
+/// as they were modified in the same time period the index was written are hashed to learn if they changed.
+/// Before hashing, files are converted with the [filters][index_to_worktree::Options::filters] as driven by their attributes,
+/// which is why `cache` must be configured to provide attributes. `find` is used to obtain attribute files from the object
+/// database if they are not present in the worktree, and the objects of entries to learn how their line endings were converted.
+///
+/// Entries with conflicts, skipped worktree entries and those that were added with the intent to add them later are ignored.
+pub fn index_to_worktree<Find, E>(
+    index: &git_index::State,
+    cache: &fs::Cache<'_>,
+    find: Find,
+    options: index_to_worktree::Options,
+    should_interrupt: &AtomicBool,
+    mut record: impl FnMut(&BStr, &git_index::Entry, index_to_worktree::Change),
+) -> Result<(), index_to_worktree::Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    for (idx, change) in index_to_worktree::changes(index, cache, find, options, should_interrupt)? {
+        let entry = index.entry(idx);
+        record(entry.path(index), entry, change);
+    }
+    Ok(())
+}
+
+/// Find all files in the worktree at [`cache.base()`][fs::Cache::base()] that aren't tracked in `index`, and call `record` with their
+/// repository-relative path, the kind of [entry][untracked::Kind] they are and whether they are a directory, in the order of
+/// their paths.
+///
+/// `cache` must be configured to provide exclude information, and `find` is used to obtain `.gitignore` files from the object
+/// database if they are not present in the worktree. `options` configure how untracked directories are reported and
+/// if ignored files are reported as well.
+pub fn untracked<Find, E>(
+    index: &git_index::State,
+    cache: &mut fs::Cache<'_>,
+    find: Find,
+    options: untracked::Options,
+    should_interrupt: &AtomicBool,
+    mut record: impl FnMut(&BStr, untracked::Kind, bool),
+) -> Result<(), untracked::Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    for item in untracked::collect(index, cache, find, options, should_interrupt)? {
+        record(item.path.as_ref(), item.kind, item.is_dir);
+    }
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use std::{
+    io,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_hash::oid;
+
+use crate::{fs, status::index_to_worktree::is_missing};
+
+/// The kind of file or directory that isn't tracked in the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The item is untracked and not ignored, so it could be added.
+    Untracked,
+    /// The item is untracked and matches an exclude pattern, so it's expected to not be added.
+    Ignored,
+}
+
+/// Configure how untracked files are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Files {
+    /// Report a directory that isn't tracked at all as a whole if it contains at least one untracked file,
+    /// similar to `status.showUntrackedFiles=normal`.
+    Collapsed,
+    /// Report each untracked file individually, similar to `status.showUntrackedFiles=all`.
+    All,
+}
+
+impl Default for Files {
+    fn default() -> Self {
+        Files::Collapsed
+    }
+}
+
+/// Options for use in [`untracked()`][super::untracked()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Configure how untracked files in directories that aren't tracked are reported.
+    pub files: Files,
+    /// If true, ignored files are reported as well. Directories whose content is ignored entirely are reported as a whole.
+    pub ignored: bool,
+}
+
+/// The error returned by [`untracked()`][super::untracked()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read directory at '{}'", .path.display())]
+    ReadDir { source: io::Error, path: PathBuf },
+    #[error("Could not obtain exclude information")]
+    Excludes(#[source] io::Error),
+    #[error("Could not convert path to UTF8: {}", .path.display())]
+    IllformedUtf8 { path: PathBuf },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// An untracked item as found in the worktree.
+pub(crate) struct Item {
+    /// The repository-relative path to the item.
+    pub path: BString,
+    pub kind: Kind,
+    pub is_dir: bool,
+}
+
+impl Item {
+    fn dir(rela_dir: &BString, kind: Kind) -> Self {
+        Item {
+            path: rela_dir.clone(),
+            kind,
+            is_dir: true,
+        }
+    }
+}
+
+/// Walk the worktree at `cache.base()` and collect all items that aren't tracked in `index`, sorted by path.
+pub(crate) fn collect<Find, E>(
+    index: &git_index::State,
+    cache: &mut fs::Cache<'_>,
+    mut find: Find,
+    options: Options,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<Item>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut out = Vec::new();
+    let mut walk = Walk {
+        index,
+        cache,
+        find: &mut find,
+        options,
+        should_interrupt,
+    };
+    walk.dir(&mut BString::default(), &mut out)?;
+    Ok(out)
+}
+
+struct Walk<'a, 'paths, Find> {
+    index: &'a git_index::State,
+    cache: &'a mut fs::Cache<'paths>,
+    find: &'a mut Find,
+    options: Options,
+    should_interrupt: &'a AtomicBool,
+}
+
+impl<'a, 'paths, Find, E> Walk<'a, 'paths, Find>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Push all untracked items in the directory at `rela_dir` to `out`, or all of them if `rela_dir` is empty.
+    fn dir(&mut self, rela_dir: &mut BString, out: &mut Vec<Item>) -> Result<(), Error> {
+        if self.should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        let dir = self.cache.base().join(git_path::from_bstr(rela_dir.as_bstr()));
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&dir).map_err(|source| Error::ReadDir {
+            source,
+            path: dir.clone(),
+        })? {
+            let entry = entry.map_err(|source| Error::ReadDir {
+                source,
+                path: dir.clone(),
+            })?;
+            let name = entry.file_name();
+            if name == ".git" {
+                continue;
+            }
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(err) if is_missing(&err) => continue,
+                Err(source) => {
+                    return Err(Error::ReadDir {
+                        source,
+                        path: entry.path(),
+                    })
+                }
+            };
+            let name =
+                git_path::os_string_into_bstring(name).map_err(|_| Error::IllformedUtf8 { path: entry.path() })?;
+            entries.push((name, file_type.is_dir()));
+        }
+        // Sort like paths are sorted in the index, where directories are followed by a slash.
+        entries.sort_by(|(a, a_is_dir), (b, b_is_dir)| {
+            let a = a.iter().chain(a_is_dir.then(|| &b'/'));
+            let b = b.iter().chain(b_is_dir.then(|| &b'/'));
+            a.cmp(b)
+        });
+
+        let prev_len = rela_dir.len();
+        for (name, is_dir) in entries {
+            if prev_len != 0 {
+                rela_dir.push_byte(b'/');
+            }
+            rela_dir.push_str(&name);
+            let res = if is_dir {
+                self.untracked_dir(rela_dir, out)
+            } else {
+                self.untracked_file(rela_dir.as_bstr(), out)
+            };
+            rela_dir.truncate(prev_len);
+            res?;
+        }
+        Ok(())
+    }
+
+    fn untracked_file(&mut self, rela_path: &BStr, out: &mut Vec<Item>) -> Result<(), Error> {
+        if self.tracked_entry(rela_path).is_some() {
+            return Ok(());
+        }
+        let kind = self.kind(rela_path, false)?;
+        if kind == Kind::Untracked || self.options.ignored {
+            out.push(Item {
+                path: rela_path.to_owned(),
+                kind,
+                is_dir: false,
+            });
+        }
+        Ok(())
+    }
+
+    fn untracked_dir(&mut self, rela_dir: &mut BString, out: &mut Vec<Item>) -> Result<(), Error> {
+        if self
+            .tracked_entry(rela_dir.as_bstr())
+            .map_or(false, |e| e.mode == git_index::entry::Mode::COMMIT)
+        {
+            // A submodule, which may be modified but can't contain untracked files as seen by us.
+            return Ok(());
+        }
+        if self.contains_tracked_files(rela_dir.as_bstr()) {
+            return self.dir(rela_dir, out);
+        }
+        let kind = self.kind(rela_dir.as_bstr(), true)?;
+        if kind == Kind::Ignored {
+            if self.options.ignored {
+                out.push(Item::dir(rela_dir, kind));
+            }
+            return Ok(());
+        }
+        let is_nested_repository = self
+            .cache
+            .base()
+            .join(git_path::from_bstr(rela_dir.as_bstr()))
+            .join(".git")
+            .symlink_metadata()
+            .is_ok();
+        if is_nested_repository {
+            out.push(Item::dir(rela_dir, Kind::Untracked));
+            return Ok(());
+        }
+        match self.options.files {
+            Files::All => self.dir(rela_dir, out),
+            Files::Collapsed => {
+                let mut items = Vec::new();
+                self.dir(rela_dir, &mut items)?;
+                if items.iter().any(|item| item.kind == Kind::Untracked) {
+                    out.push(Item::dir(rela_dir, Kind::Untracked));
+                    out.extend(items.into_iter().filter(|item| item.kind == Kind::Ignored));
+                } else if !items.is_empty() {
+                    out.push(Item::dir(rela_dir, Kind::Ignored));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn kind(&mut self, rela_path: &BStr, is_dir: bool) -> Result<Kind, Error> {
+        let is_excluded = self
+            .cache
+            .at_entry(rela_path, Some(is_dir), &mut *self.find)
+            .map_err(Error::Excludes)?
+            .is_excluded();
+        Ok(if is_excluded { Kind::Ignored } else { Kind::Untracked })
+    }
+
+    /// Return the first entry at exactly `rela_path` in the index, at any stage.
+    fn tracked_entry(&self, rela_path: &BStr) -> Option<&'a git_index::Entry> {
+        let entries = self.index.entries();
+        let idx = entries.partition_point(|e| e.path(self.index) < rela_path);
+        entries.get(idx).filter(|e| e.path(self.index) == rela_path)
+    }
+
+    /// Return true if the index has entries in the directory at `rela_dir`, or a sparse directory entry for it.
+    fn contains_tracked_files(&self, rela_dir: &BStr) -> bool {
+        let mut prefix = rela_dir.to_owned();
+        prefix.push_byte(b'/');
+        let entries = self.index.entries();
+        let idx = entries.partition_point(|e| e.path(self.index) < prefix.as_bstr());
+        entries
+            .get(idx)
+            .map_or(false, |e| e.path(self.index).starts_with(prefix.as_slice()))
+    }
+}
@@ -0,0 +1,44 @@
+#!/bin/bash
+set -eu -o pipefail
+
+cat <<EOF >user.attributes
+* global
+*.txt text=auto global-txt
+EOF
+
+mkdir repo;
+(cd repo
+  git init -q
+  git config core.attributesFile ../user.attributes
+
+  cat <<EOF >.git/info/attributes
+*.md info
+*.txt info-wins -global
+EOF
+
+  cat <<EOF >.gitattributes
+[attr]my-macro macro-a -macro-b
+*.bin binary
+*.txt -text my-macro
+dir/** dir-attr=value
+EOF
+
+  mkdir -p dir/sub
+  cat <<EOF >dir/.gitattributes
+*.txt !my-macro sub-override=1
+sub/* -dir-attr
+EOF
+
+  git add .gitattributes dir
+  git commit -q --allow-empty -m "init"
+
+  # add this attributes file to the index only, so it has to be read from there.
+  mkdir other-dir
+  skip_worktree_attributes=other-dir/.gitattributes
+  cat <<EOF >"$skip_worktree_attributes"
+*.txt from-index
+EOF
+  git add $skip_worktree_attributes && git update-index --skip-worktree $skip_worktree_attributes && rm $skip_worktree_attributes
+
+  git check-attr -a -- a.txt b.bin c.md dir/d.txt dir/d.bin dir/sub/e.txt dir/sub/f.bin other-dir/g.txt other-dir/h.md unmatched > git-check-attr.baseline
+)
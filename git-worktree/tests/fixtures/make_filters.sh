@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF >.gitattributes
+*.crlf text eol=crlf
+ident.txt ident
+*.upper filter=upper
+binary.crlf -text
+EOF
+
+printf 'a\nb\n' > text.crlf
+printf 'a\nb\n' > binary.crlf
+printf '$Id$\n' > ident.txt
+printf 'lower\n' > file.upper
+printf 'a\n' > untouched
+
+git add -A
+git commit -q -m "Commit"
@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+for dir in a b c d e f g h; do
+  for sub in 1 2 3 4; do
+    mkdir -p $dir/$sub/deep
+    for file in 1 2 3 4 5 6 7 8; do
+      echo -n "$dir/$sub/$file" > $dir/$sub/$file
+      echo -n "$dir/$sub/deep/$file" > $dir/$sub/deep/$file
+    done
+  done
+done
+touch empty
+echo -n "content" > executable
+chmod +x executable
+
+git add -A
+git commit -q -m "Commit"
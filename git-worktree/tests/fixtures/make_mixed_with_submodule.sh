@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+touch empty
+mkdir dir
+echo -n "content" > dir/content
+
+git add -A
+git update-index --add --cacheinfo 160000,1111111111111111111111111111111111111111,dir/submodule
+git update-index --add --cacheinfo 160000,2222222222222222222222222222222222222222,submodule
+git commit -m "Commit"
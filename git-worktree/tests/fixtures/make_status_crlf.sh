@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+git config core.autocrlf true
+
+cat <<EOF2 >.gitattributes
+*.txt text eol=crlf
+*.bin -text
+EOF2
+printf 'a\r\nb\r\n' > eol.txt
+printf 'a\r\nb\r\n' > auto
+printf 'a\r\nb\r\n' > binary.bin
+printf 'a\r\nb\r\n' > changed.txt
+
+git add .
+git commit -q -m "init"
+
+# Change the modification time so that the files have to be hashed to learn if they changed.
+touch -m -d "2000-01-01 00:00:00" eol.txt auto binary.bin
+printf 'c\r\nd\r\n' > changed.txt
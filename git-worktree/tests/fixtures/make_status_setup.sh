@@ -0,0 +1,34 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config commit.gpgsign false
+
+mkdir dir
+echo a > a
+echo b > dir/b
+echo c > dir/c
+echo 1 > same-size
+echo file > type-change
+echo unchanged > unchanged
+cat <<EOF2 >.gitignore
+*.ignored
+ignored-dir/
+EOF2
+
+git add .
+git commit -q -m "init"
+
+echo "changed content" > a
+rm dir/c
+chmod +x dir/b
+echo 2 > same-size
+rm type-change && mkdir type-change && touch type-change/file
+
+echo new > untracked
+mkdir untracked-dir && echo x > untracked-dir/one && echo y > untracked-dir/two.ignored
+mkdir -p dir/new/deeper && echo z > dir/new/deeper/file
+echo i > top.ignored
+mkdir ignored-dir && echo z > ignored-dir/z
+mkdir only-ignored && echo z > only-ignored/z.ignored
+mkdir empty-dir
@@ -96,7 +96,7 @@ fn check_against_baseline() -> crate::Result {
     let odb = git_odb::at(git_dir.join("objects"))?;
     let case = git_glob::pattern::Case::Sensitive;
     let state = git_worktree::fs::cache::State::for_add(
-        Default::default(), // TODO: attribute tests
+        Default::default(),
         git_worktree::fs::cache::state::Ignore::new(
             git_attributes::MatchGroup::from_overrides(vec!["!force-include"]),
             git_attributes::MatchGroup::from_git_dir(&git_dir, Some(user_exclude_path), &mut buf)?,
@@ -160,3 +160,64 @@ fn check_against_baseline() -> crate::Result {
     assert_eq!(m.pattern.text, "user-file-anywhere");
     Ok(())
 }
+
+#[test]
+fn attributes_check_against_baseline() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_read_only("make_attributes_setup.sh")?;
+    let worktree_dir = dir.join("repo");
+    let git_dir = worktree_dir.join(".git");
+    let mut buf = Vec::new();
+
+    let mut index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?;
+    let case = git_glob::pattern::Case::Sensitive;
+    let mut globals = git_attributes::MatchGroup::default();
+    globals.add_patterns_file(dir.join("user.attributes"), true, None, &mut buf)?;
+    let mut info = git_attributes::MatchGroup::default();
+    info.add_patterns_file(git_dir.join("info").join("attributes"), true, None, &mut buf)?;
+    let state = git_worktree::fs::cache::State::for_add(
+        git_worktree::fs::cache::state::Attributes::new(globals, info),
+        Default::default(),
+    );
+    let paths_storage = index.take_path_backing();
+    let attribute_files_in_index = state.build_attribute_list(&index, &paths_storage, case);
+    assert_eq!(
+        attribute_files_in_index.len(),
+        3,
+        "all attribute files are listed, including the one that only exists in the index"
+    );
+    let mut cache = fs::Cache::new(&worktree_dir, state, case, buf, attribute_files_in_index);
+
+    let baseline = std::fs::read(worktree_dir.join("git-check-attr.baseline"))?;
+    let mut expected = std::collections::BTreeMap::<bstr::BString, Vec<(String, String)>>::new();
+    for line in baseline.lines() {
+        let mut tokens = line.split_str(": ");
+        let path = tokens.next().expect("path").as_bstr().to_owned();
+        let name = tokens.next().expect("name").to_str()?.to_owned();
+        let value = tokens.next().expect("value").to_str()?.to_owned();
+        expected.entry(path).or_default().push((name, value));
+    }
+    assert!(expected.contains_key(b"other-dir/g.txt".as_bstr()));
+
+    let mut outcome = git_attributes::Outcome::default();
+    for (path, mut expected) in expected {
+        let platform = cache.at_entry(path.as_bstr(), Some(false), |oid, buf| odb.find_blob(oid, buf))?;
+        platform.matching_attributes(&mut outcome);
+        let mut actual: Vec<_> = outcome
+            .iter()
+            .map(|a| {
+                let value = match a.state {
+                    git_attributes::StateRef::Set => "set".into(),
+                    git_attributes::StateRef::Unset => "unset".into(),
+                    git_attributes::StateRef::Value(v) => v.to_string(),
+                    git_attributes::StateRef::Unspecified => unreachable!("never returned"),
+                };
+                (a.name.as_str().to_owned(), value)
+            })
+            .collect();
+        actual.sort();
+        expected.sort();
+        assert_eq!(actual, expected, "{}", path);
+    }
+    Ok(())
+}
@@ -31,23 +31,19 @@ fn accidental_writes_through_symlinks_are_prevented_if_overwriting_is_forbidden(
             stripped_prefix(&source_tree, &source_files),
             stripped_prefix(&destination, &worktree_files),
         );
-        if multi_threaded() {
-            assert_eq!(outcome.collisions.len(), 2);
-        } else {
-            assert_eq!(
-                outcome.collisions,
-                vec![
-                    Collision {
-                        path: "FAKE-DIR".into(),
-                        error_kind: AlreadyExists
-                    },
-                    Collision {
-                        path: "FAKE-FILE".into(),
-                        error_kind: AlreadyExists
-                    }
-                ]
-            );
-        }
+        assert_eq!(
+            outcome.collisions,
+            vec![
+                Collision {
+                    path: "FAKE-DIR".into(),
+                    error_kind: AlreadyExists
+                },
+                Collision {
+                    path: "FAKE-FILE".into(),
+                    error_kind: AlreadyExists
+                }
+            ]
+        );
     } else {
         let expected = ["A-dir/a", "A-file", "FAKE-DIR", "FAKE-FILE", "fake-dir/b", "fake-file"];
         assert_eq!(stripped_prefix(&source_tree, &source_files), paths(expected));
@@ -172,6 +168,161 @@ fn allow_or_disallow_symlinks() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn filters_are_applied_according_to_attributes() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.filters = git_filter::Pipeline::new(
+        Default::default(),
+        vec![git_filter::Driver {
+            name: "upper".into(),
+            smudge: Some("tr a-z A-Z".into()),
+            ..Default::default()
+        }],
+    );
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_filters")?;
+    assert!(outcome.collisions.is_empty());
+    assert!(outcome.errors.is_empty());
+
+    let read = |path: &str| fs::read(destination.path().join(path)).unwrap();
+    assert_eq!(read("text.crlf").as_bstr(), "a\r\nb\r\n");
+    assert_eq!(read("binary.crlf").as_bstr(), "a\nb\n");
+    assert_eq!(read("file.upper").as_bstr(), "LOWER\n");
+    assert_eq!(read("untouched").as_bstr(), "a\n");
+
+    let ident_id = index
+        .entries()
+        .iter()
+        .find(|e| e.path(&index) == "ident.txt")
+        .expect("present")
+        .id;
+    assert_eq!(read("ident.txt").as_bstr(), format!("$Id: {} $\n", ident_id));
+    Ok(())
+}
+
+#[test]
+fn submodules_are_checked_out_as_empty_directories() -> crate::Result {
+    let opts = opts_from_probe();
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_mixed_with_submodule")?;
+
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["dir/content", "empty"]),
+    );
+    for submodule in ["dir/submodule", "submodule"] {
+        let path = destination.path().join(submodule);
+        assert!(path.is_dir(), "{:?} must be a directory", path);
+        assert_eq!(std::fs::read_dir(&path)?.count(), 0, "it's empty");
+    }
+    assert_eq!(outcome.files_updated, index.entries().len(), "all entries are counted");
+    Ok(())
+}
+
+#[test]
+fn sparse_patterns_skip_excluded_entries() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.sparse_patterns = Some(git_worktree::sparse::Patterns::from_bytes(
+        b"/*\n!/*/\n/dir/\n!/dir/*/\n",
+        "sparse-checkout",
+        true,
+    ));
+    let (_source_tree, destination, index, _outcome) =
+        checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules")?;
+
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["dir/content", "empty", "executable"]),
+        "only files in the root and in dir/ are checked out"
+    );
+    let skipped: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(git_index::entry::Flags::SKIP_WORKTREE))
+        .map(|e| e.path(&index).to_str_lossy().into_owned())
+        .collect();
+    assert_eq!(skipped, ["dir/sub-dir/symlink"], "excluded entries are marked");
+    Ok(())
+}
+
+#[test]
+fn sparse_directories_included_by_widened_patterns_are_expanded() -> crate::Result {
+    let source_tree = fixture_path("make_mixed_without_submodules");
+    let git_dir = source_tree.join(".git");
+    let mut index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    let narrow = git_worktree::sparse::Patterns::from_bytes(b"/*\n!/*/\n/dir/\n!/dir/*/\n", "sparse-checkout", true);
+    narrow.apply(&mut index, git_glob::pattern::Case::Sensitive)?;
+    let num_collapsed = narrow.collapse(&mut index, |tree| {
+        let mut buf = Vec::new();
+        git_object::WriteTo::write_to(tree, &mut buf)?;
+        Ok::<_, std::io::Error>(git_object::compute_hash(
+            git_hash::Kind::Sha1,
+            git_object::Kind::Tree,
+            &buf,
+        ))
+    })?;
+    assert_eq!(num_collapsed, 1, "dir/sub-dir/ is now a sparse directory");
+    assert!(index.is_sparse());
+
+    let destination = tempfile::tempdir_in(std::env::current_dir()?)?;
+    let mut opts = opts_from_probe();
+    opts.sparse_patterns = Some(git_worktree::sparse::Patterns::from_bytes(
+        b"/*\n!/*/\n/dir/\n",
+        "sparse-checkout",
+        true,
+    ));
+    index::checkout(
+        &mut index,
+        destination.path(),
+        move |oid, buf| odb.find(oid, buf),
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )?;
+
+    assert!(
+        !index.is_sparse(),
+        "the sparse directory was expanded as it's included now"
+    );
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["dir/content", "dir/sub-dir/symlink", "empty", "executable"]),
+    );
+    assert!(
+        index
+            .entries()
+            .iter()
+            .all(|e| !e.flags.contains(git_index::entry::Flags::SKIP_WORKTREE)),
+        "all entries are included"
+    );
+    Ok(())
+}
+
+#[test]
+fn sparse_directory_entries_which_do_not_skip_the_worktree_are_an_error() -> crate::Result {
+    let source_tree = fixture_path("make_mixed_without_submodules");
+    let git_dir = source_tree.join(".git");
+    let mut index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    index.entries_mut()[0].mode = git_index::entry::Mode::DIR;
+    let destination = tempfile::tempdir_in(std::env::current_dir()?)?;
+
+    let res = index::checkout(
+        &mut index,
+        destination.path(),
+        move |oid, buf| odb.find(oid, buf),
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts_from_probe(),
+    );
+    assert!(
+        matches!(res, Err(index::checkout::Error::UnexpandedDirectory { .. })),
+        "sparse directories must be expanded or skipped, they can't be checked out as is"
+    );
+    Ok(())
+}
+
 #[test]
 fn keep_going_collects_results() {
     let mut opts = opts_from_probe();
@@ -247,6 +398,42 @@ fn no_case_related_collisions_on_case_sensitive_filesystem() {
     assert_eq!(num_files, index.entries().len(), "it checks out all files");
 }
 
+#[test]
+fn many_files_are_checked_out_by_multiple_threads() -> crate::Result {
+    for thread_limit in [1, 2, 4, 16] {
+        let mut opts = opts_from_probe();
+        opts.thread_limit = Some(thread_limit);
+        let (source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts.clone(), "make_many_files")?;
+
+        assert!(outcome.collisions.is_empty());
+        assert!(outcome.errors.is_empty());
+        assert_eq!(outcome.files_updated, index.entries().len());
+        let num_files = assert_equality(&source_tree, &destination, opts.fs.symlink)?;
+        assert_eq!(num_files, index.entries().len(), "it checks out all files");
+    }
+    Ok(())
+}
+
+#[test]
+fn entries_which_may_collide_when_ignoring_case_are_still_checked_out_on_a_case_sensitive_filesystem() -> crate::Result
+{
+    let mut opts = opts_from_probe();
+    if opts.fs.ignore_case {
+        eprintln!("Skipping case-sensitive testing on what would be a case-insensitive file system");
+        return Ok(());
+    }
+    // Pretend that case is ignored to have these entries checked out one by one after all threads are done.
+    opts.fs.ignore_case = true;
+    opts.thread_limit = Some(4);
+    let (source_tree, destination, index, outcome) =
+        checkout_index_in_tmp_dir(opts.clone(), "make_ignorecase_collisions")?;
+
+    assert!(outcome.collisions.is_empty());
+    let num_files = assert_equality(&source_tree, &destination, opts.fs.symlink)?;
+    assert_eq!(num_files, index.entries().len(), "it checks out all files");
+    Ok(())
+}
+
 #[test]
 fn collisions_are_detected_on_a_case_insensitive_filesystem() {
     let opts = opts_from_probe();
@@ -265,19 +452,11 @@ fn collisions_are_detected_on_a_case_insensitive_filesystem() {
     );
 
     let dest_files = dir_structure(&destination);
-    if multi_threaded() {
-        assert_eq!(
-            dest_files.len(),
-            5,
-            "can only assert on number as it's racily creating files so unclear which one clashes"
-        );
-    } else {
-        assert_eq!(
-            stripped_prefix(&destination, &dest_files),
-            paths(["D/B", "D/C", "FILE_X", "X", "link-to-X"]),
-            "we checkout files in order and generally handle collision detection differently, hence the difference"
-        );
-    }
+    assert_eq!(
+        stripped_prefix(&destination, &dest_files),
+        paths(["D/B", "D/C", "FILE_X", "X", "link-to-X"]),
+        "we checkout files in order and generally handle collision detection differently, hence the difference"
+    );
 
     let error_kind = ErrorKind::AlreadyExists;
     #[cfg(windows)]
@@ -285,40 +464,32 @@ fn collisions_are_detected_on_a_case_insensitive_filesystem() {
     #[cfg(not(windows))]
     let error_kind_dir = error_kind;
 
-    if multi_threaded() {
-        assert_eq!(
-            outcome.collisions.len(),
-            5,
-            "can only assert on number as it's racily creating files so unclear which one clashes"
-        );
-    } else {
-        assert_eq!(
-            outcome.collisions,
-            vec![
-                Collision {
-                    path: "FILE_x".into(),
-                    error_kind,
-                },
-                Collision {
-                    path: "d".into(),
-                    error_kind: error_kind_dir,
-                },
-                Collision {
-                    path: "file_X".into(),
-                    error_kind,
-                },
-                Collision {
-                    path: "file_x".into(),
-                    error_kind,
-                },
-                Collision {
-                    path: "x".into(),
-                    error_kind,
-                },
-            ],
-            "these files couldn't be checked out"
-        );
-    }
+    assert_eq!(
+        outcome.collisions,
+        vec![
+            Collision {
+                path: "FILE_x".into(),
+                error_kind,
+            },
+            Collision {
+                path: "d".into(),
+                error_kind: error_kind_dir,
+            },
+            Collision {
+                path: "file_X".into(),
+                error_kind,
+            },
+            Collision {
+                path: "file_x".into(),
+                error_kind,
+            },
+            Collision {
+                path: "x".into(),
+                error_kind,
+            },
+        ],
+        "these files couldn't be checked out"
+    );
 }
 
 fn multi_threaded() -> bool {
@@ -401,9 +572,9 @@ fn checkout_index_in_tmp_dir_opts(
         destination.path(),
         move |oid, buf| {
             if allow_return_object(oid) {
-                odb.find_blob(oid, buf)
+                odb.find(oid, buf)
             } else {
-                Err(git_odb::find::existing_object::Error::NotFound { oid: oid.to_owned() })
+                Err(git_odb::find::existing::Error::NotFound { oid: oid.to_owned() })
             }
         },
         &mut progress::Discard,
@@ -1,5 +1,7 @@
 mod fs;
 mod index;
+mod sparse;
+mod status;
 
 use std::path::{Path, PathBuf};
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -0,0 +1,105 @@
+use git_glob::pattern::Case;
+use git_worktree::sparse::Patterns;
+
+fn cone(patterns: &str) -> Patterns {
+    Patterns::from_bytes(patterns.as_bytes(), "sparse-checkout", true)
+}
+
+#[test]
+fn cone_mode_includes_files_of_parent_directories_and_recursive_directories() {
+    let patterns = cone("/*\n!/*/\n/a/\n!/a/*/\n/a/b/c/\n/d/\n");
+    assert!(matches!(patterns, Patterns::Cone { .. }));
+    for (path, expected) in [
+        ("file", true),
+        ("a/file", true),
+        ("a/x/file", false),
+        ("a/b/file", true),
+        ("a/b/x/file", false),
+        ("a/b/c/file", true),
+        ("a/b/c/x/y/file", true),
+        ("d/x/file", true),
+        ("e/file", false),
+        ("dd/file", false),
+    ] {
+        assert_eq!(patterns.is_included(path.into(), Case::Sensitive), expected, "{}", path);
+    }
+
+    for (dir, expected) in [
+        ("a/", false),
+        ("a/x/", true),
+        ("a/b/", false),
+        ("a/b/c/", false),
+        ("a/b/c/x/", false),
+        ("d/", false),
+        ("e/", true),
+        ("dd/", true),
+    ] {
+        assert_eq!(patterns.is_excluded_dir(dir.into()), expected, "{}", dir);
+    }
+}
+
+#[test]
+fn non_cone_patterns_fall_back_to_non_cone_mode() {
+    let patterns = cone("/*\n!/*/\n*.txt\n");
+    assert!(matches!(patterns, Patterns::NonCone(_)), "like git, we fall back");
+    let patterns = cone("!/a/*/\n");
+    assert!(
+        matches!(patterns, Patterns::NonCone(_)),
+        "parents must be included recursively first"
+    );
+}
+
+#[test]
+fn non_cone_mode_uses_the_last_matching_pattern() {
+    let patterns = Patterns::from_bytes(b"/*\n!/dir/\n/dir/keep/\n*.txt\n!/dir/keep/no.txt\n", "", false);
+    for (path, expected) in [
+        ("file", true),
+        ("dir/file", false),
+        ("dir/file.txt", true),
+        ("dir/keep/file", true),
+        ("dir/keep/no.txt", false),
+    ] {
+        assert_eq!(patterns.is_included(path.into(), Case::Sensitive), expected, "{}", path);
+    }
+    assert!(
+        !patterns.is_excluded_dir("dir/".into()),
+        "only cone mode can exclude dirs"
+    );
+}
+
+#[test]
+fn apply_marks_excluded_entries_and_fails_on_included_sparse_directories() -> crate::Result {
+    let mut state = git_index::State::new(git_hash::Kind::Sha1);
+    let id = git_hash::Kind::Sha1.null();
+    for (path, flags, mode) in [
+        (
+            "a/file",
+            git_index::entry::Flags::SKIP_WORKTREE,
+            git_index::entry::Mode::FILE,
+        ),
+        (
+            "b/",
+            git_index::entry::Flags::SKIP_WORKTREE,
+            git_index::entry::Mode::DIR,
+        ),
+        ("file", git_index::entry::Flags::empty(), git_index::entry::Mode::FILE),
+    ] {
+        state.upsert_entry(path.into(), Default::default(), id, flags, mode);
+    }
+
+    let num_skipped = cone("/*\n!/*/\n/a/\n").apply(&mut state, Case::Sensitive)?;
+    assert_eq!(
+        num_skipped, 1,
+        "only the sparse directory is skipped, a/file isn't skipped anymore"
+    );
+    assert!(!state.entries()[0]
+        .flags
+        .contains(git_index::entry::Flags::SKIP_WORKTREE));
+
+    let err = cone("/*\n!/*/\n/b/\n").apply(&mut state, Case::Sensitive).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The sparse directory 'b/' is included by the sparse checkout patterns and must be expanded first"
+    );
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use bstr::BString;
+use git_odb::FindExt;
+use git_worktree::{
+    fs,
+    status::{index_to_worktree, index_to_worktree::Change, untracked},
+};
+
+fn index_at(worktree: &Path) -> git_index::File {
+    git_index::File::at(worktree.join(".git/index"), git_hash::Kind::Sha1, Default::default()).unwrap()
+}
+
+fn index_to_worktree_changes(
+    worktree: &Path,
+    options: index_to_worktree::Options,
+) -> crate::Result<Vec<(BString, index_to_worktree::Change)>> {
+    let index = index_at(worktree);
+    let case = git_glob::pattern::Case::Sensitive;
+    let state = fs::cache::State::for_add(
+        fs::cache::state::Attributes::new(Default::default(), Default::default()),
+        fs::cache::state::Ignore::new(Default::default(), Default::default(), None, case),
+    );
+    let attribute_files_in_index = state.build_attribute_list(&index, index.path_backing(), case);
+    let cache = fs::Cache::new(worktree, state, case, Vec::new(), attribute_files_in_index);
+    let odb = git_odb::at(worktree.join(".git/objects"))?.into_inner().into_arc()?;
+    let mut changes = Vec::new();
+    git_worktree::status::index_to_worktree(
+        &index,
+        &cache,
+        move |oid, buf| odb.find_blob(oid, buf),
+        options,
+        &AtomicBool::default(),
+        |path, _entry, change| changes.push((BString::from(path), change)),
+    )?;
+    Ok(changes)
+}
+
+#[test]
+fn index_to_worktree_reports_modifications_removals_and_type_changes() -> crate::Result {
+    let worktree = git_testtools::scripted_fixture_read_only("make_status_setup.sh")?;
+    let changes = index_to_worktree_changes(&worktree, Default::default())?;
+    let content = |executable_bit_changed, content_changed| Change::Modification {
+        executable_bit_changed,
+        content_changed,
+    };
+    assert_eq!(
+        changes,
+        vec![
+            ("a".into(), content(false, true)),
+            ("dir/b".into(), content(true, false)),
+            ("dir/c".into(), Change::Removed),
+            ("same-size".into(), content(false, true)),
+            ("type-change".into(), Change::Type),
+        ],
+        "files with the same size are hashed, unchanged files aren't reported"
+    );
+    Ok(())
+}
+
+#[test]
+fn index_to_worktree_converts_files_like_git_before_hashing_them() -> crate::Result {
+    let worktree = git_testtools::scripted_fixture_read_only("make_status_crlf.sh")?;
+    let options = index_to_worktree::Options {
+        filters: git_filter::Pipeline::new(
+            git_filter::pipeline::Options {
+                eol_config: git_filter::eol::Configuration {
+                    auto_crlf: git_filter::eol::AutoCrlf::Enabled,
+                    eol: None,
+                },
+            },
+            Vec::new(),
+        ),
+        ..Default::default()
+    };
+    assert_eq!(
+        index_to_worktree_changes(&worktree, options)?,
+        vec![(
+            "changed.txt".into(),
+            Change::Modification {
+                executable_bit_changed: false,
+                content_changed: true
+            }
+        )],
+        "files with CRLF line endings are converted like git does, so only actual changes are reported"
+    );
+
+    let changes = index_to_worktree_changes(&worktree, Default::default())?;
+    assert_eq!(
+        changes.iter().map(|(path, _)| path.to_string()).collect::<Vec<_>>(),
+        ["auto", "changed.txt"],
+        "without core.autocrlf, only eol.txt is converted due to its attributes, so 'auto' now appears changed"
+    );
+    Ok(())
+}
+
+fn untracked_items(options: untracked::Options) -> crate::Result<Vec<(BString, untracked::Kind, bool)>> {
+    let worktree = git_testtools::scripted_fixture_read_only("make_status_setup.sh")?;
+    let index = index_at(&worktree);
+    let git_dir = worktree.join(".git");
+    let mut buf = Vec::new();
+    let case = git_glob::pattern::Case::Sensitive;
+    let state = fs::cache::State::IgnoreStack(fs::cache::state::Ignore::new(
+        Default::default(),
+        git_attributes::MatchGroup::from_git_dir(&git_dir, None, &mut buf)?,
+        None,
+        case,
+    ));
+    let mut cache = fs::Cache::new(&worktree, state, case, buf, Default::default());
+    let mut items = Vec::new();
+    git_worktree::status::untracked(
+        &index,
+        &mut cache,
+        |_oid, _buf| Err(std::io::Error::new(std::io::ErrorKind::Other, "unreachable")),
+        options,
+        &AtomicBool::default(),
+        |path, kind, is_dir| items.push((BString::from(path), kind, is_dir)),
+    )?;
+    Ok(items)
+}
+
+#[test]
+fn untracked_directories_are_collapsed_by_default() -> crate::Result {
+    use untracked::Kind::*;
+    assert_eq!(
+        untracked_items(Default::default())?,
+        vec![
+            ("dir/new".into(), Untracked, true),
+            ("type-change".into(), Untracked, true),
+            ("untracked".into(), Untracked, false),
+            ("untracked-dir".into(), Untracked, true),
+        ],
+        "empty directories and directories with only ignored files aren't reported"
+    );
+    assert_eq!(
+        untracked_items(untracked::Options {
+            files: untracked::Files::Collapsed,
+            ignored: true,
+        })?,
+        vec![
+            ("dir/new".into(), Untracked, true),
+            ("ignored-dir".into(), Ignored, true),
+            ("only-ignored".into(), Ignored, true),
+            ("top.ignored".into(), Ignored, false),
+            ("type-change".into(), Untracked, true),
+            ("untracked".into(), Untracked, false),
+            ("untracked-dir".into(), Untracked, true),
+            ("untracked-dir/two.ignored".into(), Ignored, false),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_files_can_be_listed_individually() -> crate::Result {
+    use untracked::Kind::*;
+    assert_eq!(
+        untracked_items(untracked::Options {
+            files: untracked::Files::All,
+            ignored: true,
+        })?,
+        vec![
+            ("dir/new/deeper/file".into(), Untracked, false),
+            ("ignored-dir".into(), Ignored, true),
+            ("only-ignored/z.ignored".into(), Ignored, false),
+            ("top.ignored".into(), Ignored, false),
+            ("type-change/file".into(), Untracked, false),
+            ("untracked".into(), Untracked, false),
+            ("untracked-dir/one".into(), Untracked, false),
+            ("untracked-dir/two.ignored".into(), Ignored, false),
+        ],
+        "ignored directories are still reported as a whole"
+    );
+    Ok(())
+}
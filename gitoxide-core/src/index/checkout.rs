@@ -90,12 +90,12 @@ pub fn checkout_exclusive(
                     objects.find_blob(oid, buf).ok();
                     if empty_files {
                         // We always want to query the ODB here…
-                        objects.find_blob(oid, buf)?;
+                        objects.find(oid, buf)?;
                         buf.clear();
                         // …but write nothing
-                        Ok(git::objs::BlobRef { data: buf })
+                        Ok(git::objs::Data::new(git::objs::Kind::Blob, object_hash, buf))
                     } else {
-                        objects.find_blob(oid, buf)
+                        objects.find(oid, buf)
                     }
                 }
             },
@@ -109,7 +109,7 @@ pub fn checkout_exclusive(
             dest_directory,
             |_, buf| {
                 buf.clear();
-                Ok(git::objs::BlobRef { data: buf })
+                Ok(git::objs::Data::new(git::objs::Kind::Blob, object_hash, buf))
             },
             &mut files,
             &mut bytes,
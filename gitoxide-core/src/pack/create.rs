@@ -80,6 +80,12 @@ pub struct Context<W> {
     pub thread_limit: Option<usize>,
     /// If set, statistics about the operation will be written to the output stream.
     pub statistics: Option<OutputFormat>,
+    /// The amount of similar objects each object is compared with when searching for new deltas.
+    ///
+    /// If 0, no new deltas are computed and only deltas in existing packs are reused.
+    pub delta_window: usize,
+    /// The maximum length of chains of newly computed deltas.
+    pub delta_depth: usize,
     /// The size of the cache storing fully decoded delta objects. This can greatly speed up pack decoding by reducing the length of delta
     /// chains. Note that caches also incur a cost and poorly used caches may reduce overall performance.
     /// This is a total, shared among all threads if `thread_limit` permits.
@@ -108,6 +114,8 @@ pub fn create<W, P>(
         thin,
         thread_limit,
         statistics,
+        delta_window,
+        delta_depth,
         pack_cache_size_in_bytes,
         object_cache_size_in_bytes,
         mut out,
@@ -245,10 +253,16 @@ where
             progress,
             pack::data::output::entry::iter_from_counts::Options {
                 thread_limit,
-                mode: pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                mode: if delta_window == 0 {
+                    pack::data::output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects
+                } else {
+                    pack::data::output::entry::iter_from_counts::Mode::PackCopyAndDeltaCompression
+                },
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
+                delta_window,
+                delta_depth,
             },
         ))
     };
@@ -335,6 +349,7 @@ fn human_output(
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             },
     }: Statistics,
     mut out: impl std::io::Write,
@@ -355,10 +370,11 @@ fn human_output(
     #[rustfmt::skip]
     writeln!(
         out,
-        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
+        "\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}\n\t{:<width$} {}",
         "decoded and recompressed", decoded_and_recompressed_objects,
         "pack-to-pack copies", objects_copied_from_pack,
         "ref-delta-objects", ref_delta_objects,
+        "delta-compressed objects", delta_compressed_objects,
         "missing objects", missing_objects,
         width = width
     )?;
@@ -0,0 +1,53 @@
+use std::io;
+
+use anyhow::{bail, Context};
+use git_repository as git;
+use git_repository::prelude::FindExt;
+
+use crate::OutputFormat;
+
+pub mod query {
+    use crate::OutputFormat;
+
+    pub struct Options {
+        pub format: OutputFormat,
+    }
+}
+
+pub fn query(
+    repo: git::Repository,
+    pathspecs: impl Iterator<Item = git::path::Spec>,
+    mut out: impl io::Write,
+    query::Options { format }: query::Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+
+    let worktree = repo
+        .worktree()
+        .with_context(|| "Cannot check attributes without a current worktree")?;
+    let index = worktree.index()?;
+    let mut cache = worktree.attributes(&index, None)?;
+
+    let prefix = repo.prefix().expect("worktree - we have an index by now")?;
+    let mut outcome = git::attrs::Outcome::default();
+
+    for mut spec in pathspecs {
+        for path in spec.apply_prefix(&prefix).items() {
+            let is_dir = git::path::from_bstr(path).metadata().ok().map(|m| m.is_dir());
+            let entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
+            entry.matching_attributes(&mut outcome);
+            for attr in outcome.iter() {
+                write!(out, "{}: {}: ", path, attr.name.as_str())?;
+                match attr.state {
+                    git::attrs::StateRef::Set => writeln!(out, "set")?,
+                    git::attrs::StateRef::Unset => writeln!(out, "unset")?,
+                    git::attrs::StateRef::Value(value) => writeln!(out, "{}", value)?,
+                    git::attrs::StateRef::Unspecified => writeln!(out, "unspecified")?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
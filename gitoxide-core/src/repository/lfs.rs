@@ -0,0 +1,109 @@
+use anyhow::Context;
+use git_repository as git;
+
+/// Serve the LFS filter process protocol on stdin and stdout, to be configured as `filter.lfs.process`, with objects
+/// being kept in the LFS storage of `repo` and downloaded from its LFS server if they are missing.
+pub fn filter_process(repo: git::Repository) -> anyhow::Result<()> {
+    let storage = git_lfs::Storage::at_git_dir(repo.common_dir());
+    let mut client = None;
+    git_lfs::filter::process(
+        std::io::stdin().lock(),
+        std::io::stdout().lock(),
+        &storage,
+        &mut |pointer, storage| {
+            let client = match client.as_mut() {
+                Some(client) => client,
+                None => client.insert(new_client(&repo)?),
+            };
+            download(&repo, client, pointer, storage).map_err(|err| {
+                // Only the status of the request is communicated to git, so the reason is printed here.
+                eprintln!("Error: {:#}", err);
+                err.into()
+            })
+        },
+    )?;
+    Ok(())
+}
+
+/// Return the url of the LFS server as configured in `lfs.url` or `remote.<name>.lfsurl`, or as derived from the url
+/// of the default remote.
+fn endpoint_url(repo: &git::Repository) -> anyhow::Result<String> {
+    let config = repo.config_snapshot();
+    if let Some(url) = config.string("lfs.url") {
+        return Ok(url.to_string());
+    }
+    let remote_name = repo
+        .remote_default_name(git::remote::Direction::Fetch)
+        .context("Cannot determine LFS server without 'lfs.url' or a default remote")?;
+    if let Some(url) = config.string(format!("remote.{}.lfsurl", remote_name).as_str()) {
+        return Ok(url.to_string());
+    }
+    let remote = repo.find_remote(remote_name.as_ref())?;
+    remote
+        .url(git::remote::Direction::Fetch)
+        .and_then(git_lfs::client::endpoint_url)
+        .with_context(|| format!("Cannot derive LFS server from the url of remote '{}'", remote_name))
+}
+
+#[cfg(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest"))]
+type Client = git_lfs::Client<git::protocol::transport::client::http::Impl>;
+
+#[cfg(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest"))]
+fn new_client(repo: &git::Repository) -> anyhow::Result<Client> {
+    Ok(git_lfs::Client::new(endpoint_url(repo)?))
+}
+
+#[cfg(not(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest")))]
+type Client = std::convert::Infallible;
+
+#[cfg(not(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest")))]
+fn new_client(repo: &git::Repository) -> anyhow::Result<Client> {
+    anyhow::bail!(
+        "Cannot download LFS objects from '{}' as no http backend is available",
+        endpoint_url(repo)?
+    )
+}
+
+#[cfg(not(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest")))]
+fn download(
+    _repo: &git::Repository,
+    client: &mut Client,
+    _pointer: &git_lfs::Pointer,
+    _storage: &git_lfs::Storage,
+) -> anyhow::Result<()> {
+    match *client {}
+}
+
+/// Download the object of `pointer` into `storage`, obtaining credentials for the LFS server if it requires authentication.
+#[cfg(any(feature = "lfs-http-client-curl", feature = "lfs-http-client-reqwest"))]
+fn download(
+    repo: &git::Repository,
+    client: &mut Client,
+    pointer: &git_lfs::Pointer,
+    storage: &git_lfs::Storage,
+) -> anyhow::Result<()> {
+    use git_lfs::client::download::Error;
+    match client.download(std::slice::from_ref(pointer), storage) {
+        Err(Error::Batch(err)) if err.is_permission_denied() => {
+            let (mut cascade, action, prompt_options) = repo
+                .config_snapshot()
+                .credential_helpers(git::url::parse(client.url().into())?)?;
+            let git::credentials::protocol::Outcome { identity, next } = cascade
+                .invoke(action, prompt_options.clone())?
+                .context("Credential helpers didn't provide an identity")?;
+            client.set_identity(identity);
+            match client.download(std::slice::from_ref(pointer), storage) {
+                Ok(()) => {
+                    cascade.invoke(next.store(), prompt_options)?;
+                    Ok(())
+                }
+                Err(Error::Batch(err)) if err.is_permission_denied() => {
+                    cascade.invoke(next.erase(), prompt_options)?;
+                    Err(err.into())
+                }
+                Err(err) => Err(err.into()),
+            }
+        }
+        res => res.map_err(Into::into),
+    }
+}
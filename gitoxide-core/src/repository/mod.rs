@@ -12,6 +12,7 @@ pub fn init(directory: Option<PathBuf>) -> Result<git::discover::repository::Pat
     .with_context(|| "Repository initialization failed")
 }
 
+pub mod attributes;
 pub mod commit;
 pub mod config;
 mod credential;
@@ -26,9 +27,12 @@ pub use clone::function::clone;
 #[cfg(feature = "blocking-client")]
 pub use fetch::function::fetch;
 pub mod index;
+#[cfg(feature = "blocking-client")]
+pub mod lfs;
 pub mod mailmap;
 pub mod odb;
 pub mod remote;
 pub mod revision;
+pub mod status;
 pub mod tree;
 pub mod verify;
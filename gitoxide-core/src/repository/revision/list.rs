@@ -9,6 +9,7 @@ use crate::OutputFormat;
 pub fn list(
     mut repo: git::Repository,
     spec: OsString,
+    sorting: git::traverse::commit::Sorting,
     mut out: impl std::io::Write,
     format: OutputFormat,
 ) -> anyhow::Result<()> {
@@ -18,16 +19,20 @@ pub fn list(
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
 
     let spec = git::path::os_str_into_bstr(&spec)?;
-    let id = repo
-        .rev_parse_single(spec)
-        .context("Only single revisions are currently supported")?;
-    let commit_id = id
-        .object()?
-        .peel_to_kind(git::object::Kind::Commit)
-        .context("Need commitish as starting point")?
-        .id
-        .attach(&repo);
-    for commit in commit_id.ancestors().all()? {
+    let spec = repo.rev_parse(spec)?;
+    let walk = match spec.single() {
+        Some(id) => {
+            let commit_id = id
+                .object()?
+                .peel_to_kind(git::object::Kind::Commit)
+                .context("Need commitish as starting point")?
+                .id
+                .attach(&repo);
+            repo.rev_walk([commit_id])
+        }
+        None => spec.rev_walk()?,
+    };
+    for commit in walk.sorting(sorting).all()? {
         writeln!(out, "{}", commit?.to_hex())?;
     }
     Ok(())
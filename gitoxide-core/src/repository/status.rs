@@ -0,0 +1,112 @@
+use std::{collections::BTreeMap, io, sync::atomic::AtomicBool};
+
+use anyhow::bail;
+use git_repository as git;
+use git_repository::{
+    bstr::{BString, ByteSlice},
+    status::{IndexWorktreeChange, Item, TreeIndexChange},
+};
+
+use crate::OutputFormat;
+
+/// How to show untracked files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Untracked {
+    /// Don't show untracked files.
+    No,
+    /// Show untracked directories as a whole.
+    Normal,
+    /// Show all untracked files individually.
+    All,
+}
+
+impl std::str::FromStr for Untracked {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "no" => Untracked::No,
+            "normal" => Untracked::Normal,
+            "all" => Untracked::All,
+            _ => return Err(format!("Invalid value '{}', must be one of 'no', 'normal' or 'all'", s)),
+        })
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If unset, `status.showUntrackedFiles` decides.
+    pub untracked: Option<Untracked>,
+    pub ignored: bool,
+}
+
+/// Print the status of the worktree of `repo` like `git status --porcelain` does.
+pub fn show(
+    repo: git::Repository,
+    mut out: impl io::Write,
+    Options {
+        format,
+        untracked,
+        ignored,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let mut status = repo.status()?.ignored(ignored);
+    if let Some(untracked) = untracked {
+        status = status.untracked_files(match untracked {
+            Untracked::No => None,
+            Untracked::Normal => Some(git::status::UntrackedFiles::Collapsed),
+            Untracked::All => Some(git::status::UntrackedFiles::All),
+        });
+    }
+
+    let mut tracked = BTreeMap::<BString, [u8; 2]>::new();
+    let mut untracked_and_ignored = Vec::new();
+    status.for_each(&AtomicBool::default(), |item| match item {
+        Item::TreeIndex { path, change } => {
+            tracked.entry(path.to_owned()).or_insert(*b"  ")[0] = match change {
+                TreeIndexChange::Addition { .. } => b'A',
+                TreeIndexChange::Deletion { .. } => b'D',
+                TreeIndexChange::Modification { .. } => b'M',
+            }
+        }
+        Item::Conflict {
+            path,
+            ancestor,
+            ours,
+            theirs,
+        } => {
+            let status = match (ancestor, ours, theirs) {
+                (true, false, false) => b"DD",
+                (false, true, false) => b"AU",
+                (true, true, false) => b"UD",
+                (false, false, true) => b"UA",
+                (true, false, true) => b"DU",
+                (false, true, true) => b"AA",
+                _ => b"UU",
+            };
+            tracked.insert(path.to_owned(), *status);
+        }
+        Item::IndexWorktree { path, change, .. } => {
+            tracked.entry(path.to_owned()).or_insert(*b"  ")[1] = match change {
+                IndexWorktreeChange::Removed => b'D',
+                IndexWorktreeChange::Type => b'T',
+                IndexWorktreeChange::Modification { .. } => b'M',
+            }
+        }
+        Item::Untracked { path, is_dir } => untracked_and_ignored.push(("??", path.to_owned(), is_dir)),
+        Item::Ignored { path, is_dir } => untracked_and_ignored.push(("!!", path.to_owned(), is_dir)),
+    })?;
+
+    for (path, status) in tracked {
+        writeln!(out, "{} {}", status.as_bstr(), path)?;
+    }
+    // Untracked items come first, followed by ignored ones, each in the order of their paths.
+    untracked_and_ignored.sort_by(|a, b| a.0.cmp(b.0).reverse());
+    for (status, path, is_dir) in untracked_and_ignored {
+        writeln!(out, "{} {}{}", status, path, if is_dir { "/" } else { "" })?;
+    }
+    Ok(())
+}
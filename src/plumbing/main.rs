@@ -15,7 +15,10 @@ use gitoxide_core::pack::verify;
 
 use crate::{
     plumbing::{
-        options::{commit, config, credential, exclude, free, index, mailmap, odb, revision, tree, Args, Subcommands},
+        options::{
+            attributes, commit, config, credential, exclude, free, index, mailmap, odb, revision, status, tree, Args,
+            Subcommands,
+        },
         show_progress,
     },
     shared::pretty::prepare_and_run,
@@ -374,6 +377,8 @@ pub fn main() -> Result<()> {
                     pack_cache_size_mb,
                     counting_threads,
                     object_cache_size_mb,
+                    delta_window,
+                    delta_depth,
                     output_directory,
                 } => {
                     let has_tips = !tips.is_empty();
@@ -393,6 +398,8 @@ pub fn main() -> Result<()> {
                                 pack_cache_size_in_bytes: pack_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 object_cache_size_in_bytes: object_cache_size_mb.unwrap_or(0) * 1_000_000,
                                 statistics: if statistics { Some(format) } else { None },
+                                delta_window,
+                                delta_depth,
                                 out,
                                 expansion: expansion.unwrap_or(if has_tips {
                                     core::pack::create::ObjectExpansion::TreeTraversal
@@ -647,14 +654,26 @@ pub fn main() -> Result<()> {
             },
         ),
         Subcommands::Revision(cmd) => match cmd {
-            revision::Subcommands::List { spec } => prepare_and_run(
+            revision::Subcommands::List {
+                spec,
+                topo_order,
+                author_date_order,
+            } => prepare_and_run(
                 "revision-list",
                 verbose,
                 progress,
                 progress_keep_open,
                 None,
                 move |_progress, out, _err| {
-                    core::repository::revision::list(repository(Mode::Lenient)?, spec, out, format)
+                    use git::traverse::commit::Sorting;
+                    let sorting = if topo_order {
+                        Sorting::TopoOrder
+                    } else if author_date_order {
+                        Sorting::AuthorDateOrder
+                    } else {
+                        Sorting::Topological
+                    };
+                    core::repository::revision::list(repository(Mode::Lenient)?, spec, sorting, out, format)
                 },
             ),
             revision::Subcommands::PreviousBranches => prepare_and_run(
@@ -820,6 +839,60 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
+        Subcommands::Status(status::Platform {
+            untracked_files,
+            ignored,
+        }) => prepare_and_run(
+            "status",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::status::show(
+                    repository(Mode::Lenient)?,
+                    out,
+                    core::repository::status::Options {
+                        format,
+                        untracked: untracked_files,
+                        ignored,
+                    },
+                )
+            },
+        ),
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Lfs(cmd) => match cmd {
+            crate::plumbing::options::lfs::Subcommands::FilterProcess => {
+                core::repository::lfs::filter_process(repository(Mode::Strict)?)
+            }
+        },
+        Subcommands::Attributes(cmd) => match cmd {
+            attributes::Subcommands::Query { pathspecs } => prepare_and_run(
+                "attributes-query",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    use git::bstr::ByteSlice;
+                    core::repository::attributes::query(
+                        repository(Mode::Strict)?,
+                        if pathspecs.is_empty() {
+                            Box::new(
+                                stdin_or_bail()?
+                                    .byte_lines()
+                                    .filter_map(Result::ok)
+                                    .filter_map(|line| git::path::Spec::from_bytes(line.as_bstr())),
+                            ) as Box<dyn Iterator<Item = git::path::Spec>>
+                        } else {
+                            Box::new(pathspecs.into_iter())
+                        },
+                        out,
+                        core::repository::attributes::query::Options { format },
+                    )
+                },
+            ),
+        },
         Subcommands::Exclude(cmd) => match cmd {
             exclude::Subcommands::Query {
                 patterns,
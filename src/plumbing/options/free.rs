@@ -151,6 +151,18 @@ pub mod pack {
             /// Packs produced with this option enabled are only valid in transit, but not at rest.
             thin: bool,
 
+            #[clap(long, default_value_t = 10)]
+            /// The amount of similar objects to compare each object with when searching for new deltas, like `--window` in
+            /// `git pack-objects`.
+            ///
+            /// If 0, no new deltas are computed and only deltas in existing packs are reused, which is fastest but produces
+            /// large packs.
+            delta_window: usize,
+
+            #[clap(long, default_value_t = 50)]
+            /// The maximum length of chains of newly computed deltas, like `--depth` in `git pack-objects`.
+            delta_depth: usize,
+
             /// The directory into which to write the pack file.
             #[clap(long, short = 'o')]
             output_directory: Option<PathBuf>,
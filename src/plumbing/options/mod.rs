@@ -104,9 +104,18 @@ pub enum Subcommands {
     /// Interact with the remote hosts.
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     Remote(remote::Platform),
+    /// Interact with the attribute files like .gitattributes.
+    #[clap(subcommand)]
+    Attributes(attributes::Subcommands),
     /// Interact with the exclude files like .gitignore.
     #[clap(subcommand)]
     Exclude(exclude::Subcommands),
+    /// Show changes between `HEAD`, the index and the worktree, similar to `git status --porcelain`.
+    Status(status::Platform),
+    /// Interact with git large file storage (LFS).
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    #[clap(subcommand)]
+    Lfs(lfs::Subcommands),
     #[clap(subcommand)]
     Index(index::Subcommands),
     /// Display overall progress of the gitoxide project as seen from the perspective of git-config.
@@ -330,9 +339,17 @@ pub mod revision {
     #[derive(Debug, clap::Subcommand)]
     #[clap(visible_alias = "rev", visible_alias = "r")]
     pub enum Subcommands {
-        /// List all commits reachable from the given rev-spec.
+        /// List all commits reachable from the given rev-spec, which may also be a range like `a..b` or `a...b`.
         #[clap(visible_alias = "l")]
-        List { spec: std::ffi::OsString },
+        List {
+            /// Show no parent before all of its children are shown, like `git rev-list --topo-order`.
+            #[clap(long, conflicts_with = "author-date-order")]
+            topo_order: bool,
+            /// Like `--topo-order`, but show commits in author date order otherwise, like `git rev-list --author-date-order`.
+            #[clap(long)]
+            author_date_order: bool,
+            spec: std::ffi::OsString,
+        },
         /// Provide the revision specification like `@~1` to explain.
         #[clap(visible_alias = "e")]
         Explain { spec: std::ffi::OsString },
@@ -357,6 +374,48 @@ pub mod revision {
     }
 }
 
+pub mod status {
+    use gitoxide_core as core;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Show untracked files, either not at all, untracked directories as a whole or all files individually.
+        ///
+        /// If unset, `status.showUntrackedFiles` is used.
+        #[clap(long, short = 'u', possible_values(&["no", "normal", "all"]))]
+        pub untracked_files: Option<core::repository::status::Untracked>,
+        /// Show ignored files as well.
+        #[clap(long)]
+        pub ignored: bool,
+    }
+}
+
+#[cfg(feature = "gitoxide-core-blocking-client")]
+pub mod lfs {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Serve the long-running filter process protocol on stdin and stdout, to be used as `filter.lfs.process`.
+        ///
+        /// Large files are stored in `.git/lfs` when added, and are downloaded from the LFS server on checkout
+        /// if they are missing locally.
+        FilterProcess,
+    }
+}
+
+pub mod attributes {
+    use git_repository as git;
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Print all attributes of path-specs along with their state, similar to `git check-attr --all`.
+        Query {
+            /// The git path specifications to list attributes for, or unset to read from stdin one per line.
+            #[clap(parse(try_from_os_str = std::convert::TryFrom::try_from))]
+            pathspecs: Vec<git::path::Spec>,
+        },
+    }
+}
+
 pub mod exclude {
     use std::ffi::OsString;
 
@@ -170,7 +170,13 @@ pub fn spawn_git_daemon(working_dir: impl AsRef<Path>) -> std::io::Result<GitDae
 
     let child = std::process::Command::new(EXEC_PATH.join(if cfg!(windows) { "git-daemon.exe" } else { "git-daemon" }))
         .current_dir(working_dir)
-        .args(["--verbose", "--base-path=.", "--export-all", "--user-path"])
+        .args([
+            "--verbose",
+            "--base-path=.",
+            "--export-all",
+            "--user-path",
+            "--enable=receive-pack",
+        ])
         .arg(format!("--port={free_port}"))
         .spawn()?;
 